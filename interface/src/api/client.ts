@@ -62,6 +62,7 @@ export interface WorkerStartedEvent {
 	channel_id: string | null;
 	worker_id: string;
 	task: string;
+	label?: string;
 	worker_type?: string;
 	interactive?: boolean;
 }
@@ -154,6 +155,14 @@ export interface WorkerTextEvent {
 	text: string;
 }
 
+export interface WorkerTextDeltaEvent {
+	type: "worker_text_delta";
+	agent_id: string;
+	worker_id: string;
+	text_delta: string;
+	aggregated_text: string;
+}
+
 export interface CortexChatMessageEvent {
 	type: "cortex_chat_message";
 	agent_id: string;
@@ -177,6 +186,7 @@ export type ApiEvent =
 	| ToolCompletedEvent
 	| OpenCodePartUpdatedEvent
 	| WorkerTextEvent
+	| WorkerTextDeltaEvent
 	| CortexChatMessageEvent;
 
 async function fetchJson<T>(path: string): Promise<T> {
@@ -225,6 +235,7 @@ export interface MessagesResponse {
 
 export interface WorkerStatusInfo {
 	id: string;
+	label: string;
 	task: string;
 	status: string;
 	started_at: string;
@@ -307,6 +318,7 @@ export type TranscriptStep =
 
 export interface WorkerRunInfo {
 	id: string;
+	label: string;
 	task: string;
 	status: string;
 	worker_type: string;
@@ -0,0 +1,3 @@
+-- Human-readable worker label shown next to the worker ID in listings.
+-- Cosmetic only; existing rows fall back to a slug of their task.
+ALTER TABLE worker_runs ADD COLUMN label TEXT;
@@ -1,21 +1,28 @@
 //! Agent processes: channels, branches, workers, compactor, cortex.
 
 pub mod branch;
+pub mod bulletin_history;
 pub mod channel;
 pub mod channel_attachments;
 pub mod channel_dispatch;
 pub mod channel_history;
+pub mod channel_language;
 pub mod channel_prompt;
 pub mod compactor;
 pub mod cortex;
 pub mod cortex_chat;
+pub mod debug_mirror;
 pub mod ingestion;
 #[cfg(test)]
 mod invariant_harness;
 pub mod process_control;
+pub mod prompt_escape;
 pub mod prompt_snapshot;
 pub mod status;
+pub mod token_estimator;
+pub mod tool_call_repair;
 pub mod worker;
+pub mod worker_log;
 
 pub(crate) fn panic_payload_to_string(panic_payload: &(dyn std::any::Any + Send)) -> String {
     panic_payload
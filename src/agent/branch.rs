@@ -4,7 +4,6 @@ use crate::agent::compactor::estimate_history_tokens;
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
-use crate::llm::routing::is_context_overflow_error;
 use crate::tools::MemoryPersistenceContractState;
 use crate::{AgentDeps, BranchId, ChannelId, ProcessEvent, ProcessId, ProcessType};
 use rig::agent::AgentBuilder;
@@ -62,7 +61,8 @@ impl Branch {
             ProcessType::Branch,
             Some(channel_id.clone()),
             deps.event_tx.clone(),
-        );
+        )
+        .with_tool_call_repair(deps.runtime_config.compaction.load().tool_call_repair);
         if let Some(contract_state) = &execution_config.memory_persistence_contract {
             hook = hook.with_memory_persistence_contract(contract_state.clone());
         }
@@ -110,11 +110,15 @@ impl Branch {
             .with_context(&*self.deps.agent_id, "branch")
             .with_routing((**routing).clone());
 
-        let agent = AgentBuilder::new(model)
+        let output_limit = self.deps.runtime_config.output_limits.load().branch;
+        let mut builder = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
             .default_max_turns(self.max_turns)
-            .tool_server_handle(self.tool_server.clone())
-            .build();
+            .tool_server_handle(self.tool_server.clone());
+        if output_limit.max_output_tokens > 0 {
+            builder = builder.max_tokens(output_limit.max_output_tokens);
+        }
+        let agent = builder.build();
 
         let mut current_prompt = prompt;
         let mut overflow_retries = 0;
@@ -189,7 +193,14 @@ impl Branch {
                     tracing::info!(branch_id = %self.id, %reason, "branch cancelled");
                     break format!("Branch was cancelled: {reason}");
                 }
-                Err(error) if is_context_overflow_error(&error.to_string()) => {
+                Err(error)
+                    if self
+                        .deps
+                        .runtime_config
+                        .routing
+                        .load()
+                        .is_context_overflow(&error.to_string()) =>
+                {
                     if enforce_memory_contract {
                         self.hook.set_completion_contract_request_active(false);
                     }
@@ -229,6 +240,22 @@ impl Branch {
             self.hook.set_completion_contract_request_active(false);
         }
 
+        // Memory persistence conclusions are bookkeeping, not an answer.
+        let conclusion = if enforce_memory_contract {
+            conclusion
+        } else {
+            let prompts = self.deps.runtime_config.prompts.load_full();
+            self.hook
+                .enforce_output_limit(
+                    &agent,
+                    &mut self.history,
+                    &prompts,
+                    output_limit,
+                    conclusion,
+                )
+                .await
+        };
+
         // Scrub tool secret values from the conclusion before sending to the
         // channel. Branches can spawn workers whose output may contain secrets.
         // Layer 1: exact-match redaction of known secrets from the store.
@@ -300,5 +327,11 @@ impl Branch {
              Continue with the information available.]"
         );
         self.history.insert(0, rig::message::Message::from(marker));
+        // The cut point can separate a tool call from its result.
+        crate::agent::tool_call_repair::repair_and_log(
+            &mut self.history,
+            self.deps.runtime_config.compaction.load().tool_call_repair,
+            &ProcessId::Branch(self.id),
+        );
     }
 }
@@ -0,0 +1,183 @@
+//! Timestamped snapshots of the cortex memory bulletin.
+//!
+//! Each successful synthesis can be written to disk so operators can compare
+//! what the bulletin said before and after a run. Snapshots are plain markdown
+//! files named by UTC timestamp and pruned to a fixed count, oldest first.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_PREFIX: &str = "bulletin-";
+const SNAPSHOT_SUFFIX: &str = ".md";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// One persisted bulletin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulletinSnapshot {
+    /// When the synthesis that produced this bulletin finished.
+    pub taken_at: DateTime<Utc>,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl BulletinSnapshot {
+    /// Line diff from this snapshot to a later one. See [`diff_lines`].
+    pub fn diff(&self, newer: &BulletinSnapshot) -> String {
+        diff_lines(&self.content, &newer.content)
+    }
+}
+
+/// Write `bulletin` as a new snapshot in `dir`, then delete the oldest
+/// snapshots so at most `retention` remain.
+pub fn save_snapshot(
+    dir: &Path,
+    bulletin: &str,
+    taken_at: DateTime<Utc>,
+    retention: usize,
+) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "{SNAPSHOT_PREFIX}{}{SNAPSHOT_SUFFIX}",
+        taken_at.format(TIMESTAMP_FORMAT)
+    ));
+    std::fs::write(&path, bulletin)?;
+
+    let snapshots = snapshot_paths(dir)?;
+    let excess = snapshots.len().saturating_sub(retention.max(1));
+    for (_, stale) in snapshots.into_iter().take(excess) {
+        std::fs::remove_file(&stale)?;
+    }
+
+    Ok(path)
+}
+
+/// Load every snapshot in `dir`, oldest first. A missing directory is an
+/// empty history.
+pub fn load_history(dir: &Path) -> std::io::Result<Vec<BulletinSnapshot>> {
+    snapshot_paths(dir)?
+        .into_iter()
+        .map(|(taken_at, path)| {
+            let content = std::fs::read_to_string(&path)?;
+            Ok(BulletinSnapshot {
+                taken_at,
+                path,
+                content,
+            })
+        })
+        .collect()
+}
+
+/// Simple line-based diff between two bulletins.
+///
+/// Lines only in `old` are prefixed with `- `, lines only in `new` with `+ `.
+/// Unchanged lines are left out, so identical bulletins produce an empty
+/// string.
+pub fn diff_lines(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, filled from the end so the walk
+    // below can go front to back.
+    let mut lcs = vec![vec![0usize; new_lines.len() + 1]; old_lines.len() + 1];
+    for i in (0..old_lines.len()).rev() {
+        for j in (0..new_lines.len()).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old_lines.len() || j < new_lines.len() {
+        if i < old_lines.len() && j < new_lines.len() && old_lines[i] == new_lines[j] {
+            i += 1;
+            j += 1;
+        } else if i < old_lines.len() && (j == new_lines.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            output.push_str("- ");
+            output.push_str(old_lines[i]);
+            output.push('\n');
+            i += 1;
+        } else {
+            output.push_str("+ ");
+            output.push_str(new_lines[j]);
+            output.push('\n');
+            j += 1;
+        }
+    }
+
+    output
+}
+
+/// Snapshot files in `dir` with their parsed timestamps, oldest first.
+fn snapshot_paths(dir: &Path) -> std::io::Result<Vec<(DateTime<Utc>, PathBuf)>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut snapshots = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let Some(taken_at) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_snapshot_name)
+        else {
+            continue;
+        };
+        snapshots.push((taken_at, path));
+    }
+    snapshots.sort_by_key(|(taken_at, _)| *taken_at);
+
+    Ok(snapshots)
+}
+
+fn parse_snapshot_name(name: &str) -> Option<DateTime<Utc>> {
+    let timestamp = name
+        .strip_prefix(SNAPSHOT_PREFIX)?
+        .strip_suffix(SNAPSHOT_SUFFIX)?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_lines, load_history, save_snapshot};
+    use chrono::{TimeZone as _, Utc};
+
+    #[test]
+    fn snapshots_load_oldest_first_and_prune_to_retention() {
+        let dir = std::env::temp_dir().join(format!("spacebot-bulletins-{}", uuid::Uuid::new_v4()));
+        for (index, text) in ["first", "second", "third"].iter().enumerate() {
+            let taken_at = Utc
+                .with_ymd_and_hms(2026, 1, 1, 12, index as u32, 0)
+                .unwrap();
+            save_snapshot(&dir, text, taken_at, 2).unwrap();
+        }
+
+        let history = load_history(&dir).unwrap();
+        let contents: Vec<_> = history.iter().map(|s| s.content.as_str()).collect();
+        assert_eq!(contents, ["second", "third"]);
+        assert!(history[0].taken_at < history[1].taken_at);
+
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(load_history(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diff_reports_only_added_and_removed_lines() {
+        let old = "## Identity\nLikes tea\nWorks on spacebot";
+        let new = "## Identity\nLikes coffee\nWorks on spacebot\nLives in Berlin";
+
+        assert_eq!(
+            diff_lines(old, new),
+            "- Likes tea\n+ Likes coffee\n+ Lives in Berlin\n"
+        );
+        assert_eq!(diff_lines(old, old), "");
+    }
+}
@@ -8,13 +8,17 @@ use crate::agent::channel_history::{
     extract_reply_from_tool_syntax, format_batched_user_message, format_user_message,
     message_display_name, pop_retrigger_bridge_message,
 };
+use crate::agent::channel_language::{self, LanguageTracker};
 use crate::agent::channel_prompt::{
-    MAX_RETRIGGERS_PER_TURN, RETRIGGER_DEBOUNCE_MS, RETRIGGER_MAX_TURNS, TemporalContext,
+    MAX_RETRIGGERS_PER_TURN, MAX_STATUS_REFRESHES_PER_TURN, RETRIGGER_DEBOUNCE_MS,
+    RETRIGGER_MAX_TURNS, TemporalContext,
 };
 use crate::agent::compactor::Compactor;
 use crate::agent::process_control::ControlActionResult;
 use crate::agent::status::{StatusBlock, SystemInfo};
 use crate::agent::worker::Worker;
+use crate::config::{StatusRefresh, UserContentEscaping};
+use crate::conversation::transcript_sink::{TranscriptKind, TranscriptOutcome, TranscriptRecord};
 use crate::conversation::{ChannelStore, ConversationLogger, ProcessRunLogger};
 use crate::error::{AgentError, Result};
 use crate::hooks::SpacebotHook;
@@ -58,6 +62,11 @@ struct PendingResult {
 
 const EVENT_LAG_WARNING_INTERVAL_SECS: u64 = 30;
 
+/// Upper bound on the identity text embedded in a reinforcement reminder.
+/// The full identity already lives in the system prompt; the reminder only
+/// needs enough to re-anchor tone and role.
+const IDENTITY_REMINDER_MAX_BYTES: usize = 2000;
+
 async fn recv_channel_event(
     event_rx: &mut broadcast::Receiver<ProcessEvent>,
 ) -> crate::BroadcastRecvResult<ProcessEvent> {
@@ -104,6 +113,9 @@ pub struct ChannelState {
     /// claimed under a write lock before any async spawn work and released
     /// when the worker is registered in the status block or the spawn fails.
     pub reserved_tasks: Arc<RwLock<HashSet<String>>>,
+    /// Workers spawned with `wait: true`. While any are running, the channel
+    /// holds new user messages so the next user turn sees their results.
+    pub awaited_workers: Arc<RwLock<HashSet<WorkerId>>>,
     pub status_block: Arc<RwLock<StatusBlock>>,
     pub deps: AgentDeps,
     pub conversation_logger: ConversationLogger,
@@ -171,6 +183,9 @@ impl ChannelState {
         if let Some(handle) = handle {
             handle.abort();
         }
+        // The aborted run never reaches its own checkpoint cleanup, and a
+        // cancelled worker must not be resumed on the next start.
+        crate::agent::worker::remove_checkpoint(&self.logs_dir, worker_id).await;
 
         // Now that the worker future is cancelled, drain the live transcript
         // cache. persist_transcript() inside the worker's run() method will
@@ -233,11 +248,15 @@ impl ChannelState {
         }
 
         let reason = crate::summarize_first_non_empty_line(reason, crate::EVENT_SUMMARY_MAX_CHARS);
-        let result = if reason.is_empty() {
-            "Worker cancelled.".to_string()
-        } else {
-            format!("Worker cancelled: {reason}")
-        };
+        let result = self
+            .cancellation_notice(&reason, live_steps.as_deref())
+            .unwrap_or_else(|| {
+                if reason.is_empty() {
+                    "Worker cancelled.".to_string()
+                } else {
+                    format!("Worker cancelled: {reason}")
+                }
+            });
 
         self.process_run_logger
             .log_worker_completed(worker_id, &result, false);
@@ -261,6 +280,39 @@ impl ChannelState {
         Ok(())
     }
 
+    /// Render the user-facing cancellation notice from the worker's live
+    /// transcript, or `None` when the notice is disabled or fails to render.
+    fn cancellation_notice(
+        &self,
+        reason: &str,
+        steps: Option<&[crate::conversation::worker_transcript::TranscriptStep]>,
+    ) -> Option<String> {
+        let worker_config = self.deps.runtime_config.worker.load();
+        if !worker_config.cancellation_notice {
+            return None;
+        }
+
+        let history = steps
+            .map(crate::conversation::worker_transcript::transcript_to_history)
+            .unwrap_or_default();
+        let (summary, last_output) =
+            crate::agent::worker::summarize_cancelled_work(&history, &worker_config.recap_tools);
+
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_system_worker_cancelled(reason, summary.as_deref(), last_output.as_deref())
+        {
+            Ok(notice) => Some(notice),
+            Err(error) => {
+                tracing::warn!(%error, "failed to render worker cancellation notice");
+                None
+            }
+        }
+    }
+
     /// Cancel a running branch by aborting its tokio task.
     /// Returns an error message if the branch is not found.
     pub async fn cancel_branch(&self, branch_id: BranchId) -> std::result::Result<(), String> {
@@ -421,6 +473,11 @@ pub struct Channel {
     pub compactor: Compactor,
     /// Count of user messages since last memory persistence branch.
     message_count: usize,
+    /// Count of channel turns since the last identity reminder was injected.
+    turns_since_identity_reminder: usize,
+    /// Whether context usage was above the identity reinforcement threshold
+    /// on the previous turn, so the usage trigger fires once per crossing.
+    identity_usage_above_threshold: bool,
     /// Branch IDs for silent memory persistence branches (results not injected into history).
     memory_persistence_branches: HashSet<BranchId>,
     /// Optional Discord reply target captured when each branch was started.
@@ -440,6 +497,11 @@ pub struct Channel {
     /// Background process results waiting to be embedded in the next retrigger.
     /// Accumulated during the debounce window and drained when the retrigger fires.
     pending_results: Vec<PendingResult>,
+    /// User messages held while awaited workers are running, in arrival order.
+    deferred_messages: Vec<InboundMessage>,
+    /// Deadline after which deferred messages are released even if awaited
+    /// workers are still running.
+    worker_wait_deadline: Option<tokio::time::Instant>,
     /// Optional send_agent_message tool (only when agent has active links).
     send_agent_message_tool: Option<crate::tools::SendAgentMessageTool>,
     /// Backfilled conversation history rendered as a system-prompt fragment.
@@ -453,6 +515,8 @@ pub struct Channel {
     listen_only_session_override: Option<bool>,
     /// Handle exposed to the supervision control plane.
     control_handle: ChannelControlHandle,
+    /// Sticky detection of the language the channel is conversing in.
+    language: LanguageTracker,
 }
 
 /// RAII guard that records `message_handling_duration_seconds` when dropped,
@@ -499,7 +563,8 @@ impl Channel {
             ProcessType::Channel,
             Some(id.clone()),
             deps.event_tx.clone(),
-        );
+        )
+        .with_tool_call_repair(deps.runtime_config.compaction.load().tool_call_repair);
         let status_block = Arc::new(RwLock::new(StatusBlock::new()));
         let history = Arc::new(RwLock::new(Vec::new()));
         let active_branches = Arc::new(RwLock::new(HashMap::new()));
@@ -521,6 +586,7 @@ impl Channel {
             worker_inputs: Arc::new(RwLock::new(HashMap::new())),
             worker_injections: Arc::new(RwLock::new(HashMap::new())),
             reserved_tasks: Arc::new(RwLock::new(HashSet::new())),
+            awaited_workers: Arc::new(RwLock::new(HashSet::new())),
             status_block: status_block.clone(),
             deps: deps.clone(),
             conversation_logger,
@@ -558,6 +624,14 @@ impl Channel {
         let self_tx = message_tx.clone();
         let resolved_listen_only_mode = deps.runtime_config.channel_config.load().listen_only_mode;
         let control_handle = ChannelControlHandle::new(state.clone());
+        let language = LanguageTracker::with_current(
+            deps.runtime_config
+                .settings
+                .load()
+                .as_ref()
+                .as_ref()
+                .and_then(|store| store.channel_language(id.as_ref())),
+        );
         let channel = Self {
             id: id.clone(),
             title: None,
@@ -575,6 +649,8 @@ impl Channel {
             conversation_context: None,
             compactor,
             message_count: 0,
+            turns_since_identity_reminder: 0,
+            identity_usage_above_threshold: false,
             memory_persistence_branches: HashSet::new(),
             branch_reply_targets: HashMap::new(),
             coalesce_buffer: Vec::new(),
@@ -584,11 +660,14 @@ impl Channel {
             pending_retrigger_metadata: HashMap::new(),
             retrigger_deadline: None,
             pending_results: Vec::new(),
+            deferred_messages: Vec::new(),
+            worker_wait_deadline: None,
             send_agent_message_tool,
             backfill_transcript: None,
             listen_only_mode: resolved_listen_only_mode,
             listen_only_session_override: None,
             control_handle,
+            language,
         };
 
         (channel, message_tx)
@@ -659,6 +738,43 @@ impl Channel {
         };
     }
 
+    /// Feed a user message to the language tracker, persisting the channel's
+    /// language whenever it changes.
+    fn observe_language(&mut self, text: &str) {
+        let config = self
+            .deps
+            .runtime_config
+            .channel_config
+            .load()
+            .language_detection
+            .clone();
+        if !config.enabled {
+            return;
+        }
+        let detector = channel_language::detector(&config.detector);
+        let Some(language) = self.language.observe(text, detector.as_ref(), &config) else {
+            return;
+        };
+        tracing::info!(channel_id = %self.id, language, "channel language changed");
+        if let Some(store) = self.deps.runtime_config.settings.load().as_ref().as_ref()
+            && let Err(error) = store.set_channel_language(self.id.as_ref(), &language)
+        {
+            tracing::warn!(%error, channel_id = %self.id, "failed to persist channel language");
+        }
+    }
+
+    /// Whether this channel, or every channel of the agent, is muted. Read
+    /// from the settings store on each message so API changes apply at once.
+    fn is_muted(&self) -> bool {
+        self.deps
+            .runtime_config
+            .settings
+            .load()
+            .as_ref()
+            .as_ref()
+            .is_some_and(|store| store.muted() || store.channel_muted(self.id.as_ref()))
+    }
+
     fn set_listen_only_mode(&mut self, enabled: bool) -> bool {
         let mut persisted = false;
         let settings_store = self
@@ -732,6 +848,13 @@ impl Channel {
             .upsert(&message.conversation_id, &metadata);
     }
 
+    /// Whether the channel's model takes images, so image attachments can be
+    /// passed to it as multimodal content.
+    fn model_supports_vision(&self) -> bool {
+        let routing = self.deps.runtime_config.routing.load();
+        routing.supports_vision(routing.resolve(ProcessType::Channel, None))
+    }
+
     fn suppress_plaintext_fallback(&self) -> bool {
         matches!(self.current_adapter(), Some("email"))
     }
@@ -778,7 +901,48 @@ impl Channel {
         message: &InboundMessage,
         raw_text: &str,
     ) -> (bool, bool, bool) {
-        compute_listen_mode_invocation(message, raw_text)
+        let channel_config = self.deps.runtime_config.channel_config.load();
+        apply_listen_only_triggers(
+            &channel_config.listen_only_triggers,
+            compute_listen_mode_invocation(message, raw_text),
+            raw_text,
+        )
+    }
+
+    /// Record the background results a retrigger carried in history, in
+    /// place of the retrigger bridge message if there is one. Results that
+    /// weren't `replied` to the user are flagged for the next turn.
+    async fn record_retrigger_results(&self, summary: &str, replied: bool) {
+        let record = retrigger_history_record(summary, replied);
+
+        let mut history = self.state.history.write().await;
+        // Replace the synthetic bridge message (if present) with the summary
+        // to avoid consecutive assistant messages in history.
+        let replaced = pop_retrigger_bridge_message(&mut history);
+        tracing::debug!(
+            channel_id = %self.id,
+            replaced_bridge = replaced,
+            replied,
+            "injecting retrigger summary into history"
+        );
+        history.push(rig::message::Message::Assistant {
+            id: None,
+            content: OneOrMany::one(rig::message::AssistantContent::text(record)),
+        });
+    }
+
+    /// Add messages the channel observed without replying to history, so a
+    /// later reply and memory persistence branches can see what was said.
+    async fn record_observed_messages(&mut self, texts: Vec<String>) {
+        let Ok(content) = OneOrMany::many(texts.into_iter().map(UserContent::text)) else {
+            return;
+        };
+        self.state
+            .history
+            .write()
+            .await
+            .push(rig::message::Message::User { content });
+        self.check_compaction().await;
     }
 
     /// Send a routed response paired with the current inbound message.
@@ -788,11 +952,22 @@ impl Channel {
     async fn send_routed(
         &self,
         response: OutboundResponse,
+    ) -> std::result::Result<(), mpsc::error::SendError<RoutedResponse>> {
+        self.send_routed_logged(response, None).await
+    }
+
+    /// [`send_routed`](Self::send_routed) for a response logged to the
+    /// conversation under `message_id`.
+    async fn send_routed_logged(
+        &self,
+        response: OutboundResponse,
+        message_id: Option<String>,
     ) -> std::result::Result<(), mpsc::error::SendError<RoutedResponse>> {
         let routed = match &self.current_inbound {
             Some(target) => RoutedResponse {
                 response,
                 target: target.clone(),
+                message_id,
             },
             None => {
                 tracing::warn!(
@@ -802,6 +977,7 @@ impl Channel {
                 RoutedResponse {
                     response,
                     target: InboundMessage::empty(),
+                    message_id,
                 }
             }
         };
@@ -809,7 +985,14 @@ impl Channel {
     }
 
     async fn send_builtin_text(&mut self, text: String, log_label: &str) {
-        match self.send_routed(OutboundResponse::Text(text.clone())).await {
+        let message_id = uuid::Uuid::new_v4().to_string();
+        match self
+            .send_routed_logged(
+                OutboundResponse::Text(text.clone()),
+                Some(message_id.clone()),
+            )
+            .await
+        {
             Ok(()) => {
                 #[cfg(feature = "metrics")]
                 {
@@ -819,7 +1002,8 @@ impl Channel {
                         .with_label_values(&[&self.deps.agent_id, channel_type])
                         .inc();
                 }
-                self.state.conversation_logger.log_bot_message_with_name(
+                self.state.conversation_logger.log_bot_message_with_id(
+                    &message_id,
                     &self.state.channel_id,
                     &text,
                     Some(self.agent_display_name()),
@@ -943,13 +1127,15 @@ impl Channel {
         let mut last_lag_warning: Option<std::time::Instant> = None;
 
         loop {
-            // Compute next deadline from coalesce and retrigger timers
-            let next_deadline = match (self.coalesce_deadline, self.retrigger_deadline) {
-                (Some(a), Some(b)) => Some(a.min(b)),
-                (Some(a), None) => Some(a),
-                (None, Some(b)) => Some(b),
-                (None, None) => None,
-            };
+            // Compute next deadline from coalesce, retrigger, and worker-wait timers
+            let next_deadline = [
+                self.coalesce_deadline,
+                self.retrigger_deadline,
+                self.worker_wait_deadline,
+            ]
+            .into_iter()
+            .flatten()
+            .min();
             let sleep_duration = next_deadline
                 .map(|deadline| {
                     let now = tokio::time::Instant::now();
@@ -963,6 +1149,12 @@ impl Channel {
 
             tokio::select! {
                 Some(message) = self.message_rx.recv() => {
+                    if message.source != "system"
+                        && !self.state.awaited_workers.read().await.is_empty()
+                    {
+                        self.defer_message(message);
+                        continue;
+                    }
                     let config = self.deps.runtime_config.coalesce.load();
                     if self.should_coalesce(&message, &config) {
                         self.coalesce_buffer.push(message);
@@ -996,6 +1188,7 @@ impl Channel {
                             if let Err(error) = self.handle_event(event).await {
                                 tracing::error!(%error, channel_id = %self.id, "error handling event");
                             }
+                            self.maybe_release_deferred_messages().await;
                         }
                         crate::BroadcastRecvResult::Lagged(skipped) => {
                             #[cfg(feature = "metrics")]
@@ -1036,6 +1229,18 @@ impl Channel {
                     // Check retrigger deadline
                     if self.retrigger_deadline.is_some_and(|d| d <= now) {
                         self.flush_pending_retrigger().await;
+                        self.maybe_release_deferred_messages().await;
+                    }
+                    // Stop holding user messages for workers that overran the wait timeout
+                    if self.worker_wait_deadline.is_some_and(|d| d <= now) {
+                        let abandoned = std::mem::take(&mut *self.state.awaited_workers.write().await);
+                        tracing::warn!(
+                            channel_id = %self.id,
+                            workers = abandoned.len(),
+                            deferred = self.deferred_messages.len(),
+                            "worker wait timed out, releasing deferred messages"
+                        );
+                        self.release_deferred_messages();
                     }
                 }
                 else => break,
@@ -1051,6 +1256,66 @@ impl Channel {
         Ok(())
     }
 
+    /// Hold a user message until every awaited worker has reported back.
+    fn defer_message(&mut self, message: InboundMessage) {
+        if self.worker_wait_deadline.is_none() {
+            let timeout_secs = self
+                .deps
+                .runtime_config
+                .channel_config
+                .load()
+                .worker_wait_timeout_secs;
+            self.worker_wait_deadline =
+                Some(tokio::time::Instant::now() + std::time::Duration::from_secs(timeout_secs));
+        }
+        self.deferred_messages.push(message);
+        tracing::info!(
+            channel_id = %self.id,
+            deferred = self.deferred_messages.len(),
+            "deferring message until awaited workers complete"
+        );
+    }
+
+    /// Release deferred messages once no awaited worker is running and no
+    /// retrigger is still debouncing.
+    ///
+    /// Waiting for the retrigger to be queued first keeps ordering intact: the
+    /// worker's result turn runs before the user messages that arrived while
+    /// it was working.
+    async fn maybe_release_deferred_messages(&mut self) {
+        if self.deferred_messages.is_empty()
+            || self.pending_retrigger
+            || !self.state.awaited_workers.read().await.is_empty()
+        {
+            return;
+        }
+        self.release_deferred_messages();
+    }
+
+    /// Re-queue all deferred messages behind anything already in the channel
+    /// queue, preserving arrival order.
+    fn release_deferred_messages(&mut self) {
+        self.worker_wait_deadline = None;
+        let deferred = std::mem::take(&mut self.deferred_messages);
+        if deferred.is_empty() {
+            return;
+        }
+
+        tracing::info!(
+            channel_id = %self.id,
+            count = deferred.len(),
+            "releasing deferred messages"
+        );
+        let self_tx = self.self_tx.clone();
+        tokio::spawn(async move {
+            for message in deferred {
+                if self_tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
     /// Determine if a message should be coalesced (batched with other messages).
     ///
     /// Returns false for:
@@ -1295,6 +1560,8 @@ impl Channel {
                     }
                 };
 
+                self.observe_language(&raw_text);
+
                 if self.listen_only_mode {
                     let (invoked_by_command, invoked_by_mention, invoked_by_reply) =
                         self.compute_listen_mode_invocation(message, &raw_text);
@@ -1364,12 +1631,26 @@ impl Channel {
                     &absolute_timestamp,
                     &relative_text,
                     &raw_text,
+                    self.deps
+                        .runtime_config
+                        .channel_config
+                        .load()
+                        .user_content_escaping,
                 );
 
                 pending_batch_entries.push((formatted_text, attachments, saved_data));
             }
         }
 
+        if self.is_muted() {
+            tracing::debug!(
+                channel_id = %self.id,
+                message_count,
+                "channel muted, ignoring coalesced batch"
+            );
+            return Ok(());
+        }
+
         if self.listen_only_mode && !batch_has_invoke {
             tracing::debug!(
                 channel_id = %self.id,
@@ -1377,11 +1658,17 @@ impl Channel {
                 "listen-first mode: suppressing unsolicited coalesced batch"
             );
             // Keep passive memory capture behavior aligned with single-message flow.
+            let observed = pending_batch_entries
+                .into_iter()
+                .map(|(formatted_text, _, _)| formatted_text)
+                .collect();
+            self.record_observed_messages(observed).await;
             self.message_count += message_count;
             self.check_memory_persistence().await;
             return Ok(());
         }
 
+        let vision = self.model_supports_vision();
         let mut user_contents: Vec<UserContent> = Vec::new();
         for (formatted_text, attachments, saved_data) in pending_batch_entries {
             if !attachments.is_empty() {
@@ -1394,7 +1681,7 @@ impl Channel {
                                 unsaved.push(attachment.clone());
                             } else {
                                 content.push(channel_attachments::content_from_bytes(
-                                    bytes, attachment,
+                                    bytes, attachment, vision,
                                 ));
                             }
                         } else {
@@ -1402,11 +1689,11 @@ impl Channel {
                         }
                     }
                     if !unsaved.is_empty() {
-                        content.extend(download_attachments(&self.deps, &unsaved).await);
+                        content.extend(download_attachments(&self.deps, &unsaved, vision).await);
                     }
                     content
                 } else {
-                    download_attachments(&self.deps, &attachments).await
+                    download_attachments(&self.deps, &attachments, vision).await
                 };
                 for content in attachment_content {
                     user_contents.push(content);
@@ -1470,13 +1757,12 @@ impl Channel {
         self.handle_agent_result(result, &skip_flag, &replied_flag, false)
             .await;
         // Check compaction
-        if let Err(error) = self.compactor.check_and_compact().await {
-            tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
-        }
+        self.check_compaction().await;
 
         // Increment message counter for memory persistence
         self.message_count += message_count;
         self.check_memory_persistence().await;
+        self.maybe_reinforce_identity().await;
 
         Ok(())
     }
@@ -1497,14 +1783,18 @@ impl Channel {
         let skills_prompt = skills.render_channel_prompt(&prompt_engine)?;
 
         let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_search_enabled = rc.web_search_enabled();
         let opencode_enabled = rc.opencode.load().enabled;
         let sandbox_enabled = self.deps.sandbox.containment_active();
         let mcp_tool_names = self.deps.mcp_manager.get_tool_names().await;
         let worker_capabilities = prompt_engine.render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            rc.worker.load().http_request.enabled,
             opencode_enabled,
+            rc.worker.load().memory_recall,
+            rc.worker.load().tool_gating,
+            rc.worker.load().tool_permissions,
             &mcp_tool_names,
         )?;
 
@@ -1534,7 +1824,7 @@ impl Channel {
 
         let project_context = self.build_project_context(&prompt_engine).await;
 
-        prompt_engine.render_channel_prompt_with_links(
+        let prompt = prompt_engine.render_channel_prompt_with_links(
             empty_to_none(identity_context),
             empty_to_none(memory_bulletin.to_string()),
             empty_to_none(skills_prompt),
@@ -1548,7 +1838,9 @@ impl Channel {
             adapter_prompt,
             project_context,
             self.backfill_transcript.clone(),
-        )
+        )?;
+        let prompt = self.append_user_content_note(prompt, &prompt_engine);
+        Ok(self.append_language_note(prompt, &prompt_engine))
     }
 
     /// Handle an incoming message by running the channel's LLM agent loop.
@@ -1648,6 +1940,30 @@ impl Channel {
             .map(|data| data.iter().map(|(meta, _)| meta.clone()).collect());
 
         self.persist_inbound_user_message(&message, &raw_text, saved_metas.as_deref());
+        if message.source != "system" {
+            self.observe_language(&raw_text);
+        }
+
+        // A muted channel keeps its history but doesn't act: no built-in
+        // commands, no turn, no workers, no reply.
+        match muted_handling(self.is_muted(), &message) {
+            MutedHandling::Handle => {}
+            MutedHandling::Ignore => {
+                tracing::debug!(channel_id = %self.id, message_id = %message.id, "channel muted, ignoring message");
+                return Ok(());
+            }
+            MutedHandling::KeepResults => {
+                tracing::info!(
+                    channel_id = %self.id,
+                    "channel muted, keeping background results for the next turn"
+                );
+                // Injected notices like delegated task results carry their
+                // text without a summary.
+                let summary = retrigger_summary(&message).unwrap_or(&raw_text);
+                self.record_retrigger_results(summary, false).await;
+                return Ok(());
+            }
+        }
 
         // Deterministic built-in command: bypass model output drift for agent identity checks.
         if message.source != "system" && raw_text.trim() == "/agent-id" {
@@ -1659,7 +1975,7 @@ impl Channel {
         // Deterministic liveness ping for Telegram mentions.
         // This avoids model/provider flakiness for simple "you there?" style checks.
         if message.source == "telegram" {
-            let (_, has_mention, _) = self.compute_listen_mode_invocation(&message, &raw_text);
+            let (_, has_mention, _) = compute_listen_mode_invocation(&message, &raw_text);
             if has_mention && looks_like_liveness_ping(&raw_text) {
                 self.send_builtin_text("yeah i'm here".to_string(), "telegram-ping")
                     .await;
@@ -1709,7 +2025,16 @@ impl Channel {
 
         let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
         let message_timestamp = temporal_context.format_timestamp(message.timestamp);
-        let user_text = format_user_message(&rewritten_text, &message, &message_timestamp);
+        let user_text = format_user_message(
+            &rewritten_text,
+            &message,
+            &message_timestamp,
+            self.deps
+                .runtime_config
+                .channel_config
+                .load()
+                .user_content_escaping,
+        );
 
         let mut invoked_by_command = false;
         let mut invoked_by_mention = false;
@@ -1728,8 +2053,10 @@ impl Channel {
                     "listen-first mode: suppressing unsolicited reply"
                 );
                 // In quiet/listen-first mode we still want passive memory capture.
-                // Count suppressed user messages so auto memory persistence branches
-                // continue to run on interval without requiring explicit invokes.
+                // Record the message in history and count it so auto memory
+                // persistence branches see it and keep running on interval
+                // without requiring explicit invokes.
+                self.record_observed_messages(vec![user_text]).await;
                 self.message_count += 1;
                 self.check_memory_persistence().await;
                 return Ok(());
@@ -1745,6 +2072,7 @@ impl Channel {
 
         let is_retrigger = message.source == "system";
         let attachment_content = if !attachments.is_empty() {
+            let vision = self.model_supports_vision();
             if let Some(ref saved_data) = saved_attachment_data {
                 // Reuse already-downloaded bytes for images/text; audio still
                 // needs transcription via the normal path so we fall through.
@@ -1757,8 +2085,9 @@ impl Channel {
                         if attachment.mime_type.starts_with("audio/") {
                             unsaved_attachments.push(attachment.clone());
                         } else {
-                            content
-                                .push(channel_attachments::content_from_bytes(bytes, attachment));
+                            content.push(channel_attachments::content_from_bytes(
+                                bytes, attachment, vision,
+                            ));
                         }
                     } else {
                         unsaved_attachments.push(attachment.clone());
@@ -1767,12 +2096,13 @@ impl Channel {
 
                 // Process any attachments that weren't saved (or need transcription)
                 if !unsaved_attachments.is_empty() {
-                    let extra = download_attachments(&self.deps, &unsaved_attachments).await;
+                    let extra =
+                        download_attachments(&self.deps, &unsaved_attachments, vision).await;
                     content.extend(extra);
                 }
                 content
             } else {
-                download_attachments(&self.deps, &attachments).await
+                download_attachments(&self.deps, &attachments, vision).await
             }
         } else {
             Vec::new()
@@ -1836,41 +2166,14 @@ impl Channel {
                     "skipping retrigger summary injection; relay reply already preserved"
                 );
             } else {
-                // Extract the result summaries from the metadata we attached in
-                // flush_pending_retrigger, so we record only the substance (not
-                // the retrigger instructions/template scaffolding).
-                let summary = message
-                    .metadata
-                    .get("retrigger_result_summary")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("[background work completed]");
-
-                let record = if replied {
-                    summary.to_string()
-                } else {
+                if !replied {
                     tracing::warn!(
                         channel_id = %self.id,
                         "retrigger relay failed, preserving result in history for next turn"
                     );
-                    format!(
-                        "[background work completed but relay to user failed — include this in your next response]\n{summary}"
-                    )
-                };
-
-                let mut history = self.state.history.write().await;
-                // Replace the synthetic bridge message (if present) with the summary
-                // to avoid consecutive assistant messages in history.
-                let replaced = pop_retrigger_bridge_message(&mut history);
-                tracing::debug!(
-                    channel_id = %self.id,
-                    replaced_bridge = replaced,
-                    replied,
-                    "injecting retrigger summary into history"
-                );
-                history.push(rig::message::Message::Assistant {
-                    id: None,
-                    content: OneOrMany::one(rig::message::AssistantContent::text(record)),
-                });
+                }
+                let summary = retrigger_summary(&message).unwrap_or("[background work completed]");
+                self.record_retrigger_results(summary, replied).await;
             }
 
             // Mark the completed items as relayed in the status block so their
@@ -1893,15 +2196,14 @@ impl Channel {
         }
 
         // Check context size and trigger compaction if needed
-        if let Err(error) = self.compactor.check_and_compact().await {
-            tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
-        }
+        self.check_compaction().await;
 
         // Increment message counter and spawn memory persistence branch if threshold reached
         if !is_retrigger {
             self.retrigger_count = 0;
             self.message_count += 1;
             self.check_memory_persistence().await;
+            self.maybe_reinforce_identity().await;
         }
 
         Ok(())
@@ -1942,6 +2244,64 @@ impl Channel {
         prompt_engine.render_available_channels(entries).ok()
     }
 
+    /// Tell the model which language to reply in when the detected language
+    /// differs from the one the prompts are written in.
+    fn append_language_note(
+        &self,
+        mut prompt: String,
+        prompt_engine: &crate::prompts::PromptEngine,
+    ) -> String {
+        let channel_config = self.deps.runtime_config.channel_config.load();
+        let config = &channel_config.language_detection;
+        if !config.enabled {
+            return prompt;
+        }
+        let language = self.language.current(config);
+        if language == prompt_engine.language() {
+            return prompt;
+        }
+        match prompt_engine
+            .render_channel_language(language, channel_language::language_name(language))
+        {
+            Ok(note) => {
+                prompt.push_str("\n\n");
+                prompt.push_str(&note);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render channel language note");
+            }
+        }
+        prompt
+    }
+
+    /// Explain the `<user_message>` fence when user content is fenced, so the
+    /// model reads fenced text as user data rather than instructions.
+    fn append_user_content_note(
+        &self,
+        mut prompt: String,
+        prompt_engine: &crate::prompts::PromptEngine,
+    ) -> String {
+        let escaping = self
+            .deps
+            .runtime_config
+            .channel_config
+            .load()
+            .user_content_escaping;
+        if escaping != UserContentEscaping::Fence {
+            return prompt;
+        }
+        match prompt_engine.render_user_content_fence() {
+            Ok(note) => {
+                prompt.push_str("\n\n");
+                prompt.push_str(&note);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render user content fence note");
+            }
+        }
+        prompt
+    }
+
     /// Build org context showing the agent's position in the communication hierarchy.
     fn build_org_context(&self, prompt_engine: &crate::prompts::PromptEngine) -> Option<String> {
         let agent_id = self.deps.agent_id.as_ref();
@@ -2152,14 +2512,18 @@ impl Channel {
         let skills_prompt = skills.render_channel_prompt(&prompt_engine)?;
 
         let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_search_enabled = rc.web_search_enabled();
         let opencode_enabled = rc.opencode.load().enabled;
         let sandbox_enabled = self.deps.sandbox.containment_active();
         let mcp_tool_names = self.deps.mcp_manager.get_tool_names().await;
         let worker_capabilities = prompt_engine.render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            rc.worker.load().http_request.enabled,
             opencode_enabled,
+            rc.worker.load().memory_recall,
+            rc.worker.load().tool_gating,
+            rc.worker.load().tool_permissions,
             &mcp_tool_names,
         )?;
 
@@ -2183,7 +2547,7 @@ impl Channel {
 
         let empty_to_none = |s: String| if s.is_empty() { None } else { Some(s) };
 
-        prompt_engine.render_channel_prompt_with_links(
+        let prompt = prompt_engine.render_channel_prompt_with_links(
             empty_to_none(identity_context),
             empty_to_none(memory_bulletin.to_string()),
             empty_to_none(skills_prompt),
@@ -2197,12 +2561,16 @@ impl Channel {
             adapter_prompt,
             project_context,
             self.backfill_transcript.clone(),
-        )
+        )?;
+        let prompt = self.append_user_content_note(prompt, &prompt_engine);
+        Ok(self.append_language_note(prompt, &prompt_engine))
     }
 
     /// Register per-turn tools, run the LLM agentic loop, and clean up.
     ///
     /// Returns the prompt result and per-turn flags for the caller to dispatch.
+    /// The typing indicator shown while the model works is cleared by
+    /// `handle_agent_result`, or here when the turn fails before it gets there.
     #[allow(clippy::type_complexity)]
     #[tracing::instrument(skip(self, user_text, system_prompt, attachment_content), fields(channel_id = %self.id, agent_id = %self.deps.agent_id))]
     async fn run_agent_turn(
@@ -2218,6 +2586,39 @@ impl Channel {
         crate::tools::SkipFlag,
         crate::tools::RepliedFlag,
         bool,
+    )> {
+        let turn = self
+            .run_agent_turn_inner(
+                user_text,
+                system_prompt,
+                conversation_id,
+                attachment_content,
+                is_retrigger,
+                adapter,
+            )
+            .await;
+        if turn.is_err() {
+            self.send_routed(OutboundResponse::Status(crate::StatusUpdate::StopTyping))
+                .await
+                .ok();
+        }
+        turn
+    }
+
+    #[allow(clippy::type_complexity)]
+    async fn run_agent_turn_inner(
+        &self,
+        user_text: &str,
+        system_prompt: &str,
+        conversation_id: &str,
+        attachment_content: Vec<UserContent>,
+        is_retrigger: bool,
+        adapter: Option<&str>,
+    ) -> Result<(
+        std::result::Result<String, rig::completion::PromptError>,
+        crate::tools::SkipFlag,
+        crate::tools::RepliedFlag,
+        bool,
     )> {
         let skip_flag = crate::tools::new_skip_flag();
         let replied_flag = crate::tools::new_replied_flag();
@@ -2275,11 +2676,15 @@ impl Channel {
             .with_context(&*self.deps.agent_id, "channel")
             .with_routing((**routing).clone());
 
-        let agent = AgentBuilder::new(model)
+        let mut builder = AgentBuilder::new(model)
             .preamble(system_prompt)
             .default_max_turns(max_turns)
-            .tool_server_handle(self.tool_server.clone())
-            .build();
+            .tool_server_handle(self.tool_server.clone());
+        let max_output_tokens = rc.output_limits.load().channel.max_output_tokens;
+        if max_output_tokens > 0 {
+            builder = builder.max_tokens(max_output_tokens);
+        }
+        let agent = builder.build();
 
         self.send_routed(OutboundResponse::Status(crate::StatusUpdate::Thinking))
             .await
@@ -2330,7 +2735,29 @@ impl Channel {
         // ── Prompt snapshot capture (fire-and-forget) ──
         self.maybe_capture_snapshot(system_prompt, user_text, &history);
 
-        let mut result = self.hook.prompt_once(&agent, &mut history, user_text).await;
+        // With on-change refresh, a watcher feeds updated status blocks into
+        // the hook's injection receiver so the model sees background work
+        // that finishes while this turn is still running.
+        let (hook, status_watcher) =
+            if rc.channel_config.load().status_refresh == StatusRefresh::OnChange {
+                let (status_tx, status_rx) = mpsc::channel(MAX_STATUS_REFRESHES_PER_TURN);
+                (
+                    self.hook.clone().with_inject_rx(status_rx),
+                    Some(self.spawn_status_watcher(status_tx)),
+                )
+            } else {
+                (self.hook.clone(), None)
+            };
+
+        // A retrigger's text comes from the runtime, not the user.
+        let prompt = if is_retrigger {
+            crate::llm::marker::message(user_text)
+        } else {
+            rig::message::Message::from(user_text)
+        };
+        let mut result = self
+            .prompt_with_status_refresh(&hook, &agent, &mut history, prompt)
+            .await?;
 
         // If the LLM responded with text that looks like tool call syntax, it failed
         // to use the tool calling API. Inject a correction and retry a couple
@@ -2354,11 +2781,24 @@ impl Channel {
             let prompt_engine = self.deps.runtime_config.prompts.load();
             let correction = prompt_engine.render_system_tool_syntax_correction()?;
             result = self
-                .hook
-                .prompt_once(&agent, &mut history, &correction)
-                .await;
+                .prompt_with_status_refresh(
+                    &hook,
+                    &agent,
+                    &mut history,
+                    crate::llm::marker::message(correction),
+                )
+                .await?;
+        }
+
+        if let Some(status_watcher) = status_watcher {
+            status_watcher.abort();
         }
 
+        self.archive_turn_transcript(
+            &result,
+            history.get(history_len_before..).unwrap_or_default(),
+        );
+
         let retrigger_reply_preserved = {
             let mut guard = self.state.history.write().await;
             apply_history_after_turn(
@@ -2380,9 +2820,170 @@ impl Channel {
         Ok((result, skip_flag, replied_flag, retrigger_reply_preserved))
     }
 
+    /// Archive this turn's new messages when the transcript archive covers
+    /// channel turns. Runs in the background.
+    fn archive_turn_transcript(
+        &self,
+        result: &std::result::Result<String, rig::completion::PromptError>,
+        turn_messages: &[rig::message::Message],
+    ) {
+        let Some(archive) = self
+            .deps
+            .runtime_config
+            .worker
+            .load()
+            .transcript_archive
+            .clone()
+            .filter(|archive| archive.channel_turns)
+        else {
+            return;
+        };
+        let outcome = match result {
+            Ok(_) => TranscriptOutcome::Success,
+            Err(error) => TranscriptOutcome::Failure {
+                error: error.to_string(),
+            },
+        };
+        let record = TranscriptRecord::new(
+            TranscriptKind::ChannelTurn,
+            uuid::Uuid::new_v4().to_string(),
+            self.deps.agent_id.to_string(),
+            Some(self.id.to_string()),
+            None,
+            outcome,
+            turn_messages,
+        );
+        crate::conversation::transcript_sink::archive(
+            &archive,
+            self.deps.llm_manager.http_client(),
+            &self.deps.runtime_config.instance_dir,
+            record,
+        );
+    }
+
+    /// Prompt once, then keep re-prompting with each refreshed status block the
+    /// hook cut the turn short for. Without a status watcher attached this is
+    /// a plain `prompt_once`.
+    async fn prompt_with_status_refresh<M>(
+        &self,
+        hook: &SpacebotHook,
+        agent: &rig::agent::Agent<M>,
+        history: &mut Vec<rig::message::Message>,
+        prompt: rig::message::Message,
+    ) -> Result<std::result::Result<String, rig::completion::PromptError>>
+    where
+        M: CompletionModel,
+    {
+        let mut result = hook.prompt_once(agent, history, prompt).await;
+        while let Err(rig::completion::PromptError::PromptCancelled { reason, .. }) = &result
+            && SpacebotHook::is_context_injection_reason(reason)
+        {
+            // Only the latest status matters; earlier ones are already stale.
+            let Some(status) = hook.take_injected_messages().pop() else {
+                tracing::warn!(
+                    channel_id = %self.id,
+                    "status refresh interrupted the turn but no status was buffered"
+                );
+                break;
+            };
+            tracing::debug!(channel_id = %self.id, "injecting refreshed status block");
+            let refresh = self
+                .deps
+                .runtime_config
+                .prompts
+                .load()
+                .render_system_status_refresh(&status)?;
+            result = hook
+                .prompt_once(agent, history, crate::llm::marker::message(refresh))
+                .await;
+        }
+        Ok(result)
+    }
+
+    /// Watch the event bus while a turn is in flight and send a re-rendered
+    /// status block whenever a worker or branch of this channel changes state.
+    ///
+    /// The channel's own event loop is blocked on the turn, so those events
+    /// haven't reached the shared status block yet. Each render replays the
+    /// events seen so far over a fresh snapshot, which also picks up workers
+    /// spawned during the turn.
+    fn spawn_status_watcher(&self, status_tx: mpsc::Sender<String>) -> tokio::task::JoinHandle<()> {
+        let mut event_rx = self.deps.event_tx.subscribe();
+        let status_block = self.state.status_block.clone();
+        let runtime_config = self.deps.runtime_config.clone();
+        let channel_id = self.id.clone();
+
+        tokio::spawn(async move {
+            let mut seen = Vec::new();
+            let mut sent = 0;
+            while sent < MAX_STATUS_REFRESHES_PER_TURN {
+                let event = match event_rx.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !should_process_event_for_channel(&event, &channel_id) {
+                    continue;
+                }
+                let changes_state = matches!(
+                    event,
+                    ProcessEvent::WorkerStatus { .. }
+                        | ProcessEvent::WorkerIdle { .. }
+                        | ProcessEvent::WorkerClarification { .. }
+                        | ProcessEvent::WorkerComplete { .. }
+                        | ProcessEvent::BranchResult { .. }
+                );
+                let affects_status = changes_state
+                    || matches!(
+                        event,
+                        ProcessEvent::ToolCompleted { .. } | ProcessEvent::AgentMessageSent { .. }
+                    );
+                if !affects_status {
+                    continue;
+                }
+                seen.push(event);
+                if !changes_state {
+                    continue;
+                }
+
+                let mut snapshot = status_block.read().await.clone();
+                for event in &seen {
+                    snapshot.update(event);
+                }
+                let current_time_line =
+                    TemporalContext::from_runtime(runtime_config.as_ref()).current_time_line();
+                let rendered = snapshot.render_with_time_context(Some(&current_time_line));
+                if status_tx.send(rendered).await.is_err() {
+                    break;
+                }
+                sent += 1;
+            }
+        })
+    }
+
+    /// Truncate fallback reply text to the channel's output limit. The reply
+    /// tool enforces the limit itself; this covers text the model answered
+    /// with directly.
+    fn truncate_to_output_limit(&self, text: String) -> String {
+        let limit = self.deps.runtime_config.output_limits.load().channel;
+        if !limit.exceeded_by(&text) {
+            return text;
+        }
+        tracing::info!(
+            channel_id = %self.id,
+            length = text.chars().count(),
+            max_chars = limit.max_chars,
+            "truncating fallback reply over output limit"
+        );
+        crate::tools::truncate_at_sentence(&text, limit.max_chars)
+    }
+
     /// Send outbound text and record send metrics.
-    async fn send_outbound_text(&self, text: String, error_context: &str) {
-        match self.send_routed(OutboundResponse::Text(text)).await {
+    async fn send_outbound_text(&self, text: String, message_id: String, error_context: &str) {
+        match self
+            .send_routed_logged(OutboundResponse::Text(text), Some(message_id))
+            .await
+        {
             Ok(()) => {
                 #[cfg(feature = "metrics")]
                 {
@@ -2471,19 +3072,23 @@ impl Channel {
                                 .as_deref()
                                 .and_then(|conversation_id| conversation_id.split(':').next())
                                 .unwrap_or("unknown");
-                            let final_text = crate::tools::reply::normalize_discord_mention_tokens(
-                                extracted.as_deref().unwrap_or(text),
-                                source,
+                            let final_text = self.truncate_to_output_limit(
+                                crate::tools::reply::normalize_discord_mention_tokens(
+                                    extracted.as_deref().unwrap_or(text),
+                                    source,
+                                ),
                             );
                             if !final_text.is_empty() {
                                 if extracted.is_some() {
                                     tracing::warn!(channel_id = %self.id, "extracted reply from malformed tool syntax in retrigger fallback");
                                 }
-                                self.state
+                                let message_id = self
+                                    .state
                                     .conversation_logger
                                     .log_bot_message(&self.state.channel_id, &final_text);
                                 self.send_outbound_text(
                                     final_text,
+                                    message_id,
                                     "failed to send retrigger fallback reply",
                                 )
                                 .await;
@@ -2539,16 +3144,20 @@ impl Channel {
                                 .as_deref()
                                 .and_then(|conversation_id| conversation_id.split(':').next())
                                 .unwrap_or("unknown");
-                            let final_text = crate::tools::reply::normalize_discord_mention_tokens(
-                                extracted.as_deref().unwrap_or(text),
-                                source,
+                            let final_text = self.truncate_to_output_limit(
+                                crate::tools::reply::normalize_discord_mention_tokens(
+                                    extracted.as_deref().unwrap_or(text),
+                                    source,
+                                ),
                             );
                             if !final_text.is_empty() {
-                                self.state
+                                let message_id = self
+                                    .state
                                     .conversation_logger
                                     .log_bot_message(&self.state.channel_id, &final_text);
                                 self.send_outbound_text(
                                     final_text,
+                                    message_id,
                                     "failed to send retrigger fallback reply",
                                 )
                                 .await;
@@ -2590,21 +3199,28 @@ impl Channel {
                             .as_deref()
                             .and_then(|conversation_id| conversation_id.split(':').next())
                             .unwrap_or("unknown");
-                        let final_text = crate::tools::reply::normalize_discord_mention_tokens(
-                            extracted.as_deref().unwrap_or(text),
-                            source,
+                        let final_text = self.truncate_to_output_limit(
+                            crate::tools::reply::normalize_discord_mention_tokens(
+                                extracted.as_deref().unwrap_or(text),
+                                source,
+                            ),
                         );
                         if !final_text.is_empty() {
                             if extracted.is_some() {
                                 tracing::warn!(channel_id = %self.id, "extracted reply from malformed tool syntax in LLM text output");
                             }
-                            self.state.conversation_logger.log_bot_message_with_name(
-                                &self.state.channel_id,
-                                &final_text,
-                                Some(self.agent_display_name()),
-                            );
-                            self.send_outbound_text(final_text, "failed to send fallback reply")
-                                .await;
+                            let message_id =
+                                self.state.conversation_logger.log_bot_message_with_name(
+                                    &self.state.channel_id,
+                                    &final_text,
+                                    Some(self.agent_display_name()),
+                                );
+                            self.send_outbound_text(
+                                final_text,
+                                message_id,
+                                "failed to send fallback reply",
+                            )
+                            .await;
                         }
                     }
 
@@ -2752,6 +3368,7 @@ impl Channel {
                 worker_id,
                 channel_id,
                 task,
+                label,
                 worker_type,
                 interactive,
                 directory,
@@ -2761,6 +3378,7 @@ impl Channel {
                     channel_id.as_ref(),
                     *worker_id,
                     task,
+                    label,
                     worker_type,
                     &self.deps.agent_id,
                     *interactive,
@@ -2775,6 +3393,32 @@ impl Channel {
             ProcessEvent::WorkerIdle { worker_id, .. } => {
                 run_logger.log_worker_idle(*worker_id);
             }
+            ProcessEvent::WorkerClarification {
+                worker_id,
+                question,
+                ..
+            } => {
+                // The worker is blocked on the user, so a waiting channel
+                // must not keep waiting on it.
+                self.state.awaited_workers.write().await.remove(worker_id);
+                run_logger.log_worker_idle(*worker_id);
+                self.pending_results.push(PendingResult {
+                    process_type: "worker",
+                    process_id: worker_id.to_string(),
+                    result: format!(
+                        "The task is paused until the user answers this question:\n\n\
+                         {question}\n\n\
+                         Ask the user exactly this, then pass their answer to worker \
+                         {worker_id} with the `route` tool."
+                    ),
+                    success: true,
+                });
+                should_retrigger = true;
+                tracing::info!(
+                    worker_id = %worker_id,
+                    "worker clarification queued for retrigger"
+                );
+            }
             ProcessEvent::WorkerComplete {
                 worker_id,
                 result,
@@ -2782,6 +3426,8 @@ impl Channel {
                 success,
                 ..
             } => {
+                self.state.awaited_workers.write().await.remove(worker_id);
+
                 // Use worker_handles as the source of truth for active workers.
                 // (active_workers is never populated because Worker is consumed by .run())
                 if self
@@ -2829,6 +3475,8 @@ impl Channel {
                 // Interactive worker completed a task (initial or follow-up)
                 // but stays alive for more input. Deliver the result to the
                 // channel without removing the worker from the active set.
+                // A waiting channel only waits for the first result.
+                self.state.awaited_workers.write().await.remove(worker_id);
                 self.pending_results.push(PendingResult {
                     process_type: "worker",
                     process_id: worker_id.to_string(),
@@ -3068,6 +3716,28 @@ impl Channel {
         status.render_full(&current_time_line, &system_info)
     }
 
+    /// Run compaction if context usage calls for it.
+    ///
+    /// With `before_compaction` persistence enabled, a memory persistence
+    /// branch is spawned first. The branch snapshots history when it starts,
+    /// so it still sees the messages compaction is about to remove, and the
+    /// conversation is not held up while it runs.
+    async fn check_compaction(&mut self) {
+        let config = **self.deps.runtime_config.memory_persistence.load();
+        if config.enabled
+            && config.before_compaction
+            && self.memory_persistence_branches.is_empty()
+            && self.compactor.pending_action().await.is_some()
+        {
+            self.message_count = 0;
+            self.spawn_memory_persistence("pre-compaction").await;
+        }
+
+        if let Err(error) = self.compactor.check_and_compact().await {
+            tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
+        }
+    }
+
     /// Check if a memory persistence branch should be spawned based on message count.
     async fn check_memory_persistence(&mut self) {
         let config = **self.deps.runtime_config.memory_persistence.load();
@@ -3082,13 +3752,17 @@ impl Channel {
         // Reset counter before spawning so subsequent messages don't pile up
         self.message_count = 0;
 
+        self.spawn_memory_persistence("message interval").await;
+    }
+
+    async fn spawn_memory_persistence(&mut self, trigger: &'static str) {
         match spawn_memory_persistence_branch(&self.state, &self.deps).await {
             Ok(branch_id) => {
                 self.memory_persistence_branches.insert(branch_id);
                 tracing::info!(
                     channel_id = %self.id,
                     branch_id = %branch_id,
-                    interval = config.message_interval,
+                    trigger,
                     "memory persistence branch spawned"
                 );
             }
@@ -3102,6 +3776,69 @@ impl Channel {
         }
     }
 
+    /// Append a condensed identity reminder to history when the configured
+    /// cadence is due or context usage has just crossed the threshold.
+    ///
+    /// Keeps the persona anchored in long conversations without re-rendering
+    /// the whole system prompt.
+    async fn maybe_reinforce_identity(&mut self) {
+        let config = **self.deps.runtime_config.identity_reinforcement.load();
+        if !config.enabled {
+            return;
+        }
+
+        self.turns_since_identity_reminder += 1;
+
+        let context_window = (**self.deps.runtime_config.context_window.load()).max(1);
+        let usage = {
+            let history = self.state.history.read().await;
+            crate::agent::compactor::estimate_history_tokens(&history) as f32
+                / context_window as f32
+        };
+        let above_threshold = config.usage_threshold > 0.0 && usage >= config.usage_threshold;
+        let threshold_crossed = above_threshold && !self.identity_usage_above_threshold;
+        self.identity_usage_above_threshold = above_threshold;
+
+        let interval_due =
+            config.turn_interval > 0 && self.turns_since_identity_reminder >= config.turn_interval;
+        if !interval_due && !threshold_crossed {
+            return;
+        }
+
+        let identity_context = self.deps.runtime_config.identity.load().render();
+        if identity_context.trim().is_empty() {
+            return;
+        }
+        let condensed = crate::tools::truncate_utf8_ellipsis(
+            identity_context.trim(),
+            IDENTITY_REMINDER_MAX_BYTES,
+        );
+
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        let reminder = match prompt_engine.render_system_identity_reminder(&condensed) {
+            Ok(reminder) => reminder,
+            Err(error) => {
+                tracing::warn!(channel_id = %self.id, %error, "failed to render identity reminder");
+                return;
+            }
+        };
+
+        self.state
+            .history
+            .write()
+            .await
+            .push(crate::llm::marker::message(reminder));
+        self.turns_since_identity_reminder = 0;
+
+        tracing::debug!(
+            channel_id = %self.id,
+            usage = %format!("{:.1}%", usage * 100.0),
+            interval_due,
+            threshold_crossed,
+            "identity reminder injected"
+        );
+    }
+
     /// If prompt capture is enabled for this channel, snapshot the current
     /// system prompt sections and conversation history. The save is
     /// fire-and-forget so it never blocks the agentic loop.
@@ -3255,6 +3992,40 @@ fn compute_listen_mode_invocation(message: &InboundMessage, raw_text: &str) -> (
     (invoked_by_command, invoked_by_mention, invoked_by_reply)
 }
 
+/// Narrow raw invocation flags to the configured listen-only triggers. A
+/// keyword match counts as a mention, since it addresses the bot by name.
+fn apply_listen_only_triggers(
+    triggers: &crate::config::ListenOnlyTriggers,
+    (invoked_by_command, invoked_by_mention, invoked_by_reply): (bool, bool, bool),
+    raw_text: &str,
+) -> (bool, bool, bool) {
+    let text_lower = raw_text.to_lowercase();
+    let invoked_by_keyword = triggers
+        .keywords
+        .iter()
+        .any(|keyword| contains_word(&text_lower, keyword));
+    (
+        triggers.command && invoked_by_command,
+        (triggers.mention && invoked_by_mention) || invoked_by_keyword,
+        triggers.reply && invoked_by_reply,
+    )
+}
+
+/// Whether `needle` appears in `haystack` with no word character on either
+/// side. Both are expected to be lowercase already.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    let is_word = |character: char| character.is_alphanumeric() || character == '_';
+    !needle.is_empty()
+        && haystack.match_indices(needle).any(|(start, _)| {
+            let end = start + needle.len();
+            haystack[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !is_word(c))
+                && haystack[end..].chars().next().is_none_or(|c| !is_word(c))
+        })
+}
+
 fn looks_like_liveness_ping(text: &str) -> bool {
     let text = text.trim().to_lowercase();
     text.contains("you here")
@@ -3279,6 +4050,48 @@ fn should_send_discord_quiet_mode_ping_ack(
     (invoked_by_mention || invoked_by_reply) && looks_like_liveness_ping(raw_text)
 }
 
+/// How a channel treats an inbound message given its mute state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MutedHandling {
+    /// Not muted: run the turn as usual.
+    Handle,
+    /// A muted channel drops the message without a turn or reply.
+    Ignore,
+    /// A retrigger on a muted channel: nothing is relayed, but its results
+    /// are kept in history so the first turn after unmuting can report them.
+    KeepResults,
+}
+
+fn muted_handling(muted: bool, message: &InboundMessage) -> MutedHandling {
+    match (muted, message.source.as_str()) {
+        (false, _) => MutedHandling::Handle,
+        (true, "system") => MutedHandling::KeepResults,
+        (true, _) => MutedHandling::Ignore,
+    }
+}
+
+/// The result summaries `flush_pending_retrigger` attached to a retrigger,
+/// so history records the substance rather than the retrigger instructions
+/// and template scaffolding.
+fn retrigger_summary(message: &InboundMessage) -> Option<&str> {
+    message
+        .metadata
+        .get("retrigger_result_summary")
+        .and_then(|v| v.as_str())
+}
+
+/// The history entry recording a retrigger's results, flagged for the next
+/// turn when they weren't `replied` to the user.
+fn retrigger_history_record(summary: &str, replied: bool) -> String {
+    if replied {
+        summary.to_string()
+    } else {
+        format!(
+            "[background work completed but relay to user failed — include this in your next response]\n{summary}"
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct QuietModeFallbackState {
     listen_only_mode: bool,
@@ -3309,9 +4122,10 @@ fn should_send_quiet_mode_fallback(
 #[cfg(test)]
 mod tests {
     use super::{
-        QuietModeFallbackState, compute_listen_mode_invocation, recv_channel_event,
-        should_process_event_for_channel, should_send_discord_quiet_mode_ping_ack,
-        should_send_quiet_mode_fallback,
+        MutedHandling, QuietModeFallbackState, apply_listen_only_triggers,
+        compute_listen_mode_invocation, muted_handling, recv_channel_event,
+        retrigger_history_record, retrigger_summary, should_process_event_for_channel,
+        should_send_discord_quiet_mode_ping_ack, should_send_quiet_mode_fallback,
     };
     use crate::memory::MemoryType;
     use crate::{AgentId, ChannelId, InboundMessage, MessageContent, ProcessEvent, ProcessId};
@@ -3342,6 +4156,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn muted_channels_skip_the_turn_but_keep_background_results() {
+        let user = inbound_message("discord", &[], "can you deploy this?");
+        assert_eq!(muted_handling(false, &user), MutedHandling::Handle);
+        // No turn runs, so nothing replies and no worker is spawned.
+        assert_eq!(muted_handling(true, &user), MutedHandling::Ignore);
+
+        let retrigger = inbound_message(
+            "system",
+            &[(
+                "retrigger_result_summary",
+                serde_json::json!("[worker abc completed]: deployed"),
+            )],
+            "relay these results",
+        );
+        assert_eq!(muted_handling(false, &retrigger), MutedHandling::Handle);
+        assert_eq!(muted_handling(true, &retrigger), MutedHandling::KeepResults);
+
+        let summary = retrigger_summary(&retrigger).unwrap();
+        let record = retrigger_history_record(summary, false);
+        assert!(record.contains("include this in your next response"));
+        assert!(record.ends_with("[worker abc completed]: deployed"));
+        assert_eq!(retrigger_history_record(summary, true), summary);
+        assert_eq!(retrigger_summary(&user), None);
+    }
+
     #[tokio::test]
     async fn channel_event_loop_continues_after_lagged_broadcast() {
         let (event_tx, mut event_rx) = tokio::sync::broadcast::channel::<ProcessEvent>(2);
@@ -3501,6 +4341,36 @@ mod tests {
         assert!(!invoked_by_reply);
     }
 
+    #[test]
+    fn listen_only_triggers_narrow_invocation_and_match_keywords() {
+        let triggers = crate::config::ListenOnlyTriggers {
+            command: false,
+            keywords: vec!["sprocket".into(), "ops bot".into()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            apply_listen_only_triggers(&triggers, (true, false, true), "/deploy"),
+            (false, false, true)
+        );
+        assert_eq!(
+            apply_listen_only_triggers(
+                &triggers,
+                (false, false, false),
+                "hey Sprocket, any alerts?"
+            ),
+            (false, true, false)
+        );
+        assert_eq!(
+            apply_listen_only_triggers(&triggers, (false, false, false), "ask the Ops Bot"),
+            (false, true, false)
+        );
+        assert_eq!(
+            apply_listen_only_triggers(&triggers, (false, false, false), "sprockets are cheap"),
+            (false, false, false)
+        );
+    }
+
     #[test]
     fn discord_quiet_mode_ping_ack_requires_directed_ping() {
         let directed_message = inbound_message(
@@ -28,21 +28,30 @@ const TEXT_MIME_PREFIXES: &[&str] = &[
     "application/yaml",
 ];
 
+/// Whether `attachment` goes to the model as an image: a supported image
+/// type, and a model that can see it (`vision`). Images for text-only models
+/// fall back to a metadata-only description like any other binary file.
+fn is_vision_image(attachment: &crate::Attachment, vision: bool) -> bool {
+    vision
+        && IMAGE_MIME_PREFIXES
+            .iter()
+            .any(|p| attachment.mime_type.starts_with(p))
+}
+
 /// Download attachments and convert them to LLM-ready UserContent parts.
 ///
-/// Images become `UserContent::Image` (base64). Text files get inlined.
-/// Other file types get a metadata-only description.
+/// Images become `UserContent::Image` (base64) when `vision` is set. Text
+/// files get inlined. Other file types get a metadata-only description.
 pub(crate) async fn download_attachments(
     deps: &AgentDeps,
     attachments: &[crate::Attachment],
+    vision: bool,
 ) -> Vec<UserContent> {
     let http = deps.llm_manager.http_client();
     let mut parts = Vec::new();
 
     for attachment in attachments {
-        let is_image = IMAGE_MIME_PREFIXES
-            .iter()
-            .any(|p| attachment.mime_type.starts_with(p));
+        let is_image = is_vision_image(attachment, vision);
         let is_text = TEXT_MIME_PREFIXES
             .iter()
             .any(|p| attachment.mime_type.starts_with(p));
@@ -455,10 +464,12 @@ pub(crate) type SavedAttachmentWithBytes = (SavedAttachmentMeta, Vec<u8>);
 /// which needs `AgentDeps`. When saving is enabled, audio files are saved to
 /// disk and transcribed via the normal `download_attachments` path (which will
 /// be called separately).
-pub(crate) fn content_from_bytes(bytes: &[u8], attachment: &crate::Attachment) -> UserContent {
-    let is_image = IMAGE_MIME_PREFIXES
-        .iter()
-        .any(|p| attachment.mime_type.starts_with(p));
+pub(crate) fn content_from_bytes(
+    bytes: &[u8],
+    attachment: &crate::Attachment,
+    vision: bool,
+) -> UserContent {
+    let is_image = is_vision_image(attachment, vision);
     let is_text = TEXT_MIME_PREFIXES
         .iter()
         .any(|p| attachment.mime_type.starts_with(p));
@@ -7,9 +7,11 @@
 use crate::agent::branch::{Branch, BranchExecutionConfig};
 use crate::agent::channel::ChannelState;
 use crate::agent::channel_prompt::TemporalContext;
-use crate::agent::worker::Worker;
+use crate::agent::worker::{Worker, fit_task_to_budget};
 use crate::error::{AgentError, Error as SpacebotError};
-use crate::tools::{BranchToolProfile, MemoryPersistenceContractState};
+use crate::tools::{
+    BranchToolProfile, MemoryPersistenceContractState, ToolPermissions, WorkerToolset,
+};
 use crate::{AgentDeps, BranchId, ChannelId, ProcessEvent, WorkerId};
 use futures::FutureExt as _;
 use std::sync::Arc;
@@ -32,6 +34,24 @@ pub(crate) fn reserve_worker_slot_local(
     Ok(())
 }
 
+/// Refuse a spawn from a process already at the maximum spawn depth.
+///
+/// `current_depth` is the depth of the spawning process, so the new worker
+/// would land at `current_depth + 1`.
+pub(crate) fn check_spawn_depth(
+    current_depth: usize,
+    max_depth: usize,
+) -> std::result::Result<(), AgentError> {
+    if current_depth >= max_depth {
+        return Err(AgentError::SpawnDepthExceeded {
+            depth: current_depth,
+            max: max_depth,
+        });
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum WorkerCompletionKind {
     Success,
@@ -424,18 +444,47 @@ async fn release_task_reservation(state: &ChannelState, task: &str) {
 }
 
 /// Spawn a worker from a ChannelState. Used by the SpawnWorkerTool.
+/// `read_only` caps the worker at [`ToolPermissions::READ_ONLY`].
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_worker_from_state(
     state: &ChannelState,
     task: impl Into<String>,
     interactive: bool,
     suggested_skills: &[&str],
+    memory_recall: bool,
+    tool_hints: Option<&[String]>,
+    direct: bool,
+    read_only: bool,
+    label: Option<&str>,
+    model: Option<&str>,
 ) -> std::result::Result<WorkerId, AgentError> {
+    check_spawn_depth(
+        state.deps.spawn_depth,
+        state.deps.runtime_config.worker.load().max_spawn_depth,
+    )?;
     check_worker_limit(state).await?;
-    let task = task.into();
+    let task = fit_task_to_budget(
+        task.into(),
+        &state.deps.runtime_config.worker.load(),
+        &state.deps.runtime_config.saved_dir(),
+        &state.deps.runtime_config.prompts.load(),
+    )?;
     reserve_task_if_unique(state, &task).await?;
     ensure_dispatch_readiness(state, "worker");
 
-    let result = spawn_worker_inner(state, &task, interactive, suggested_skills).await;
+    let result = spawn_worker_inner(
+        state,
+        &task,
+        interactive,
+        suggested_skills,
+        memory_recall,
+        tool_hints,
+        direct,
+        read_only,
+        label,
+        model,
+    )
+    .await;
 
     // Release the reservation regardless of success or failure.
     // On success the task is now in the status block; on failure it needs cleanup.
@@ -446,14 +495,25 @@ pub async fn spawn_worker_from_state(
 
 /// Inner implementation of worker spawning, separated so the caller can
 /// handle task reservation cleanup in a single place.
+#[allow(clippy::too_many_arguments)]
 async fn spawn_worker_inner(
     state: &ChannelState,
     task: &str,
     interactive: bool,
     suggested_skills: &[&str],
+    memory_recall: bool,
+    tool_hints: Option<&[String]>,
+    direct: bool,
+    read_only: bool,
+    label: Option<&str>,
+    model: Option<&str>,
 ) -> std::result::Result<WorkerId, AgentError> {
     let rc = &state.deps.runtime_config;
     let prompt_engine = rc.prompts.load();
+    let memory_recall = memory_recall && rc.worker.load().memory_recall;
+    // Memory recall is a tool, so a worker that needs it can't skip the
+    // tool server.
+    let direct = direct && !memory_recall;
 
     let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
 
@@ -483,33 +543,30 @@ async fn spawn_worker_inner(
         )
         .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
     let skills = rc.skills.load();
-    let brave_search_key = (**rc.brave_search_key.load()).clone();
+    let search_backend = rc.search_backend();
 
     // Append skills listing to worker system prompt. Suggested skills are
     // flagged so the worker knows the channel's intent, but it can read any
     // skill it decides is relevant via the read_skill tool.
-    let system_prompt = match skills.render_worker_skills(suggested_skills, &prompt_engine) {
-        Ok(skills_prompt) if !skills_prompt.is_empty() => {
-            format!("{worker_system_prompt}\n\n{skills_prompt}")
-        }
-        Ok(_) => worker_system_prompt,
-        Err(error) => {
-            tracing::warn!(%error, "failed to render worker skills listing, spawning without skills context");
-            worker_system_prompt
-        }
-    };
+    let system_prompt = crate::prompts::assembly::append_worker_skills(
+        worker_system_prompt,
+        &skills,
+        suggested_skills,
+        &prompt_engine,
+    );
 
     let worker = if interactive {
         let (worker, input_tx, inject_tx) = Worker::new_interactive(
             Some(state.channel_id.clone()),
             task,
             &system_prompt,
-            state.deps.clone(),
+            state.deps.for_spawned_worker(),
             browser_config.clone(),
             state.screenshot_dir.clone(),
-            brave_search_key.clone(),
+            search_backend.clone(),
             state.logs_dir.clone(),
         );
+        let worker = with_requested_model(worker, model)?;
         let worker_id = worker.id;
         state
             .worker_inputs
@@ -527,25 +584,68 @@ async fn spawn_worker_inner(
             Some(state.channel_id.clone()),
             task,
             &system_prompt,
-            state.deps.clone(),
+            state.deps.for_spawned_worker(),
             browser_config,
             state.screenshot_dir.clone(),
-            brave_search_key,
+            search_backend,
             state.logs_dir.clone(),
         );
+        let worker = with_requested_model(worker, model)?;
         state
             .worker_injections
             .write()
             .await
             .insert(worker.id, inject_tx);
+        // Fire-and-forget workers have no follow-up loop, so the input slot
+        // is free to carry clarification answers. Direct-answer workers have
+        // no tools to ask with.
+        if rc.worker.load().allow_clarifications && !direct {
+            let (worker, answer_tx) = worker.with_clarifications();
+            state
+                .worker_inputs
+                .write()
+                .await
+                .insert(worker.id, answer_tx);
+            worker
+        } else {
+            worker
+        }
+    };
+    let worker = if memory_recall {
+        worker.with_memory_recall()
+    } else {
+        worker
+    };
+    let worker = if read_only {
+        worker.with_permissions(ToolPermissions::READ_ONLY)
+    } else {
         worker
     };
+    let worker = if direct {
+        worker.with_direct_answer()
+    } else {
+        let toolset = WorkerToolset::resolve(task, tool_hints, rc.worker.load().tool_gating);
+        if toolset != WorkerToolset::ALL {
+            tracing::debug!(
+                worker_id = %worker.id,
+                tools = ?toolset.names(),
+                "worker optional tools narrowed"
+            );
+        }
+        worker.with_toolset(toolset)
+    };
+    let worker = match label {
+        Some(label) => worker.with_label(label),
+        None => worker,
+    };
 
     let worker_id = worker.id;
+    let label = worker.label.clone();
 
     let worker_span = tracing::info_span!(
         "worker.run",
         worker_id = %worker_id,
+        worker_label = %label,
         channel_id = %state.channel_id,
     );
     let secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
@@ -563,7 +663,7 @@ async fn spawn_worker_inner(
 
     {
         let mut status = state.status_block.write().await;
-        status.add_worker(worker_id, task, false, interactive);
+        status.add_worker(worker_id, &label, task, false, interactive);
     }
 
     state
@@ -574,17 +674,33 @@ async fn spawn_worker_inner(
             worker_id,
             channel_id: Some(state.channel_id.clone()),
             task: task.to_string(),
+            label: label.clone(),
             worker_type: "builtin".into(),
             interactive,
             directory: None,
         })
         .ok();
 
-    tracing::info!(worker_id = %worker_id, task = %task, interactive, "worker spawned");
+    tracing::info!(worker_id = %worker_id, worker_label = %label, task = %task, interactive, "worker spawned");
 
     Ok(worker_id)
 }
 
+/// Apply the model a spawn asked for, if any. Checked before the worker is
+/// registered, so an unknown model fails the spawn without leaving state
+/// behind.
+fn with_requested_model(
+    worker: Worker,
+    model: Option<&str>,
+) -> std::result::Result<Worker, AgentError> {
+    match model {
+        Some(model) => worker
+            .with_model_override(model)
+            .map_err(|error| AgentError::Other(anyhow::anyhow!("{error}"))),
+        None => Ok(worker),
+    }
+}
+
 /// Spawn an OpenCode-backed worker for coding tasks.
 ///
 /// Instead of a Rig agent loop, this spawns an OpenCode subprocess that has its
@@ -595,6 +711,7 @@ pub async fn spawn_opencode_worker_from_state(
     task: impl Into<String>,
     directory: &str,
     interactive: bool,
+    label: Option<&str>,
 ) -> std::result::Result<crate::WorkerId, AgentError> {
     if !interactive {
         return Err(AgentError::Other(anyhow::anyhow!(
@@ -602,12 +719,16 @@ pub async fn spawn_opencode_worker_from_state(
         )));
     }
 
+    check_spawn_depth(
+        state.deps.spawn_depth,
+        state.deps.runtime_config.worker.load().max_spawn_depth,
+    )?;
     check_worker_limit(state).await?;
     let task = task.into();
     reserve_task_if_unique(state, &task).await?;
     ensure_dispatch_readiness(state, "opencode_worker");
 
-    let result = spawn_opencode_worker_inner(state, &task, directory, interactive).await;
+    let result = spawn_opencode_worker_inner(state, &task, directory, interactive, label).await;
 
     // Release the reservation regardless of success or failure.
     release_task_reservation(state, &task).await;
@@ -622,6 +743,7 @@ async fn spawn_opencode_worker_inner(
     task: &str,
     directory: &str,
     interactive: bool,
+    label: Option<&str>,
 ) -> std::result::Result<crate::WorkerId, AgentError> {
     let directory = expand_tilde(directory);
 
@@ -698,10 +820,12 @@ async fn spawn_opencode_worker_inner(
     };
 
     let worker_id = worker.id;
+    let label = crate::agent::worker::resolve_worker_label(label, task);
 
     let worker_span = tracing::info_span!(
         "worker.run",
         worker_id = %worker_id,
+        worker_label = %label,
         channel_id = %state.channel_id,
         worker_type = "opencode",
     );
@@ -755,7 +879,7 @@ async fn spawn_opencode_worker_inner(
     let opencode_task = format!("[opencode] {task}");
     {
         let mut status = state.status_block.write().await;
-        status.add_worker(worker_id, &opencode_task, false, interactive);
+        status.add_worker(worker_id, &label, &opencode_task, false, interactive);
     }
 
     state
@@ -766,6 +890,7 @@ async fn spawn_opencode_worker_inner(
             worker_id,
             channel_id: Some(state.channel_id.clone()),
             task: opencode_task,
+            label,
             worker_type: "opencode".into(),
             interactive,
             directory: Some(persist_directory.to_string_lossy().to_string()),
@@ -889,8 +1014,10 @@ where
 ///
 /// Loads the prior transcript, creates a resumed worker (builtin or opencode),
 /// registers it into the channel's worker_inputs/worker_handles/status_block,
-/// and spawns the follow-up loop. Returns `Ok(worker_id)` on success, or
-/// an error string if the worker couldn't be resumed.
+/// and spawns the follow-up loop. Interrupted fire-and-forget workers are
+/// handed to [`resume_interrupted_worker_into_state`]. Returns
+/// `Ok(worker_id)` on success, or an error string if the worker couldn't be
+/// resumed.
 pub async fn resume_idle_worker_into_state(
     state: &ChannelState,
     idle_worker: &crate::conversation::history::IdleWorkerRow,
@@ -952,9 +1079,14 @@ pub async fn resume_idle_worker_into_state(
                 .await
                 .insert(worker_id, input_tx);
 
+            let label = crate::agent::worker::resolve_worker_label(
+                idle_worker.label.as_deref(),
+                &idle_worker.task,
+            );
             let worker_span = tracing::info_span!(
                 "worker.resume",
                 worker_id = %worker_id,
+                worker_label = %label,
                 channel_id = %state.channel_id,
                 worker_type = "opencode",
             );
@@ -1000,7 +1132,7 @@ pub async fn resume_idle_worker_into_state(
             let opencode_task = format!("[opencode] {}", idle_worker.task);
             {
                 let mut status = state.status_block.write().await;
-                status.add_worker(worker_id, &opencode_task, false, true);
+                status.add_worker(worker_id, &label, &opencode_task, false, true);
             }
 
             state
@@ -1011,6 +1143,7 @@ pub async fn resume_idle_worker_into_state(
                     worker_id,
                     channel_id: Some(state.channel_id.clone()),
                     task: opencode_task,
+                    label,
                     worker_type: "opencode".into(),
                     interactive: true,
                     directory: Some(directory_str.clone()),
@@ -1020,6 +1153,9 @@ pub async fn resume_idle_worker_into_state(
             tracing::info!(worker_id = %worker_id, task = %idle_worker.task, "OpenCode worker resumed");
             Ok(worker_id)
         }
+        _ if !idle_worker.interactive => {
+            resume_interrupted_worker_into_state(state, idle_worker, worker_id).await
+        }
         _ => {
             // Builtin worker resume: deserialize transcript blob back into
             // Rig message history so the LLM can continue the conversation.
@@ -1031,48 +1167,25 @@ pub async fn resume_idle_worker_into_state(
                 return Err("no transcript blob to restore history from".into());
             };
 
-            let rc = &state.deps.runtime_config;
-            let prompt_engine = rc.prompts.load();
-
-            let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
-
-            let sandbox_enabled = state.deps.sandbox.mode_enabled();
-            let sandbox_containment_active = state.deps.sandbox.containment_active();
-            let sandbox_read_allowlist = state.deps.sandbox.prompt_read_allowlist();
-            let sandbox_write_allowlist = state.deps.sandbox.prompt_write_allowlist();
-            let secrets_guard = rc.secrets.load();
-            let tool_secret_names = match (*secrets_guard).as_ref() {
-                Some(store) => store.tool_secret_names(),
-                None => Vec::new(),
-            };
-            let browser_config = (**rc.browser_config.load()).clone();
-            let system_prompt = prompt_engine
-                .render_worker_prompt(
-                    &rc.instance_dir.display().to_string(),
-                    &rc.workspace_dir.display().to_string(),
-                    sandbox_enabled,
-                    sandbox_containment_active,
-                    sandbox_read_allowlist,
-                    sandbox_write_allowlist,
-                    &tool_secret_names,
-                    browser_config.persist_session,
-                    worker_status_text,
-                )
-                .map_err(|error| format!("failed to render worker prompt: {error}"))?;
-            let brave_search_key = (**rc.brave_search_key.load()).clone();
+            let (system_prompt, browser_config) = render_resumed_worker_prompt(state)?;
+            let search_backend = state.deps.runtime_config.search_backend();
 
-            let (worker, input_tx, inject_tx) = Worker::resume_interactive(
+            let (mut worker, input_tx, inject_tx) = Worker::resume_interactive(
                 worker_id,
                 Some(state.channel_id.clone()),
                 &idle_worker.task,
                 &system_prompt,
-                state.deps.clone(),
+                state.deps.for_spawned_worker(),
                 browser_config,
                 state.screenshot_dir.clone(),
-                brave_search_key,
+                search_backend,
                 state.logs_dir.clone(),
                 prior_history,
             );
+            if let Some(label) = &idle_worker.label {
+                worker = worker.with_label(label);
+            }
+            let label = worker.label.clone();
 
             state
                 .worker_inputs
@@ -1088,6 +1201,7 @@ pub async fn resume_idle_worker_into_state(
             let worker_span = tracing::info_span!(
                 "worker.resume",
                 worker_id = %worker_id,
+                worker_label = %label,
                 channel_id = %state.channel_id,
             );
             let secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
@@ -1105,7 +1219,7 @@ pub async fn resume_idle_worker_into_state(
 
             {
                 let mut status = state.status_block.write().await;
-                status.add_worker(worker_id, &idle_worker.task, false, true);
+                status.add_worker(worker_id, &label, &idle_worker.task, false, true);
             }
 
             state
@@ -1116,6 +1230,7 @@ pub async fn resume_idle_worker_into_state(
                     worker_id,
                     channel_id: Some(state.channel_id.clone()),
                     task: idle_worker.task.clone(),
+                    label,
                     worker_type: "builtin".into(),
                     interactive: true,
                     directory: None,
@@ -1128,6 +1243,134 @@ pub async fn resume_idle_worker_into_state(
     }
 }
 
+/// Resume a builtin fire-and-forget worker interrupted by a restart.
+///
+/// The worker continues its task from the history it checkpointed under the
+/// channel's logs directory, keeping its ID so the `worker_runs` row and the
+/// completion report stay linked. Fails when no checkpoint is left.
+async fn resume_interrupted_worker_into_state(
+    state: &ChannelState,
+    interrupted: &crate::conversation::history::IdleWorkerRow,
+    worker_id: WorkerId,
+) -> std::result::Result<WorkerId, String> {
+    let checkpoint = crate::agent::worker::checkpoint_path(&state.logs_dir, worker_id);
+    if !checkpoint.exists() {
+        return Err("no history checkpoint to resume from".into());
+    }
+
+    let rc = &state.deps.runtime_config;
+    let (worker_prompt, browser_config) = render_resumed_worker_prompt(state)?;
+    let system_prompt = crate::prompts::assembly::append_worker_skills(
+        worker_prompt,
+        &rc.skills.load(),
+        &[],
+        &rc.prompts.load(),
+    );
+
+    let (worker, inject_tx) = Worker::resume(
+        worker_id,
+        Some(state.channel_id.clone()),
+        &interrupted.task,
+        &system_prompt,
+        state.deps.for_spawned_worker(),
+        browser_config,
+        state.screenshot_dir.clone(),
+        rc.search_backend(),
+        state.logs_dir.clone(),
+    );
+    let toolset = WorkerToolset::resolve(&interrupted.task, None, rc.worker.load().tool_gating);
+    let worker = worker.with_toolset(toolset);
+    let worker = match &interrupted.label {
+        Some(label) => worker.with_label(label),
+        None => worker,
+    };
+    let label = worker.label.clone();
+
+    state
+        .worker_injections
+        .write()
+        .await
+        .insert(worker_id, inject_tx);
+
+    let worker_span = tracing::info_span!(
+        "worker.resume",
+        worker_id = %worker_id,
+        worker_label = %label,
+        channel_id = %state.channel_id,
+    );
+    let secrets_store = rc.secrets.load().as_ref().clone();
+    let handle = spawn_worker_task(
+        worker_id,
+        state.deps.event_tx.clone(),
+        state.deps.agent_id.clone(),
+        Some(state.channel_id.clone()),
+        secrets_store,
+        "builtin",
+        worker.run().instrument(worker_span),
+    );
+
+    state.worker_handles.write().await.insert(worker_id, handle);
+
+    {
+        let mut status = state.status_block.write().await;
+        status.add_worker(worker_id, &label, &interrupted.task, false, false);
+    }
+
+    state
+        .deps
+        .event_tx
+        .send(ProcessEvent::WorkerStarted {
+            agent_id: state.deps.agent_id.clone(),
+            worker_id,
+            channel_id: Some(state.channel_id.clone()),
+            task: interrupted.task.clone(),
+            label,
+            worker_type: "builtin".into(),
+            interactive: false,
+            directory: None,
+        })
+        .ok();
+
+    tracing::info!(worker_id = %worker_id, task = %interrupted.task, "interrupted worker resumed from checkpoint");
+    Ok(worker_id)
+}
+
+/// Render the system prompt for a builtin worker resumed after restart,
+/// with the browser config it was rendered against.
+fn render_resumed_worker_prompt(
+    state: &ChannelState,
+) -> std::result::Result<(String, crate::config::BrowserConfig), String> {
+    let rc = &state.deps.runtime_config;
+    let prompt_engine = rc.prompts.load();
+
+    let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
+
+    let sandbox_enabled = state.deps.sandbox.mode_enabled();
+    let sandbox_containment_active = state.deps.sandbox.containment_active();
+    let sandbox_read_allowlist = state.deps.sandbox.prompt_read_allowlist();
+    let sandbox_write_allowlist = state.deps.sandbox.prompt_write_allowlist();
+    let secrets_guard = rc.secrets.load();
+    let tool_secret_names = match (*secrets_guard).as_ref() {
+        Some(store) => store.tool_secret_names(),
+        None => Vec::new(),
+    };
+    let browser_config = (**rc.browser_config.load()).clone();
+    let system_prompt = prompt_engine
+        .render_worker_prompt(
+            &rc.instance_dir.display().to_string(),
+            &rc.workspace_dir.display().to_string(),
+            sandbox_enabled,
+            sandbox_containment_active,
+            sandbox_read_allowlist,
+            sandbox_write_allowlist,
+            &tool_secret_names,
+            browser_config.persist_session,
+            worker_status_text,
+        )
+        .map_err(|error| format!("failed to render worker prompt: {error}"))?;
+    Ok((system_prompt, browser_config))
+}
+
 /// Expand a leading `~` or `~/` in a path to the user's home directory.
 ///
 /// LLMs consistently produce tilde-prefixed paths because that's what appears
@@ -1148,13 +1391,33 @@ fn expand_tilde(path: &str) -> std::path::PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use super::{WorkerCompletionError, map_worker_completion_result, spawn_worker_task};
+    use super::{
+        WorkerCompletionError, check_spawn_depth, map_worker_completion_result, spawn_worker_task,
+    };
+    use crate::error::AgentError;
     use crate::{ProcessEvent, WorkerId};
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::sync::broadcast;
     use uuid::Uuid;
 
+    #[test]
+    fn spawning_past_max_depth_is_refused() {
+        // Channel (depth 0) spawns a worker at depth 1, which may spawn one
+        // at depth 2; with a max of 2 that second worker cannot spawn again.
+        assert!(check_spawn_depth(0, 2).is_ok());
+        assert!(check_spawn_depth(1, 2).is_ok());
+
+        let error = check_spawn_depth(2, 2).expect_err("spawn at max depth must be refused");
+        assert!(matches!(
+            error,
+            AgentError::SpawnDepthExceeded { depth: 2, max: 2 }
+        ));
+        assert!(error.to_string().contains("max spawn depth (2)"));
+
+        assert!(check_spawn_depth(0, 0).is_err());
+    }
+
     #[test]
     fn cancelled_errors_are_classified_as_cancelled_results() {
         let (text, notify, success) =
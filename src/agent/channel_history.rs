@@ -4,6 +4,8 @@
 //! history reconciliation after LLM turns, user message formatting,
 //! reply extraction from cancelled turns, and event filtering.
 
+use crate::agent::prompt_escape::{escape_display_name, escape_user_content};
+use crate::config::UserContentEscaping;
 use crate::{ChannelId, InboundMessage, ProcessEvent};
 
 /// Write history back after the agentic loop completes.
@@ -182,12 +184,12 @@ fn extract_reply_content_from_cancelled_history(
 }
 
 /// Returns true if a message is a User message containing only text content
-/// (i.e., an actual user prompt, not a tool result).
-fn is_user_text_message(message: &rig::message::Message) -> bool {
+/// (i.e., an actual user prompt or an injected marker, not a tool result).
+pub(crate) fn is_user_text_message(message: &rig::message::Message) -> bool {
     match message {
-        rig::message::Message::User { content } => content
-            .iter()
-            .all(|c| matches!(c, rig::message::UserContent::Text(_))),
+        rig::message::Message::User { content } => content.iter().all(|c| {
+            matches!(c, rig::message::UserContent::Text(_)) || crate::llm::marker::is_tag(c)
+        }),
         _ => false,
     }
 }
@@ -268,6 +270,7 @@ pub(crate) fn format_user_message(
     raw_text: &str,
     message: &InboundMessage,
     timestamp_text: &str,
+    escaping: UserContentEscaping,
 ) -> String {
     if message.source == "system" {
         // System messages should never be empty, but guard against it
@@ -278,7 +281,7 @@ pub(crate) fn format_user_message(
         };
     }
 
-    let display_name = message_display_name(message);
+    let display_name = escape_display_name(message_display_name(message), escaping);
 
     let bot_tag = if message
         .metadata
@@ -296,11 +299,18 @@ pub(crate) fn format_user_message(
         .get("reply_to_author")
         .and_then(|v| v.as_str())
         .map(|author| {
+            let author = escape_display_name(author, escaping);
             let content_preview = message
                 .metadata
                 .get(crate::metadata_keys::REPLY_TO_TEXT)
                 .and_then(|v| v.as_str())
                 .unwrap_or("");
+            // The quoted message is inline, so it is escaped but never fenced.
+            let preview_escaping = match escaping {
+                UserContentEscaping::Fence => UserContentEscaping::Escape,
+                other => other,
+            };
+            let content_preview = escape_user_content(content_preview, preview_escaping);
             if content_preview.is_empty() {
                 format!(" (replying to {author})")
             } else {
@@ -312,9 +322,9 @@ pub(crate) fn format_user_message(
     // If raw_text is empty or just whitespace, use a placeholder to avoid
     // sending empty text content blocks to the LLM API.
     let text_content = if raw_text.trim().is_empty() {
-        "[attachment or empty message]"
+        "[attachment or empty message]".into()
     } else {
-        raw_text
+        escape_user_content(raw_text, escaping)
     };
 
     let sender_context = message
@@ -335,11 +345,13 @@ pub(crate) fn format_batched_user_message(
     absolute_timestamp: &str,
     relative_text: &str,
     raw_text: &str,
+    escaping: UserContentEscaping,
 ) -> String {
+    let display_name = escape_display_name(display_name, escaping);
     let text_content = if raw_text.trim().is_empty() {
-        "[attachment or empty message]"
+        "[attachment or empty message]".into()
     } else {
-        raw_text
+        escape_user_content(raw_text, escaping)
     };
     format!("[{display_name}] ({absolute_timestamp}; {relative_text}): {text_content}")
 }
@@ -422,9 +434,17 @@ pub(crate) fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId)
             channel_id: event_channel,
             ..
         }
+        | ProcessEvent::WorkerLifecycle {
+            channel_id: event_channel,
+            ..
+        }
         | ProcessEvent::WorkerInitialResult {
             channel_id: event_channel,
             ..
+        }
+        | ProcessEvent::WorkerClarification {
+            channel_id: event_channel,
+            ..
         } => event_channel.as_ref() == Some(channel_id),
         ProcessEvent::OpenCodeSessionCreated {
             channel_id: event_channel,
@@ -434,6 +454,7 @@ pub(crate) fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId)
         | ProcessEvent::StatusUpdate { .. }
         | ProcessEvent::TaskUpdated { .. }
         | ProcessEvent::WorkerText { .. }
+        | ProcessEvent::WorkerTextDelta { .. }
         | ProcessEvent::CortexChatUpdate { .. } => false,
     }
 }
@@ -441,6 +462,7 @@ pub(crate) fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId)
 #[cfg(test)]
 mod tests {
     use super::{apply_history_after_turn, event_is_for_channel};
+    use crate::config::UserContentEscaping;
     use crate::{ChannelId, ProcessEvent, ProcessId};
     use rig::completion::{CompletionError, PromptError};
     use rig::message::Message;
@@ -889,7 +911,12 @@ mod tests {
             timestamp: Utc::now(),
         };
 
-        let formatted = format_user_message("", &message, "2026-02-26 12:00:00 UTC");
+        let formatted = format_user_message(
+            "",
+            &message,
+            "2026-02-26 12:00:00 UTC",
+            UserContentEscaping::default(),
+        );
         assert!(
             !formatted.trim().is_empty(),
             "formatted message should not be empty"
@@ -900,7 +927,12 @@ mod tests {
         );
 
         // Test whitespace-only text
-        let formatted_ws = format_user_message("   ", &message, "2026-02-26 12:00:00 UTC");
+        let formatted_ws = format_user_message(
+            "   ",
+            &message,
+            "2026-02-26 12:00:00 UTC",
+            UserContentEscaping::default(),
+        );
         assert!(
             formatted_ws.contains("[attachment or empty message]"),
             "should use placeholder for whitespace-only text"
@@ -920,14 +952,24 @@ mod tests {
             timestamp: Utc::now(),
         };
 
-        let formatted_sys = format_user_message("", &system_message, "2026-02-26 12:00:00 UTC");
+        let formatted_sys = format_user_message(
+            "",
+            &system_message,
+            "2026-02-26 12:00:00 UTC",
+            UserContentEscaping::default(),
+        );
         assert_eq!(
             formatted_sys, "[system event]",
             "system messages should use [system event] placeholder"
         );
 
         // Test normal message with text
-        let formatted_normal = format_user_message("hello", &message, "2026-02-26 12:00:00 UTC");
+        let formatted_normal = format_user_message(
+            "hello",
+            &message,
+            "2026-02-26 12:00:00 UTC",
+            UserContentEscaping::default(),
+        );
         assert!(
             formatted_normal.contains("hello"),
             "normal messages should preserve text"
@@ -1048,6 +1090,7 @@ mod tests {
             "2026-02-26 15:04:05 PST (America/Los_Angeles, UTC-08:00)",
             "12s ago",
             "ship it",
+            UserContentEscaping::default(),
         );
         assert!(
             formatted.contains("2026-02-26 15:04:05 PST"),
@@ -1070,6 +1113,7 @@ mod tests {
             "2026-02-26 15:04:05 PST (America/Los_Angeles, UTC-08:00)",
             "just now",
             "   ",
+            UserContentEscaping::default(),
         );
         assert!(
             formatted.contains("[attachment or empty message]"),
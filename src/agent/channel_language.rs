@@ -0,0 +1,373 @@
+//! Sticky per-channel language detection.
+//!
+//! A single message is weak evidence of a conversation's language: a short
+//! reply, a quoted error, or a code snippet can all look like a different
+//! language than the one people are actually speaking. [`LanguageTracker`]
+//! pools detector scores over the last few messages and only switches the
+//! channel's language once one language clearly dominates that window.
+//! Sustained mixed content falls back to the configured default.
+//!
+//! Detection itself goes through the [`LanguageDetector`] trait. The built-in
+//! [`ScriptDetector`] needs no model or dependency; embedders can install
+//! their own implementation under a name with [`register_detector`] and
+//! select it with `[channel.language_detection] detector`.
+
+use crate::config::LanguageDetectionConfig;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Name the built-in detector is registered under.
+pub const BUILTIN_DETECTOR: &str = "builtin";
+
+/// Scores the languages a piece of text may be written in.
+pub trait LanguageDetector: Send + Sync {
+    /// Candidate language codes (ISO 639-1, e.g. `"en"`) with scores in
+    /// `0.0..=1.0`. Scores should sum to at most 1.0; an empty result means
+    /// the text carries no usable evidence.
+    fn detect(&self, text: &str) -> Vec<(String, f32)>;
+}
+
+static DETECTORS: LazyLock<RwLock<HashMap<String, Arc<dyn LanguageDetector>>>> =
+    LazyLock::new(|| {
+        let builtin: Arc<dyn LanguageDetector> = Arc::new(ScriptDetector);
+        RwLock::new(HashMap::from([(BUILTIN_DETECTOR.to_string(), builtin)]))
+    });
+
+/// Make `detector` selectable by `name` in config, replacing any detector
+/// already registered under that name.
+pub fn register_detector(name: impl Into<String>, detector: Arc<dyn LanguageDetector>) {
+    DETECTORS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(name.into(), detector);
+}
+
+/// The detector registered under `name`, falling back to the built-in one.
+pub fn detector(name: &str) -> Arc<dyn LanguageDetector> {
+    let detectors = DETECTORS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(detector) = detectors.get(name) {
+        return detector.clone();
+    }
+    tracing::warn!(
+        detector = name,
+        "unknown language detector, using the built-in one"
+    );
+    detectors[BUILTIN_DETECTOR].clone()
+}
+
+/// English name for a language code, for prompts and the API.
+pub fn language_name(code: &str) -> &str {
+    match code {
+        "en" => "English",
+        "es" => "Spanish",
+        "fr" => "French",
+        "de" => "German",
+        "pt" => "Portuguese",
+        "it" => "Italian",
+        "nl" => "Dutch",
+        "ru" => "Russian",
+        "uk" => "Ukrainian",
+        "el" => "Greek",
+        "ar" => "Arabic",
+        "he" => "Hebrew",
+        "hi" => "Hindi",
+        "th" => "Thai",
+        "zh" => "Chinese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        other => other,
+    }
+}
+
+/// Accumulates detection evidence for one channel.
+#[derive(Debug, Clone, Default)]
+pub struct LanguageTracker {
+    window: VecDeque<Vec<(String, f32)>>,
+    current: Option<String>,
+}
+
+impl LanguageTracker {
+    /// Start from a previously detected language, e.g. one persisted across
+    /// restarts.
+    pub fn with_current(current: Option<String>) -> Self {
+        Self {
+            window: VecDeque::new(),
+            current,
+        }
+    }
+
+    /// The channel's language: the last confident detection, or the
+    /// configured default before there is one.
+    pub fn current<'a>(&'a self, config: &'a LanguageDetectionConfig) -> &'a str {
+        self.current.as_deref().unwrap_or(&config.default_language)
+    }
+
+    /// Add a message as evidence. Returns the new language when this message
+    /// changed it.
+    pub fn observe(
+        &mut self,
+        text: &str,
+        detector: &dyn LanguageDetector,
+        config: &LanguageDetectionConfig,
+    ) -> Option<String> {
+        if text.trim().chars().count() < config.min_chars {
+            return None;
+        }
+        let scores = detector.detect(text);
+        if scores.is_empty() {
+            return None;
+        }
+        self.window.push_back(scores);
+        while self.window.len() > config.window {
+            self.window.pop_front();
+        }
+
+        let mut totals: HashMap<&str, f32> = HashMap::new();
+        for (language, score) in self.window.iter().flatten() {
+            *totals.entry(language).or_default() += score.clamp(0.0, 1.0);
+        }
+        let total: f32 = totals.values().sum();
+        let (top, top_score) = totals
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))?;
+        let confidence = if total > 0.0 { top_score / total } else { 0.0 };
+
+        let next = if confidence >= config.confidence_threshold {
+            top.to_string()
+        } else if self.window.len() >= config.window || self.current.is_none() {
+            // Mixed across a full window, or nothing confident yet.
+            config.default_language.clone()
+        } else {
+            return None;
+        };
+        if self.current.as_deref() == Some(next.as_str()) {
+            return None;
+        }
+        self.current = Some(next.clone());
+        Some(next)
+    }
+}
+
+/// Dependency-free detector: non-Latin scripts identify their language
+/// directly, and Latin-script text is scored by common function words.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScriptDetector;
+
+/// Frequent short words that rarely occur in the other listed languages.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "that", "this", "with", "have", "what", "for", "not",
+            "it", "of", "to", "can",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "que", "es", "y", "por", "para", "con", "una", "pero",
+            "como", "del", "está", "qué",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "les", "est", "et", "vous", "je", "pas", "une", "des", "que", "pour", "avec",
+            "dans", "mais", "c'est", "qui",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "sie", "mit", "ein", "eine", "auf",
+            "für", "auch", "wie", "zu",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "que", "é", "e", "não", "um", "uma", "para", "com", "do", "da", "mas",
+            "você", "isso", "em",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "di", "che", "è", "e", "non", "un", "una", "per", "con", "sono", "gli", "della",
+            "ma", "anche", "questo",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "je", "van", "dat", "met", "op", "voor",
+            "maar", "ook", "zijn",
+        ],
+    ),
+];
+
+impl LanguageDetector for ScriptDetector {
+    fn detect(&self, text: &str) -> Vec<(String, f32)> {
+        let mut scripts: HashMap<&str, usize> = HashMap::new();
+        let mut letters = 0usize;
+        let mut has_kana = false;
+        for c in text.chars().filter(|c| c.is_alphabetic()) {
+            letters += 1;
+            let script = match c as u32 {
+                0x0041..=0x024F => "latin",
+                0x0370..=0x03FF => "el",
+                0x0400..=0x04FF => {
+                    // Letters used in Ukrainian but not Russian.
+                    if matches!(c, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ') {
+                        "uk"
+                    } else {
+                        "ru"
+                    }
+                }
+                0x0590..=0x05FF => "he",
+                0x0600..=0x06FF => "ar",
+                0x0900..=0x097F => "hi",
+                0x0E00..=0x0E7F => "th",
+                0x3040..=0x30FF => {
+                    has_kana = true;
+                    "ja"
+                }
+                0x4E00..=0x9FFF => "han",
+                0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+                _ => continue,
+            };
+            *scripts.entry(script).or_default() += 1;
+        }
+        if letters == 0 {
+            return Vec::new();
+        }
+        // Cyrillic text with any Ukrainian-only letter is Ukrainian.
+        if let Some(uk) = scripts.remove("uk") {
+            *scripts.entry("uk").or_default() += uk + scripts.remove("ru").unwrap_or(0);
+        }
+        // Han characters are Japanese when kana appear alongside them.
+        if let Some(han) = scripts.remove("han") {
+            let language = if has_kana { "ja" } else { "zh" };
+            *scripts.entry(language).or_default() += han;
+        }
+
+        let mut scores = Vec::new();
+        for (script, count) in scripts {
+            let share = count as f32 / letters as f32;
+            if script == "latin" {
+                scores.extend(score_latin(text, share));
+            } else {
+                scores.push((script.to_string(), share));
+            }
+        }
+        scores
+    }
+}
+
+/// Split `share` between Latin-script languages by stopword hits.
+fn score_latin(text: &str, share: f32) -> Vec<(String, f32)> {
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let hits: Vec<(&str, usize)> = STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let count = words.iter().filter(|word| stopwords.contains(word)).count();
+            (*language, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let total: usize = hits.iter().map(|(_, count)| count).sum();
+    hits.into_iter()
+        .map(|(language, count)| (language.to_string(), share * count as f32 / total as f32))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LanguageDetector, LanguageTracker, ScriptDetector};
+    use crate::config::LanguageDetectionConfig;
+
+    fn top(text: &str) -> String {
+        let mut scores = ScriptDetector.detect(text);
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores
+            .first()
+            .map(|(code, _)| code.clone())
+            .unwrap_or_default()
+    }
+
+    fn config() -> LanguageDetectionConfig {
+        LanguageDetectionConfig {
+            enabled: true,
+            window: 3,
+            min_chars: 5,
+            ..LanguageDetectionConfig::default()
+        }
+    }
+
+    #[test]
+    fn builtin_detector_identifies_scripts_and_latin_languages() {
+        assert_eq!(top("Can you check what the build is doing?"), "en");
+        assert_eq!(
+            top("¿Puedes revisar qué está pasando con el servidor?"),
+            "es"
+        );
+        assert_eq!(top("Est-ce que vous pouvez regarder les logs?"), "fr");
+        assert_eq!(top("Привет, как дела?"), "ru");
+        assert_eq!(top("これは日本語の文章です"), "ja");
+        assert_eq!(top("这是中文"), "zh");
+        assert!(ScriptDetector.detect("12345 !!!").is_empty());
+    }
+
+    #[test]
+    fn one_message_in_another_language_does_not_flip_the_channel() {
+        let config = config();
+        let mut tracker = LanguageTracker::default();
+        let english = "Can you check what the build is doing with the tests?";
+        let spanish = "¿Qué pasa con el servidor? No funciona para los usuarios.";
+
+        assert_eq!(
+            tracker
+                .observe(english, &ScriptDetector, &config)
+                .as_deref(),
+            Some("en")
+        );
+        assert_eq!(tracker.observe(english, &ScriptDetector, &config), None);
+        assert_eq!(tracker.observe(spanish, &ScriptDetector, &config), None);
+        assert_eq!(tracker.current(&config), "en");
+
+        // Once Spanish dominates the window the channel switches.
+        assert_eq!(tracker.observe(spanish, &ScriptDetector, &config), None);
+        assert_eq!(
+            tracker
+                .observe(spanish, &ScriptDetector, &config)
+                .as_deref(),
+            Some("es")
+        );
+    }
+
+    #[test]
+    fn low_confidence_falls_back_to_the_default() {
+        let config = LanguageDetectionConfig {
+            default_language: "de".into(),
+            ..config()
+        };
+        let mut tracker = LanguageTracker::default();
+        assert_eq!(tracker.current(&config), "de");
+
+        // Too short to count as evidence.
+        assert_eq!(tracker.observe("ok", &ScriptDetector, &config), None);
+        // Evenly mixed content is not confident enough to pick either.
+        let mixed = "the build está roto";
+        assert_eq!(
+            tracker.observe(mixed, &ScriptDetector, &config).as_deref(),
+            Some("de")
+        );
+    }
+}
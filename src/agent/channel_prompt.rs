@@ -15,6 +15,11 @@ pub(crate) const RETRIGGER_DEBOUNCE_MS: u64 = 500;
 /// infinite retrigger cascades where each retrigger spawns more work.
 pub(crate) const MAX_RETRIGGERS_PER_TURN: usize = 3;
 
+/// Maximum refreshed status blocks injected into a single channel turn when
+/// `status_refresh = "on_change"`. Each injection re-prompts the model, so a
+/// busy channel stops refreshing rather than re-prompting indefinitely.
+pub(crate) const MAX_STATUS_REFRESHES_PER_TURN: usize = 3;
+
 /// Max LLM turns for retrigger relay. Retriggers are simple relay tasks —
 /// the LLM just needs to call the reply tool once. A low cap avoids wasting
 /// tokens on retries when the model struggles with the retrigger format.
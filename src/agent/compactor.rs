@@ -4,6 +4,10 @@
 //! spawns compaction workers when thresholds are crossed. The LLM work (summarization
 //! + memory extraction) happens in the spawned worker, not here.
 
+use crate::agent::token_estimator::token_estimator_for;
+use crate::agent::tool_call_repair::repair_and_log;
+use crate::agent::worker::build_worker_recap;
+use crate::config::{CompactorFallback, RecapPriority};
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
@@ -12,6 +16,7 @@ use rig::agent::AgentBuilder;
 use rig::completion::CompletionModel;
 use rig::message::{AssistantContent, Message, UserContent};
 use rig::tool::server::ToolServerHandle;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
@@ -40,32 +45,7 @@ impl Compactor {
     ///
     /// Called by the channel after each turn. Returns the action taken, if any.
     pub async fn check_and_compact(&self) -> Result<Option<CompactionAction>> {
-        let is_compacting = *self.is_compacting.read().await;
-        if is_compacting {
-            return Ok(None);
-        }
-
-        let rc = &self.deps.runtime_config;
-        let context_window = **rc.context_window.load();
-        let compaction_config = **rc.compaction.load();
-
-        let usage = {
-            let history = self.history.read().await;
-            let estimated_tokens = estimate_history_tokens(&history);
-            estimated_tokens as f32 / context_window as f32
-        };
-
-        let action = if usage >= compaction_config.emergency_threshold {
-            Some(CompactionAction::EmergencyTruncate)
-        } else if usage >= compaction_config.aggressive_threshold {
-            Some(CompactionAction::Aggressive)
-        } else if usage >= compaction_config.background_threshold {
-            Some(CompactionAction::Background)
-        } else {
-            None
-        };
-
-        if let Some(action) = action {
+        if let Some((action, usage)) = self.pending_action().await {
             tracing::info!(
                 channel_id = %self.channel_id,
                 usage = %format!("{:.1}%", usage * 100.0),
@@ -105,6 +85,45 @@ impl Compactor {
         }
     }
 
+    /// The compaction `check_and_compact` would run right now, with the
+    /// context usage that triggers it, or `None` if it would do nothing.
+    ///
+    /// Lets the channel act on history (e.g. persist memories) before
+    /// compaction drops or summarizes it.
+    pub async fn pending_action(&self) -> Option<(CompactionAction, f32)> {
+        let is_compacting = *self.is_compacting.read().await;
+        if is_compacting {
+            return None;
+        }
+
+        let rc = &self.deps.runtime_config;
+        let context_window = **rc.context_window.load();
+        let compaction_config = **rc.compaction.load();
+
+        let model_name = rc
+            .routing
+            .load()
+            .resolve(ProcessType::Channel, None)
+            .to_string();
+        let usage = {
+            let history = self.history.read().await;
+            let estimated_tokens = token_estimator_for(&model_name).estimate_history(&history);
+            estimated_tokens as f32 / context_window as f32
+        };
+
+        let action = if usage >= compaction_config.emergency_threshold {
+            CompactionAction::EmergencyTruncate
+        } else if usage >= compaction_config.aggressive_threshold {
+            CompactionAction::Aggressive
+        } else if usage >= compaction_config.background_threshold {
+            CompactionAction::Background
+        } else {
+            return None;
+        };
+
+        Some((action, usage))
+    }
+
     /// Spawn a compaction worker in the background.
     ///
     /// The worker reads old messages, runs an LLM to produce a summary + extract
@@ -180,7 +199,12 @@ impl Compactor {
         // Insert a marker at the beginning
         let prompt_engine = self.deps.runtime_config.prompts.load();
         let marker = prompt_engine.render_system_truncation(remove_count)?;
-        history.insert(0, Message::from(marker));
+        history.insert(0, crate::llm::marker::message(marker));
+        repair_and_log(
+            &mut history,
+            self.deps.runtime_config.compaction.load().tool_call_repair,
+            &ProcessId::Channel(self.channel_id.clone()),
+        );
 
         tracing::warn!(
             channel_id = %self.channel_id,
@@ -252,19 +276,26 @@ async fn run_compaction(
         .prompt_once(&agent, &mut compaction_history, &transcript)
         .await;
 
-    let summary = match response {
-        Ok(text) => extract_summary_section(&text),
-        Err(error) => {
-            tracing::warn!(%error, "compaction LLM failed, using fallback summary");
-            format!("[Compaction summary of {remove_count} messages — LLM summarization failed]")
-        }
-    };
+    let compaction_config = **deps.runtime_config.compaction.load();
+    let summary = summary_or_fallback(
+        response,
+        &removed_messages,
+        compaction_config.compactor_fallback,
+        &deps.runtime_config.worker.load().recap_tools,
+        compaction_config.max_recap_bytes,
+    );
 
     // 4. Insert the summary at the beginning of the channel's history
     {
         let mut hist = history.write().await;
         let summary_message = format!("[Compaction Summary]: {summary}");
         hist.insert(0, Message::from(summary_message));
+        // The cut point can separate a tool call from its result.
+        repair_and_log(
+            &mut hist,
+            compaction_config.tool_call_repair,
+            &ProcessId::Channel(channel_id.clone()),
+        );
     }
 
     Ok(remove_count)
@@ -274,75 +305,164 @@ async fn run_compaction(
 ///
 /// This is intentionally rough — it's only used for threshold checks, not billing.
 /// Overestimates slightly, which is the safe direction for compaction triggers.
+/// Prefer [`token_estimator_for`] where the model is known.
 pub fn estimate_history_tokens(history: &[Message]) -> usize {
-    let mut chars = 0usize;
+    let chars: usize = history.iter().map(estimate_message_chars).sum();
+
+    // ~4 chars per token for English text. Slightly conservative.
+    chars / 4
+}
+
+/// Text parts smaller than this are never truncated; cutting them saves
+/// little and the marker would outweigh what's left.
+const MIN_TRUNCATED_PART_BYTES: usize = 1_000;
 
-    for message in history {
+/// Truncate in place every message whose estimated tokens exceed
+/// `max_tokens`, returning how many were cut.
+///
+/// Dropping other messages can't help when one message alone fills the
+/// context window. Each oversized message instead keeps the head of its text
+/// and tool-result parts, scaled down to fit, followed by a marker.
+pub fn truncate_oversized_messages(history: &mut [Message], max_tokens: usize) -> usize {
+    let max_chars = max_tokens.saturating_mul(4);
+    let mut truncated = 0;
+
+    for message in history.iter_mut() {
+        let chars = estimate_message_chars(message);
+        if chars <= max_chars {
+            continue;
+        }
+        let keep = max_chars as f64 / chars as f64;
+        let mut changed = false;
         match message {
             Message::User { content } => {
-                for item in content.iter() {
-                    chars += estimate_user_content_chars(item);
+                for item in content.iter_mut() {
+                    match item {
+                        UserContent::Text(t) => changed |= truncate_text_part(&mut t.text, keep),
+                        UserContent::ToolResult(tr) => {
+                            for part in tr.content.iter_mut() {
+                                if let rig::message::ToolResultContent::Text(t) = part {
+                                    changed |= truncate_text_part(&mut t.text, keep);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
                 }
             }
             Message::Assistant { content, .. } => {
-                for item in content.iter() {
-                    chars += estimate_assistant_content_chars(item);
+                for item in content.iter_mut() {
+                    if let AssistantContent::Text(t) = item {
+                        changed |= truncate_text_part(&mut t.text, keep);
+                    }
                 }
             }
         }
+        if changed {
+            truncated += 1;
+        }
     }
 
-    // ~4 chars per token for English text. Slightly conservative.
-    chars / 4
+    truncated
+}
+
+/// Keep the leading `keep` share of `text` and note what was cut.
+fn truncate_text_part(text: &mut String, keep: f64) -> bool {
+    let total = text.len();
+    let mut end = (total as f64 * keep) as usize;
+    if total < MIN_TRUNCATED_PART_BYTES || end >= total {
+        return false;
+    }
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str(&format!(
+        "\n\n[message truncated: kept {end} of {total} bytes, it alone exceeded the context budget]"
+    ));
+    true
+}
+
+fn estimate_message_chars(message: &Message) -> usize {
+    measure_message(message, &str::len, &|chars| chars)
+}
+
+/// Size of `message`: text parts are measured with `text_size`, parts with
+/// no readable text (media, signatures, encrypted reasoning) with
+/// `opaque_size` applied to their character estimate.
+pub(crate) fn measure_message(
+    message: &Message,
+    text_size: &dyn Fn(&str) -> usize,
+    opaque_size: &dyn Fn(usize) -> usize,
+) -> usize {
+    match message {
+        Message::User { content } => content
+            .iter()
+            .map(|content| measure_user_content(content, text_size, opaque_size))
+            .sum(),
+        Message::Assistant { content, .. } => content
+            .iter()
+            .map(|content| measure_assistant_content(content, text_size, opaque_size))
+            .sum(),
+    }
 }
 
-fn estimate_user_content_chars(content: &UserContent) -> usize {
+fn measure_user_content(
+    content: &UserContent,
+    text_size: &dyn Fn(&str) -> usize,
+    opaque_size: &dyn Fn(usize) -> usize,
+) -> usize {
     match content {
-        UserContent::Text(t) => t.text.len(),
+        UserContent::Text(t) => text_size(&t.text),
         UserContent::ToolResult(tr) => {
             let mut size = 0;
             for item in tr.content.iter() {
                 match item {
-                    rig::message::ToolResultContent::Text(t) => size += t.text.len(),
-                    rig::message::ToolResultContent::Image(_) => size += 100,
+                    rig::message::ToolResultContent::Text(t) => size += text_size(&t.text),
+                    rig::message::ToolResultContent::Image(_) => size += opaque_size(100),
                 }
             }
             size
         }
-        UserContent::Image(_) => 500,
-        UserContent::Audio(_) => 500,
-        UserContent::Video(_) => 500,
-        UserContent::Document(_) => 1000,
+        UserContent::Image(_) => opaque_size(500),
+        UserContent::Audio(_) => opaque_size(500),
+        UserContent::Video(_) => opaque_size(500),
+        UserContent::Document(_) if crate::llm::marker::is_tag(content) => 0,
+        UserContent::Document(_) => opaque_size(1000),
     }
 }
 
-fn estimate_assistant_content_chars(content: &AssistantContent) -> usize {
+fn measure_assistant_content(
+    content: &AssistantContent,
+    text_size: &dyn Fn(&str) -> usize,
+    opaque_size: &dyn Fn(usize) -> usize,
+) -> usize {
     match content {
-        AssistantContent::Text(t) => t.text.len(),
+        AssistantContent::Text(t) => text_size(&t.text),
         AssistantContent::ToolCall(tc) => {
-            tc.function.name.len() + tc.function.arguments.to_string().len()
+            text_size(&tc.function.name) + text_size(&tc.function.arguments.to_string())
         }
         AssistantContent::Reasoning(r) => r
             .content
             .iter()
             .map(|content| match content {
                 rig::message::ReasoningContent::Text { text, signature } => {
-                    text.len() + signature.as_ref().map_or(0, String::len)
+                    text_size(text) + signature.as_ref().map_or(0, |s| opaque_size(s.len()))
                 }
-                rig::message::ReasoningContent::Encrypted(data) => data.len(),
-                rig::message::ReasoningContent::Redacted { data } => data.len(),
-                rig::message::ReasoningContent::Summary(summary) => summary.len(),
+                rig::message::ReasoningContent::Encrypted(data) => opaque_size(data.len()),
+                rig::message::ReasoningContent::Redacted { data } => opaque_size(data.len()),
+                rig::message::ReasoningContent::Summary(summary) => text_size(summary),
                 // Future variants default to 0; update this match when new variants are added
                 #[allow(unreachable_patterns)]
                 _ => 0,
             })
             .sum(),
-        AssistantContent::Image(_) => 500,
+        AssistantContent::Image(_) => opaque_size(500),
     }
 }
 
 /// Render messages into a human-readable transcript for the compaction LLM.
-fn render_messages_as_transcript(messages: &[Message]) -> String {
+pub(crate) fn render_messages_as_transcript(messages: &[Message]) -> String {
     let mut output = String::new();
 
     for message in messages {
@@ -392,6 +512,37 @@ fn render_messages_as_transcript(messages: &[Message]) -> String {
     output
 }
 
+/// Summary text for the removed messages. When the compactor model call
+/// failed, falls back according to `fallback` so compaction always completes
+/// and overflow recovery never waits on an unavailable model.
+fn summary_or_fallback<E: std::fmt::Display>(
+    response: std::result::Result<String, E>,
+    removed: &[Message],
+    fallback: CompactorFallback,
+    recap_tools: &HashMap<String, RecapPriority>,
+    max_recap_bytes: usize,
+) -> String {
+    let error = match response {
+        Ok(text) => return extract_summary_section(&text),
+        Err(error) => error,
+    };
+
+    let remove_count = removed.len();
+    match fallback {
+        CompactorFallback::Recap => {
+            tracing::warn!(%error, remove_count, "compaction LLM failed, using programmatic recap");
+            format!(
+                "LLM summarization failed; recap of {remove_count} compacted messages:\n{}",
+                build_worker_recap(removed, recap_tools, max_recap_bytes)
+            )
+        }
+        CompactorFallback::Marker => {
+            tracing::warn!(%error, remove_count, "compaction LLM failed, using fallback marker");
+            format!("[Compaction summary of {remove_count} messages — LLM summarization failed]")
+        }
+    }
+}
+
 /// Extract the summary from the compaction LLM's first response.
 ///
 /// The prompt asks for plain summary text. If the LLM wraps it in a
@@ -415,3 +566,93 @@ pub enum CompactionAction {
     /// Emergency truncation (no LLM, drop oldest 50%).
     EmergencyTruncate,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_history_tokens, summary_or_fallback, truncate_oversized_messages};
+    use crate::config::{CompactorFallback, WorkerConfig};
+    use rig::message::{AssistantContent, Message};
+    use rig::one_or_many::OneOrMany;
+
+    #[test]
+    fn a_single_oversized_message_is_truncated_in_place() {
+        let paste = "x".repeat(400_000);
+        let mut history = vec![
+            Message::from("please summarize this"),
+            Message::from(paste.as_str()),
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::one(AssistantContent::text("On it.")),
+            },
+        ];
+
+        // 100k tokens of paste against a 10k-token budget.
+        assert_eq!(truncate_oversized_messages(&mut history, 10_000), 1);
+        assert_eq!(history.len(), 3);
+        assert!(estimate_history_tokens(&history[1..2]) <= 10_100);
+
+        let Message::User { content } = &history[1] else {
+            panic!("expected the paste to stay a user message");
+        };
+        let rig::message::UserContent::Text(text) = content.first() else {
+            panic!("expected text content");
+        };
+        assert!(text.text.starts_with("xxxx"));
+        assert!(
+            text.text
+                .ends_with("of 400000 bytes, it alone exceeded the context budget]")
+        );
+
+        // Already within budget: nothing more to cut.
+        assert_eq!(truncate_oversized_messages(&mut history, 10_100), 0);
+        assert!(matches!(
+            &history[0],
+            Message::User { content } if matches!(
+                content.first(),
+                rig::message::UserContent::Text(t) if t.text == "please summarize this"
+            )
+        ));
+    }
+
+    #[test]
+    fn failed_compactor_call_falls_back_to_programmatic_recap() {
+        let removed = vec![
+            Message::from("what's in the repo?"),
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::one(AssistantContent::text("Listed the repo: 3 crates.")),
+            },
+        ];
+        let recap_tools = WorkerConfig::default().recap_tools;
+        let failed: Result<String, &str> = Err("provider unavailable");
+
+        let summary = summary_or_fallback(
+            failed,
+            &removed,
+            CompactorFallback::Recap,
+            &recap_tools,
+            6_000,
+        );
+        assert!(summary.contains("recap of 2 compacted messages"));
+        assert!(summary.contains("- Noted: Listed the repo: 3 crates."));
+
+        let marker = summary_or_fallback(
+            Err::<String, _>("provider unavailable"),
+            &removed,
+            CompactorFallback::Marker,
+            &recap_tools,
+            6_000,
+        );
+        assert!(marker.contains("LLM summarization failed"));
+        assert!(!marker.contains("Listed the repo"));
+
+        let ok = summary_or_fallback(
+            Ok::<_, &str>("## Summary\nUser asked about the repo.".to_string()),
+            &removed,
+            CompactorFallback::Recap,
+            &recap_tools,
+            6_000,
+        );
+        assert_eq!(ok, "User asked about the repo.");
+    }
+}
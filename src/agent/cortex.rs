@@ -26,6 +26,7 @@ use crate::{
 };
 
 use futures::FutureExt as _;
+use futures::StreamExt as _;
 use rig::agent::AgentBuilder;
 use rig::completion::{CompletionModel, Prompt, TypedPrompt};
 use serde::Serialize;
@@ -1338,11 +1339,23 @@ fn signal_from_event(event: ProcessEvent) -> Option<Signal> {
             channel_id,
             status: "idle".to_string(),
         },
+        ProcessEvent::WorkerClarification {
+            worker_id,
+            channel_id,
+            ..
+        } => Signal::WorkerStatus {
+            worker_id,
+            channel_id,
+            status: "waiting for clarification".to_string(),
+        },
+        // Mirrors status updates the cortex already receives.
+        ProcessEvent::WorkerLifecycle { .. } => return None,
         // UI-only events — no cortex signal needed.
         ProcessEvent::OpenCodeSessionCreated { .. }
         | ProcessEvent::OpenCodePartUpdated { .. }
         | ProcessEvent::WorkerInitialResult { .. }
         | ProcessEvent::WorkerText { .. }
+        | ProcessEvent::WorkerTextDelta { .. }
         | ProcessEvent::CortexChatUpdate { .. } => return None,
     })
 }
@@ -2208,63 +2221,148 @@ const BULLETIN_SECTIONS: &[BulletinSection] = &[
     },
 ];
 
-/// Gather raw memory data for each bulletin section by querying the store directly.
-/// Returns formatted sections ready for LLM synthesis.
-async fn gather_bulletin_sections(deps: &AgentDeps) -> String {
-    let mut output = String::new();
+/// A source of raw bulletin data.
+#[derive(Clone, Copy)]
+enum BulletinSource {
+    Memories(&'static BulletinSection),
+    /// Non-done tasks from the task store.
+    ActiveTasks,
+}
 
-    for section in BULLETIN_SECTIONS {
-        let config = SearchConfig {
-            mode: section.mode,
-            memory_type: section.memory_type,
-            sort_by: section.sort_by,
-            max_results: section.max_results,
-            ..Default::default()
-        };
+impl BulletinSource {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Memories(section) => section.label,
+            Self::ActiveTasks => "Active Tasks",
+        }
+    }
 
-        let results = match deps.memory_search.search("", &config).await {
-            Ok(results) => results,
-            Err(error) => {
+    async fn gather(self, deps: &AgentDeps) -> anyhow::Result<String> {
+        match self {
+            Self::Memories(section) => gather_memory_section(deps, section).await,
+            Self::ActiveTasks => gather_active_tasks(deps).await,
+        }
+    }
+}
+
+/// Gather raw data for each bulletin section by querying the stores directly.
+/// Sources are queried `bulletin_gather_concurrency` at a time; any that have
+/// not finished within `bulletin_gather_timeout_secs` are left out so a slow
+/// query can't hold up synthesis. Returns formatted sections ready for LLM
+/// synthesis, in section order.
+async fn gather_bulletin_sections(deps: &AgentDeps) -> String {
+    let cortex_config = **deps.runtime_config.cortex.load();
+    // Collected up front: a lazy iterator adapter held across the awaits
+    // below makes the spawned cortex futures fail the `Send` check.
+    let sources: Vec<_> = BULLETIN_SECTIONS
+        .iter()
+        .map(BulletinSource::Memories)
+        .chain(std::iter::once(BulletinSource::ActiveTasks))
+        .collect();
+    let outcomes = gather_within_budget(
+        sources,
+        cortex_config.bulletin_gather_concurrency,
+        Duration::from_secs(cortex_config.bulletin_gather_timeout_secs),
+        |source| source.gather(deps),
+    )
+    .await;
+
+    let mut output = String::new();
+    let mut contributed = Vec::new();
+    let mut timed_out = Vec::new();
+    for (source, outcome) in outcomes {
+        match outcome {
+            Some(Ok(section)) => {
+                if !section.is_empty() {
+                    output.push_str(&section);
+                    contributed.push(source.label());
+                }
+            }
+            Some(Err(error)) => {
                 tracing::warn!(
-                    section = section.label,
+                    section = source.label(),
                     %error,
                     "bulletin section query failed"
                 );
-                continue;
             }
-        };
-
-        if results.is_empty() {
-            continue;
+            None => timed_out.push(source.label()),
         }
+    }
 
-        output.push_str(&format!("### {}\n\n", section.label));
-        for result in &results {
-            output.push_str(&format!(
-                "- [{}] (importance: {:.1}) {}\n",
-                result.memory.memory_type,
-                result.memory.importance,
-                result
-                    .memory
-                    .content
-                    .lines()
-                    .next()
-                    .unwrap_or(&result.memory.content),
-            ));
-        }
-        output.push('\n');
+    if timed_out.is_empty() {
+        tracing::debug!(?contributed, "gathered bulletin sections");
+    } else {
+        tracing::warn!(
+            ?contributed,
+            ?timed_out,
+            timeout_secs = cortex_config.bulletin_gather_timeout_secs,
+            "bulletin sections timed out, synthesizing without them"
+        );
     }
 
-    // Append active tasks (non-done) from the task store.
-    match gather_active_tasks(deps).await {
-        Ok(section) if !section.is_empty() => output.push_str(&section),
-        Err(error) => {
-            tracing::warn!(%error, "failed to gather active tasks for bulletin");
+    output
+}
+
+/// Run `gather` for each source, at most `concurrency` at a time, and stop
+/// waiting once `budget` has passed. Outcomes come back in source order; a
+/// source that had not finished by the deadline gets `None`.
+async fn gather_within_budget<S, F, T>(
+    sources: impl IntoIterator<Item = S>,
+    concurrency: usize,
+    budget: Duration,
+    gather: impl Fn(S) -> F,
+) -> Vec<(S, Option<T>)>
+where
+    S: Copy,
+    F: Future<Output = T>,
+{
+    let deadline = tokio::time::Instant::now() + budget;
+    futures::stream::iter(sources.into_iter().map(|source| {
+        let gathered = gather(source);
+        async move {
+            let outcome = tokio::time::timeout_at(deadline, gathered).await.ok();
+            (source, outcome)
         }
-        _ => {}
+    }))
+    .buffered(concurrency.max(1))
+    .collect()
+    .await
+}
+
+/// Query the memory store for one bulletin section and format the results.
+async fn gather_memory_section(
+    deps: &AgentDeps,
+    section: &BulletinSection,
+) -> anyhow::Result<String> {
+    let config = SearchConfig {
+        mode: section.mode,
+        memory_type: section.memory_type,
+        sort_by: section.sort_by,
+        max_results: section.max_results,
+        ..Default::default()
+    };
+    let results = deps.memory_search.search("", &config).await?;
+
+    if results.is_empty() {
+        return Ok(String::new());
     }
 
-    output
+    let mut output = format!("### {}\n\n", section.label);
+    for result in &results {
+        output.push_str(&format!(
+            "- [{}] (importance: {:.1}) {}\n",
+            result.memory.memory_type,
+            result.memory.importance,
+            result
+                .memory
+                .content
+                .lines()
+                .next()
+                .unwrap_or(&result.memory.content),
+        ));
+    }
+    output.push('\n');
+    Ok(output)
 }
 
 /// Query the task store for non-done tasks and format them as a bulletin section.
@@ -2383,6 +2481,18 @@ pub async fn generate_bulletin(deps: &AgentDeps, logger: &CortexLogger) -> bool
             let word_count = bulletin.split_whitespace().count();
             let duration_ms = started.elapsed().as_millis() as u64;
             tracing::info!(words = word_count, "cortex bulletin generated");
+            let history_config = deps.runtime_config.bulletin_history.load();
+            if history_config.enabled
+                && let Some(dir) = &history_config.dir
+                && let Err(error) = crate::agent::bulletin_history::save_snapshot(
+                    dir,
+                    &bulletin,
+                    chrono::Utc::now(),
+                    history_config.retention,
+                )
+            {
+                tracing::warn!(%error, path = %dir.display(), "failed to save bulletin snapshot");
+            }
             deps.runtime_config
                 .memory_bulletin
                 .store(Arc::new(bulletin));
@@ -2726,6 +2836,12 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
             task_prompt.push_str(&format!("{}. {} {}\n", index + 1, marker, subtask.title));
         }
     }
+    let task_prompt = crate::agent::worker::fit_task_to_budget(
+        task_prompt,
+        &deps.runtime_config.worker.load(),
+        &deps.runtime_config.saved_dir(),
+        &deps.runtime_config.prompts.load(),
+    )?;
 
     let screenshot_dir = deps
         .runtime_config
@@ -2744,15 +2860,15 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
         tracing::warn!(%error, path = %logs_dir.display(), "failed to create logs directory");
     }
 
-    let brave_search_key = (**deps.runtime_config.brave_search_key.load()).clone();
+    let search_backend = deps.runtime_config.search_backend();
     let (worker, inject_tx) = Worker::new(
         None,
         task_prompt,
         worker_system_prompt,
-        deps.clone(),
+        deps.for_spawned_worker(),
         browser_config,
         screenshot_dir,
-        brave_search_key,
+        search_backend,
         logs_dir,
     );
 
@@ -2760,8 +2876,10 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
     // stored in ChannelState. The inject_tx is dropped here — detached task
     // workers don't support mid-flight context injection.
     drop(inject_tx);
+    let worker = worker.with_label(&task.title);
 
     let worker_id = worker.id;
+    let label = worker.label.clone();
     let (detached_worker_lifecycle, mut detached_cancel_rx) = register_detached_worker_for_pickup(
         &deps.process_control_registry,
         deps.task_store.as_ref(),
@@ -2785,6 +2903,7 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
         worker_id,
         channel_id: None,
         task: task_description.clone(),
+        label: label.clone(),
         worker_type: "task".to_string(),
         interactive: false,
         directory: None,
@@ -2797,6 +2916,7 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
         None,
         worker_id,
         &task_description,
+        &label,
         "task",
         &deps.agent_id,
         false,
@@ -3545,13 +3665,13 @@ mod tests {
         BulletinRefreshOutcome, CortexReceiverOutcome, HealthRuntimeState,
         MAINTENANCE_TASK_CANCEL_GRACE_SECS, MaintenanceTimeoutAction, ReceiverClosedBehavior,
         Signal, WorkerTracker, apply_cancelled_warmup_status, build_kill_targets,
-        claim_detached_completion, detached_timeout_transition, handle_cortex_receiver_result,
-        has_completed_initial_warmup, is_cancelled_control_result, is_terminal_control_result,
-        maintenance_task_timeout, maintenance_timeout_action, maybe_close_bulletin_refresh_circuit,
-        maybe_generate_bulletin_under_lock, parse_structured_success_flag, push_signal_into_buffer,
-        record_bulletin_refresh_failure, should_execute_warmup,
-        should_generate_bulletin_from_bulletin_loop, signal_from_event, summarize_signal_text,
-        take_lagged_control_flag,
+        claim_detached_completion, detached_timeout_transition, gather_within_budget,
+        handle_cortex_receiver_result, has_completed_initial_warmup, is_cancelled_control_result,
+        is_terminal_control_result, maintenance_task_timeout, maintenance_timeout_action,
+        maybe_close_bulletin_refresh_circuit, maybe_generate_bulletin_under_lock,
+        parse_structured_success_flag, push_signal_into_buffer, record_bulletin_refresh_failure,
+        should_execute_warmup, should_generate_bulletin_from_bulletin_loop, signal_from_event,
+        summarize_signal_text, take_lagged_control_flag,
     };
     use crate::ProcessEvent;
     use crate::agent::process_control::ControlActionResult;
@@ -3828,6 +3948,7 @@ mod tests {
                 worker_id,
                 channel_id: Some(channel_id.clone()),
                 task: "do work".to_string(),
+                label: "do-work".to_string(),
                 worker_type: "shell".to_string(),
                 interactive: false,
                 directory: None,
@@ -3944,6 +4065,12 @@ mod tests {
                 channel_id: Some(channel_id.clone()),
                 result: "initial result".to_string(),
             },
+            ProcessEvent::WorkerClarification {
+                agent_id: Arc::from("agent"),
+                worker_id,
+                channel_id: Some(channel_id.clone()),
+                question: "which branch?".to_string(),
+            },
         ];
 
         for event in events {
@@ -4616,4 +4743,32 @@ mod tests {
             MAX_DROPPED_EVENTS_BUDGET
         );
     }
+
+    #[tokio::test]
+    async fn bulletin_gathering_skips_sources_that_exceed_the_budget() {
+        let started = Instant::now();
+        let outcomes = gather_within_budget(
+            [("identity", 0u64), ("slow", 60_000), ("recent", 10)],
+            2,
+            Duration::from_millis(200),
+            |(label, delay_ms)| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                label
+            },
+        )
+        .await;
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|((label, _), outcome)| (*label, *outcome))
+                .collect::<Vec<_>>(),
+            [
+                ("identity", Some("identity")),
+                ("slow", None),
+                ("recent", Some("recent")),
+            ]
+        );
+    }
 }
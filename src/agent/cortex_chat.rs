@@ -803,13 +803,17 @@ impl CortexChatSession {
         );
 
         let browser_enabled = runtime_config.browser_config.load().enabled;
-        let web_search_enabled = runtime_config.brave_search_key.load().is_some();
+        let web_search_enabled = runtime_config.web_search_enabled();
         let opencode_enabled = runtime_config.opencode.load().enabled;
         let mcp_tool_names = self.deps.mcp_manager.get_tool_names().await;
         let worker_capabilities = prompt_engine.render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            runtime_config.worker.load().http_request.enabled,
             opencode_enabled,
+            runtime_config.worker.load().memory_recall,
+            runtime_config.worker.load().tool_gating,
+            runtime_config.worker.load().tool_permissions,
             &mcp_tool_names,
         )?;
 
@@ -0,0 +1,209 @@
+//! Debug mirror for model reasoning.
+//!
+//! Subscribes to an agent's event bus and copies tool calls, tool results,
+//! and workers' intermediate text to a separate destination: a JSONL log on
+//! disk and, optionally, a messaging target such as a private Discord channel.
+//! Nothing here ever reaches the user-facing conversation. The loop checks
+//! `debug_mirror.enabled` on every event, so toggling it in config takes
+//! effect without a restart.
+
+use crate::config::DebugMirrorConfig;
+use crate::{AgentDeps, OutboundResponse, ProcessEvent};
+
+use std::io::Write as _;
+use tokio::sync::broadcast;
+
+/// Longest text copied into a messaging destination, in characters. The
+/// JSONL log always gets the full text.
+const MAX_TARGET_CHARS: usize = 1_500;
+
+/// Spawn the debug mirror loop for one agent.
+pub fn spawn_debug_mirror(deps: AgentDeps) -> tokio::task::JoinHandle<()> {
+    let mut event_rx = deps.event_tx.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let event = match event_rx.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "debug mirror lagged behind the event bus");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let config = deps.runtime_config.debug_mirror.load();
+            if !config.enabled {
+                continue;
+            }
+            let Some(entry) = mirror_entry(&event) else {
+                continue;
+            };
+
+            if let Some(log_path) = &config.log_path
+                && let Err(error) = append_entry(log_path, &entry)
+            {
+                tracing::warn!(%error, path = %log_path.display(), "failed to write debug mirror log");
+            }
+
+            forward_to_target(&deps, &config, &entry).await;
+        }
+    })
+}
+
+/// Build the mirrored record for an event, or `None` for events that carry
+/// no model reasoning.
+pub fn mirror_entry(event: &ProcessEvent) -> Option<serde_json::Value> {
+    let (agent_id, process_id, channel_id, kind, tool_name, text) = match event {
+        ProcessEvent::ToolStarted {
+            agent_id,
+            process_id,
+            channel_id,
+            tool_name,
+            args,
+        } => (
+            agent_id,
+            process_id.to_string(),
+            channel_id,
+            "tool_call",
+            Some(tool_name),
+            args,
+        ),
+        ProcessEvent::ToolCompleted {
+            agent_id,
+            process_id,
+            channel_id,
+            tool_name,
+            result,
+        } => (
+            agent_id,
+            process_id.to_string(),
+            channel_id,
+            "tool_result",
+            Some(tool_name),
+            result,
+        ),
+        ProcessEvent::WorkerText {
+            agent_id,
+            worker_id,
+            channel_id,
+            text,
+        } => (
+            agent_id,
+            format!("worker:{worker_id}"),
+            channel_id,
+            "text",
+            None,
+            text,
+        ),
+        _ => return None,
+    };
+
+    Some(serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "agent_id": agent_id.as_ref(),
+        "process_id": process_id,
+        "channel_id": channel_id.as_deref(),
+        "kind": kind,
+        "tool_name": tool_name,
+        "text": text,
+    }))
+}
+
+fn append_entry(path: &std::path::Path, entry: &serde_json::Value) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{entry}")
+}
+
+async fn forward_to_target(
+    deps: &AgentDeps,
+    config: &DebugMirrorConfig,
+    entry: &serde_json::Value,
+) {
+    let Some(raw_target) = config.target.as_deref() else {
+        return;
+    };
+    let Some(messaging) = &deps.messaging_manager else {
+        return;
+    };
+    let Some(target) = crate::messaging::target::parse_delivery_target(raw_target) else {
+        tracing::warn!(target = raw_target, "invalid debug mirror target");
+        return;
+    };
+
+    let line = summary_line(entry);
+    if let Err(error) = messaging
+        .broadcast(
+            &target.adapter,
+            &target.target,
+            OutboundResponse::Text(line),
+        )
+        .await
+    {
+        tracing::warn!(%error, %target, "failed to forward debug mirror entry");
+    }
+}
+
+/// One-line rendering of an entry for chat destinations.
+fn summary_line(entry: &serde_json::Value) -> String {
+    let field = |key: &str| {
+        entry
+            .get(key)
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+    };
+    let mut text: String = field("text").chars().take(MAX_TARGET_CHARS).collect();
+    if text.len() < field("text").len() {
+        text.push('…');
+    }
+
+    match entry.get("tool_name").and_then(|value| value.as_str()) {
+        Some(tool_name) => format!(
+            "[{}] {} `{tool_name}`: {text}",
+            field("process_id"),
+            field("kind")
+        ),
+        None => format!("[{}] {text}", field("process_id")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mirror_entry, summary_line};
+    use crate::ProcessEvent;
+    use std::sync::Arc;
+
+    #[test]
+    fn tool_calls_are_mirrored_and_unrelated_events_are_not() {
+        let worker_id = uuid::Uuid::new_v4();
+        let event = ProcessEvent::ToolStarted {
+            agent_id: Arc::from("main"),
+            process_id: crate::ProcessId::Worker(worker_id),
+            channel_id: Some(Arc::from("discord:1")),
+            tool_name: "shell".into(),
+            args: "{\"command\":\"ls\"}".into(),
+        };
+
+        let entry = mirror_entry(&event).expect("tool call should be mirrored");
+        assert_eq!(entry["kind"], "tool_call");
+        assert_eq!(entry["tool_name"], "shell");
+        assert_eq!(entry["channel_id"], "discord:1");
+        assert_eq!(
+            summary_line(&entry),
+            format!("[worker:{worker_id}] tool_call `shell`: {{\"command\":\"ls\"}}")
+        );
+
+        let status = ProcessEvent::WorkerStatus {
+            agent_id: Arc::from("main"),
+            worker_id,
+            channel_id: None,
+            status: "working".into(),
+        };
+        assert!(mirror_entry(&status).is_none());
+    }
+}
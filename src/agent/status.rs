@@ -62,9 +62,12 @@ impl SystemInfo {
         if rc.browser_config.load().enabled {
             capabilities.push("browser".to_string());
         }
-        if rc.brave_search_key.load().is_some() {
+        if rc.web_search_enabled() {
             capabilities.push("web_search".to_string());
         }
+        if rc.worker.load().http_request.enabled {
+            capabilities.push("http_request".to_string());
+        }
         if rc.opencode.load().enabled {
             capabilities.push("opencode".to_string());
         }
@@ -152,6 +155,8 @@ pub struct BranchStatus {
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct WorkerStatus {
     pub id: WorkerId,
+    /// Short human-readable name shown next to the ID.
+    pub label: String,
     pub task: String,
     pub status: String,
     pub started_at: DateTime<Utc>,
@@ -207,7 +212,10 @@ impl StatusBlock {
                     worker.status.clone_from(status);
                 }
             }
-            ProcessEvent::WorkerIdle { worker_id, .. } => {
+            // A worker waiting on a clarification answer is idle from the
+            // channel's point of view, so `route` delivers the answer as input.
+            ProcessEvent::WorkerIdle { worker_id, .. }
+            | ProcessEvent::WorkerClarification { worker_id, .. } => {
                 if let Some(worker) = self.active_workers.iter_mut().find(|w| w.id == *worker_id) {
                     worker.status = "idle".to_string();
                 }
@@ -307,12 +315,14 @@ impl StatusBlock {
     pub fn add_worker(
         &mut self,
         id: WorkerId,
+        label: impl Into<String>,
         task: impl Into<String>,
         notify_on_complete: bool,
         interactive: bool,
     ) {
         self.active_workers.push(WorkerStatus {
             id,
+            label: label.into(),
             task: task.into(),
             status: "starting".to_string(),
             started_at: Utc::now(),
@@ -386,8 +396,9 @@ impl StatusBlock {
                     String::new()
                 };
                 output.push_str(&format!(
-                    "- [{}] {} ({}{}): {}\n",
+                    "- [{}] {}: {} ({}{}): {}\n",
                     worker.id,
+                    worker.label,
                     worker.task,
                     worker.started_at.format("%H:%M"),
                     tool_calls_str,
@@ -646,7 +657,13 @@ mod tests {
     fn find_duplicate_exact_match() {
         let mut status = StatusBlock::new();
         let worker_id = Uuid::new_v4();
-        status.add_worker(worker_id, "Build a landing page", true, false);
+        status.add_worker(
+            worker_id,
+            "build-a-landing-page",
+            "Build a landing page",
+            true,
+            false,
+        );
 
         let found = status.find_duplicate_worker_task("Build a landing page");
         assert_eq!(found, Some(worker_id));
@@ -656,7 +673,13 @@ mod tests {
     fn find_duplicate_no_match() {
         let mut status = StatusBlock::new();
         let worker_id = Uuid::new_v4();
-        status.add_worker(worker_id, "Build a landing page", true, false);
+        status.add_worker(
+            worker_id,
+            "build-a-landing-page",
+            "Build a landing page",
+            true,
+            false,
+        );
 
         let found = status.find_duplicate_worker_task("Fix the CSS bug");
         assert_eq!(found, None);
@@ -666,7 +689,13 @@ mod tests {
     fn find_duplicate_strips_opencode_prefix() {
         let mut status = StatusBlock::new();
         let worker_id = Uuid::new_v4();
-        status.add_worker(worker_id, "[opencode] Build a landing page", true, false);
+        status.add_worker(
+            worker_id,
+            "build-a-landing-page",
+            "[opencode] Build a landing page",
+            true,
+            false,
+        );
 
         // Should match without the prefix
         let found = status.find_duplicate_worker_task("Build a landing page");
@@ -681,7 +710,13 @@ mod tests {
     fn find_duplicate_strips_opencode_prefix_in_query() {
         let mut status = StatusBlock::new();
         let worker_id = Uuid::new_v4();
-        status.add_worker(worker_id, "Build a landing page", true, false);
+        status.add_worker(
+            worker_id,
+            "build-a-landing-page",
+            "Build a landing page",
+            true,
+            false,
+        );
 
         // Querying with prefix should still find the non-prefixed worker
         let found = status.find_duplicate_worker_task("[opencode] Build a landing page");
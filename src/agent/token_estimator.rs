@@ -0,0 +1,138 @@
+//! Token counting for context-usage checks.
+//!
+//! Compaction thresholds and overflow recovery compare an estimate of the
+//! history's size against the context window. The default chars/4 heuristic
+//! is cheap but drifts from real tokenization, most with code, non-English
+//! text and JSON tool results. With the `tokenizer` feature, models that
+//! have a known BPE encoding are counted with it instead; every other model
+//! keeps the heuristic.
+
+use crate::agent::compactor::estimate_history_tokens;
+
+use rig::message::Message;
+
+/// Estimates how many tokens a history takes up in a model's context.
+pub trait TokenEstimator: Send + Sync {
+    fn estimate_history(&self, history: &[Message]) -> usize;
+}
+
+/// About four characters per token. Overestimates slightly for English,
+/// which is the safe direction for compaction triggers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicEstimator;
+
+impl TokenEstimator for HeuristicEstimator {
+    fn estimate_history(&self, history: &[Message]) -> usize {
+        estimate_history_tokens(history)
+    }
+}
+
+/// The estimator for `model_name` (`provider/model` or bare), falling back
+/// to [`HeuristicEstimator`] when the model has no known encoding.
+pub fn token_estimator_for(
+    #[cfg_attr(not(feature = "tokenizer"), allow(unused_variables))] model_name: &str,
+) -> &'static dyn TokenEstimator {
+    #[cfg(feature = "tokenizer")]
+    if let Some(estimator) = bpe::estimator_for(model_name) {
+        return estimator;
+    }
+    &HeuristicEstimator
+}
+
+#[cfg(feature = "tokenizer")]
+mod bpe {
+    use super::TokenEstimator;
+    use crate::agent::compactor::measure_message;
+
+    use rig::message::Message;
+    use std::sync::LazyLock;
+    use tiktoken_rs::CoreBPE;
+    use tiktoken_rs::tokenizer::{Tokenizer, get_tokenizer};
+
+    /// Counts text with a BPE encoding. Parts with no text to encode keep
+    /// the heuristic's four characters per token.
+    pub(super) struct BpeEstimator(CoreBPE);
+
+    impl TokenEstimator for BpeEstimator {
+        fn estimate_history(&self, history: &[Message]) -> usize {
+            history
+                .iter()
+                .map(|message| {
+                    measure_message(
+                        message,
+                        &|text| self.0.encode_ordinary(text).len(),
+                        &|chars| chars / 4,
+                    )
+                })
+                .sum()
+        }
+    }
+
+    // Encodings are large, so each is built once, on first use.
+    static O200K: LazyLock<Option<BpeEstimator>> =
+        LazyLock::new(|| load("o200k_base", tiktoken_rs::o200k_base()));
+    static CL100K: LazyLock<Option<BpeEstimator>> =
+        LazyLock::new(|| load("cl100k_base", tiktoken_rs::cl100k_base()));
+
+    fn load(name: &str, encoding: anyhow::Result<CoreBPE>) -> Option<BpeEstimator> {
+        match encoding {
+            Ok(bpe) => Some(BpeEstimator(bpe)),
+            Err(error) => {
+                tracing::warn!(encoding = name, %error, "failed to load BPE encoding, using the chars/4 heuristic");
+                None
+            }
+        }
+    }
+
+    pub(super) fn estimator_for(model_name: &str) -> Option<&'static dyn TokenEstimator> {
+        let model = model_name
+            .split_once('/')
+            .map_or(model_name, |(_, model)| model);
+        let estimator = match get_tokenizer(model)? {
+            Tokenizer::O200kBase => O200K.as_ref(),
+            Tokenizer::Cl100kBase => CL100K.as_ref(),
+            _ => None,
+        };
+        estimator.map(|estimator| estimator as &'static dyn TokenEstimator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::token_estimator_for;
+    use crate::agent::compactor::estimate_history_tokens;
+    use rig::message::Message;
+
+    #[test]
+    fn models_without_an_encoding_use_the_heuristic() {
+        let history = vec![
+            Message::from("Summarise the last three deploys and flag any rollbacks."),
+            Message::from("Two deploys went out cleanly; the third was rolled back."),
+        ];
+        for model in ["anthropic/claude-haiku-4.5-20250514", "no-such-model", ""] {
+            assert_eq!(
+                token_estimator_for(model).estimate_history(&history),
+                estimate_history_tokens(&history)
+            );
+        }
+    }
+
+    #[cfg(feature = "tokenizer")]
+    #[test]
+    fn models_with_an_encoding_are_counted_with_it() {
+        // These encodings split numbers into runs of at most three digits,
+        // so a ten-digit ID is four tokens where the heuristic counts 2.5.
+        let history = vec![Message::from("1234567890 ".repeat(50))];
+        let heuristic = estimate_history_tokens(&history);
+        for model in ["openai/gpt-4o", "gpt-4o-mini", "openai/gpt-4"] {
+            assert!(
+                token_estimator_for(model).estimate_history(&history) > heuristic,
+                "{model}"
+            );
+        }
+        assert_eq!(
+            token_estimator_for("anthropic/claude-sonnet-4").estimate_history(&history),
+            heuristic
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Tool-call ID repair for Rig histories.
+//!
+//! Providers correlate each tool result with its call by ID. Some providers
+//! reuse IDs across turns or emit the same placeholder for every call, and
+//! retries or compaction can leave a call without its result (or a result
+//! without its call). Any of these gets the next request rejected as invalid
+//! messages, which lands in the hard-error arm and rolls the turn back.
+//! [`repair_tool_call_ids`] runs before each prompt and after compaction to
+//! put history back into a shape every provider accepts.
+
+use crate::ProcessId;
+use crate::config::ToolCallRepair;
+
+use rig::OneOrMany;
+use rig::message::{AssistantContent, Message, UserContent};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// What a repair pass changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToolCallRepairReport {
+    /// Repeated calls that were given a fresh ID.
+    pub regenerated: usize,
+    /// Repeated calls dropped together with their results.
+    pub deduplicated: usize,
+    /// Calls dropped because no result followed them.
+    pub orphaned_calls: usize,
+    /// Results dropped because no call preceded them.
+    pub orphaned_results: usize,
+}
+
+impl ToolCallRepairReport {
+    /// True when the history needed no changes.
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Planned change for one tool call or tool result, keyed by message and
+/// content index.
+enum Fate {
+    Rename(String),
+    Drop,
+}
+
+type Position = (usize, usize);
+
+/// Repair duplicate and orphaned tool-call IDs in place.
+///
+/// Calls and results are paired in order per ID, the same way providers read
+/// them. A call whose ID was already used earlier in history is a duplicate:
+/// `Regenerate` gives it a fresh ID and re-points its result, `Dedup` drops
+/// both. Calls left without a result and results without a call are dropped,
+/// and messages left empty by that are removed.
+pub fn repair_tool_call_ids(
+    history: &mut Vec<Message>,
+    mode: ToolCallRepair,
+) -> ToolCallRepairReport {
+    let mut report = ToolCallRepairReport::default();
+    if mode == ToolCallRepair::Off {
+        return report;
+    }
+
+    let mut taken: HashSet<String> = HashSet::new();
+    for message in history.iter() {
+        if let Message::Assistant { content, .. } = message {
+            for item in content.iter() {
+                if let AssistantContent::ToolCall(call) = item {
+                    taken.insert(correlation_key(&call.id, call.call_id.as_deref()).to_string());
+                }
+            }
+        }
+    }
+
+    let mut fates: HashMap<Position, Fate> = HashMap::new();
+    let mut duplicates: HashSet<Position> = HashSet::new();
+    let mut call_counts: HashMap<String, usize> = HashMap::new();
+    let mut pending: HashMap<String, VecDeque<Position>> = HashMap::new();
+
+    for (message_index, message) in history.iter().enumerate() {
+        match message {
+            Message::Assistant { content, .. } => {
+                for (item_index, item) in content.iter().enumerate() {
+                    let AssistantContent::ToolCall(call) = item else {
+                        continue;
+                    };
+                    let position = (message_index, item_index);
+                    let key = correlation_key(&call.id, call.call_id.as_deref()).to_string();
+                    let count = call_counts.entry(key.clone()).or_default();
+                    *count += 1;
+
+                    if *count > 1 {
+                        duplicates.insert(position);
+                        let fate = match mode {
+                            ToolCallRepair::Dedup => Fate::Drop,
+                            _ => Fate::Rename(fresh_id(&key, *count, &mut taken)),
+                        };
+                        fates.insert(position, fate);
+                    }
+                    pending.entry(key).or_default().push_back(position);
+                }
+            }
+            Message::User { content } => {
+                for (item_index, item) in content.iter().enumerate() {
+                    let UserContent::ToolResult(result) = item else {
+                        continue;
+                    };
+                    let position = (message_index, item_index);
+                    let key = correlation_key(&result.id, result.call_id.as_deref());
+
+                    let Some(call_position) = pending.get_mut(key).and_then(VecDeque::pop_front)
+                    else {
+                        fates.insert(position, Fate::Drop);
+                        report.orphaned_results += 1;
+                        continue;
+                    };
+                    // The result follows whatever happened to its call.
+                    let inherited = match fates.get(&call_position) {
+                        Some(Fate::Rename(new_id)) => Some(Fate::Rename(new_id.clone())),
+                        Some(Fate::Drop) => Some(Fate::Drop),
+                        None => None,
+                    };
+                    if let Some(fate) = inherited {
+                        fates.insert(position, fate);
+                    }
+                }
+            }
+        }
+    }
+
+    for position in pending.into_values().flatten() {
+        if !matches!(fates.get(&position), Some(Fate::Drop)) {
+            fates.insert(position, Fate::Drop);
+            duplicates.remove(&position);
+            report.orphaned_calls += 1;
+        }
+    }
+    for position in &duplicates {
+        match fates.get(position) {
+            Some(Fate::Rename(_)) => report.regenerated += 1,
+            Some(Fate::Drop) => report.deduplicated += 1,
+            None => {}
+        }
+    }
+
+    if fates.is_empty() {
+        return report;
+    }
+
+    let messages = std::mem::take(history);
+    for (message_index, message) in messages.into_iter().enumerate() {
+        match message {
+            Message::Assistant { id, content } => {
+                let items: Vec<AssistantContent> = content
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(item_index, mut item)| {
+                        match fates.remove(&(message_index, item_index)) {
+                            Some(Fate::Drop) => return None,
+                            Some(Fate::Rename(new_id)) => {
+                                if let AssistantContent::ToolCall(call) = &mut item {
+                                    set_correlation_key(&mut call.id, &mut call.call_id, new_id);
+                                }
+                            }
+                            None => {}
+                        }
+                        Some(item)
+                    })
+                    .collect();
+                if let Ok(content) = OneOrMany::many(items) {
+                    history.push(Message::Assistant { id, content });
+                }
+            }
+            Message::User { content } => {
+                let items: Vec<UserContent> = content
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(item_index, mut item)| {
+                        match fates.remove(&(message_index, item_index)) {
+                            Some(Fate::Drop) => return None,
+                            Some(Fate::Rename(new_id)) => {
+                                if let UserContent::ToolResult(result) = &mut item {
+                                    set_correlation_key(
+                                        &mut result.id,
+                                        &mut result.call_id,
+                                        new_id,
+                                    );
+                                }
+                            }
+                            None => {}
+                        }
+                        Some(item)
+                    })
+                    .collect();
+                if let Ok(content) = OneOrMany::many(items) {
+                    history.push(Message::User { content });
+                }
+            }
+        }
+    }
+
+    report
+}
+
+/// Run [`repair_tool_call_ids`] and log what it fixed.
+pub fn repair_and_log(
+    history: &mut Vec<Message>,
+    mode: ToolCallRepair,
+    process_id: &ProcessId,
+) -> ToolCallRepairReport {
+    let report = repair_tool_call_ids(history, mode);
+    if !report.is_clean() {
+        tracing::warn!(
+            %process_id,
+            regenerated = report.regenerated,
+            deduplicated = report.deduplicated,
+            orphaned_calls = report.orphaned_calls,
+            orphaned_results = report.orphaned_results,
+            "repaired tool-call IDs in history"
+        );
+    }
+    report
+}
+
+/// The ID providers correlate on: `call_id` when set, `id` otherwise. Matches
+/// what the OpenAI adapters in `llm::model` send.
+fn correlation_key<'a>(id: &'a str, call_id: Option<&'a str>) -> &'a str {
+    call_id.unwrap_or(id)
+}
+
+fn set_correlation_key(id: &mut String, call_id: &mut Option<String>, new_id: String) {
+    match call_id {
+        Some(call_id) => *call_id = new_id,
+        None => *id = new_id,
+    }
+}
+
+/// A new ID derived from `key` that no other call in history uses.
+fn fresh_id(key: &str, occurrence: usize, taken: &mut HashSet<String>) -> String {
+    let mut suffix = occurrence;
+    loop {
+        let candidate = format!("{key}_{suffix}");
+        if taken.insert(candidate.clone()) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ToolCallRepairReport, repair_tool_call_ids};
+    use crate::config::ToolCallRepair;
+    use rig::OneOrMany;
+    use rig::message::{AssistantContent, Message, ToolResult, ToolResultContent, UserContent};
+
+    fn call(id: &str) -> Message {
+        Message::Assistant {
+            id: None,
+            content: OneOrMany::one(AssistantContent::tool_call(
+                id,
+                "shell",
+                serde_json::json!({"command": "ls"}),
+            )),
+        }
+    }
+
+    fn result(id: &str, text: &str) -> Message {
+        Message::User {
+            content: OneOrMany::one(UserContent::ToolResult(ToolResult {
+                id: id.to_string(),
+                call_id: None,
+                content: OneOrMany::one(ToolResultContent::text(text)),
+            })),
+        }
+    }
+
+    fn ids(history: &[Message]) -> Vec<String> {
+        history
+            .iter()
+            .flat_map(|message| match message {
+                Message::Assistant { content, .. } => content
+                    .iter()
+                    .filter_map(|item| match item {
+                        AssistantContent::ToolCall(call) => Some(format!("call:{}", call.id)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+                Message::User { content } => content
+                    .iter()
+                    .filter_map(|item| match item {
+                        UserContent::ToolResult(result) => Some(format!("result:{}", result.id)),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_ids_are_regenerated_and_results_follow_their_call() {
+        let mut history = vec![
+            call("call_0"),
+            result("call_0", "first"),
+            call("call_0"),
+            result("call_0", "second"),
+        ];
+
+        let report = repair_tool_call_ids(&mut history, ToolCallRepair::Regenerate);
+
+        assert_eq!(report.regenerated, 1);
+        assert_eq!(
+            ids(&history),
+            [
+                "call:call_0",
+                "result:call_0",
+                "call:call_0_2",
+                "result:call_0_2"
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_ids_are_dropped_with_their_results_in_dedup_mode() {
+        let mut history = vec![
+            call("call_0"),
+            result("call_0", "first"),
+            call("call_0"),
+            result("call_0", "second"),
+        ];
+
+        let report = repair_tool_call_ids(&mut history, ToolCallRepair::Dedup);
+
+        assert_eq!(report.deduplicated, 1);
+        assert_eq!(ids(&history), ["call:call_0", "result:call_0"]);
+    }
+
+    #[test]
+    fn orphaned_calls_and_results_are_dropped() {
+        // A compaction that cut between a call and its result leaves the
+        // result at the front; a cancelled turn leaves a call at the end.
+        let mut history = vec![
+            result("call_a", "cut off from its call"),
+            Message::from("[Compaction Summary]: earlier work".to_string()),
+            call("call_b"),
+            result("call_b", "ok"),
+            call("call_c"),
+        ];
+
+        let report = repair_tool_call_ids(&mut history, ToolCallRepair::Regenerate);
+
+        assert_eq!(
+            report,
+            ToolCallRepairReport {
+                orphaned_calls: 1,
+                orphaned_results: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(history.len(), 3);
+        assert_eq!(ids(&history), ["call:call_b", "result:call_b"]);
+    }
+
+    #[test]
+    fn off_mode_and_clean_history_are_left_untouched() {
+        let clean = vec![call("call_a"), result("call_a", "ok")];
+        let mut history = clean.clone();
+        assert!(repair_tool_call_ids(&mut history, ToolCallRepair::Regenerate).is_clean());
+        assert_eq!(history, clean);
+
+        let mut broken = vec![result("call_x", "orphan")];
+        assert!(repair_tool_call_ids(&mut broken, ToolCallRepair::Off).is_clean());
+        assert_eq!(broken.len(), 1);
+    }
+}
@@ -1,20 +1,120 @@
 //! Worker: Independent task execution process.
 
-use crate::agent::compactor::estimate_history_tokens;
-use crate::config::BrowserConfig;
-use crate::error::Result;
-use crate::hooks::SpacebotHook;
+use crate::agent::compactor::truncate_oversized_messages;
+use crate::agent::token_estimator::{TokenEstimator, token_estimator_for};
+use crate::agent::worker_log;
+use crate::config::{
+    BrowserConfig, CompactionStrategy, FollowUpMode, RecapPriority, TaskOverflowPolicy,
+    WorkerConfig,
+};
+use crate::conversation::transcript_sink::{TranscriptKind, TranscriptOutcome, TranscriptRecord};
+use crate::error::{AgentError, Result};
+use crate::hooks::{SpacebotHook, TokenUsage, ToolNudgePolicy, TouchedResources};
 use crate::llm::SpacebotModel;
-use crate::llm::routing::{is_context_overflow_error, is_retriable_error};
-use crate::{AgentDeps, ChannelId, ProcessId, ProcessType, WorkerId};
+use crate::llm::routing::{
+    MAX_RETRY_AFTER_WAIT, is_overloaded_error, is_retriable_error, parse_retry_after,
+};
+use crate::tools::set_progress::advance_progress;
+use crate::tools::{
+    AskClarificationTool, MemoryRecallTool, PlannedCall, SearchBackendDyn, ToolPermissions,
+    WorkerMode, WorkerToolset,
+};
+use crate::{AgentDeps, ChannelId, ProcessId, ProcessType, WorkerEvent, WorkerId};
 use rig::agent::AgentBuilder;
-use rig::completion::CompletionModel;
+use rig::completion::{CompletionModel, Prompt};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fmt::Write as _;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
 use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
 use uuid::Uuid;
 
+/// Most follow-ups merged into a single turn in [`FollowUpMode::Coalesce`].
+/// Later ones stay queued for the next turn.
+const MAX_COALESCED_FOLLOW_UPS: usize = 10;
+
+/// Whether an interactive worker has been handed off to finish in the
+/// background: the channel dropped its input sender and nothing is left to
+/// process. See `RouteTool`'s `continue_in_background`.
+fn handed_off(input_rx: &mpsc::Receiver<String>) -> bool {
+    input_rx.is_closed() && input_rx.is_empty()
+}
+
+/// Resolve once `true` is sent on a worker's cancel channel. Never resolves
+/// if the sender goes away without cancelling.
+async fn cancellation_requested(cancel_rx: &mut watch::Receiver<bool>) {
+    if cancel_rx.wait_for(|cancelled| *cancelled).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Resolve once an idle worker has waited `timeout` for a follow-up. Never
+/// resolves without a timeout.
+async fn idle_expired(timeout: Option<Duration>) {
+    match timeout {
+        Some(timeout) => tokio::time::sleep(timeout).await,
+        None => std::future::pending::<()>().await,
+    }
+}
+
+/// Reason recorded when a worker is stopped through its cancel channel.
+const CANCEL_REASON: &str = "cancellation requested";
+
+/// Longest wait for the compactor model under [`CompactionStrategy::Llm`]
+/// before compaction falls back to the programmatic recap.
+const COMPACTION_SUMMARY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long an LLM call may run past the worker's deadline before it is
+/// abandoned mid-call. Until then the deadline is only checked between
+/// segments.
+const DEADLINE_GRACE: Duration = Duration::from_secs(60);
+
+/// Await `prompt`, giving up on it [`DEADLINE_GRACE`] after `deadline`.
+/// `None` means the call was abandoned.
+async fn within_deadline<T>(
+    deadline: Option<Instant>,
+    prompt: impl Future<Output = T>,
+) -> Option<T> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline + DEADLINE_GRACE, prompt)
+            .await
+            .ok(),
+        None => Some(prompt.await),
+    }
+}
+
+/// Whether `deadline` has passed.
+fn past_deadline(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Pull every follow-up already queued on `input_rx` into `pending`, then keep
+/// waiting up to `debounce` after each one for the next, so a user who sends
+/// several messages in a row gets a single combined turn.
+async fn collect_follow_ups(
+    input_rx: &mut mpsc::Receiver<String>,
+    pending: &mut Vec<String>,
+    debounce: Duration,
+) {
+    while pending.len() < MAX_COALESCED_FOLLOW_UPS {
+        if let Ok(follow_up) = input_rx.try_recv() {
+            pending.push(follow_up);
+            continue;
+        }
+        if debounce.is_zero() {
+            break;
+        }
+        match tokio::time::timeout(debounce, input_rx.recv()).await {
+            Ok(Some(follow_up)) => pending.push(follow_up),
+            _ => break,
+        }
+    }
+}
+
 /// How many turns per segment before we check context and potentially compact.
 ///
 /// Kept relatively low so compaction checks run frequently. Fast models can
@@ -24,27 +124,72 @@ use uuid::Uuid;
 /// within a single segment.
 const TURNS_PER_SEGMENT: usize = 15;
 
-/// Max consecutive context overflow recoveries before giving up.
-/// Each retry dedup-strips stale tool results and force-compacts 75% of
-/// remaining messages. Two retries is enough to handle the edge case where
-/// a single message is enormous — beyond that, something is fundamentally
-/// broken (system prompt alone exceeds the context window, or the compaction
-/// floor of 4 messages is still too large).
-const MAX_OVERFLOW_RETRIES: usize = 2;
+/// Longest backoff between worker-level transient retries. Transient errors
+/// (upstream 500s, timeouts, rate limits that survived model-level retries)
+/// are retried `transient_retries` times so the worker survives temporary
+/// provider outages; the cap keeps a long retry budget from stalling it
+/// for hours.
+const TRANSIENT_RETRY_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Delay before the next worker-level transient retry: `base_secs` doubled
+/// per attempt up to [`TRANSIENT_RETRY_MAX_DELAY`]. A `Retry-After` hint
+/// carried in the error wins over the exponential schedule, bounded by
+/// [`MAX_RETRY_AFTER_WAIT`] so a provider can't park the worker.
+fn transient_retry_delay(error: &str, attempt: usize, base_secs: u64) -> std::time::Duration {
+    let doublings = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+    match parse_retry_after(error) {
+        Some(wait) => wait.min(MAX_RETRY_AFTER_WAIT),
+        None => std::time::Duration::from_secs(base_secs)
+            .saturating_mul(2u32.saturating_pow(doublings))
+            .min(TRANSIENT_RETRY_MAX_DELAY),
+    }
+}
+
+/// Count one more retry of a provider error against `max_retries` and return
+/// the backoff before it, or `None` once the budget is spent. Overloaded and
+/// transient errors each keep their own counter and base delay.
+fn next_retry_delay(
+    retries: &mut usize,
+    max_retries: usize,
+    error: &str,
+    base_secs: u64,
+) -> Option<std::time::Duration> {
+    *retries += 1;
+    (*retries <= max_retries).then(|| transient_retry_delay(error, *retries, base_secs))
+}
 
-/// Max consecutive transient provider error retries before giving up.
-/// Transient errors (upstream 500s, timeouts, rate limits that survived
-/// model-level retries) get a backoff-and-retry at the worker level so
-/// the worker survives temporary provider outages.
-const MAX_TRANSIENT_RETRIES: usize = 5;
+/// Used in place of `fragments/system/worker_overflow` when it fails to
+/// render, so a template bug can't stop overflow recovery.
+const FALLBACK_OVERFLOW_NOTICE: &str =
+    "[System: Previous attempt exceeded context limit. Older history has been compacted.]";
 
-/// Base delay for worker-level transient error backoff (doubles each retry).
-const TRANSIENT_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+/// Outcome of the capability check run before a worker's task.
+#[derive(Debug, PartialEq, Eq)]
+enum PreflightVerdict {
+    Ready,
+    Blocked(String),
+}
 
-/// Max segments before the worker gives up and returns a partial result.
-/// Prevents unbounded worker loops when the LLM keeps hitting max_turns
-/// without completing the task.
-const MAX_SEGMENTS: usize = 10;
+/// Read the check's reply. Only an explicit `BLOCKED` line stops the worker;
+/// anything else, including a reply that ignores the format, counts as ready.
+fn parse_preflight_verdict(response: &str) -> PreflightVerdict {
+    for line in response.lines() {
+        let line = line.trim().trim_matches(|c| c == '`' || c == '*').trim();
+        let Some(prefix) = line.get(..7) else {
+            continue;
+        };
+        if prefix.eq_ignore_ascii_case("blocked") {
+            let reason = line[7..].trim_start_matches([':', '*', '`']).trim();
+            let reason = if reason.is_empty() {
+                "no reason given"
+            } else {
+                reason
+            };
+            return PreflightVerdict::Blocked(reason.to_string());
+        }
+    }
+    PreflightVerdict::Ready
+}
 
 /// Worker state machine.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -59,11 +204,108 @@ pub enum WorkerState {
     Failed,
 }
 
+/// Final result of a completed worker run.
+#[derive(Debug, Clone)]
+pub struct WorkerOutcome {
+    /// The worker's final response text.
+    pub result: String,
+    /// Files and URLs the worker's tools touched during the run.
+    pub touched: TouchedResources,
+    /// Prompt tokens consumed by the run, as reported by the provider.
+    /// Calls whose provider reported no usage are estimated from history.
+    pub tokens_in: u64,
+    /// Completion tokens, reported or estimated like `tokens_in`.
+    pub tokens_out: u64,
+    /// Segments run for the initial task.
+    pub segments: usize,
+    /// Side-effecting tool calls a plan-only worker intended to make, in
+    /// order. Empty in execute mode.
+    pub planned_calls: Vec<PlannedCall>,
+}
+
+impl WorkerOutcome {
+    /// Render the outcome as the result text relayed to the channel, with a
+    /// provenance section appended when the worker touched anything and the
+    /// planned calls listed for plan-only workers.
+    pub fn into_result_text(self) -> String {
+        let mut text = match self.touched.summary() {
+            Some(summary) if !self.result.is_empty() => {
+                format!("{}\n\nActivity:\n{summary}", self.result)
+            }
+            _ => self.result,
+        };
+        if !self.planned_calls.is_empty() {
+            text.push_str("\n\nPlanned tool calls (not run):");
+            for call in &self.planned_calls {
+                text.push_str(&format!("\n- {} {}", call.tool, call.args));
+            }
+        }
+        text
+    }
+}
+
+/// Longest worker label, in characters.
+const MAX_LABEL_CHARS: usize = 40;
+
+/// Default label for a worker: a slug of the first few words of its task,
+/// e.g. `fix-the-login-redirect` for "Fix the login redirect on staging".
+/// `[opencode]` and similar prefixes are skipped. Falls back to `worker`.
+pub fn worker_label(task: &str) -> String {
+    let task = task.trim_start();
+    let task = match task.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+        Some((_, rest)) => rest,
+        None => task,
+    };
+    let label = slugify(task, 4);
+    if label.is_empty() {
+        "worker".to_string()
+    } else {
+        label
+    }
+}
+
+/// Label for a worker spawned with an optional explicit `label`: the label
+/// slugged, or [`worker_label`] of the task when none is given or it slugs to
+/// nothing.
+pub fn resolve_worker_label(label: Option<&str>, task: &str) -> String {
+    label
+        .map(|label| slugify(label, usize::MAX))
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| worker_label(task))
+}
+
+/// Lowercase ASCII-alphanumeric words of `text`, at most `max_words` of
+/// them, joined with `-` and cut to [`MAX_LABEL_CHARS`] on a word boundary.
+fn slugify(text: &str, max_words: usize) -> String {
+    let mut slug = String::new();
+    let words = text
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(max_words);
+    for word in words {
+        let separator = usize::from(!slug.is_empty());
+        if slug.len() + separator + word.len() > MAX_LABEL_CHARS {
+            if slug.is_empty() {
+                slug.push_str(&word[..MAX_LABEL_CHARS].to_ascii_lowercase());
+            }
+            break;
+        }
+        if separator == 1 {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug
+}
+
 /// A worker process that executes tasks independently.
 pub struct Worker {
     pub id: WorkerId,
     pub channel_id: Option<ChannelId>,
     pub task: String,
+    /// Short human-readable name shown next to the id in status, logs and
+    /// listings. Cosmetic; `id` stays the key.
+    pub label: String,
     pub state: WorkerState,
     pub deps: AgentDeps,
     pub hook: SpacebotHook,
@@ -81,15 +323,292 @@ pub struct Worker {
     pub browser_config: BrowserConfig,
     /// Directory for browser screenshots.
     pub screenshot_dir: PathBuf,
-    /// Brave Search API key for web search tool.
-    pub brave_search_key: Option<String>,
+    /// Backend for the web search tool. `None` leaves the tool out.
+    pub search_backend: Option<Arc<dyn SearchBackendDyn>>,
     /// Directory for writing execution logs on failure.
     pub logs_dir: PathBuf,
     /// Status updates.
     pub status_tx: watch::Sender<String>,
     pub status_rx: watch::Receiver<String>,
+    /// Percent done, 0-100. Raised by the `set_progress` tool and by a
+    /// floor derived from segments run; it never goes backwards.
+    pub progress_tx: watch::Sender<u8>,
+    pub progress_rx: watch::Receiver<u8>,
+    /// Context pressure, updated at segment boundaries and compactions.
+    pub telemetry_tx: watch::Sender<WorkerTelemetry>,
+    pub telemetry_rx: watch::Receiver<WorkerTelemetry>,
+    /// Cancellation control. Sending `true` stops the worker between
+    /// segments, once the current prompt returns, or while it waits for
+    /// follow-up input. See [`Worker::cancel_handle`].
+    pub cancel_tx: watch::Sender<bool>,
+    cancel_rx: watch::Receiver<bool>,
+    /// Wall-clock limit on the run, from `max_duration_secs` unless set with
+    /// `with_max_duration`. Each interactive follow-up gets a fresh budget.
+    pub max_duration: Option<Duration>,
+    /// How long an interactive worker waits for a follow-up before it
+    /// finishes, from `idle_timeout_secs` unless set with `with_idle_timeout`.
+    pub idle_timeout: Option<Duration>,
+    /// Messages compaction must keep, matched by content (set by `pin`).
+    /// The task message is always pinned on top of these.
+    pinned: Vec<rig::message::Message>,
     /// Prior conversation history for resumed workers (set by `resume_interactive`).
     pub prior_history: Option<Vec<rig::message::Message>>,
+    /// History reloaded from a checkpoint (set by `resume`). Unlike
+    /// `prior_history`, the initial task continues from it.
+    restored_history: Option<Vec<rig::message::Message>>,
+    /// Answers to clarifying questions (set by `with_clarifications`). When
+    /// present the worker gets the `ask_clarification` tool.
+    pub clarification_rx: Option<mpsc::Receiver<String>>,
+    /// Whether the worker gets the read-only `memory_recall` tool (set by
+    /// `with_memory_recall`).
+    pub memory_recall: bool,
+    /// Optional tools (shell, browser, web search) this worker gets (set by
+    /// `with_toolset`). Everything by default.
+    pub toolset: WorkerToolset,
+    /// Hard cap on the tools this worker may use, whatever its toolset asks
+    /// for. Starts from `worker.tool_permissions` and only narrows (see
+    /// `with_permissions`).
+    pub permissions: ToolPermissions,
+    /// Answer with plain completions and no tool server at all (set by
+    /// `with_direct_answer`).
+    pub direct: bool,
+    /// Whether side-effecting tools run or are only recorded as a plan (set
+    /// by `with_mode`).
+    pub mode: WorkerMode,
+    /// Model to run on instead of the worker route (set by
+    /// `with_model_override`).
+    model_override: Option<String>,
+    /// Token counting for the model this worker runs on, behind the
+    /// compaction thresholds and telemetry.
+    token_estimator: &'static dyn TokenEstimator,
+    /// Isolated working directory created for this run, when
+    /// `isolated_workdir` is enabled and the directory isn't retained.
+    workdir: Option<WorkdirGuard>,
+    /// Counters for the summary line logged when the worker terminates.
+    run_stats: WorkerRunStats,
+    /// Latest full history, refreshed whenever the transcript is persisted.
+    transcript: WorkerTranscript,
+}
+
+/// Owns a worker's isolated working directory and deletes it when dropped.
+///
+/// Held on the [`Worker`], so the directory goes away however the run ends:
+/// a cancelled worker is aborted mid-await and only its drop runs. Removal
+/// happens on the blocking pool so a large tree doesn't stall the runtime.
+#[derive(Debug)]
+struct WorkdirGuard {
+    worker_id: WorkerId,
+    path: PathBuf,
+}
+
+impl Drop for WorkdirGuard {
+    fn drop(&mut self) {
+        let worker_id = self.worker_id;
+        let path = std::mem::take(&mut self.path);
+        let remove = move || {
+            if let Err(error) = std::fs::remove_dir_all(&path)
+                && error.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!(
+                    %worker_id,
+                    %error,
+                    path = %path.display(),
+                    "failed to remove worker working directory"
+                );
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn_blocking(remove);
+            }
+            Err(_) => remove(),
+        }
+    }
+}
+
+/// Finishes a run whose future is dropped before `run_to_completion` ends.
+///
+/// Cancelling a worker aborts its task mid-await, so nothing after the
+/// pending await runs. This guard lives in the run's future and, unless
+/// disarmed, archives the transcript so far as cancelled when dropped.
+struct AbortedRunCleanup {
+    deps: AgentDeps,
+    transcript: WorkerTranscript,
+    armed: bool,
+}
+
+impl AbortedRunCleanup {
+    /// The run ended on its own and cleans up with its real outcome.
+    fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for AbortedRunCleanup {
+    fn drop(&mut self) {
+        // Archiving spawns an upload, which needs a runtime. A run dropped
+        // while the runtime shuts down has nowhere to send it.
+        if !self.armed || tokio::runtime::Handle::try_current().is_err() {
+            return;
+        }
+        archive_transcript(
+            &self.deps,
+            &self.transcript,
+            TranscriptOutcome::Failure {
+                error: "worker cancelled".to_string(),
+            },
+        );
+    }
+}
+
+/// Hand a worker's transcript to the configured archive, if any. Runs in
+/// the background and never fails the worker.
+fn archive_transcript(deps: &AgentDeps, transcript: &WorkerTranscript, outcome: TranscriptOutcome) {
+    let Some(archive) = deps.runtime_config.worker.load().transcript_archive.clone() else {
+        return;
+    };
+    let record = TranscriptRecord::new(
+        TranscriptKind::Worker,
+        transcript.worker_id.to_string(),
+        deps.agent_id.to_string(),
+        transcript.channel_id.as_ref().map(|id| id.to_string()),
+        Some(transcript.task.clone()),
+        outcome,
+        &transcript.snapshot(),
+    );
+    crate::conversation::transcript_sink::archive(
+        &archive,
+        deps.llm_manager.http_client(),
+        &deps.runtime_config.instance_dir,
+        record,
+    );
+}
+
+/// Live context-pressure snapshot published on [`Worker::telemetry_rx`].
+///
+/// Unlike the status label this is structured, so a monitoring view can plot
+/// a worker's token usage over time and mark where compactions happened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+pub struct WorkerTelemetry {
+    /// Segments started so far, counting the current one.
+    pub segment: usize,
+    /// Estimated tokens in the live history.
+    pub estimated_tokens: usize,
+    pub context_window: usize,
+    /// `estimated_tokens / context_window`, 0.0 when the window is unknown.
+    pub context_usage: f32,
+    /// Compactions performed so far.
+    pub compactions: usize,
+}
+
+impl WorkerTelemetry {
+    fn measure(
+        estimator: &dyn TokenEstimator,
+        history: &[rig::message::Message],
+        context_window: usize,
+        segment: usize,
+        compactions: usize,
+    ) -> Self {
+        let estimated_tokens = estimator.estimate_history(history);
+        let context_usage = if context_window == 0 {
+            0.0
+        } else {
+            estimated_tokens as f32 / context_window as f32
+        };
+        Self {
+            segment,
+            estimated_tokens,
+            context_window,
+            context_usage,
+            compactions,
+        }
+    }
+}
+
+/// Run counters reported in the worker's termination summary.
+///
+/// Atomics because they are bumped through `&self` from compaction and
+/// transcript persistence, which run while the worker future holds borrows.
+#[derive(Debug, Default)]
+struct WorkerRunStats {
+    segments: AtomicUsize,
+    compactions: AtomicUsize,
+    tool_calls: AtomicUsize,
+    estimated_tokens: AtomicUsize,
+    /// While running, estimates for segment and follow-up calls whose
+    /// provider reported no usage. `run_to_completion` adds the reported
+    /// usage when the run ends, making these the run's totals.
+    tokens_in: AtomicU64,
+    tokens_out: AtomicU64,
+}
+
+/// Cloneable handle to a worker's latest history.
+///
+/// The worker itself is moved into its task by `run()`, so anything that
+/// wants the transcript while it runs (or sits paused waiting for follow-up
+/// input) takes a handle first via [`Worker::transcript_handle`]. The
+/// snapshot is refreshed at every transcript persistence point, i.e. segment
+/// boundaries, follow-up turns, and termination.
+#[derive(Debug, Clone)]
+pub struct WorkerTranscript {
+    worker_id: WorkerId,
+    channel_id: Option<ChannelId>,
+    task: String,
+    history: Arc<std::sync::Mutex<Vec<rig::message::Message>>>,
+}
+
+impl WorkerTranscript {
+    /// Render the current history as the plain-text log format used by the
+    /// success and failure logs.
+    pub fn export(&self) -> String {
+        self.render(None)
+    }
+
+    /// Render the current history as JSON transcript steps, the same shape
+    /// that is persisted to `worker_runs.transcript`.
+    pub fn export_json(&self) -> serde_json::Value {
+        let steps = crate::conversation::worker_transcript::convert_history(&self.snapshot());
+        serde_json::json!({
+            "worker_id": self.worker_id.to_string(),
+            "channel_id": self.channel_id.as_deref(),
+            "task": self.task,
+            "exported_at": chrono::Utc::now().to_rfc3339(),
+            "steps": steps,
+        })
+    }
+
+    fn render(&self, state: Option<WorkerState>) -> String {
+        let history = self.snapshot();
+        let mut log = String::with_capacity(4096);
+        let _ = writeln!(log, "=== Worker Transcript ===");
+        let _ = writeln!(log, "Worker ID: {}", self.worker_id);
+        if let Some(channel_id) = &self.channel_id {
+            let _ = writeln!(log, "Channel ID: {channel_id}");
+        }
+        let _ = writeln!(log, "Timestamp: {}", chrono::Utc::now().to_rfc3339());
+        if let Some(state) = state {
+            let _ = writeln!(log, "State: {state:?}");
+        }
+        let _ = writeln!(log);
+        let _ = writeln!(log, "--- Task ---");
+        let _ = writeln!(log, "{}", self.task);
+        format_history_log(&mut log, &history);
+        log
+    }
+
+    fn snapshot(&self) -> Vec<rig::message::Message> {
+        self.history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    fn update(&self, full_history: Vec<rig::message::Message>) {
+        *self
+            .history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = full_history;
+    }
 }
 
 impl Worker {
@@ -101,7 +620,7 @@ impl Worker {
         deps: AgentDeps,
         browser_config: BrowserConfig,
         screenshot_dir: PathBuf,
-        brave_search_key: Option<String>,
+        search_backend: Option<Arc<dyn SearchBackendDyn>>,
         logs_dir: PathBuf,
         input_rx: Option<mpsc::Receiver<String>>,
     ) -> (Self, mpsc::Sender<String>) {
@@ -113,15 +632,45 @@ impl Worker {
             ProcessType::Worker,
             channel_id.clone(),
             deps.event_tx.clone(),
-        );
+        )
+        .with_tool_call_repair(deps.runtime_config.compaction.load().tool_call_repair);
         let (status_tx, status_rx) = watch::channel("starting".to_string());
+        let (progress_tx, progress_rx) = watch::channel(0);
+        let token_estimator = token_estimator_for(
+            deps.runtime_config
+                .routing
+                .load()
+                .resolve(ProcessType::Worker, None),
+        );
+        let (telemetry_tx, telemetry_rx) = watch::channel(WorkerTelemetry::default());
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let worker_config = deps.runtime_config.worker.load();
+        let max_duration = match worker_config.max_duration_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        };
+        let idle_timeout = match worker_config.idle_timeout_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        };
+        let permissions = worker_config.tool_permissions;
+        drop(worker_config);
         let (inject_tx, inject_rx) = mpsc::channel(8);
+        let task = task.into();
+        let label = worker_label(&task);
+        let transcript = WorkerTranscript {
+            worker_id: id,
+            channel_id: channel_id.clone(),
+            task: task.clone(),
+            history: Arc::default(),
+        };
 
         (
             Self {
                 id,
                 channel_id,
-                task: task.into(),
+                task,
+                label,
                 state: WorkerState::Running,
                 deps,
                 hook,
@@ -130,11 +679,32 @@ impl Worker {
                 inject_rx: Some(inject_rx),
                 browser_config,
                 screenshot_dir,
-                brave_search_key,
+                search_backend,
                 logs_dir,
                 status_tx,
                 status_rx,
+                progress_tx,
+                progress_rx,
+                telemetry_tx,
+                telemetry_rx,
+                cancel_tx,
+                cancel_rx,
+                max_duration,
+                idle_timeout,
+                pinned: Vec::new(),
                 prior_history: None,
+                restored_history: None,
+                clarification_rx: None,
+                memory_recall: false,
+                toolset: WorkerToolset::default(),
+                permissions,
+                direct: false,
+                mode: WorkerMode::Execute,
+                model_override: None,
+                token_estimator,
+                workdir: None,
+                run_stats: WorkerRunStats::default(),
+                transcript,
             },
             inject_tx,
         )
@@ -153,7 +723,7 @@ impl Worker {
         deps: AgentDeps,
         browser_config: BrowserConfig,
         screenshot_dir: PathBuf,
-        brave_search_key: Option<String>,
+        search_backend: Option<Arc<dyn SearchBackendDyn>>,
         logs_dir: PathBuf,
     ) -> (Self, mpsc::Sender<String>) {
         Self::build(
@@ -163,7 +733,7 @@ impl Worker {
             deps,
             browser_config,
             screenshot_dir,
-            brave_search_key,
+            search_backend,
             logs_dir,
             None,
         )
@@ -182,7 +752,7 @@ impl Worker {
         deps: AgentDeps,
         browser_config: BrowserConfig,
         screenshot_dir: PathBuf,
-        brave_search_key: Option<String>,
+        search_backend: Option<Arc<dyn SearchBackendDyn>>,
         logs_dir: PathBuf,
     ) -> (Self, mpsc::Sender<String>, mpsc::Sender<String>) {
         let (input_tx, input_rx) = mpsc::channel(32);
@@ -193,7 +763,7 @@ impl Worker {
             deps,
             browser_config,
             screenshot_dir,
-            brave_search_key,
+            search_backend,
             logs_dir,
             Some(input_rx),
         );
@@ -215,7 +785,7 @@ impl Worker {
         deps: AgentDeps,
         browser_config: BrowserConfig,
         screenshot_dir: PathBuf,
-        brave_search_key: Option<String>,
+        search_backend: Option<Arc<dyn SearchBackendDyn>>,
         logs_dir: PathBuf,
         prior_history: Vec<rig::message::Message>,
     ) -> (Self, mpsc::Sender<String>, mpsc::Sender<String>) {
@@ -227,28 +797,66 @@ impl Worker {
             deps,
             browser_config,
             screenshot_dir,
-            brave_search_key,
+            search_backend,
             logs_dir,
             Some(input_rx),
         );
-        // Reuse the original worker ID so DB row stays linked.
-        worker.id = existing_id;
-        // Rebuild the hook so it publishes events under the correct worker ID
-        // (Self::build creates it with a fresh random ID).
-        let process_id = ProcessId::Worker(existing_id);
-        worker.hook = SpacebotHook::new(
-            worker.deps.agent_id.clone(),
-            process_id,
-            ProcessType::Worker,
-            worker.channel_id.clone(),
-            worker.deps.event_tx.clone(),
-        );
+        worker.adopt_id(existing_id);
         worker.state = WorkerState::WaitingForInput;
         // Stash the prior history so `run_follow_up_loop()` can pick it up.
         worker.prior_history = Some(prior_history);
         (worker, input_tx, inject_tx)
     }
 
+    /// Resume a fire-and-forget worker that was interrupted mid-task.
+    ///
+    /// Reloads the history checkpointed under `logs_dir` (see
+    /// `checkpoint_history`) and continues the task from there. A missing or
+    /// corrupt checkpoint is logged and the task starts from scratch.
+    #[allow(clippy::too_many_arguments)]
+    pub fn resume(
+        existing_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        task: impl Into<String>,
+        system_prompt: impl Into<String>,
+        deps: AgentDeps,
+        browser_config: BrowserConfig,
+        screenshot_dir: PathBuf,
+        search_backend: Option<Arc<dyn SearchBackendDyn>>,
+        logs_dir: PathBuf,
+    ) -> (Self, mpsc::Sender<String>) {
+        let (mut worker, inject_tx) = Self::build(
+            channel_id,
+            task,
+            system_prompt,
+            deps,
+            browser_config,
+            screenshot_dir,
+            search_backend,
+            logs_dir,
+            None,
+        );
+        worker.adopt_id(existing_id);
+        worker.restored_history = load_checkpoint(&checkpoint_path(&worker.logs_dir, worker.id));
+        (worker, inject_tx)
+    }
+
+    /// Take over an existing worker ID so the DB row and checkpoint stay
+    /// linked. Rebuilds the hook so it publishes events under that ID
+    /// (`build` creates it with a fresh random one).
+    fn adopt_id(&mut self, existing_id: WorkerId) {
+        self.id = existing_id;
+        self.transcript.worker_id = existing_id;
+        self.hook = SpacebotHook::new(
+            self.deps.agent_id.clone(),
+            ProcessId::Worker(existing_id),
+            ProcessType::Worker,
+            self.channel_id.clone(),
+            self.deps.event_tx.clone(),
+        )
+        .with_tool_call_repair(self.deps.runtime_config.compaction.load().tool_call_repair);
+    }
+
     /// Check if the worker can transition to a new state.
     pub fn can_transition_to(&self, target: WorkerState) -> bool {
         use WorkerState::*;
@@ -259,6 +867,7 @@ impl Worker {
                 | (Running, Done)
                 | (Running, Failed)
                 | (WaitingForInput, Running)
+                | (WaitingForInput, Done)
                 | (WaitingForInput, Failed)
         )
     }
@@ -283,126 +892,704 @@ impl Worker {
     /// and compacts if the worker is approaching the context window limit.
     /// This prevents long-running workers from dying mid-task due to context
     /// exhaustion.
-    pub async fn run(mut self) -> Result<String> {
-        // Wire the injection receiver into the hook so `on_completion_call`
-        // can drain pending injected context before each LLM turn.
-        if let Some(inject_rx) = self.inject_rx.take() {
-            self.hook = self.hook.clone().with_inject_rx(inject_rx);
-        }
-
-        self.status_tx.send_modify(|s| *s = "running".to_string());
-        self.hook.send_status("running");
+    pub async fn run(self) -> Result<String> {
+        self.run_with_outcome()
+            .await
+            .map(WorkerOutcome::into_result_text)
+    }
 
-        tracing::info!(worker_id = %self.id, task = %self.task, "worker starting");
+    /// Let this worker ask the user clarifying questions.
+    ///
+    /// Adds the clarifications section to the system prompt and returns the
+    /// sender the channel uses to deliver answers. Register it as the worker's
+    /// input so `route` reaches it while the worker is waiting.
+    pub fn with_clarifications(mut self) -> (Self, mpsc::Sender<String>) {
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_worker_clarifications()
+        {
+            Ok(section) => {
+                self.system_prompt.push_str("\n\n");
+                self.system_prompt.push_str(&section);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render worker clarifications section");
+            }
+        }
 
-        let mcp_tools = self.deps.mcp_manager.get_tools().await;
+        let (answer_tx, answer_rx) = mpsc::channel(8);
+        self.clarification_rx = Some(answer_rx);
+        (self, answer_tx)
+    }
 
-        // Create per-worker ToolServer with task tools
-        let worker_tool_server = crate::tools::create_worker_tool_server(
-            self.deps.agent_id.clone(),
-            self.id,
-            self.channel_id.clone(),
-            self.deps.task_store.clone(),
-            self.deps.event_tx.clone(),
-            self.browser_config.clone(),
-            self.screenshot_dir.clone(),
-            self.brave_search_key.clone(),
-            self.deps.runtime_config.workspace_dir.clone(),
-            self.deps.sandbox.clone(),
-            mcp_tools,
-            self.deps.runtime_config.clone(),
-        );
+    /// Sender that cancels this worker when `true` is sent on it. Unlike
+    /// aborting the task, the worker stops on its own: it is marked failed
+    /// with status "cancelled", writes a failure log and persists its
+    /// transcript. Works for fire-and-forget and interactive workers alike.
+    pub fn cancel_handle(&self) -> watch::Sender<bool> {
+        self.cancel_tx.clone()
+    }
 
-        let routing = self.deps.runtime_config.routing.load();
-        let model_name = routing.resolve(ProcessType::Worker, None).to_string();
-        let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
-            .with_context(&*self.deps.agent_id, "worker")
-            .with_worker_type("builtin")
-            .with_routing((**routing).clone());
+    /// Keep `message` through compaction. It still counts against the
+    /// context window; see [`drain_unpinned`].
+    pub fn pin(mut self, message: rig::message::Message) -> Self {
+        self.pinned.push(message);
+        self
+    }
 
-        let agent = AgentBuilder::new(model)
-            .preamble(&self.system_prompt)
-            .default_max_turns(TURNS_PER_SEGMENT)
-            .tool_server_handle(worker_tool_server)
-            .build();
+    /// Override the wall-clock limit from config. `None` lets the worker run
+    /// as long as it needs.
+    pub fn with_max_duration(mut self, max_duration: Option<Duration>) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
 
-        // If this is a resumed worker, load the prior history into `history`
-        // (not `compacted_history`) so the LLM sees it as conversation context
-        // on the next follow-up call.
-        let resuming = self.prior_history.is_some();
-        let mut history = self.prior_history.take().unwrap_or_default();
-        let mut compacted_history = Vec::new();
+    /// Override the idle timeout from config. `None` waits for follow-ups
+    /// indefinitely.
+    pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
 
-        if resuming {
-            tracing::info!(
-                worker_id = %self.id,
-                prior_messages = history.len(),
-                "resuming interactive worker with prior history"
-            );
-            self.hook.send_status("resumed — waiting for input");
-            self.hook.send_worker_idle();
+    /// Name this worker instead of deriving a label from its task. A label
+    /// that slugs to nothing is ignored.
+    pub fn with_label(mut self, label: &str) -> Self {
+        let label = slugify(label, usize::MAX);
+        if !label.is_empty() {
+            self.label = label;
         }
+        self
+    }
 
-        // Run the initial task in segments with compaction checkpoints
-        // (skipped entirely for resumed workers).
-        let mut prompt = self.task.clone();
-        let mut segments_run = 0;
-        let mut overflow_retries = 0;
-        let mut transient_retries = 0;
+    /// Give this worker read-only access to the agent's memories through
+    /// the same recall tool branches use. Saving stays channel-only.
+    pub fn with_memory_recall(mut self) -> Self {
+        self.memory_recall = true;
+        self
+    }
 
-        let mut result = if resuming {
-            // For resumed workers, synthesize a "result" from the task
-            // since the original initial result was already relayed.
-            String::new()
-        } else {
-            loop {
-                segments_run += 1;
+    /// Restrict which optional tools this worker gets.
+    pub fn with_toolset(mut self, toolset: WorkerToolset) -> Self {
+        self.toolset = toolset;
+        self
+    }
 
-                // Pre-prompt maintenance: dedup stale tool results and check
-                // context usage *before* each LLM call, not just at segment
-                // boundaries. Fast models can accumulate large tool results
-                // within a single segment and exceed the context window before
-                // we ever reach a checkpoint.
-                if segments_run > 1 {
-                    dedup_tool_results(&mut history);
-                    self.maybe_compact_history(&mut compacted_history, &mut history)
-                        .await;
+    /// Narrow this worker's tool permissions. Permissions only ever shrink,
+    /// so a spawn can't grant what the agent's config denies.
+    pub fn with_permissions(mut self, permissions: ToolPermissions) -> Self {
+        self.permissions = self.permissions.intersect(permissions);
+        self
+    }
+
+    /// Set whether side-effecting tools run. In [`WorkerMode::Plan`] the
+    /// worker is told it is planning, its shell, file write, browser and MCP
+    /// calls are recorded instead of run, and the recorded calls come back
+    /// as [`WorkerOutcome::planned_calls`].
+    pub fn with_mode(mut self, mode: WorkerMode) -> Self {
+        if mode.is_plan() {
+            match self
+                .deps
+                .runtime_config
+                .prompts
+                .load()
+                .render_worker_plan_mode()
+            {
+                Ok(section) => {
+                    self.system_prompt.push_str("\n\n");
+                    self.system_prompt.push_str(&section);
                 }
+                Err(error) => {
+                    tracing::warn!(%error, "failed to render worker plan mode section");
+                }
+            }
+        }
+        self.mode = mode;
+        self
+    }
 
-                match self
-                    .hook
-                    .prompt_with_tool_nudge_retry(&agent, &mut history, &prompt)
-                    .await
-                {
-                    Ok(response) => {
-                        break response;
-                    }
-                    Err(rig::completion::PromptError::MaxTurnsError { .. }) => {
-                        overflow_retries = 0;
-                        transient_retries = 0;
+    /// Run this worker on `model` instead of the routed worker model, for a
+    /// task that needs a stronger or cheaper model than the default. Global
+    /// routing is untouched. Fails when the model's provider isn't
+    /// configured in the LLM manager.
+    pub fn with_model_override(mut self, model: impl Into<String>) -> Result<Self> {
+        let model = model.into();
+        self.deps.llm_manager.check_model(&model)?;
+        self.token_estimator = token_estimator_for(&model);
+        self.model_override = Some(model);
+        Ok(self)
+    }
 
-                        if segments_run >= MAX_SEGMENTS {
-                            tracing::warn!(
-                                worker_id = %self.id,
-                                segments = segments_run,
-                                "worker hit max segments, returning partial result"
-                            );
-                            self.hook.send_status("done (max segments)");
-                            break crate::agent::extract_last_assistant_text(&history)
-                                .unwrap_or_else(|| {
-                                    "Worker reached maximum segments without a final response."
-                                        .to_string()
-                                });
-                        }
+    /// Run this worker as a direct answer: no tool server, MCP tools, or
+    /// working directory, and no tool nudging, so a Q&A task costs a single
+    /// completion. Follow-ups on interactive workers still work.
+    pub fn with_direct_answer(mut self) -> Self {
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_worker_direct_answer()
+        {
+            Ok(section) => {
+                self.system_prompt.push_str("\n\n");
+                self.system_prompt.push_str(&section);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render worker direct answer section");
+            }
+        }
 
-                        self.persist_transcript(&compacted_history, &history).await;
-                        dedup_tool_results(&mut history);
-                        self.maybe_compact_history(&mut compacted_history, &mut history)
+        self.direct = true;
+        self.toolset = WorkerToolset::NONE;
+        self.hook = self
+            .hook
+            .clone()
+            .with_tool_nudge_policy(ToolNudgePolicy::Disabled);
+        self
+    }
+
+    /// Run the worker and return the structured outcome instead of the
+    /// rendered result text.
+    ///
+    /// Logs one summary line when the worker terminates, whatever the
+    /// outcome, so log aggregation can build stats per worker.
+    pub async fn run_with_outcome(self) -> Result<WorkerOutcome> {
+        let worker_id = self.id;
+        let started = std::time::Instant::now();
+        let (outcome, stats) = self.run_to_completion().await;
+
+        let outcome_label = match &outcome {
+            Ok(_) => "success",
+            Err(crate::error::Error::Agent(error))
+                if matches!(**error, AgentError::Cancelled { .. }) =>
+            {
+                "cancelled"
+            }
+            Err(_) => "failed",
+        };
+        tracing::info!(
+            worker_id = %worker_id,
+            outcome = outcome_label,
+            segments = stats.segments.load(Ordering::Relaxed),
+            compactions = stats.compactions.load(Ordering::Relaxed),
+            tool_calls = stats.tool_calls.load(Ordering::Relaxed),
+            estimated_tokens = stats.estimated_tokens.load(Ordering::Relaxed),
+            tokens_in = stats.tokens_in.load(Ordering::Relaxed),
+            tokens_out = stats.tokens_out.load(Ordering::Relaxed),
+            duration_ms = started.elapsed().as_millis() as u64,
+            "worker summary"
+        );
+
+        outcome
+    }
+
+    /// Drive the worker to termination, handing back its run counters
+    /// alongside the outcome.
+    async fn run_to_completion(mut self) -> (Result<WorkerOutcome>, WorkerRunStats) {
+        let cleanup = AbortedRunCleanup {
+            deps: self.deps.clone(),
+            transcript: self.transcript.clone(),
+            armed: true,
+        };
+        let mut outcome = self.run_inner().await;
+        let reported = self.hook.token_usage();
+        let tokens_in = self
+            .run_stats
+            .tokens_in
+            .fetch_add(reported.input_tokens, Ordering::Relaxed)
+            + reported.input_tokens;
+        let tokens_out = self
+            .run_stats
+            .tokens_out
+            .fetch_add(reported.output_tokens, Ordering::Relaxed)
+            + reported.output_tokens;
+        if let Ok(outcome) = &mut outcome {
+            outcome.tokens_in = tokens_in;
+            outcome.tokens_out = tokens_out;
+            outcome.segments = self.run_stats.segments.load(Ordering::Relaxed);
+        }
+        remove_checkpoint(&self.logs_dir, self.id).await;
+        self.hook.send_worker_event(terminal_event(&outcome));
+        // Start removing the workdir now; an aborted run removes it when
+        // the worker is dropped instead.
+        drop(self.workdir.take());
+        cleanup.disarm();
+        let archived = match &outcome {
+            Ok(_) => TranscriptOutcome::Success,
+            Err(error) => TranscriptOutcome::Failure {
+                error: error.to_string(),
+            },
+        };
+        archive_transcript(&self.deps, &self.transcript, archived);
+        (outcome, std::mem::take(&mut self.run_stats))
+    }
+
+    /// Create this worker's isolated working directory and describe it in
+    /// the system prompt. Returns `None` when workers share the workspace or
+    /// the directory can't be created.
+    fn prepare_workdir(&mut self) -> Option<PathBuf> {
+        let worker_config = self.deps.runtime_config.worker.load();
+        let workdir =
+            worker_config.workdir_for(&self.deps.runtime_config.workspace_dir, self.id)?;
+
+        if let Err(error) = std::fs::create_dir_all(&workdir) {
+            tracing::warn!(
+                worker_id = %self.id,
+                %error,
+                path = %workdir.display(),
+                "failed to create worker working directory, using the workspace"
+            );
+            return None;
+        }
+
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_worker_workdir(&workdir.display().to_string(), worker_config.retain_workdir)
+        {
+            Ok(section) => {
+                self.system_prompt.push_str("\n\n");
+                self.system_prompt.push_str(&section);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render worker workdir section");
+            }
+        }
+
+        if !worker_config.retain_workdir {
+            self.workdir = Some(WorkdirGuard {
+                worker_id: self.id,
+                path: workdir.clone(),
+            });
+        }
+        Some(workdir)
+    }
+
+    /// Tell the worker which tools its permissions deny, so it doesn't plan
+    /// around tools it won't find.
+    fn describe_tool_restrictions(&mut self) {
+        let denied = self.permissions.denied_tools();
+        if denied.is_empty() {
+            return;
+        }
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_worker_tool_restrictions(&denied)
+        {
+            Ok(section) => {
+                self.system_prompt.push_str("\n\n");
+                self.system_prompt.push_str(&section);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "failed to render worker tool restrictions section");
+            }
+        }
+    }
+
+    async fn run_inner(&mut self) -> Result<WorkerOutcome> {
+        let mut deadline = self.max_duration.map(|limit| Instant::now() + limit);
+        // Wire the injection receiver into the hook so `on_completion_call`
+        // can drain pending injected context before each LLM turn.
+        if let Some(inject_rx) = self.inject_rx.take() {
+            self.hook = self.hook.clone().with_inject_rx(inject_rx);
+        }
+
+        self.status_tx.send_modify(|s| *s = "running".to_string());
+        self.hook.send_status("running");
+
+        tracing::info!(worker_id = %self.id, task = %self.task, "worker starting");
+
+        // Direct-answer workers skip tool setup entirely. Clarifications and
+        // memory recall are tools, so asking for either keeps the tool server.
+        let direct = self.direct && self.clarification_rx.is_none() && !self.memory_recall;
+        let can_clarify = self.clarification_rx.is_some();
+        let worker_tool_server = if direct {
+            tracing::debug!(worker_id = %self.id, "direct answer worker, skipping tool server");
+            None
+        } else {
+            let mcp_tools = self.deps.mcp_manager.get_tools().await;
+            let workdir = self.prepare_workdir();
+            self.describe_tool_restrictions();
+
+            // Create per-worker ToolServer with task tools
+            let worker_tool_server = crate::tools::create_worker_tool_server(
+                self.deps.agent_id.clone(),
+                self.id,
+                self.channel_id.clone(),
+                self.deps.task_store.clone(),
+                self.deps.event_tx.clone(),
+                self.browser_config.clone(),
+                self.screenshot_dir.clone(),
+                self.search_backend.clone(),
+                self.deps.runtime_config.workspace_dir.clone(),
+                workdir,
+                self.deps.sandbox.clone(),
+                mcp_tools,
+                self.deps.runtime_config.clone(),
+                self.toolset,
+                self.permissions,
+                self.progress_tx.clone(),
+                self.mode.clone(),
+            );
+
+            if let Some(answer_rx) = self.clarification_rx.take() {
+                let timeout = Duration::from_secs(
+                    self.deps
+                        .runtime_config
+                        .worker
+                        .load()
+                        .clarification_timeout_secs,
+                );
+                let tool = AskClarificationTool::new(
+                    self.deps.agent_id.clone(),
+                    self.id,
+                    self.channel_id.clone(),
+                    self.deps.event_tx.clone(),
+                    answer_rx,
+                    timeout,
+                );
+                if let Err(error) = worker_tool_server.add_tool(tool).await {
+                    tracing::warn!(worker_id = %self.id, %error, "failed to add ask_clarification tool");
+                }
+            }
+
+            if self.memory_recall {
+                let tool = MemoryRecallTool::new(self.deps.memory_search.clone());
+                if let Err(error) = worker_tool_server.add_tool(tool).await {
+                    tracing::warn!(worker_id = %self.id, %error, "failed to add memory_recall tool");
+                }
+            }
+
+            Some(worker_tool_server)
+        };
+
+        let routing = self.deps.runtime_config.routing.load();
+        let model_name = self.model_name();
+        let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
+            .with_context(&*self.deps.agent_id, "worker")
+            .with_worker_type("builtin")
+            .with_routing((**routing).clone())
+            .with_text_deltas(self.hook.text_delta_sink());
+
+        let worker_config = self.deps.runtime_config.worker.load();
+        let max_output_tokens = self
+            .deps
+            .runtime_config
+            .output_limits
+            .load()
+            .worker
+            .max_output_tokens;
+        let mut builder = AgentBuilder::new(model.clone()).preamble(&self.system_prompt);
+        if max_output_tokens > 0 {
+            builder = builder.max_tokens(max_output_tokens);
+        }
+        let (agent, wrap_up_agent, preflight_agent) = match worker_tool_server {
+            Some(worker_tool_server) => {
+                // The capability check gets the same tools, so it can look
+                // around or ask for clarification, on a tight turn budget.
+                let preflight_agent = worker_config.preflight_check.then(|| {
+                    AgentBuilder::new(model.clone())
+                        .preamble(&self.system_prompt)
+                        .default_max_turns(worker_config.preflight_turns)
+                        .tool_server_handle(worker_tool_server.clone())
+                        .build()
+                });
+                // Same tools, smaller turn budget, for forcing convergence
+                // once the worker keeps running out of turns.
+                let mut wrap_up_builder = AgentBuilder::new(model)
+                    .preamble(&self.system_prompt)
+                    .default_max_turns(worker_config.wrap_up_turns)
+                    .tool_server_handle(worker_tool_server.clone());
+                if max_output_tokens > 0 {
+                    wrap_up_builder = wrap_up_builder.max_tokens(max_output_tokens);
+                }
+                let wrap_up_agent = wrap_up_builder.build();
+                let agent = builder
+                    .default_max_turns(TURNS_PER_SEGMENT)
+                    .tool_server_handle(worker_tool_server)
+                    .build();
+                (agent, Some(wrap_up_agent), preflight_agent)
+            }
+            // Without tools every response is final, so one turn is enough.
+            None => (builder.default_max_turns(1).build(), None, None),
+        };
+
+        // If this is a resumed worker, load the prior history into `history`
+        // (not `compacted_history`) so the LLM sees it as conversation context
+        // on the next follow-up call.
+        let resuming = self.prior_history.is_some();
+        let mut history = self.prior_history.take().unwrap_or_default();
+        let mut compacted_history = Vec::new();
+        let restored = !resuming && self.restored_history.is_some();
+        if let Some(restored_history) = self.restored_history.take().filter(|_| restored) {
+            tracing::info!(
+                worker_id = %self.id,
+                restored_messages = restored_history.len(),
+                "resuming worker from checkpoint"
+            );
+            history = restored_history;
+        }
+
+        if resuming {
+            tracing::info!(
+                worker_id = %self.id,
+                prior_messages = history.len(),
+                "resuming interactive worker with prior history"
+            );
+            self.hook.send_status("resumed — waiting for input");
+            self.hook.send_worker_idle();
+        }
+
+        // Run the initial task in segments with compaction checkpoints
+        // (skipped entirely for resumed workers).
+        let mut prompt = if restored {
+            self.deps
+                .runtime_config
+                .prompts
+                .load()
+                .render_system_worker_resume()?
+        } else {
+            self.task.clone()
+        };
+        let checkpoint_history = worker_config.checkpoint_history;
+        let max_segments = worker_config.max_segments;
+        let max_unfinished_segments = worker_config.max_unfinished_segments;
+        let max_transient_retries = worker_config.transient_retries;
+        // Each overflow retry dedup-strips stale tool results and
+        // force-compacts 75% of remaining messages. Past the configured limit
+        // something is fundamentally broken: the system prompt alone exceeds
+        // the context window, or the compaction floor is still too large.
+        let max_overflow_retries = worker_config.overflow_retries;
+        let transient_backoff_secs = worker_config.transient_backoff_secs;
+        let max_overloaded_retries = worker_config.overloaded_retries;
+        let overloaded_backoff_secs = worker_config.overloaded_backoff_secs;
+        drop(worker_config);
+        let mut segments_run = 0;
+        let mut unfinished_segments = 0;
+        let mut wrapping_up = false;
+        let mut overflow_retries = 0;
+        let mut transient_retries = 0;
+        let mut overloaded_retries = 0;
+
+        if let Some(preflight_agent) = preflight_agent.as_ref().filter(|_| !resuming && !restored) {
+            self.hook.send_status("checking task");
+            let check = self
+                .deps
+                .runtime_config
+                .prompts
+                .load()
+                .render_system_worker_preflight(&self.task, can_clarify)?;
+            let history_len = history.len();
+            match self
+                .hook
+                .prompt_once(
+                    preflight_agent,
+                    &mut history,
+                    crate::llm::marker::message(check),
+                )
+                .await
+            {
+                Ok(response) => match parse_preflight_verdict(&response) {
+                    PreflightVerdict::Ready => {
+                        // The check already put the task in history.
+                        prompt = self
+                            .deps
+                            .runtime_config
+                            .prompts
+                            .load()
+                            .render_system_worker_preflight_proceed()?;
+                        self.hook.send_status("running");
+                    }
+                    PreflightVerdict::Blocked(blocker) => {
+                        let reason = format!("preflight check found a blocker: {blocker}");
+                        self.state = WorkerState::Failed;
+                        self.hook.send_status("failed (preflight)");
+                        self.write_failure_log(&history, &reason);
+                        self.persist_transcript(&compacted_history, &history).await;
+                        tracing::warn!(worker_id = %self.id, %blocker, "worker blocked at preflight");
+                        return Err(crate::error::AgentError::Other(anyhow::anyhow!(reason)).into());
+                    }
+                },
+                Err(rig::completion::PromptError::PromptCancelled { reason, .. }) => {
+                    return Err(self
+                        .fail_cancelled(&compacted_history, &history, &reason)
+                        .await);
+                }
+                Err(error) => {
+                    // An inconclusive check shouldn't cost the task; drop the
+                    // partial exchange and start as if it never ran.
+                    history.truncate(history_len);
+                    self.hook.send_status("running");
+                    tracing::warn!(
+                        worker_id = %self.id,
+                        %error,
+                        "preflight check inconclusive, starting task"
+                    );
+                }
+            }
+        }
+
+        let mut result = if resuming {
+            // For resumed workers, synthesize a "result" from the task
+            // since the original initial result was already relayed.
+            String::new()
+        } else {
+            loop {
+                if self.cancel_requested() {
+                    return Err(self
+                        .fail_cancelled(&compacted_history, &history, CANCEL_REASON)
+                        .await);
+                }
+                if past_deadline(deadline) {
+                    return Err(self
+                        .fail_timed_out(&compacted_history, &history, segments_run)
+                        .await);
+                }
+                segments_run += 1;
+                self.run_stats.segments.fetch_add(1, Ordering::Relaxed);
+                self.hook.send_worker_event(WorkerEvent::SegmentStarted {
+                    index: segments_run,
+                });
+                let floor = progress_floor(segments_run - 1, max_segments);
+                self.progress_tx
+                    .send_if_modified(|progress| advance_progress(progress, floor));
+                let progress_before = *self.progress_rx.borrow();
+                self.publish_telemetry(&history);
+
+                // Pre-prompt maintenance: dedup stale tool results and check
+                // context usage *before* each LLM call, not just at segment
+                // boundaries. Fast models can accumulate large tool results
+                // within a single segment and exceed the context window before
+                // we ever reach a checkpoint.
+                if segments_run > 1 {
+                    self.prune_tool_results(&mut history);
+                    self.maybe_compact_history(&mut compacted_history, &mut history)
+                        .await;
+                }
+                // Written after compaction so the checkpoint stays bounded
+                // by the context window rather than the whole run.
+                if checkpoint_history {
+                    write_checkpoint(&self.logs_dir, self.id, &history).await;
+                }
+
+                let segment_agent = match &wrap_up_agent {
+                    Some(wrap_up_agent) if wrapping_up => wrap_up_agent,
+                    _ => &agent,
+                };
+                let usage_before = self.hook.token_usage();
+                let history_len = history.len();
+                let outcome = within_deadline(
+                    deadline,
+                    self.hook
+                        .prompt_with_tool_nudge_retry(segment_agent, &mut history, &prompt),
+                )
+                .await;
+                let Some(outcome) = outcome else {
+                    return Err(self
+                        .fail_timed_out(&compacted_history, &history, segments_run)
+                        .await);
+                };
+                self.account_unreported_tokens(usage_before, &history, history_len);
+                if self.cancel_requested() {
+                    return Err(self
+                        .fail_cancelled(&compacted_history, &history, CANCEL_REASON)
+                        .await);
+                }
+                match outcome {
+                    Ok(response) => {
+                        break response;
+                    }
+                    Err(rig::completion::PromptError::MaxTurnsError { .. }) => {
+                        overflow_retries = 0;
+                        transient_retries = 0;
+                        overloaded_retries = 0;
+                        unfinished_segments += 1;
+
+                        // The wrap-up segment was the last chance to converge.
+                        if wrapping_up {
+                            let reason = format!(
+                                "no final answer after {unfinished_segments} consecutive segments, \
+                                 including a wrap-up segment"
+                            );
+                            self.state = WorkerState::Failed;
+                            self.hook.send_status("failed (no final answer)");
+                            self.write_failure_log(&history, &reason);
+                            self.persist_transcript(&compacted_history, &history).await;
+                            tracing::error!(
+                                worker_id = %self.id,
+                                segments = segments_run,
+                                unfinished_segments,
+                                "worker ignored wrap-up directive"
+                            );
+                            return Err(
+                                crate::error::AgentError::Other(anyhow::anyhow!(reason)).into()
+                            );
+                        }
+
+                        // Bound runaway workers that keep exhausting their turn
+                        // budget without ever converging on a final answer.
+                        if segments_run >= max_segments {
+                            self.state = WorkerState::Failed;
+                            self.hook.send_status("failed (max segments)");
+                            self.write_failure_log(
+                                &history,
+                                &format!("max segments reached after {segments_run} segments"),
+                            );
+                            self.persist_transcript(&compacted_history, &history).await;
+                            tracing::error!(
+                                worker_id = %self.id,
+                                segments = segments_run,
+                                "worker hit max segments"
+                            );
+                            return Err(crate::error::AgentError::Other(anyhow::anyhow!(
+                                "max segments reached after {segments_run} segments"
+                            ))
+                            .into());
+                        }
+
+                        // A worker that moved its own progress forward with
+                        // `set_progress` during the segment is still converging,
+                        // so the run of unfinished segments starts over.
+                        if *self.progress_rx.borrow() > progress_before {
+                            unfinished_segments = 0;
+                        }
+
+                        self.persist_transcript(&compacted_history, &history).await;
+                        self.prune_tool_results(&mut history);
+                        self.maybe_compact_history(&mut compacted_history, &mut history)
                             .await;
-                        prompt =
-                            "Continue where you left off. Do not repeat completed work.".into();
-                        self.hook
-                            .send_status(format!("working (segment {segments_run})"));
+                        if max_unfinished_segments > 0
+                            && unfinished_segments >= max_unfinished_segments
+                        {
+                            tracing::warn!(
+                                worker_id = %self.id,
+                                segment = segments_run,
+                                unfinished_segments,
+                                "worker not converging, sending wrap-up directive"
+                            );
+                            wrapping_up = true;
+                            prompt = self
+                                .deps
+                                .runtime_config
+                                .prompts
+                                .load()
+                                .render_system_worker_wrap_up()?;
+                            self.hook.send_status("wrapping up");
+                            self.hook.send_worker_event(WorkerEvent::WrapUp);
+                        } else {
+                            prompt =
+                                "Continue where you left off. Do not repeat completed work.".into();
+                            self.hook
+                                .send_status(format!("working (segment {segments_run})"));
+                        }
 
                         tracing::debug!(
                             worker_id = %self.id,
@@ -412,22 +1599,39 @@ impl Worker {
                         );
                     }
                     Err(rig::completion::PromptError::PromptCancelled { reason, .. }) => {
+                        return Err(self
+                            .fail_cancelled(&compacted_history, &history, &reason)
+                            .await);
+                    }
+                    Err(error)
+                        if self.is_context_overflow(&error) && !self.compaction_enabled() =>
+                    {
                         self.state = WorkerState::Failed;
-                        self.hook.send_status("cancelled");
-                        self.write_failure_log(&history, &format!("cancelled: {reason}"));
+                        self.hook.send_status("failed");
+                        self.write_failure_log(
+                            &history,
+                            &format!("context overflow with compaction disabled: {error}"),
+                        );
                         self.persist_transcript(&compacted_history, &history).await;
-                        tracing::info!(worker_id = %self.id, %reason, "worker cancelled");
-                        return Err(crate::error::AgentError::Cancelled { reason }.into());
+                        tracing::error!(worker_id = %self.id, %error, "worker context overflow with compaction disabled");
+                        return Err(AgentError::ContextOverflowUnrecoverable { attempts: 0 }.into());
                     }
-                    Err(error) if is_context_overflow_error(&error.to_string()) => {
+                    Err(error) if self.is_context_overflow(&error) => {
                         overflow_retries += 1;
-                        if overflow_retries > MAX_OVERFLOW_RETRIES {
+                        if let Some(failure) =
+                            overflow_exhausted(overflow_retries, max_overflow_retries)
+                        {
                             self.state = WorkerState::Failed;
                             self.hook.send_status("failed");
-                            self.write_failure_log(&history, &format!("context overflow after {MAX_OVERFLOW_RETRIES} compaction attempts: {error}"));
+                            self.write_failure_log(
+                                &history,
+                                &format!(
+                                    "{failure} (overflow_retries = {max_overflow_retries}): {error}"
+                                ),
+                            );
                             self.persist_transcript(&compacted_history, &history).await;
                             tracing::error!(worker_id = %self.id, %error, "worker context overflow unrecoverable");
-                            return Err(crate::error::AgentError::Other(error.into()).into());
+                            return Err(failure.into());
                         }
 
                         tracing::warn!(
@@ -437,7 +1641,10 @@ impl Worker {
                             "context overflow, compacting and retrying"
                         );
                         self.hook.send_status("compacting (overflow recovery)");
-                        dedup_tool_results(&mut history);
+                        self.hook.send_worker_event(WorkerEvent::OverflowRetry {
+                            attempt: overflow_retries,
+                        });
+                        self.prune_tool_results(&mut history);
                         self.force_compact_history(&mut compacted_history, &mut history)
                             .await;
                         prompt = "Continue where you left off. Do not repeat completed work. \
@@ -445,26 +1652,67 @@ impl Worker {
                               has been compacted."
                             .into();
                     }
+                    Err(error) if is_overloaded_error(&error.to_string()) => {
+                        let Some(delay) = next_retry_delay(
+                            &mut overloaded_retries,
+                            max_overloaded_retries,
+                            &error.to_string(),
+                            overloaded_backoff_secs,
+                        ) else {
+                            self.state = WorkerState::Failed;
+                            self.hook.send_status("failed");
+                            self.write_failure_log(&history, &format!(
+                                "provider overloaded after {max_overloaded_retries} retries: {error}"
+                            ));
+                            self.persist_transcript(&compacted_history, &history).await;
+                            tracing::error!(
+                                worker_id = %self.id,
+                                retries = max_overloaded_retries,
+                                %error,
+                                "worker overloaded retries exhausted"
+                            );
+                            return Err(crate::error::AgentError::Other(error.into()).into());
+                        };
+                        tracing::warn!(
+                            worker_id = %self.id,
+                            attempt = overloaded_retries,
+                            delay_secs = delay.as_secs(),
+                            %error,
+                            "provider overloaded, backing off and retrying"
+                        );
+                        self.hook.send_status(format!(
+                            "provider overloaded, retrying in {}s ({overloaded_retries}/{max_overloaded_retries})",
+                            delay.as_secs()
+                        ));
+                        self.hook.send_worker_event(WorkerEvent::OverloadedRetry {
+                            attempt: overloaded_retries,
+                            delay_secs: delay.as_secs(),
+                        });
+                        tokio::time::sleep(delay).await;
+                        // Same as transient errors: the request never reached
+                        // the model, so retry the segment with the same prompt.
+                    }
                     Err(error) if is_retriable_error(&error.to_string()) => {
-                        transient_retries += 1;
-                        if transient_retries > MAX_TRANSIENT_RETRIES {
+                        let Some(delay) = next_retry_delay(
+                            &mut transient_retries,
+                            max_transient_retries,
+                            &error.to_string(),
+                            transient_backoff_secs,
+                        ) else {
                             self.state = WorkerState::Failed;
                             self.hook.send_status("failed");
                             self.write_failure_log(&history, &format!(
-                                "transient provider error after {MAX_TRANSIENT_RETRIES} retries: {error}"
+                                "transient provider error after {max_transient_retries} retries: {error}"
                             ));
                             self.persist_transcript(&compacted_history, &history).await;
                             tracing::error!(
                                 worker_id = %self.id,
-                                retries = MAX_TRANSIENT_RETRIES,
+                                retries = max_transient_retries,
                                 %error,
                                 "worker transient error retries exhausted"
                             );
                             return Err(crate::error::AgentError::Other(error.into()).into());
-                        }
-
-                        let delay =
-                            TRANSIENT_RETRY_BASE_DELAY * 2u32.pow((transient_retries - 1) as u32);
+                        };
                         tracing::warn!(
                             worker_id = %self.id,
                             attempt = transient_retries,
@@ -473,9 +1721,13 @@ impl Worker {
                             "transient provider error, backing off and retrying"
                         );
                         self.hook.send_status(format!(
-                            "provider error, retrying in {}s ({transient_retries}/{MAX_TRANSIENT_RETRIES})",
+                            "provider error, retrying in {}s ({transient_retries}/{max_transient_retries})",
                             delay.as_secs()
                         ));
+                        self.hook.send_worker_event(WorkerEvent::TransientRetry {
+                            attempt: transient_retries,
+                            delay_secs: delay.as_secs(),
+                        });
                         tokio::time::sleep(delay).await;
 
                         // Don't change the prompt — just retry with the same
@@ -494,6 +1746,17 @@ impl Worker {
             }
         };
 
+        if !resuming {
+            result = prefer_last_assistant_text(&history, result);
+        }
+
+        if !resuming
+            && result.trim().is_empty()
+            && let Some(recovered) = self.recover_empty_result(&agent, &mut history).await
+        {
+            result = recovered;
+        }
+
         // Safety net: if the worker produced an empty result (e.g. reasoning-only
         // response that slipped past the nudge gate), treat it as a failure — unless
         // the worker already signaled a meaningful outcome via set_status. A worker
@@ -523,8 +1786,11 @@ impl Worker {
         }
 
         // For interactive workers, enter a follow-up loop
-        let mut follow_up_failure: Option<String> = None;
-        if let Some(mut input_rx) = self.input_rx.take() {
+        let mut follow_up_failure: Option<AgentError> = None;
+        let mut idle_timed_out = false;
+        // A handoff can land while the initial task is still running; then
+        // the initial result is already the final one.
+        if let Some(mut input_rx) = self.input_rx.take().filter(|rx| !handed_off(rx)) {
             if !resuming {
                 // Fresh worker: persist transcript and signal idle for the first time.
                 // Resumed workers already did this in the preamble above.
@@ -534,25 +1800,118 @@ impl Worker {
                 self.hook.send_worker_idle();
             }
 
-            while let Some(follow_up) = input_rx.recv().await {
+            let mut cancel_rx = self.cancel_rx.clone();
+            loop {
+                let first_follow_up = tokio::select! {
+                    () = cancellation_requested(&mut cancel_rx) => {
+                        return Err(self
+                            .fail_cancelled(&compacted_history, &history, CANCEL_REASON)
+                            .await);
+                    }
+                    follow_up = input_rx.recv() => match follow_up {
+                        Some(follow_up) => follow_up,
+                        None => break,
+                    },
+                    () = idle_expired(self.idle_timeout) => {
+                        input_rx.close();
+                        idle_timed_out = true;
+                        self.hook.send_status("idle timeout");
+                        tracing::info!(
+                            worker_id = %self.id,
+                            idle_timeout_secs = self.idle_timeout.unwrap_or_default().as_secs(),
+                            "interactive worker idle timeout, finishing"
+                        );
+                        break;
+                    }
+                };
                 self.state = WorkerState::Running;
                 self.hook.send_status("processing follow-up");
+                // Time spent idle doesn't count against the limit.
+                deadline = self.max_duration.map(|limit| Instant::now() + limit);
+
+                let worker_config = self.deps.runtime_config.worker.load();
+                let follow_up_mode = worker_config.follow_up_mode;
+                let debounce = Duration::from_millis(worker_config.follow_up_debounce_ms);
+                drop(worker_config);
+
+                let mut follow_ups = vec![first_follow_up];
+                if follow_up_mode == FollowUpMode::Coalesce {
+                    collect_follow_ups(&mut input_rx, &mut follow_ups, debounce).await;
+                }
 
                 // Dedup stale tool results and compact before follow-up if needed
-                dedup_tool_results(&mut history);
+                self.prune_tool_results(&mut history);
                 self.maybe_compact_history(&mut compacted_history, &mut history)
                     .await;
 
+                match follow_up_mode {
+                    // Anything that arrived while compacting joins this turn.
+                    FollowUpMode::Coalesce => {
+                        collect_follow_ups(&mut input_rx, &mut follow_ups, Duration::ZERO).await;
+                    }
+                    FollowUpMode::Queue => {
+                        let queued = input_rx.len();
+                        if queued > 0 {
+                            self.hook.send_status(format!(
+                                "processing follow-up ({queued} more queued)"
+                            ));
+                        }
+                    }
+                }
+
+                let follow_up = if follow_ups.len() == 1 {
+                    follow_ups.remove(0)
+                } else {
+                    tracing::info!(
+                        worker_id = %self.id,
+                        count = follow_ups.len(),
+                        "coalesced queued follow-ups into one turn"
+                    );
+                    self.deps
+                        .runtime_config
+                        .prompts
+                        .load()
+                        .render_system_worker_follow_ups(&follow_ups)
+                        .unwrap_or_else(|error| {
+                            tracing::error!(worker_id = %self.id, %error, "failed to render coalesced follow-ups, joining them");
+                            follow_ups.join("\n\n")
+                        })
+                };
+
                 let mut follow_up_prompt = follow_up.clone();
                 let mut follow_up_overflow_retries = 0;
-                let mut follow_up_transient_retries = 0u32;
+                let mut follow_up_transient_retries = 0;
+                let mut follow_up_overloaded_retries = 0;
 
-                let follow_up_result: std::result::Result<String, String> = loop {
-                    match self
-                        .hook
-                        .prompt_with_tool_nudge_retry(&agent, &mut history, &follow_up_prompt)
-                        .await
-                    {
+                let follow_up_result: std::result::Result<String, AgentError> = loop {
+                    if past_deadline(deadline) {
+                        return Err(self
+                            .fail_timed_out(&compacted_history, &history, segments_run)
+                            .await);
+                    }
+                    let usage_before = self.hook.token_usage();
+                    let history_len = history.len();
+                    let outcome = within_deadline(
+                        deadline,
+                        self.hook.prompt_with_tool_nudge_retry(
+                            &agent,
+                            &mut history,
+                            &follow_up_prompt,
+                        ),
+                    )
+                    .await;
+                    let Some(outcome) = outcome else {
+                        return Err(self
+                            .fail_timed_out(&compacted_history, &history, segments_run)
+                            .await);
+                    };
+                    self.account_unreported_tokens(usage_before, &history, history_len);
+                    if self.cancel_requested() {
+                        return Err(self
+                            .fail_cancelled(&compacted_history, &history, CANCEL_REASON)
+                            .await);
+                    }
+                    match outcome {
                         Ok(response) => break Ok(response),
                         Err(rig::completion::PromptError::PromptCancelled {
                             ref reason, ..
@@ -567,17 +1926,32 @@ impl Worker {
                                 %reason,
                                 "follow-up completion contract retries exhausted"
                             );
-                            break Err(failure_reason);
+                            break Err(AgentError::Other(anyhow::anyhow!(failure_reason)));
+                        }
+                        Err(error)
+                            if self.is_context_overflow(&error) && !self.compaction_enabled() =>
+                        {
+                            let failure = AgentError::ContextOverflowUnrecoverable { attempts: 0 };
+                            self.write_failure_log(
+                                &history,
+                                &format!("follow-up {failure}: compaction disabled: {error}"),
+                            );
+                            tracing::error!(worker_id = %self.id, %error, "follow-up context overflow with compaction disabled");
+                            break Err(failure);
                         }
-                        Err(error) if is_context_overflow_error(&error.to_string()) => {
+                        Err(error) if self.is_context_overflow(&error) => {
                             follow_up_overflow_retries += 1;
-                            if follow_up_overflow_retries > MAX_OVERFLOW_RETRIES {
-                                let failure_reason = format!(
-                                    "follow-up context overflow after {MAX_OVERFLOW_RETRIES} compaction attempts: {error}"
+                            if let Some(failure) =
+                                overflow_exhausted(follow_up_overflow_retries, max_overflow_retries)
+                            {
+                                self.write_failure_log(
+                                    &history,
+                                    &format!(
+                                        "follow-up {failure} (overflow_retries = {max_overflow_retries}): {error}"
+                                    ),
                                 );
-                                self.write_failure_log(&history, &failure_reason);
                                 tracing::error!(worker_id = %self.id, %error, "follow-up context overflow unrecoverable");
-                                break Err(failure_reason);
+                                break Err(failure);
                             }
                             tracing::warn!(
                                 worker_id = %self.id,
@@ -586,52 +1960,103 @@ impl Worker {
                                 "follow-up context overflow, compacting and retrying"
                             );
                             self.hook.send_status("compacting (overflow recovery)");
-                            dedup_tool_results(&mut history);
+                            self.hook.send_worker_event(WorkerEvent::OverflowRetry {
+                                attempt: follow_up_overflow_retries,
+                            });
+                            self.prune_tool_results(&mut history);
                             self.force_compact_history(&mut compacted_history, &mut history)
                                 .await;
-                            let prompt_engine = self.deps.runtime_config.prompts.load();
-                            let overflow_msg = prompt_engine.render_system_worker_overflow()?;
+                            let overflow_msg = self.overflow_notice();
                             follow_up_prompt = format!("{follow_up}\n\n{overflow_msg}");
                         }
-                        Err(error) if is_retriable_error(&error.to_string()) => {
-                            follow_up_transient_retries += 1;
-                            if follow_up_transient_retries > MAX_TRANSIENT_RETRIES as u32 {
+                        Err(error) if is_overloaded_error(&error.to_string()) => {
+                            let Some(delay) = next_retry_delay(
+                                &mut follow_up_overloaded_retries,
+                                max_overloaded_retries,
+                                &error.to_string(),
+                                overloaded_backoff_secs,
+                            ) else {
                                 let failure_reason = format!(
-                                    "follow-up transient error after {MAX_TRANSIENT_RETRIES} retries: {error}"
+                                    "follow-up provider overloaded after {max_overloaded_retries} retries: {error}"
                                 );
                                 self.write_failure_log(&history, &failure_reason);
-                                tracing::error!(worker_id = %self.id, %error, "follow-up transient retries exhausted");
-                                break Err(failure_reason);
-                            }
-                            let delay = TRANSIENT_RETRY_BASE_DELAY
-                                * 2u32.pow(follow_up_transient_retries - 1);
+                                tracing::error!(worker_id = %self.id, %error, "follow-up overloaded retries exhausted");
+                                break Err(AgentError::Other(anyhow::anyhow!(failure_reason)));
+                            };
                             tracing::warn!(
                                 worker_id = %self.id,
-                                attempt = follow_up_transient_retries,
+                                attempt = follow_up_overloaded_retries,
                                 delay_secs = delay.as_secs(),
                                 %error,
-                                "follow-up transient error, backing off and retrying"
+                                "follow-up provider overloaded, backing off and retrying"
                             );
                             self.hook.send_status(format!(
-                                "provider error, retrying in {}s ({follow_up_transient_retries}/{MAX_TRANSIENT_RETRIES})",
+                                "provider overloaded, retrying in {}s ({follow_up_overloaded_retries}/{max_overloaded_retries})",
                                 delay.as_secs()
                             ));
+                            self.hook.send_worker_event(WorkerEvent::OverloadedRetry {
+                                attempt: follow_up_overloaded_retries,
+                                delay_secs: delay.as_secs(),
+                            });
                             tokio::time::sleep(delay).await;
                         }
-                        Err(error) => {
-                            let failure_reason = format!("follow-up failed: {error}");
-                            self.write_failure_log(&history, &failure_reason);
-                            tracing::error!(worker_id = %self.id, %error, "worker follow-up failed");
-                            break Err(failure_reason);
-                        }
-                    }
-                };
-
+                        Err(error) if is_retriable_error(&error.to_string()) => {
+                            let Some(delay) = next_retry_delay(
+                                &mut follow_up_transient_retries,
+                                max_transient_retries,
+                                &error.to_string(),
+                                transient_backoff_secs,
+                            ) else {
+                                let failure_reason = format!(
+                                    "follow-up transient error after {max_transient_retries} retries: {error}"
+                                );
+                                self.write_failure_log(&history, &failure_reason);
+                                tracing::error!(worker_id = %self.id, %error, "follow-up transient retries exhausted");
+                                break Err(AgentError::Other(anyhow::anyhow!(failure_reason)));
+                            };
+                            tracing::warn!(
+                                worker_id = %self.id,
+                                attempt = follow_up_transient_retries,
+                                delay_secs = delay.as_secs(),
+                                %error,
+                                "follow-up transient error, backing off and retrying"
+                            );
+                            self.hook.send_status(format!(
+                                "provider error, retrying in {}s ({follow_up_transient_retries}/{max_transient_retries})",
+                                delay.as_secs()
+                            ));
+                            self.hook.send_worker_event(WorkerEvent::TransientRetry {
+                                attempt: follow_up_transient_retries,
+                                delay_secs: delay.as_secs(),
+                            });
+                            tokio::time::sleep(delay).await;
+                        }
+                        Err(error) => {
+                            let failure_reason = format!("follow-up failed: {error}");
+                            self.write_failure_log(&history, &failure_reason);
+                            tracing::error!(worker_id = %self.id, %error, "worker follow-up failed");
+                            break Err(AgentError::Other(anyhow::anyhow!(failure_reason)));
+                        }
+                    }
+                };
+
                 match follow_up_result {
+                    // Handed off to the background: this answer is the final
+                    // result, relayed once the worker completes.
+                    Ok(response) if handed_off(&input_rx) => {
+                        tracing::info!(
+                            worker_id = %self.id,
+                            "interactive worker finished in the background"
+                        );
+                        if !response.trim().is_empty() {
+                            result = response;
+                        }
+                        break;
+                    }
                     Ok(response) => {
                         // Emit follow-up result so the channel can retrigger
                         // and relay this to the user — same as initial result.
-                        if !response.is_empty() {
+                        if !response.trim().is_empty() {
                             let scrubbed = if let Some(store) =
                                 self.deps.runtime_config.secrets.load().as_ref().as_ref()
                             {
@@ -651,10 +2076,10 @@ impl Worker {
                                 .ok();
                         }
                     }
-                    Err(failure_reason) => {
+                    Err(failure) => {
                         self.state = WorkerState::Failed;
                         self.hook.send_status("failed");
-                        follow_up_failure = Some(failure_reason);
+                        follow_up_failure = Some(failure);
                         break;
                     }
                 }
@@ -666,14 +2091,28 @@ impl Worker {
             }
         }
 
-        if let Some(failure_reason) = follow_up_failure {
+        if let Some(failure) = follow_up_failure {
             self.persist_transcript(&compacted_history, &history).await;
-            tracing::error!(worker_id = %self.id, reason = %failure_reason, "worker failed");
-            return Err(crate::error::AgentError::Other(anyhow::anyhow!(failure_reason)).into());
+            tracing::error!(worker_id = %self.id, reason = %failure, "worker failed");
+            return Err(failure.into());
         }
 
+        let output_limit = self.deps.runtime_config.output_limits.load().worker;
+        let prompts = self.deps.runtime_config.prompts.load_full();
+        let result = self
+            .hook
+            .enforce_output_limit(&agent, &mut history, &prompts, output_limit, result)
+            .await;
+        let result = self.condense_long_result(result).await;
+
         self.state = WorkerState::Done;
-        self.hook.send_status("completed");
+        self.progress_tx
+            .send_if_modified(|progress| advance_progress(progress, 100));
+        self.hook.send_status(if idle_timed_out {
+            "idle timeout"
+        } else {
+            "completed"
+        });
 
         // Write success log based on the worker log mode setting
         let log_mode = self.get_worker_log_mode();
@@ -684,30 +2123,258 @@ impl Worker {
         // Persist transcript blob
         self.persist_transcript(&compacted_history, &history).await;
 
-        tracing::info!(worker_id = %self.id, "worker completed");
-        Ok(result)
+        // Planned calls never ran, so they touched nothing.
+        let touched = if self.mode.is_plan() {
+            TouchedResources::default()
+        } else {
+            self.hook.touched_resources()
+        };
+        let planned_calls = self.mode.planned_calls();
+        tracing::info!(
+            worker_id = %self.id,
+            files_modified = touched.files_modified.len(),
+            urls_visited = touched.urls_visited.len(),
+            planned_calls = planned_calls.len(),
+            "worker completed"
+        );
+        Ok(WorkerOutcome {
+            result,
+            touched,
+            tokens_in: 0,
+            tokens_out: 0,
+            segments: 0,
+            planned_calls,
+        })
+    }
+
+    /// Condense a final result over `result_summary_threshold_chars` with the
+    /// compactor model, optionally saving the full text for the channel to
+    /// attach. Short results, and results whose summary fails or comes back
+    /// blank, are returned unchanged.
+    async fn condense_long_result(&self, result: String) -> String {
+        let worker_config = self.deps.runtime_config.worker.load();
+        let threshold = worker_config.result_summary_threshold_chars;
+        let target_chars = worker_config.result_summary_target_chars;
+        let attach = worker_config.attach_full_result;
+        drop(worker_config);
+        let length = result.chars().count();
+        if !needs_condensing(length, threshold, target_chars) {
+            return result;
+        }
+
+        self.hook.send_status("summarizing result");
+        let preamble = match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_system_worker_result_summary(target_chars)
+        {
+            Ok(preamble) => preamble,
+            Err(error) => {
+                tracing::warn!(worker_id = %self.id, %error, "failed to render result summary prompt");
+                return result;
+            }
+        };
+        let routing = self.deps.runtime_config.routing.load();
+        let model_name = routing.resolve(ProcessType::Compactor, None).to_string();
+        let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
+            .with_context(&*self.deps.agent_id, "compactor")
+            .with_routing((**routing).clone());
+        let agent = AgentBuilder::new(model).preamble(&preamble).build();
+
+        let summary = match agent.prompt(&result).await {
+            Ok(summary) if !summary.trim().is_empty() => summary.trim().to_string(),
+            Ok(_) => {
+                tracing::warn!(worker_id = %self.id, "result summary came back blank, keeping full result");
+                return result;
+            }
+            Err(error) => {
+                tracing::warn!(worker_id = %self.id, %error, "result summary failed, keeping full result");
+                return result;
+            }
+        };
+        tracing::info!(
+            worker_id = %self.id,
+            length,
+            summary_length = summary.chars().count(),
+            "condensed long worker result"
+        );
+        if !attach {
+            return summary;
+        }
+
+        // The channel may send this file to the user as-is, so it gets the
+        // same scrubbing the result text gets on its way to the channel.
+        let full = match self.deps.runtime_config.secrets.load().as_ref().as_ref() {
+            Some(store) => crate::secrets::scrub::scrub_with_store(&result, store),
+            None => result,
+        };
+        let full = crate::secrets::scrub::scrub_leaks(&full);
+        let saved_dir = self.deps.runtime_config.saved_dir();
+        let path = saved_dir.join(format!("worker-result-{}.md", self.id));
+        if let Err(error) =
+            std::fs::create_dir_all(&saved_dir).and_then(|()| std::fs::write(&path, &full))
+        {
+            tracing::warn!(worker_id = %self.id, %error, path = %path.display(), "failed to save full worker result");
+            return summary;
+        }
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_system_worker_result_attached(&path.display().to_string(), length)
+        {
+            Ok(note) => format!("{summary}\n\n{note}"),
+            Err(error) => {
+                tracing::warn!(worker_id = %self.id, %error, "failed to render full result note");
+                summary
+            }
+        }
+    }
+
+    /// Recover a usable result after the final response came back blank.
+    ///
+    /// Re-prompts once for a summary of the work. If that is blank or fails,
+    /// falls back to the most recent non-blank assistant text in history.
+    async fn recover_empty_result(
+        &self,
+        agent: &rig::agent::Agent<SpacebotModel>,
+        history: &mut Vec<rig::message::Message>,
+    ) -> Option<String> {
+        tracing::warn!(worker_id = %self.id, "worker produced empty result, asking for a summary");
+        self.hook.send_status("summarizing");
+
+        match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_system_worker_empty_result()
+        {
+            Ok(prompt) => match self.hook.prompt_once(agent, history, prompt).await {
+                Ok(summary) if !summary.trim().is_empty() => return Some(summary),
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!(worker_id = %self.id, %error, "empty-result summary prompt failed");
+                }
+            },
+            Err(error) => {
+                tracing::warn!(worker_id = %self.id, %error, "failed to render empty-result summary prompt");
+            }
+        }
+
+        let fallback = last_non_blank_assistant_text(history);
+        if fallback.is_some() {
+            tracing::info!(
+                worker_id = %self.id,
+                "using last assistant text as worker result"
+            );
+        }
+        fallback
+    }
+
+    /// Strip tool-result bulk that no longer earns its place in context:
+    /// superseded snapshots and operator-configured ephemeral tool output.
+    fn prune_tool_results(&self, history: &mut [rig::message::Message]) {
+        dedup_tool_results(history);
+        let worker_config = self.deps.runtime_config.worker.load();
+        if !worker_config.ephemeral_tools.is_empty() {
+            elide_ephemeral_tool_results(history, &worker_config.ephemeral_tools);
+        }
     }
 
     /// Check context usage and compact history if approaching the limit.
     ///
     /// Workers don't have a full Compactor instance — they do inline compaction
     /// by summarizing older tool calls and results into a condensed recap.
-    /// No LLM call, just programmatic truncation with a summary marker.
+    /// By default there is no LLM call, just programmatic truncation with a
+    /// summary marker; [`CompactionStrategy::Llm`] asks the compactor model.
     async fn maybe_compact_history(
         &self,
         compacted_history: &mut Vec<rig::message::Message>,
         history: &mut Vec<rig::message::Message>,
     ) {
-        let context_window = **self.deps.runtime_config.context_window.load();
-        let estimated = estimate_history_tokens(history);
+        self.publish_telemetry(history);
+        if !self.compaction_enabled() {
+            return;
+        }
+
+        let context_window = self.context_window();
+        let estimated = self.token_estimator.estimate_history(history);
         let usage = estimated as f32 / context_window as f32;
 
         if usage < 0.70 {
             return;
         }
 
-        self.compact_history(compacted_history, history, 0.50, "worker history compacted")
-            .await;
+        if let Err(error) = self
+            .compact_history(compacted_history, history, 0.50, "worker history compacted")
+            .await
+        {
+            tracing::error!(worker_id = %self.id, %error, "failed to render worker compact marker, used fallback");
+        }
+    }
+
+    /// Publish the current context pressure on `telemetry_tx`. Watchers are
+    /// only woken when a value actually changed.
+    fn publish_telemetry(&self, history: &[rig::message::Message]) {
+        let telemetry = WorkerTelemetry::measure(
+            self.token_estimator,
+            history,
+            self.context_window(),
+            self.run_stats.segments.load(Ordering::Relaxed),
+            self.run_stats.compactions.load(Ordering::Relaxed),
+        );
+        self.telemetry_tx.send_if_modified(|current| {
+            let changed = *current != telemetry;
+            *current = telemetry;
+            changed
+        });
+    }
+
+    /// Model this worker runs on: the override, or the worker route.
+    fn model_name(&self) -> String {
+        match &self.model_override {
+            Some(model) => model.clone(),
+            None => self
+                .deps
+                .runtime_config
+                .routing
+                .load()
+                .resolve(ProcessType::Worker, None)
+                .to_string(),
+        }
+    }
+
+    /// Context window that compaction and telemetry measure this worker's
+    /// usage against: the smallest among its model and that model's
+    /// fallbacks, since any of them may serve the next call. Models without
+    /// a known window use the agent's `context_window`.
+    fn context_window(&self) -> usize {
+        let default = **self.deps.runtime_config.context_window.load();
+        self.deps
+            .runtime_config
+            .routing
+            .load()
+            .chain_context_window(&self.model_name(), default)
+    }
+
+    /// Whether `error` is a context overflow, by the built-in phrasings and
+    /// any configured `context_overflow_patterns`.
+    fn is_context_overflow(&self, error: &impl std::fmt::Display) -> bool {
+        self.deps
+            .runtime_config
+            .routing
+            .load()
+            .is_context_overflow(&error.to_string())
+    }
+
+    /// Whether history compaction is allowed for this worker. When it is
+    /// not, context overflow fails the worker instead of being recovered.
+    fn compaction_enabled(&self) -> bool {
+        self.deps.runtime_config.worker.load().compaction_enabled
     }
 
     /// Aggressive compaction for context overflow recovery.
@@ -720,48 +2387,163 @@ impl Worker {
         compacted_history: &mut Vec<rig::message::Message>,
         history: &mut Vec<rig::message::Message>,
     ) {
-        self.compact_history(
-            compacted_history,
-            history,
-            0.75,
-            "worker history force-compacted (overflow recovery)",
-        )
-        .await;
+        if !self.compaction_enabled() {
+            return;
+        }
+
+        let context_window = self.context_window();
+        let max_message_fraction = self
+            .deps
+            .runtime_config
+            .compaction
+            .load()
+            .max_message_fraction;
+        let max_message_tokens = (context_window as f32 * max_message_fraction) as usize;
+        let truncated = truncate_oversized_messages(history, max_message_tokens);
+        if truncated > 0 {
+            tracing::warn!(
+                worker_id = %self.id,
+                truncated,
+                max_message_tokens,
+                "truncated oversized messages in worker history"
+            );
+        }
+
+        if let Err(error) = self
+            .compact_history(
+                compacted_history,
+                history,
+                0.75,
+                "worker history force-compacted (overflow recovery)",
+            )
+            .await
+        {
+            tracing::error!(worker_id = %self.id, %error, "failed to render worker compact marker, used fallback");
+        }
+    }
+
+    /// Summarize compacted-away messages with the compactor model, for
+    /// [`CompactionStrategy::Llm`]. `None` when the call fails, times out or
+    /// comes back blank, so compaction falls back to the recap instead of
+    /// failing.
+    async fn summarize_compacted(&self, removed: &[rig::message::Message]) -> Option<String> {
+        let preamble = match self
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_static("compactor")
+        {
+            Ok(preamble) => preamble,
+            Err(error) => {
+                tracing::warn!(worker_id = %self.id, %error, "failed to render compactor prompt, using recap");
+                return None;
+            }
+        };
+        let routing = self.deps.runtime_config.routing.load();
+        let model_name = routing.resolve(ProcessType::Compactor, None).to_string();
+        let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
+            .with_context(&*self.deps.agent_id, "compactor")
+            .with_routing((**routing).clone());
+        let agent = AgentBuilder::new(model).preamble(&preamble).build();
+        let transcript = crate::agent::compactor::render_messages_as_transcript(removed);
+
+        match tokio::time::timeout(COMPACTION_SUMMARY_TIMEOUT, agent.prompt(&transcript)).await {
+            Ok(Ok(summary)) if !summary.trim().is_empty() => Some(summary.trim().to_string()),
+            Ok(Ok(_)) => {
+                tracing::warn!(worker_id = %self.id, "compaction summary came back blank, using recap");
+                None
+            }
+            Ok(Err(error)) => {
+                tracing::warn!(worker_id = %self.id, %error, "compaction summary failed, using recap");
+                None
+            }
+            Err(_) => {
+                tracing::warn!(
+                    worker_id = %self.id,
+                    timeout_secs = COMPACTION_SUMMARY_TIMEOUT.as_secs(),
+                    "compaction summary timed out, using recap"
+                );
+                None
+            }
+        }
     }
 
     /// Compact worker history by removing a fraction of the oldest messages.
+    ///
+    /// The messages are gone by the time the marker is rendered, so when
+    /// `fragments/system/worker_compact` fails the compaction still goes
+    /// through with [`fallback_compact_marker`] and the render error is
+    /// returned for the caller to report.
     async fn compact_history(
         &self,
         compacted_history: &mut Vec<rig::message::Message>,
         history: &mut Vec<rig::message::Message>,
         fraction: f32,
         log_message: &str,
-    ) {
+    ) -> Result<()> {
         let total = history.len();
         if total <= 4 {
-            return;
+            return Ok(());
         }
 
-        let context_window = **self.deps.runtime_config.context_window.load();
-        let estimated = estimate_history_tokens(history);
+        let context_window = self.context_window();
+        let estimated = self.token_estimator.estimate_history(history);
         let usage = estimated as f32 / context_window as f32;
 
         let remove_count = ((total as f32 * fraction) as usize)
             .max(1)
             .min(total.saturating_sub(2));
-        let removed: Vec<rig::message::Message> = history.drain(..remove_count).collect();
+        // The first user text message is the task (or the preflight check
+        // that carries it). It stays first because the marker goes after it.
+        let task_index = history
+            .iter()
+            .position(crate::agent::channel_history::is_user_text_message);
+        let (removed, marker_index) = drain_unpinned(history, remove_count, |index, message| {
+            Some(index) == task_index || self.pinned.contains(message)
+        });
+        if removed.is_empty() {
+            tracing::warn!(
+                worker_id = %self.id,
+                pinned = self.pinned.len() + usize::from(task_index.is_some()),
+                "nothing to compact, every older message is pinned"
+            );
+            return Ok(());
+        }
+        let remove_count = removed.len();
         compacted_history.extend(removed.iter().cloned());
 
-        let recap = build_worker_recap(&removed);
-        let prompt_engine = self.deps.runtime_config.prompts.load();
-        let marker = match prompt_engine.render_system_worker_compact(remove_count, &recap) {
-            Ok(m) => m,
-            Err(error) => {
-                tracing::error!(%error, "failed to render worker compact marker");
-                return;
-            }
+        let worker_config = self.deps.runtime_config.worker.load();
+        let strategy = worker_config.compaction_strategy;
+        let recap_tools = worker_config.recap_tools.clone();
+        drop(worker_config);
+        let summary = match strategy {
+            CompactionStrategy::Recap => None,
+            CompactionStrategy::Llm => self.summarize_compacted(&removed).await,
         };
-        history.insert(0, rig::message::Message::from(marker));
+        let recap = summary.unwrap_or_else(|| {
+            let max_recap_bytes = self.deps.runtime_config.compaction.load().max_recap_bytes;
+            build_worker_recap(&removed, &recap_tools, max_recap_bytes)
+        });
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        let (marker, rendered) =
+            match prompt_engine.render_system_worker_compact(remove_count, &recap) {
+                Ok(marker) => (marker, Ok(())),
+                Err(error) => (fallback_compact_marker(remove_count, &recap), Err(error)),
+            };
+        history.insert(marker_index, crate::llm::marker::message(marker));
+        // The cut point can separate a tool call from its result.
+        crate::agent::tool_call_repair::repair_and_log(
+            history,
+            self.deps.runtime_config.compaction.load().tool_call_repair,
+            &ProcessId::Worker(self.id),
+        );
+        self.run_stats.compactions.fetch_add(1, Ordering::Relaxed);
+        self.publish_telemetry(history);
+        self.hook.send_worker_event(WorkerEvent::Compacted {
+            removed: remove_count,
+            remaining: history.len(),
+        });
 
         tracing::info!(
             worker_id = %self.id,
@@ -770,6 +2552,85 @@ impl Worker {
             usage = %format!("{:.0}%", usage * 100.0),
             "{log_message}"
         );
+        rendered
+    }
+
+    /// The note appended to a prompt retried after context overflow.
+    fn overflow_notice(&self) -> String {
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        prompt_engine
+            .render_system_worker_overflow()
+            .unwrap_or_else(|error| {
+                tracing::error!(worker_id = %self.id, %error, "failed to render worker overflow notice, using fallback");
+                FALLBACK_OVERFLOW_NOTICE.to_string()
+            })
+    }
+
+    /// Whether `true` has been sent on the cancel channel.
+    fn cancel_requested(&self) -> bool {
+        *self.cancel_rx.borrow()
+    }
+
+    /// Stop as cancelled: mark the worker failed, write the failure log and
+    /// persist the transcript, returning the error `run` hands back.
+    async fn fail_cancelled(
+        &mut self,
+        compacted_history: &[rig::message::Message],
+        history: &[rig::message::Message],
+        reason: &str,
+    ) -> crate::error::Error {
+        self.state = WorkerState::Failed;
+        self.hook.send_status("cancelled");
+        self.write_failure_log(history, &format!("cancelled: {reason}"));
+        self.persist_transcript(compacted_history, history).await;
+        tracing::info!(worker_id = %self.id, %reason, "worker cancelled");
+        AgentError::Cancelled {
+            reason: reason.to_string(),
+        }
+        .into()
+    }
+
+    /// Stop as timed out: mark the worker failed, write a failure log naming
+    /// the limit and how far it got, persist the transcript and return the
+    /// error `run` hands back.
+    async fn fail_timed_out(
+        &mut self,
+        compacted_history: &[rig::message::Message],
+        history: &[rig::message::Message],
+        segments: usize,
+    ) -> crate::error::Error {
+        let limit_secs = self.max_duration.unwrap_or_default().as_secs();
+        let error = AgentError::TimedOut {
+            limit_secs,
+            segments,
+        };
+        self.state = WorkerState::Failed;
+        self.hook.send_status("timed out");
+        self.write_failure_log(history, &error.to_string());
+        self.persist_transcript(compacted_history, history).await;
+        tracing::warn!(worker_id = %self.id, limit_secs, segments, "worker timed out");
+        error.into()
+    }
+
+    /// Estimate the tokens of a prompt call the provider reported no usage
+    /// for. `history_len` is the history length before the call.
+    fn account_unreported_tokens(
+        &self,
+        usage_before: TokenUsage,
+        history: &[rig::message::Message],
+        history_len: usize,
+    ) {
+        if !self.hook.token_usage().since(usage_before).is_empty() {
+            return;
+        }
+        let (tokens_in, tokens_out) =
+            estimate_call_tokens(self.token_estimator, history, history_len);
+        self.run_stats
+            .tokens_in
+            .fetch_add(tokens_in, Ordering::Relaxed);
+        self.run_stats
+            .tokens_out
+            .fetch_add(tokens_out, Ordering::Relaxed);
     }
 
     /// Persist the compressed transcript blob to worker_runs.
@@ -800,6 +2661,14 @@ impl Worker {
                 _ => None,
             })
             .sum();
+        self.run_stats
+            .tool_calls
+            .store(tool_calls as usize, Ordering::Relaxed);
+        self.run_stats.estimated_tokens.store(
+            self.token_estimator.estimate_history(history),
+            Ordering::Relaxed,
+        );
+        self.transcript.update(full_history);
 
         if let Err(error) =
             sqlx::query("UPDATE worker_runs SET transcript = ?, tool_calls = ? WHERE id = ?")
@@ -862,6 +2731,7 @@ impl Worker {
         };
         let _ = writeln!(log, "=== Worker {log_type} Log ===");
         let _ = writeln!(log, "Worker ID: {}", self.id);
+        let _ = writeln!(log, "Label: {}", self.label);
         if let Some(channel_id) = &self.channel_id {
             let _ = writeln!(log, "Channel ID: {channel_id}");
         }
@@ -877,98 +2747,71 @@ impl Worker {
             let _ = writeln!(log, "{err}");
         }
 
-        let _ = writeln!(log);
-        let _ = writeln!(log, "--- History ({} messages) ---", history.len());
-
-        for (index, message) in history.iter().enumerate() {
-            let _ = writeln!(log);
-            match message {
-                rig::message::Message::User { content } => {
-                    let _ = writeln!(log, "[{index}] User:");
-                    for item in content.iter() {
-                        match item {
-                            rig::message::UserContent::Text(t) => {
-                                let _ = writeln!(log, "  {}", t.text);
-                            }
-                            rig::message::UserContent::ToolResult(tr) => {
-                                let call_id = tr.call_id.as_deref().unwrap_or("unknown");
-                                let _ = writeln!(log, "  Tool Result (id: {call_id}):");
-                                for c in tr.content.iter() {
-                                    if let rig::message::ToolResultContent::Text(t) = c {
-                                        let text = if t.text.len() > 2000 {
-                                            let end = t.text.floor_char_boundary(2000);
-                                            format!("{}...[truncated]", &t.text[..end])
-                                        } else {
-                                            t.text.clone()
-                                        };
-                                        let _ = writeln!(log, "    {text}");
-                                    }
-                                }
-                            }
-                            _ => {
-                                let _ = writeln!(log, "  [non-text content]");
-                            }
-                        }
-                    }
-                }
-                rig::message::Message::Assistant { content, .. } => {
-                    let _ = writeln!(log, "[{index}] Assistant:");
-                    for item in content.iter() {
-                        match item {
-                            rig::message::AssistantContent::Text(t) => {
-                                let _ = writeln!(log, "  {}", t.text);
-                            }
-                            rig::message::AssistantContent::ToolCall(tc) => {
-                                let args = tc.function.arguments.to_string();
-                                let args_display = if args.len() > 500 {
-                                    let end = args.floor_char_boundary(500);
-                                    format!("{}...[truncated]", &args[..end])
-                                } else {
-                                    args
-                                };
-                                let _ = writeln!(
-                                    log,
-                                    "  Tool Call: {} (id: {})\n    Args: {args_display}",
-                                    tc.function.name, tc.id
-                                );
-                            }
-                            _ => {
-                                let _ = writeln!(log, "  [other content]");
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        format_history_log(&mut log, history);
 
         log
     }
 
+    /// Render the worker's transcript so far in the plain-text log format.
+    ///
+    /// Works at any point in the worker's life, not just on failure. Before
+    /// the first persistence point the history section is empty.
+    pub fn export_transcript(&self) -> String {
+        self.transcript.render(Some(self.state))
+    }
+
+    /// JSON variant of [`export_transcript`](Self::export_transcript).
+    pub fn export_transcript_json(&self) -> serde_json::Value {
+        self.transcript.export_json()
+    }
+
+    /// Handle for exporting the transcript after the worker has been moved
+    /// into its task, e.g. from an admin command.
+    pub fn transcript_handle(&self) -> WorkerTranscript {
+        self.transcript.clone()
+    }
+
     /// Write a structured log file for a successful worker execution.
     fn write_success_log(&self, history: &[rig::message::Message]) {
+        let log = self.build_log_content(history, None);
+        self.write_log_file(&log, true, &format!("{}.log", self.log_file_stem()));
+    }
+
+    /// Log file name without extension: `worker_<id>_<label>_<timestamp>`.
+    fn log_file_stem(&self) -> String {
         let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("worker_{}_{}.log", self.id, timestamp);
-        let log_dir = self.get_log_directory(true);
-        let path = log_dir.join(&filename);
+        format!("worker_{}_{}_{}", self.id, self.label, timestamp)
+    }
 
-        let log = self.build_log_content(history, None);
+    /// Best-effort atomic write of a worker log, recorded in the run
+    /// manifest when `[worker] log_manifest` is on.
+    fn write_log_file(&self, log: &str, is_success: bool, filename: &str) {
+        let kind = if is_success { "success" } else { "failure" };
+        let log_dir = self.get_log_directory(is_success);
+
+        let path = match worker_log::write_atomically(&log_dir, filename, log) {
+            Ok(path) => path,
+            Err(write_error) => {
+                tracing::warn!(
+                    worker_id = %self.id,
+                    path = %log_dir.join(filename).display(),
+                    %write_error,
+                    "failed to write worker {kind} log"
+                );
+                return;
+            }
+        };
+        tracing::info!(
+            worker_id = %self.id,
+            path = %path.display(),
+            "worker {kind} log written"
+        );
 
-        // Best-effort write
-        if let Err(write_error) =
-            std::fs::create_dir_all(&log_dir).and_then(|()| std::fs::write(&path, &log))
+        if self.deps.runtime_config.worker.load().log_manifest
+            && let Err(error) =
+                worker_log::record_in_manifest(&self.logs_dir, &path, self.id, is_success)
         {
-            tracing::warn!(
-                worker_id = %self.id,
-                path = %path.display(),
-                %write_error,
-                "failed to write worker success log"
-            );
-        } else {
-            tracing::info!(
-                worker_id = %self.id,
-                path = %path.display(),
-                "worker success log written"
-            );
+            tracing::warn!(worker_id = %self.id, %error, "failed to update worker log manifest");
         }
     }
 
@@ -976,33 +2819,246 @@ impl Worker {
     /// trace (task, history, error). Called on failure so we have something
     /// to inspect after the fact.
     fn write_failure_log(&self, history: &[rig::message::Message], error: &str) {
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("worker_{}_{}.log", self.id, timestamp);
-        let log_dir = self.get_log_directory(false);
-        let path = log_dir.join(&filename);
-
+        let format = self.deps.runtime_config.worker.load().failure_log_format;
+        let stem = self.log_file_stem();
+        if format.writes_json() {
+            let log = self.build_log_json(history, error);
+            match serde_json::to_string_pretty(&log) {
+                Ok(log) => self.write_log_file(&log, false, &format!("{stem}.json")),
+                Err(error) => {
+                    tracing::warn!(worker_id = %self.id, %error, "failed to serialize worker failure log");
+                }
+            }
+        }
         let log = self.build_log_content(history, Some(error));
+        if format.writes_text() {
+            self.write_log_file(&log, false, &format!("{stem}.log"));
+        }
+        self.send_failure_log_to_sink(error, log);
+    }
 
-        // Best-effort write
-        if let Err(write_error) =
-            std::fs::create_dir_all(&log_dir).and_then(|()| std::fs::write(&path, &log))
-        {
-            tracing::warn!(
-                worker_id = %self.id,
-                path = %path.display(),
-                %write_error,
-                "failed to write worker failure log"
-            );
-        } else {
-            tracing::info!(
-                worker_id = %self.id,
-                path = %path.display(),
-                "worker failure log written"
-            );
+    /// The failure log as structured JSON, carrying the same fields as the
+    /// text format.
+    fn build_log_json(&self, history: &[rig::message::Message], error: &str) -> serde_json::Value {
+        serde_json::json!({
+            "worker_id": self.id.to_string(),
+            "label": self.label,
+            "channel_id": self.channel_id.as_deref(),
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "state": format!("{:?}", self.state),
+            "task": self.task,
+            "error": error,
+            "history": history_log_json(history),
+        })
+    }
+
+    /// POST a failure log to the configured remote sink, if any.
+    ///
+    /// Runs detached with the sink's timeout so delivery never delays worker
+    /// termination. Failures are logged and dropped; the local file remains
+    /// the fallback.
+    fn send_failure_log_to_sink(&self, error: &str, log: String) {
+        let Some(sink) = self
+            .deps
+            .runtime_config
+            .worker
+            .load()
+            .failure_log_sink
+            .clone()
+        else {
+            return;
+        };
+
+        let payload = serde_json::json!({
+            "worker_id": self.id.to_string(),
+            "agent_id": self.deps.agent_id.to_string(),
+            "channel_id": self.channel_id.as_ref().map(|id| id.to_string()),
+            "task": self.task,
+            "error": error,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "log": log,
+        });
+        let http_client = self.deps.llm_manager.http_client().clone();
+        let worker_id = self.id;
+
+        tokio::spawn(async move {
+            let mut request = http_client
+                .post(&sink.url)
+                .timeout(std::time::Duration::from_secs(sink.timeout_secs))
+                .json(&payload);
+            if let Some(token) = &sink.auth_token {
+                request = request.bearer_auth(token);
+            }
+
+            match request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                Ok(_) => {
+                    tracing::debug!(%worker_id, "worker failure log delivered to remote sink");
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        %worker_id,
+                        %error,
+                        "failed to deliver worker failure log to remote sink"
+                    );
+                }
+            }
+        });
+    }
+}
+
+/// Longest tool result kept in a worker log, in bytes.
+const LOG_RESULT_MAX_BYTES: usize = 2000;
+
+/// Longest tool-call arguments kept in a worker log, in bytes.
+const LOG_ARGS_MAX_BYTES: usize = 500;
+
+/// Cut `text` to `max_bytes` on a char boundary, returning whether it was.
+fn truncate_for_log(text: &str, max_bytes: usize) -> (&str, bool) {
+    if text.len() > max_bytes {
+        (&text[..text.floor_char_boundary(max_bytes)], true)
+    } else {
+        (text, false)
+    }
+}
+
+/// Append the `--- History ---` section shared by worker logs and
+/// transcript exports. Long tool results and arguments are truncated.
+fn format_history_log(log: &mut String, history: &[rig::message::Message]) {
+    let _ = writeln!(log);
+    let _ = writeln!(log, "--- History ({} messages) ---", history.len());
+
+    for (index, message) in history.iter().enumerate() {
+        let _ = writeln!(log);
+        match message {
+            rig::message::Message::User { content } => {
+                let _ = writeln!(log, "[{index}] User:");
+                for item in content.iter() {
+                    match item {
+                        rig::message::UserContent::Text(t) => {
+                            let _ = writeln!(log, "  {}", t.text);
+                        }
+                        rig::message::UserContent::ToolResult(tr) => {
+                            let call_id = tr.call_id.as_deref().unwrap_or("unknown");
+                            let _ = writeln!(log, "  Tool Result (id: {call_id}):");
+                            for c in tr.content.iter() {
+                                if let rig::message::ToolResultContent::Text(t) = c {
+                                    let (text, truncated) =
+                                        truncate_for_log(&t.text, LOG_RESULT_MAX_BYTES);
+                                    let marker = if truncated { "...[truncated]" } else { "" };
+                                    let _ = writeln!(log, "    {text}{marker}");
+                                }
+                            }
+                        }
+                        _ => {
+                            let _ = writeln!(log, "  [non-text content]");
+                        }
+                    }
+                }
+            }
+            rig::message::Message::Assistant { content, .. } => {
+                let _ = writeln!(log, "[{index}] Assistant:");
+                for item in content.iter() {
+                    match item {
+                        rig::message::AssistantContent::Text(t) => {
+                            let _ = writeln!(log, "  {}", t.text);
+                        }
+                        rig::message::AssistantContent::ToolCall(tc) => {
+                            let args = tc.function.arguments.to_string();
+                            let (args_display, truncated) =
+                                truncate_for_log(&args, LOG_ARGS_MAX_BYTES);
+                            let marker = if truncated { "...[truncated]" } else { "" };
+                            let _ = writeln!(
+                                log,
+                                "  Tool Call: {} (id: {})\n    Args: {args_display}{marker}",
+                                tc.function.name, tc.id
+                            );
+                        }
+                        _ => {
+                            let _ = writeln!(log, "  [other content]");
+                        }
+                    }
+                }
+            }
         }
     }
 }
 
+/// The history as JSON for structured failure logs: one entry per message
+/// with its role and typed content parts. Tool results carry the name of
+/// the call they answer. Results and arguments are truncated to the text
+/// log's limits, with a `truncated` flag on each part that was cut.
+fn history_log_json(history: &[rig::message::Message]) -> Vec<serde_json::Value> {
+    let tool_names = tool_names_by_call_id(history, |_| true);
+
+    history
+        .iter()
+        .enumerate()
+        .map(|(index, message)| match message {
+            rig::message::Message::User { content } => {
+                let parts: Vec<_> = content
+                    .iter()
+                    .map(|item| match item {
+                        rig::message::UserContent::Text(t) => {
+                            serde_json::json!({ "type": "text", "text": t.text })
+                        }
+                        rig::message::UserContent::ToolResult(tr) => {
+                            let text = tr
+                                .content
+                                .iter()
+                                .filter_map(|c| match c {
+                                    rig::message::ToolResultContent::Text(t) => {
+                                        Some(t.text.as_str())
+                                    }
+                                    _ => None,
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            let (text, truncated) = truncate_for_log(&text, LOG_RESULT_MAX_BYTES);
+                            let call_id = tr.call_id.as_deref();
+                            serde_json::json!({
+                                "type": "tool_result",
+                                "call_id": call_id,
+                                "tool": tool_names.get(call_id.unwrap_or(&tr.id)),
+                                "text": text,
+                                "truncated": truncated,
+                            })
+                        }
+                        _ => serde_json::json!({ "type": "other" }),
+                    })
+                    .collect();
+                serde_json::json!({ "index": index, "role": "user", "content": parts })
+            }
+            rig::message::Message::Assistant { content, .. } => {
+                let parts: Vec<_> = content
+                    .iter()
+                    .map(|item| match item {
+                        rig::message::AssistantContent::Text(t) => {
+                            serde_json::json!({ "type": "text", "text": t.text })
+                        }
+                        rig::message::AssistantContent::ToolCall(tc) => {
+                            let args = tc.function.arguments.to_string();
+                            let (args, truncated) = truncate_for_log(&args, LOG_ARGS_MAX_BYTES);
+                            serde_json::json!({
+                                "type": "tool_call",
+                                "id": tc.id,
+                                "tool": tc.function.name,
+                                "args": args,
+                                "truncated": truncated,
+                            })
+                        }
+                        _ => serde_json::json!({ "type": "other" }),
+                    })
+                    .collect();
+                serde_json::json!({ "index": index, "role": "assistant", "content": parts })
+            }
+        })
+        .collect()
+}
+
 /// Tool names whose results are bulky and superseded by the latest call.
 /// Only the most recent result for each tool is kept in full; older results
 /// are replaced with a short marker to save context space.
@@ -1011,22 +3067,19 @@ impl Worker {
 /// always has the latest snapshot but doesn't waste context on stale ones.
 const DEDUP_TOOL_RESULTS: &[&str] = &["browser_snapshot", "browser_tab_list"];
 
-/// Replace all but the most recent result for each tool in `DEDUP_TOOL_RESULTS`
-/// with a short placeholder. This dramatically reduces context usage for
-/// browser-heavy workflows where `browser_snapshot` returns large ARIA trees
-/// on every call.
-///
-/// Note: this mutates `history` in-place, so superseded results are also
-/// replaced in the persisted transcript.
-fn dedup_tool_results(history: &mut [rig::message::Message]) {
-    // Step 1: Build a map from tool-call ID → tool name for dedup-eligible tools.
-    // We need this because ToolResult only has call_id, not the tool name.
-    let mut call_id_to_tool: HashMap<String, String> = HashMap::new();
-    for message in history.iter() {
+/// Map from tool-call ID to tool name for every call in `history` whose tool
+/// `include` accepts. Tool results only carry the call ID, so this is how a
+/// result is traced back to the tool that produced it.
+fn tool_names_by_call_id(
+    history: &[rig::message::Message],
+    include: impl Fn(&str) -> bool,
+) -> HashMap<String, String> {
+    let mut call_id_to_tool = HashMap::new();
+    for message in history {
         if let rig::message::Message::Assistant { content, .. } = message {
             for item in content.iter() {
                 if let rig::message::AssistantContent::ToolCall(tc) = item
-                    && DEDUP_TOOL_RESULTS.contains(&tc.function.name.as_str())
+                    && include(&tc.function.name)
                 {
                     // Rig uses call_id when present, falls back to id.
                     let effective_id = tc.call_id.as_ref().unwrap_or(&tc.id);
@@ -1035,6 +3088,19 @@ fn dedup_tool_results(history: &mut [rig::message::Message]) {
             }
         }
     }
+    call_id_to_tool
+}
+
+/// Replace all but the most recent result for each tool in `DEDUP_TOOL_RESULTS`
+/// with a short placeholder. This dramatically reduces context usage for
+/// browser-heavy workflows where `browser_snapshot` returns large ARIA trees
+/// on every call.
+///
+/// Note: this mutates `history` in-place, so superseded results are also
+/// replaced in the persisted transcript.
+fn dedup_tool_results(history: &mut [rig::message::Message]) {
+    // Step 1: Build a map from tool-call ID → tool name for dedup-eligible tools.
+    let call_id_to_tool = tool_names_by_call_id(history, |name| DEDUP_TOOL_RESULTS.contains(&name));
 
     if call_id_to_tool.is_empty() {
         return;
@@ -1095,11 +3161,419 @@ fn dedup_tool_results(history: &mut [rig::message::Message]) {
     }
 }
 
+/// Fit a task description into the configured character budget before a
+/// worker is built from it.
+///
+/// Tasks within `max_task_chars` pass through unchanged. Longer tasks are
+/// refused under [`TaskOverflowPolicy::Reject`]. Under
+/// [`TaskOverflowPolicy::Attach`] the full text is saved to `saved_dir` and the
+/// worker gets the leading portion plus the `task_truncation` marker pointing
+/// at the file. The file name is derived from the task content, so respawning
+/// the same task yields the same text and duplicate-task detection keeps
+/// working.
+pub fn fit_task_to_budget(
+    task: String,
+    worker_config: &WorkerConfig,
+    saved_dir: &Path,
+    prompts: &crate::prompts::PromptEngine,
+) -> std::result::Result<String, AgentError> {
+    let max_chars = worker_config.max_task_chars;
+    let length = task.chars().count();
+    if length <= max_chars {
+        return Ok(task);
+    }
+
+    match worker_config.task_overflow {
+        TaskOverflowPolicy::Reject => Err(AgentError::TaskTooLong {
+            length,
+            max: max_chars,
+        }),
+        TaskOverflowPolicy::Attach => {
+            let digest = hex::encode(Sha256::digest(task.as_bytes()));
+            let path = saved_dir.join(format!("worker-task-{}.md", &digest[..16]));
+            std::fs::create_dir_all(saved_dir)
+                .and_then(|()| std::fs::write(&path, &task))
+                .map_err(|error| {
+                    AgentError::Other(anyhow::anyhow!(
+                        "failed to save over-length worker task to {}: {error}",
+                        path.display()
+                    ))
+                })?;
+            tracing::info!(
+                length,
+                max_chars,
+                path = %path.display(),
+                "worker task over budget, full text moved to a reference file"
+            );
+
+            let head: String = task.chars().take(max_chars).collect();
+            let marker = prompts
+                .render_system_task_truncation(max_chars, length, &path.display().to_string())
+                .map_err(|error| AgentError::Other(anyhow::anyhow!("{error}")))?;
+            Ok(format!("{head}\n\n{marker}"))
+        }
+    }
+}
+
+/// Whether a result of `length` characters should be condensed. A target at
+/// or above the result's own length would not shorten it, so that never
+/// triggers a summary.
+fn needs_condensing(length: usize, threshold: usize, target_chars: usize) -> bool {
+    threshold > 0 && length > threshold && target_chars < length
+}
+
+/// Most recent assistant text in `history` that isn't empty or whitespace.
+fn last_non_blank_assistant_text(history: &[rig::message::Message]) -> Option<String> {
+    history.iter().rev().find_map(|message| {
+        crate::agent::extract_last_assistant_text(std::slice::from_ref(message))
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    })
+}
+
+/// Remove up to `remove_count` of the oldest unpinned messages in `history`,
+/// compacting around pinned ones, which stay in place. The two most recent
+/// messages are never removed. Returns the removed messages, oldest first,
+/// and the index the compaction marker belongs at: where the first removed
+/// message was.
+///
+/// Pinned messages keep their full size, so they still count against the
+/// context window and compaction frees less the more is pinned. When
+/// everything older is pinned, nothing is removed; an overflow then can't be
+/// recovered and the worker fails once its overflow retries run out.
+fn drain_unpinned(
+    history: &mut Vec<rig::message::Message>,
+    remove_count: usize,
+    is_pinned: impl Fn(usize, &rig::message::Message) -> bool,
+) -> (Vec<rig::message::Message>, usize) {
+    let eligible = history.len().saturating_sub(2);
+    let mut remove = vec![false; history.len()];
+    let mut marked = 0;
+    for (index, message) in history.iter().enumerate().take(eligible) {
+        if marked == remove_count {
+            break;
+        }
+        if !is_pinned(index, message) {
+            remove[index] = true;
+            marked += 1;
+        }
+    }
+    let marker_index = remove.iter().position(|removed| *removed).unwrap_or(0);
+
+    let mut removed = Vec::with_capacity(marked);
+    let mut kept = Vec::with_capacity(history.len() - marked);
+    for (message, remove) in history.drain(..).zip(remove) {
+        if remove {
+            removed.push(message);
+        } else {
+            kept.push(message);
+        }
+    }
+    *history = kept;
+    (removed, marker_index)
+}
+
+/// The worker's conclusion: the last non-blank assistant text in `history`,
+/// or the segment `response` when there is none. After a run of
+/// `MaxTurnsError` segments the response that ends the loop answers a
+/// "continue" prompt and may not be the substantive conclusion; the history
+/// is what the worker actually said last.
+fn prefer_last_assistant_text(history: &[rig::message::Message], response: String) -> String {
+    last_non_blank_assistant_text(history).unwrap_or(response)
+}
+
+/// Where a worker's history checkpoint lives under `logs_dir`.
+pub fn checkpoint_path(logs_dir: &Path, worker_id: impl std::fmt::Display) -> PathBuf {
+    logs_dir
+        .join("checkpoints")
+        .join(format!("{worker_id}.json"))
+}
+
+/// Overwrite a worker's history checkpoint. Written to a temporary file and
+/// renamed so a crash mid-write leaves the previous checkpoint intact.
+async fn write_checkpoint(logs_dir: &Path, worker_id: WorkerId, history: &[rig::message::Message]) {
+    let path = checkpoint_path(logs_dir, worker_id);
+    let result = match serde_json::to_vec(history) {
+        Ok(bytes) => {
+            let tmp_path = path.with_extension("json.tmp");
+            async {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&tmp_path, bytes).await?;
+                tokio::fs::rename(&tmp_path, &path).await
+            }
+            .await
+        }
+        Err(error) => Err(std::io::Error::other(error)),
+    };
+    if let Err(error) = result {
+        tracing::warn!(
+            %worker_id,
+            %error,
+            path = %path.display(),
+            "failed to write worker checkpoint"
+        );
+    }
+}
+
+/// Delete a worker's history checkpoint once its run has ended, whether it
+/// finished or was cancelled. Only a crash or restart leaves one behind.
+pub async fn remove_checkpoint(logs_dir: &Path, worker_id: WorkerId) {
+    let path = checkpoint_path(logs_dir, worker_id);
+    if let Err(error) = tokio::fs::remove_file(&path).await
+        && error.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!(
+            %worker_id,
+            %error,
+            path = %path.display(),
+            "failed to remove worker checkpoint"
+        );
+    }
+}
+
+/// Read a history checkpoint written by `write_checkpoint`. A missing,
+/// unreadable, corrupt or empty checkpoint is logged and yields `None`, so
+/// the worker starts the task fresh.
+fn load_checkpoint(path: &Path) -> Option<Vec<rig::message::Message>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            tracing::warn!(%error, path = %path.display(), "no usable worker checkpoint, starting fresh");
+            return None;
+        }
+    };
+    match serde_json::from_slice::<Vec<rig::message::Message>>(&bytes) {
+        Ok(history) if !history.is_empty() => Some(history),
+        Ok(_) => {
+            tracing::warn!(path = %path.display(), "worker checkpoint is empty, starting fresh");
+            None
+        }
+        Err(error) => {
+            tracing::warn!(%error, path = %path.display(), "worker checkpoint is corrupt, starting fresh");
+            None
+        }
+    }
+}
+
+/// Rough token counts for a prompt call that appended `history[before..]`.
+/// Everything the model read counts as input: the prior history plus the new
+/// prompt and tool results. Its own assistant messages count as output.
+/// Each turn of a multi-turn call rereads the history, so this undercounts.
+fn estimate_call_tokens(
+    estimator: &dyn TokenEstimator,
+    history: &[rig::message::Message],
+    before: usize,
+) -> (u64, u64) {
+    let (prior, added) = history.split_at(before.min(history.len()));
+    let (output, input): (Vec<_>, Vec<_>) = added
+        .iter()
+        .cloned()
+        .partition(|message| matches!(message, rig::message::Message::Assistant { .. }));
+    let tokens_in = estimator.estimate_history(prior) + estimator.estimate_history(&input);
+    (tokens_in as u64, estimator.estimate_history(&output) as u64)
+}
+
+/// The error a worker fails with once `retries` overflow recoveries exceed
+/// `max_overflow_retries`, or `None` while it may compact and retry again.
+fn overflow_exhausted(retries: usize, max_overflow_retries: usize) -> Option<AgentError> {
+    (retries > max_overflow_retries).then_some(AgentError::ContextOverflowUnrecoverable {
+        attempts: max_overflow_retries,
+    })
+}
+
+/// Fixed English marker used when `fragments/system/worker_compact` fails to
+/// render, so the recap isn't dropped with the messages it replaces.
+fn fallback_compact_marker(remove_count: usize, recap: &str) -> String {
+    format!(
+        "[System: Earlier work has been summarized to free up context. \
+         {remove_count} messages compacted.]\n\n## Work completed so far:\n\n{recap}"
+    )
+}
+
+/// Progress implied by segments alone: a linear share of the segment budget,
+/// capped at 90 so it never claims the task is done. Workers that report
+/// progress with `set_progress` usually move past it.
+fn progress_floor(segments_completed: usize, max_segments: usize) -> u8 {
+    if max_segments == 0 {
+        return 0;
+    }
+    (segments_completed * 90 / max_segments).min(90) as u8
+}
+
+/// The lifecycle event that closes a run with `outcome`.
+fn terminal_event(outcome: &Result<WorkerOutcome>) -> WorkerEvent {
+    match outcome {
+        Ok(_) => WorkerEvent::Completed,
+        Err(crate::error::Error::Agent(error)) => match **error {
+            AgentError::Cancelled { .. } => WorkerEvent::Cancelled,
+            AgentError::TimedOut { .. } => WorkerEvent::TimedOut,
+            _ => WorkerEvent::Failed {
+                reason: error.to_string(),
+            },
+        },
+        Err(error) => WorkerEvent::Failed {
+            reason: error.to_string(),
+        },
+    }
+}
+
+/// Replace the results of ephemeral tools with a placeholder once the model
+/// has consumed them.
+///
+/// A result counts as consumed when an assistant message follows it in
+/// history. The tool call stays intact so the model still knows it ran the
+/// command; only the output is dropped.
+fn elide_ephemeral_tool_results(history: &mut [rig::message::Message], ephemeral_tools: &[String]) {
+    let call_id_to_tool = tool_names_by_call_id(history, |name| {
+        ephemeral_tools.iter().any(|tool| tool == name)
+    });
+
+    if call_id_to_tool.is_empty() {
+        return;
+    }
+
+    let Some(last_assistant_index) = history
+        .iter()
+        .rposition(|message| matches!(message, rig::message::Message::Assistant { .. }))
+    else {
+        return;
+    };
+
+    let mut elided = 0usize;
+    for message in history[..last_assistant_index].iter_mut() {
+        if let rig::message::Message::User { content } = message {
+            for item in content.iter_mut() {
+                if let rig::message::UserContent::ToolResult(tr) = item
+                    && let Some(call_id) = &tr.call_id
+                    && let Some(tool_name) = call_id_to_tool.get(call_id)
+                {
+                    let placeholder = format!("[ephemeral result for `{tool_name}`, elided]");
+                    let already_elided = matches!(
+                        tr.content.first(),
+                        rig::message::ToolResultContent::Text(text) if text.text == placeholder
+                    );
+                    if !already_elided {
+                        tr.content =
+                            rig::OneOrMany::one(rig::message::ToolResultContent::text(placeholder));
+                        elided += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    if elided > 0 {
+        tracing::debug!(elided, "elided ephemeral tool results from history");
+    }
+}
+
 /// Build a recap of removed worker history for the compaction marker.
 ///
 /// Extracts tool calls, assistant text, and tool results so the worker
-/// retains full context of what it already did after compaction.
-fn build_worker_recap(messages: &[rig::message::Message]) -> String {
+/// retains full context of what it already did after compaction. How much of
+/// each tool's activity survives is set per tool by `recap_tools`; tools not
+/// listed get [`RecapPriority::Normal`].
+pub(crate) fn build_worker_recap(
+    messages: &[rig::message::Message],
+    recap_tools: &HashMap<String, RecapPriority>,
+    max_bytes: usize,
+) -> String {
+    let recap = recap_lines(messages, recap_tools);
+    if recap.is_empty() {
+        "No significant actions recorded in compacted history.".into()
+    } else {
+        cap_recap(&recap, max_bytes)
+    }
+}
+
+/// Trim a recap to `max_bytes`, keeping the most recent actions whole and
+/// replacing the older ones with a count. An action is a `- ` line plus any
+/// continuation lines (results, wrapped text) that follow it.
+fn cap_recap(recap: &str, max_bytes: usize) -> String {
+    if recap.len() <= max_bytes {
+        return recap.to_string();
+    }
+
+    let mut actions: Vec<String> = Vec::new();
+    for line in recap.lines() {
+        match actions.last_mut() {
+            Some(action) if !line.starts_with("- ") => {
+                action.push_str(line);
+                action.push('\n');
+            }
+            _ => actions.push(format!("{line}\n")),
+        }
+    }
+
+    // Reserve room for the elision line so adding it never breaks the cap.
+    let elision_budget = format!("- …and {} more earlier actions\n", actions.len()).len();
+    let budget = max_bytes.saturating_sub(elision_budget);
+    let mut kept_bytes = 0;
+    let mut kept = 0;
+    for action in actions.iter().rev() {
+        if kept_bytes + action.len() > budget {
+            break;
+        }
+        kept_bytes += action.len();
+        kept += 1;
+    }
+
+    let elided = actions.len() - kept;
+    let mut capped = format!(
+        "- …and {elided} more earlier action{}\n",
+        if elided == 1 { "" } else { "s" }
+    );
+    for action in &actions[elided..] {
+        capped.push_str(action);
+    }
+    capped
+}
+
+/// Longest activity recap included in a cancellation notice.
+const CANCELLATION_RECAP_MAX_BYTES: usize = 1_500;
+
+/// Summarize what a cancelled worker got done, for the notice sent to the
+/// user: a recap of its activity (trimmed to whole lines within a byte
+/// budget) and its last assistant text. Either is `None` when there is
+/// nothing to report.
+pub(crate) fn summarize_cancelled_work(
+    history: &[rig::message::Message],
+    recap_tools: &HashMap<String, RecapPriority>,
+) -> (Option<String>, Option<String>) {
+    let recap = recap_lines(history, recap_tools);
+    let mut summary = String::new();
+    let mut full = false;
+    let mut omitted = 0;
+    for line in recap.lines() {
+        full = full || summary.len() + line.len() >= CANCELLATION_RECAP_MAX_BYTES;
+        if !full {
+            summary.push_str(line);
+            summary.push('\n');
+        } else if line.starts_with("- ") {
+            omitted += 1;
+        }
+    }
+    if omitted > 0 {
+        let _ = writeln!(summary, "- …and {omitted} more step(s)");
+    }
+
+    let summary = Some(summary.trim_end().to_string()).filter(|s| !s.is_empty());
+    (summary, crate::agent::extract_last_assistant_text(history))
+}
+
+/// Recap lines for tool calls, notes, and results, or an empty string.
+fn recap_lines(
+    messages: &[rig::message::Message],
+    recap_tools: &HashMap<String, RecapPriority>,
+) -> String {
+    let priority_for = |tool_name: &str| recap_tools.get(tool_name).copied().unwrap_or_default();
+
+    // Results only carry the call ID, so resolve each result's tool through
+    // the calls seen in the same slice.
+    let call_id_to_tool = tool_names_by_call_id(messages, |_| true);
+
     let mut recap = String::new();
 
     for message in messages {
@@ -1107,8 +3581,14 @@ fn build_worker_recap(messages: &[rig::message::Message]) -> String {
             rig::message::Message::Assistant { content, .. } => {
                 for item in content.iter() {
                     if let rig::message::AssistantContent::ToolCall(tc) = item {
-                        let args =
-                            crate::tools::truncate_output(&tc.function.arguments.to_string(), 200);
+                        let priority = priority_for(&tc.function.name);
+                        if priority == RecapPriority::Omit {
+                            continue;
+                        }
+                        let args = crate::tools::truncate_output(
+                            &tc.function.arguments.to_string(),
+                            priority.preview_bytes(),
+                        );
                         recap.push_str(&format!("- Called `{}` ({args})\n", tc.function.name));
                     }
                     if let rig::message::AssistantContent::Text(t) = item
@@ -1121,9 +3601,21 @@ fn build_worker_recap(messages: &[rig::message::Message]) -> String {
             rig::message::Message::User { content } => {
                 for item in content.iter() {
                     if let rig::message::UserContent::ToolResult(tr) = item {
+                        let priority = tr
+                            .call_id
+                            .as_deref()
+                            .and_then(|call_id| call_id_to_tool.get(call_id))
+                            .map(|tool_name| priority_for(tool_name))
+                            .unwrap_or_default();
+                        if priority == RecapPriority::Omit {
+                            continue;
+                        }
                         for c in tr.content.iter() {
                             if let rig::message::ToolResultContent::Text(t) = c {
-                                let truncated = crate::tools::truncate_output(&t.text, 200);
+                                let truncated = crate::tools::truncate_output(
+                                    &t.text,
+                                    priority.preview_bytes(),
+                                );
                                 recap.push_str(&format!("  Result: {truncated}\n"));
                             }
                         }
@@ -1133,9 +3625,726 @@ fn build_worker_recap(messages: &[rig::message::Message]) -> String {
         }
     }
 
-    if recap.is_empty() {
-        "No significant actions recorded in compacted history.".into()
-    } else {
-        recap
+    recap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DEADLINE_GRACE, PreflightVerdict, WorkdirGuard, WorkerOutcome, WorkerState,
+        WorkerTelemetry, WorkerTranscript, cancellation_requested, cap_recap, checkpoint_path,
+        collect_follow_ups, drain_unpinned, elide_ephemeral_tool_results, estimate_call_tokens,
+        fallback_compact_marker, fit_task_to_budget, handed_off, history_log_json, idle_expired,
+        last_non_blank_assistant_text, load_checkpoint, needs_condensing, next_retry_delay,
+        overflow_exhausted, parse_preflight_verdict, past_deadline, prefer_last_assistant_text,
+        progress_floor, remove_checkpoint, resolve_worker_label, summarize_cancelled_work,
+        terminal_event, transient_retry_delay, within_deadline, worker_label, write_checkpoint,
+    };
+    use crate::WorkerEvent;
+    use crate::agent::token_estimator::HeuristicEstimator;
+    use crate::config::{TaskOverflowPolicy, WorkerConfig};
+    use crate::error::AgentError;
+    use rig::message::{AssistantContent, Message};
+    use rig::one_or_many::OneOrMany;
+
+    fn assistant_text(text: &str) -> Message {
+        Message::Assistant {
+            id: None,
+            content: OneOrMany::one(AssistantContent::text(text)),
+        }
+    }
+
+    #[test]
+    fn telemetry_reports_usage_as_a_fraction_of_the_context_window() {
+        // 400 chars of text at the chars/4 estimate is 100 tokens.
+        let history = vec![assistant_text(&"x".repeat(400))];
+
+        let telemetry = WorkerTelemetry::measure(&HeuristicEstimator, &history, 1_000, 3, 1);
+        assert_eq!(telemetry.estimated_tokens, 100);
+        assert!((telemetry.context_usage - 0.1).abs() < f32::EPSILON);
+        assert_eq!((telemetry.segment, telemetry.compactions), (3, 1));
+
+        assert_eq!(
+            WorkerTelemetry::measure(&HeuristicEstimator, &history, 0, 1, 0).context_usage,
+            0.0
+        );
+    }
+
+    #[test]
+    fn empty_result_falls_back_to_last_non_blank_assistant_text() {
+        let history = vec![
+            Message::from("do the task"),
+            assistant_text("Created `report.md` with the findings."),
+            Message::from("continue"),
+            assistant_text("  \n\t "),
+        ];
+
+        assert_eq!(
+            last_non_blank_assistant_text(&history).as_deref(),
+            Some("Created `report.md` with the findings.")
+        );
+    }
+
+    #[test]
+    fn compaction_keeps_pinned_messages_in_place() {
+        let mut history = vec![
+            Message::from("the task"),
+            assistant_text("reading config"),
+            Message::from("config contents"),
+            assistant_text("editing"),
+            assistant_text("done editing"),
+            Message::from("latest"),
+        ];
+        let pinned = Message::from("config contents");
+
+        let (removed, marker_index) = drain_unpinned(&mut history, 3, |index, message| {
+            index == 0 || *message == pinned
+        });
+        assert_eq!(
+            removed,
+            [
+                assistant_text("reading config"),
+                assistant_text("editing"),
+                assistant_text("done editing"),
+            ]
+        );
+        assert_eq!(marker_index, 1);
+        assert_eq!(
+            history,
+            [Message::from("the task"), pinned, Message::from("latest")]
+        );
+
+        // With everything older pinned there is nothing to remove.
+        let (removed, _) = drain_unpinned(&mut history, 3, |_, _| true);
+        assert!(removed.is_empty());
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn unreported_call_tokens_split_by_who_wrote_them() {
+        use crate::agent::compactor::estimate_history_tokens;
+
+        let prior = vec![Message::from("a".repeat(400))];
+        let prompt = Message::from("b".repeat(40));
+        let reply = assistant_text(&"c".repeat(200));
+        let mut history = prior.clone();
+        history.extend([prompt.clone(), reply.clone()]);
+
+        let (tokens_in, tokens_out) =
+            estimate_call_tokens(&HeuristicEstimator, &history, prior.len());
+        assert_eq!(
+            tokens_in,
+            (estimate_history_tokens(&prior) + estimate_history_tokens(&[prompt])) as u64
+        );
+        assert_eq!(tokens_out, estimate_history_tokens(&[reply]) as u64);
+        assert_eq!(
+            estimate_call_tokens(&HeuristicEstimator, &history, history.len()).1,
+            0
+        );
+    }
+
+    #[test]
+    fn last_assistant_text_wins_over_the_segment_response() {
+        let history = vec![
+            Message::from("do the task"),
+            assistant_text("Looked at the logs."),
+            Message::from("Continue where you left off. Do not repeat completed work."),
+            assistant_text("The outage was caused by an expired TLS certificate on the proxy."),
+            assistant_text("   "),
+        ];
+
+        assert_eq!(
+            prefer_last_assistant_text(&history, "ok".to_string()),
+            "The outage was caused by an expired TLS certificate on the proxy."
+        );
+        assert_eq!(
+            prefer_last_assistant_text(&[Message::from("do the task")], "ok".to_string()),
+            "ok"
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoints_round_trip_and_bad_ones_start_fresh() {
+        let logs = tempfile::tempdir().unwrap();
+        let worker_id = uuid::Uuid::new_v4();
+        let history = vec![
+            Message::from("do the task"),
+            assistant_text("Checked the config."),
+        ];
+
+        write_checkpoint(logs.path(), worker_id, &history).await;
+        let good = checkpoint_path(logs.path(), worker_id);
+        assert_eq!(load_checkpoint(&good), Some(history));
+        remove_checkpoint(logs.path(), worker_id).await;
+        assert!(!good.exists());
+
+        let dir = logs.path();
+
+        let corrupt = dir.join("corrupt.json");
+        std::fs::write(&corrupt, b"[{\"role\": \"user\", \"cont").unwrap();
+        assert_eq!(load_checkpoint(&corrupt), None);
+
+        let empty = dir.join("empty.json");
+        std::fs::write(&empty, b"[]").unwrap();
+        assert_eq!(load_checkpoint(&empty), None);
+
+        assert_eq!(load_checkpoint(&dir.join("missing.json")), None);
+    }
+
+    #[tokio::test]
+    async fn an_aborted_run_still_removes_its_workdir() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("worker");
+        std::fs::create_dir_all(path.join("src")).unwrap();
+        std::fs::write(path.join("src/notes.txt"), "scratch").unwrap();
+
+        let guard = WorkdirGuard {
+            worker_id: uuid::Uuid::new_v4(),
+            path: path.clone(),
+        };
+        let run = tokio::spawn(async move {
+            let _guard = guard;
+            std::future::pending::<()>().await;
+        });
+        tokio::task::yield_now().await;
+        run.abort();
+        assert!(run.await.unwrap_err().is_cancelled());
+
+        // Removal runs on the blocking pool.
+        for _ in 0..100 {
+            if !path.exists() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn progress_floor_tracks_segments_but_stops_short_of_done() {
+        assert_eq!(progress_floor(0, 10), 0);
+        assert_eq!(progress_floor(5, 10), 45);
+        assert_eq!(progress_floor(10, 10), 90);
+        assert_eq!(progress_floor(25, 10), 90);
+        assert_eq!(progress_floor(3, 0), 0);
+    }
+
+    #[test]
+    fn terminal_event_follows_the_outcome() {
+        let completed = Ok(WorkerOutcome {
+            result: "done".to_string(),
+            touched: Default::default(),
+            tokens_in: 0,
+            tokens_out: 0,
+            segments: 1,
+            planned_calls: Vec::new(),
+        });
+        assert_eq!(terminal_event(&completed), WorkerEvent::Completed);
+
+        let cancelled = Err(AgentError::Cancelled {
+            reason: "user asked".to_string(),
+        }
+        .into());
+        assert_eq!(terminal_event(&cancelled), WorkerEvent::Cancelled);
+
+        let timed_out = Err(AgentError::TimedOut {
+            limit_secs: 600,
+            segments: 4,
+        }
+        .into());
+        assert_eq!(terminal_event(&timed_out), WorkerEvent::TimedOut);
+
+        let failed = Err(AgentError::Other(anyhow::anyhow!("max segments reached")).into());
+        assert_eq!(
+            terminal_event(&failed),
+            WorkerEvent::Failed {
+                reason: "max segments reached".to_string()
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(WorkerEvent::Compacted {
+                removed: 12,
+                remaining: 8
+            })
+            .unwrap(),
+            serde_json::json!({"kind": "compacted", "removed": 12, "remaining": 8})
+        );
+    }
+
+    #[test]
+    fn json_failure_log_history_flags_truncated_parts() {
+        let history = vec![
+            Message::from("read the file"),
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::one(AssistantContent::tool_call(
+                    "call_1",
+                    "file_read",
+                    serde_json::json!({ "path": "a".repeat(600) }),
+                )),
+            },
+            Message::User {
+                content: OneOrMany::one(rig::message::UserContent::ToolResult(
+                    rig::message::ToolResult {
+                        id: "call_1".to_string(),
+                        call_id: None,
+                        content: OneOrMany::one(rig::message::ToolResultContent::text("ok")),
+                    },
+                )),
+            },
+        ];
+
+        let entries = history_log_json(&history);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0]["role"], "user");
+        assert_eq!(entries[0]["content"][0]["type"], "text");
+
+        let call = &entries[1]["content"][0];
+        assert_eq!(call["type"], "tool_call");
+        assert_eq!(call["tool"], "file_read");
+        assert_eq!(call["truncated"], true);
+        assert_eq!(call["args"].as_str().unwrap().len(), 500);
+
+        let result = &entries[2]["content"][0];
+        assert_eq!(result["type"], "tool_result");
+        assert_eq!(result["tool"], "file_read");
+        assert_eq!(result["text"], "ok");
+        assert_eq!(result["truncated"], false);
+    }
+
+    #[test]
+    fn consumed_ephemeral_results_are_elided() {
+        let call = |id: &str| Message::Assistant {
+            id: None,
+            content: OneOrMany::one(AssistantContent::tool_call(
+                id,
+                "ls",
+                serde_json::json!({ "path": "." }),
+            )),
+        };
+        let result = |id: &str, text: &str| Message::User {
+            content: OneOrMany::one(rig::message::UserContent::ToolResult(
+                rig::message::ToolResult {
+                    id: id.to_string(),
+                    call_id: Some(id.to_string()),
+                    content: OneOrMany::one(rig::message::ToolResultContent::text(text)),
+                },
+            )),
+        };
+        let result_text = |message: &Message| match message {
+            Message::User { content } => match content.first() {
+                rig::message::UserContent::ToolResult(result) => match result.content.first() {
+                    rig::message::ToolResultContent::Text(text) => text.text,
+                    _ => panic!("expected a text result"),
+                },
+                _ => panic!("expected a tool result"),
+            },
+            _ => panic!("expected a user message"),
+        };
+        let mut history = vec![
+            Message::from("list the files"),
+            call("call_1"),
+            result("call_1", "a.txt\nb.txt"),
+            call("call_2"),
+            result("call_2", "c.txt"),
+        ];
+
+        elide_ephemeral_tool_results(&mut history, &["ls".to_string()]);
+        // The first result was read before the second call; the second
+        // hasn't been read yet.
+        assert_eq!(
+            result_text(&history[2]),
+            "[ephemeral result for `ls`, elided]"
+        );
+        assert_eq!(result_text(&history[4]), "c.txt");
+
+        let mut untouched = history.clone();
+        elide_ephemeral_tool_results(&mut untouched, &[]);
+        assert_eq!(result_text(&untouched[4]), "c.txt");
+    }
+
+    #[test]
+    fn empty_result_without_any_text_has_no_fallback() {
+        let history = vec![
+            Message::from("do the task"),
+            assistant_text(""),
+            assistant_text("   "),
+        ];
+
+        assert_eq!(last_non_blank_assistant_text(&history), None);
+    }
+
+    #[test]
+    fn cancelled_work_summary_lists_progress_and_last_text() {
+        let history = vec![
+            Message::from("do the task"),
+            assistant_text("Fetched the first two pages."),
+        ];
+        let recap_tools = WorkerConfig::default().recap_tools;
+
+        let (summary, last_output) = summarize_cancelled_work(&history, &recap_tools);
+        assert_eq!(
+            summary.as_deref(),
+            Some("- Noted: Fetched the first two pages.")
+        );
+        assert_eq!(last_output.as_deref(), Some("Fetched the first two pages."));
+
+        assert_eq!(summarize_cancelled_work(&[], &recap_tools), (None, None));
+    }
+
+    #[test]
+    fn over_length_task_is_rejected_or_attached_per_policy() {
+        let saved_dir =
+            std::env::temp_dir().join(format!("spacebot-task-{}", uuid::Uuid::new_v4()));
+        let prompts = crate::prompts::PromptEngine::new("en").unwrap();
+        let mut config = WorkerConfig {
+            max_task_chars: 10,
+            task_overflow: TaskOverflowPolicy::Reject,
+            ..WorkerConfig::default()
+        };
+
+        let short = fit_task_to_budget("short".into(), &config, &saved_dir, &prompts).unwrap();
+        assert_eq!(short, "short");
+
+        let long_task = "a".repeat(25);
+        let error =
+            fit_task_to_budget(long_task.clone(), &config, &saved_dir, &prompts).unwrap_err();
+        assert!(matches!(
+            error,
+            AgentError::TaskTooLong {
+                length: 25,
+                max: 10
+            }
+        ));
+
+        config.task_overflow = TaskOverflowPolicy::Attach;
+        let attached =
+            fit_task_to_budget(long_task.clone(), &config, &saved_dir, &prompts).unwrap();
+        assert!(attached.starts_with(&"a".repeat(10)));
+        assert!(!attached.contains(&"a".repeat(11)));
+        assert!(attached.contains("cut off at 10 of 25 characters"));
+
+        let saved: Vec<_> = std::fs::read_dir(&saved_dir).unwrap().collect();
+        assert_eq!(saved.len(), 1);
+        let saved_path = saved[0].as_ref().unwrap().path();
+        assert!(attached.contains(&saved_path.display().to_string()));
+        assert_eq!(std::fs::read_to_string(&saved_path).unwrap(), long_task);
+
+        std::fs::remove_dir_all(&saved_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn queued_follow_ups_are_collected_within_debounce() {
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::channel(8);
+        input_tx.send("second".to_string()).await.unwrap();
+        input_tx.send("third".to_string()).await.unwrap();
+
+        let mut pending = vec!["first".to_string()];
+        collect_follow_ups(&mut input_rx, &mut pending, std::time::Duration::ZERO).await;
+        assert_eq!(pending, ["first", "second", "third"]);
+
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            input_tx.send("late".to_string()).await.unwrap();
+        });
+        collect_follow_ups(
+            &mut input_rx,
+            &mut pending,
+            std::time::Duration::from_secs(5),
+        )
+        .await;
+        sender.await.unwrap();
+        assert_eq!(pending.last().map(String::as_str), Some("late"));
+    }
+
+    #[test]
+    fn capped_recap_keeps_most_recent_actions() {
+        let recap = "- Called `shell` ({\"command\":\"ls\"})\n  Result: a b c\n\
+                     - Called `file` ({\"path\":\"a\"})\n  Result: contents\n\
+                     - Noted: done reading\n";
+        assert_eq!(cap_recap(recap, 10_000), recap);
+
+        let capped = cap_recap(recap, 120);
+        assert!(capped.len() <= 120);
+        assert!(capped.starts_with("- …and 1 more earlier action\n"));
+        assert!(capped.contains("Called `file`"));
+        assert!(capped.contains("  Result: contents"));
+        assert!(capped.ends_with("- Noted: done reading\n"));
+        assert!(!capped.contains("Called `shell`"));
+    }
+
+    #[test]
+    fn transcript_handle_exports_the_latest_snapshot() {
+        let transcript = WorkerTranscript {
+            worker_id: uuid::Uuid::nil(),
+            channel_id: Some("discord:1".into()),
+            task: "summarize the docs".to_string(),
+            history: Default::default(),
+        };
+        let handle = transcript.clone();
+        assert!(handle.export().contains("--- History (0 messages) ---"));
+
+        transcript.update(vec![assistant_text("all done")]);
+        let text = handle.render(Some(WorkerState::Done));
+        assert!(text.contains("State: Done"));
+        assert!(text.contains("--- Task ---\nsummarize the docs"));
+        assert!(text.contains("[0] Assistant:\n  all done"));
+
+        let json = handle.export_json();
+        assert_eq!(json["channel_id"], "discord:1");
+        assert_eq!(json["steps"].as_array().map(Vec::len), Some(1));
+    }
+
+    #[tokio::test]
+    async fn deadline_abandons_only_calls_that_overrun_the_grace_period() {
+        let now = tokio::time::Instant::now();
+        assert!(!past_deadline(None));
+        assert!(!past_deadline(Some(
+            now + std::time::Duration::from_secs(3600)
+        )));
+        assert!(past_deadline(Some(now)));
+
+        assert_eq!(within_deadline(None, std::future::ready(1)).await, Some(1));
+        let Some(long_ago) = now.checked_sub(DEADLINE_GRACE * 2) else {
+            return;
+        };
+        // A call that completes is kept even past the deadline.
+        assert_eq!(
+            within_deadline(Some(long_ago), std::future::ready(2)).await,
+            Some(2)
+        );
+        assert_eq!(
+            within_deadline(Some(long_ago), std::future::pending::<()>()).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn cancellation_resolves_only_once_requested() {
+        let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+        let wait = std::time::Duration::from_millis(20);
+        assert!(
+            tokio::time::timeout(wait, cancellation_requested(&mut cancel_rx))
+                .await
+                .is_err()
+        );
+
+        cancel_tx.send(true).unwrap();
+        tokio::time::timeout(wait, cancellation_requested(&mut cancel_rx))
+            .await
+            .expect("cancel signal should be observed");
+
+        // A sender dropped without cancelling never counts as a cancel.
+        let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+        drop(cancel_tx);
+        assert!(
+            tokio::time::timeout(wait, cancellation_requested(&mut cancel_rx))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn idle_timeout_resolves_only_when_set() {
+        let timeout = std::time::Duration::from_millis(40);
+        assert!(
+            tokio::time::timeout(timeout / 4, idle_expired(Some(timeout)))
+                .await
+                .is_err()
+        );
+        tokio::time::timeout(timeout * 4, idle_expired(Some(timeout)))
+            .await
+            .expect("idle timeout should fire");
+
+        // Unbounded by default.
+        assert!(
+            tokio::time::timeout(timeout * 2, idle_expired(None))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn dropping_the_input_sender_hands_the_worker_off() {
+        let (input_tx, mut input_rx) = tokio::sync::mpsc::channel::<String>(8);
+        assert!(!handed_off(&input_rx));
+
+        // The route tool delivers the final instruction, then drops the sender.
+        input_tx.send("finish up".to_string()).await.unwrap();
+        drop(input_tx);
+        assert!(
+            !handed_off(&input_rx),
+            "queued instruction must be processed first"
+        );
+
+        assert_eq!(input_rx.recv().await.as_deref(), Some("finish up"));
+        assert!(handed_off(&input_rx));
+    }
+
+    #[test]
+    fn only_an_explicit_blocked_line_stops_the_worker() {
+        assert_eq!(parse_preflight_verdict("READY"), PreflightVerdict::Ready);
+        assert_eq!(
+            parse_preflight_verdict("I can reach the API.\n**BLOCKED:** no browser tool"),
+            PreflightVerdict::Blocked("no browser tool".to_string())
+        );
+        assert_eq!(
+            parse_preflight_verdict("`blocked`"),
+            PreflightVerdict::Blocked("no reason given".to_string())
+        );
+        assert_eq!(
+            parse_preflight_verdict("Looks fine, nothing is blocked."),
+            PreflightVerdict::Ready
+        );
+    }
+
+    #[test]
+    fn overloaded_errors_are_told_apart_from_rate_limits_and_overflow() {
+        use crate::llm::routing::{
+            is_context_overflow_error, is_overloaded_error, is_rate_limit_error,
+        };
+
+        let error =
+            "CompletionError: ProviderError: Anthropic API error (503): service unavailable";
+        assert!(is_overloaded_error(error));
+        assert!(is_overloaded_error(
+            r#"Anthropic API error (529): {"type":"overloaded_error","message":"Overloaded"}"#
+        ));
+        // Backed off on its own counter, not treated as a rate limit or an
+        // overflow, and not left to the fatal arm.
+        assert!(!is_rate_limit_error(error));
+        assert!(!is_context_overflow_error(error));
+        assert!(!is_overloaded_error(
+            "Anthropic API error (401): invalid x-api-key"
+        ));
+    }
+
+    #[test]
+    fn transient_backoff_doubles_up_to_the_cap() {
+        use crate::llm::routing::is_retriable_error;
+        use std::time::Duration;
+
+        let error = "OpenAI API error (500): internal server error";
+        assert!(is_retriable_error(error));
+        assert!(!is_retriable_error(
+            "OpenAI API error (401): invalid api key"
+        ));
+
+        assert_eq!(transient_retry_delay(error, 1, 1), Duration::from_secs(1));
+        assert_eq!(transient_retry_delay(error, 3, 1), Duration::from_secs(4));
+        assert_eq!(transient_retry_delay(error, 12, 5), Duration::from_secs(60));
+        // The doubling saturates instead of overflowing.
+        assert_eq!(
+            transient_retry_delay(error, 64, 10),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            transient_retry_delay(&format!("{error} (retry-after: 90)"), 1, 5),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            transient_retry_delay(&format!("{error} (retry-after: 3600)"), 1, 10),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_single_overloaded_error_is_retried_to_success() {
+        use crate::llm::routing::is_overloaded_error;
+        use std::time::Duration;
+
+        let overloaded = "Anthropic API error (503): service unavailable";
+        let mut responses = vec![Err(overloaded.to_string()), Ok("done".to_string())].into_iter();
+        let mut retries = 0;
+        let started = tokio::time::Instant::now();
+
+        let result = loop {
+            match responses.next().expect("no request after success") {
+                Ok(response) => break Ok(response),
+                Err(error) if is_overloaded_error(&error) => {
+                    match next_retry_delay(&mut retries, 5, &error, 10) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => break Err(error),
+                    }
+                }
+                Err(error) => break Err(error),
+            }
+        };
+
+        assert_eq!(result.as_deref(), Ok("done"));
+        assert_eq!(retries, 1);
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+
+        // A budget of one retry turns the second failure fatal.
+        let mut retries = 0;
+        assert!(next_retry_delay(&mut retries, 1, overloaded, 10).is_some());
+        assert_eq!(next_retry_delay(&mut retries, 1, overloaded, 10), None);
+    }
+
+    #[test]
+    fn a_broken_compact_template_falls_back_and_keeps_the_recap() {
+        let overrides = std::collections::HashMap::from([(
+            "fragments/system/worker_compact".to_string(),
+            "{% for line in remove_count %}{{ line }}{% endfor %}{{ recap }}".to_string(),
+        )]);
+        let engine = crate::prompts::PromptEngine::with_overrides("en", &overrides).unwrap();
+        let recap = "- shell: cargo test (passed)";
+        assert!(engine.render_system_worker_compact(12, recap).is_err());
+
+        let marker = fallback_compact_marker(12, recap);
+        assert!(marker.starts_with("[System: Earlier work"), "{marker}");
+        assert!(marker.contains("12 messages compacted"), "{marker}");
+        assert!(marker.ends_with(recap), "{marker}");
+    }
+
+    #[test]
+    fn exhausted_overflow_recovery_fails_with_a_typed_error() {
+        assert!(overflow_exhausted(3, 3).is_none());
+
+        let failure = overflow_exhausted(4, 3).expect("retries are exhausted");
+        assert!(matches!(
+            failure,
+            AgentError::ContextOverflowUnrecoverable { attempts: 3 }
+        ));
+        // The worker's result keeps the variant for callers to match on,
+        // e.g. to retry on a larger-context model.
+        let error: crate::Error = failure.into();
+        assert!(matches!(
+            &error,
+            crate::Error::Agent(agent) if matches!(
+                **agent,
+                AgentError::ContextOverflowUnrecoverable { attempts: 3 }
+            )
+        ));
+    }
+
+    #[test]
+    fn only_results_over_the_threshold_are_condensed() {
+        // Disabled by default.
+        assert!(!needs_condensing(50_000, 0, 1500));
+        assert!(!needs_condensing(3_000, 4_000, 1500));
+        assert!(!needs_condensing(4_000, 4_000, 1500));
+        assert!(needs_condensing(4_001, 4_000, 1500));
+        // A target longer than the result wouldn't shorten it.
+        assert!(!needs_condensing(4_001, 4_000, 5000));
+    }
+
+    #[test]
+    fn worker_labels_are_short_slugs_of_the_task() {
+        assert_eq!(
+            worker_label("Fix the login redirect on staging, then report back"),
+            "fix-the-login-redirect"
+        );
+        assert_eq!(
+            worker_label("[opencode] Refactor auth.rs"),
+            "refactor-auth-rs"
+        );
+        assert_eq!(worker_label("  ¿?  "), "worker");
+        assert!(worker_label(&"x".repeat(100)).len() <= 40);
+        assert_eq!(
+            resolve_worker_label(Some("Deploy Watcher"), "anything"),
+            "deploy-watcher"
+        );
+        assert_eq!(resolve_worker_label(Some("!!"), "Run tests"), "run-tests");
     }
 }
@@ -0,0 +1,149 @@
+//! Concurrency-safe writing of worker log files.
+//!
+//! Many workers can finish at once, each writing its log into the same
+//! `logs_dir`. Log files are written to a temporary name and renamed into
+//! place, so a reader (or a crash) never sees a half-written log. When
+//! enabled, every written log is also recorded in a per-run manifest, one
+//! JSON line per log, appended under a process-wide lock.
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Identifies this process run in the manifest file name.
+static RUN_ID: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "{}_{}",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+        std::process::id()
+    )
+});
+
+/// Serializes manifest appends within the process.
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Write `contents` to `dir/filename` atomically, creating `dir` if needed.
+///
+/// The file is written and synced under a unique temporary name in the same
+/// directory, then renamed over the final path. Returns the final path.
+pub fn write_atomically(dir: &Path, filename: &str, contents: &str) -> std::io::Result<PathBuf> {
+    create_dir(dir)?;
+
+    let path = dir.join(filename);
+    let temp_path = dir.join(format!(".{filename}.{}.tmp", uuid::Uuid::new_v4().simple()));
+    let result = std::fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&temp_path, &path));
+    if let Err(error) = result {
+        if let Err(cleanup_error) = std::fs::remove_file(&temp_path)
+            && cleanup_error.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!(
+                path = %temp_path.display(),
+                error = %cleanup_error,
+                "failed to remove temporary log file"
+            );
+        }
+        return Err(error);
+    }
+    Ok(path)
+}
+
+/// Path of this run's manifest inside `logs_dir`.
+pub fn manifest_path(logs_dir: &Path) -> PathBuf {
+    logs_dir.join(format!("manifest_{}.jsonl", *RUN_ID))
+}
+
+/// Append an entry for a written log to this run's manifest.
+///
+/// Each entry is a single JSON line written with one `write_all` on a file
+/// opened for appending, and appends are serialized, so concurrent workers
+/// never interleave partial lines.
+pub fn record_in_manifest(
+    logs_dir: &Path,
+    log_path: &Path,
+    worker_id: uuid::Uuid,
+    success: bool,
+) -> std::io::Result<()> {
+    let entry = serde_json::json!({
+        "worker_id": worker_id.to_string(),
+        "outcome": if success { "success" } else { "failure" },
+        "path": log_path.strip_prefix(logs_dir).unwrap_or(log_path),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    });
+    let mut line = entry.to_string();
+    line.push('\n');
+
+    create_dir(logs_dir)?;
+    let _guard = MANIFEST_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(manifest_path(logs_dir))?
+        .write_all(line.as_bytes())
+}
+
+/// `create_dir_all` that treats a directory created concurrently by another
+/// writer as success.
+fn create_dir(dir: &Path) -> std::io::Result<()> {
+    match std::fs::create_dir_all(dir) {
+        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists && dir.is_dir() => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{manifest_path, record_in_manifest, write_atomically};
+
+    #[test]
+    fn concurrent_writes_produce_complete_logs_and_manifest_lines() {
+        let logs_dir = std::env::temp_dir().join(format!("worker_log_{}", uuid::Uuid::new_v4()));
+        let contents = "x".repeat(64 * 1024);
+
+        std::thread::scope(|scope| {
+            for index in 0..16 {
+                let logs_dir = &logs_dir;
+                let contents = &contents;
+                scope.spawn(move || {
+                    let dir = logs_dir.join(if index % 2 == 0 {
+                        "successful"
+                    } else {
+                        "failed"
+                    });
+                    let path = write_atomically(&dir, &format!("worker_{index}.log"), contents)
+                        .expect("log write");
+                    record_in_manifest(logs_dir, &path, uuid::Uuid::new_v4(), index % 2 == 0)
+                        .expect("manifest append");
+                });
+            }
+        });
+
+        let manifest = std::fs::read_to_string(manifest_path(&logs_dir)).unwrap();
+        let entries: Vec<serde_json::Value> = manifest
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("whole JSON line"))
+            .collect();
+        assert_eq!(entries.len(), 16);
+        for entry in entries {
+            let path = logs_dir.join(entry["path"].as_str().unwrap());
+            assert_eq!(std::fs::read_to_string(path).unwrap(), contents);
+        }
+        let leftovers = ["successful", "failed"]
+            .iter()
+            .flat_map(|dir| std::fs::read_dir(logs_dir.join(dir)).unwrap())
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let _ = std::fs::remove_dir_all(&logs_dir);
+    }
+}
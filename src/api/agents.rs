@@ -439,6 +439,7 @@ pub(super) async fn trigger_warmup(
                     crate::agent::process_control::ProcessControlRegistry::new(),
                 ),
                 injection_tx,
+                spawn_depth: 0,
             };
             let logger = CortexLogger::new(sqlite_pool);
             crate::agent::cortex::run_warmup_once(&deps, &logger, "api_trigger", force).await;
@@ -611,14 +612,20 @@ pub async fn create_agent_internal(
         context_window: None,
         compaction: None,
         memory_persistence: None,
+        identity_reinforcement: None,
+        output_limits: None,
         coalesce: None,
+        worker: None,
         ingestion: None,
         cortex: None,
+        bulletin_history: None,
+        debug_mirror: None,
         warmup: None,
         browser: None,
         channel: None,
         mcp: None,
         brave_search_key: None,
+        web_search: None,
         cron_timezone: None,
         user_timezone: None,
         sandbox: None,
@@ -789,6 +796,7 @@ pub async fn create_agent_internal(
             crate::agent::process_control::ProcessControlRegistry::new(),
         ),
         injection_tx: state.injection_tx.clone(),
+        spawn_depth: 0,
         agent_names: {
             let configs = state.agent_configs.load();
             let mut names: std::collections::HashMap<String, String> = configs
@@ -837,7 +845,7 @@ pub async fn create_agent_internal(
     let cron_tool = crate::tools::CronTool::new(cron_store.clone(), scheduler.clone());
 
     let browser_config = (**runtime_config.browser_config.load()).clone();
-    let brave_search_key = (**runtime_config.brave_search_key.load()).clone();
+    let search_backend = runtime_config.search_backend();
     let conversation_logger =
         crate::conversation::history::ConversationLogger::new(db.sqlite.clone());
     let channel_store = crate::conversation::ChannelStore::new(db.sqlite.clone());
@@ -854,7 +862,7 @@ pub async fn create_agent_internal(
         run_logger,
         browser_config,
         agent_config.screenshot_dir(),
-        brave_search_key,
+        search_backend,
         runtime_config.workspace_dir.clone(),
         sandbox.clone(),
         runtime_config.clone(),
@@ -418,14 +418,18 @@ pub(super) async fn inspect_prompt(
         .unwrap_or_default();
 
     let browser_enabled = rc.browser_config.load().enabled;
-    let web_search_enabled = rc.brave_search_key.load().is_some();
+    let web_search_enabled = rc.web_search_enabled();
     let opencode_enabled = rc.opencode.load().enabled;
     let mcp_tool_names = channel_state.deps.mcp_manager.get_tool_names().await;
     let worker_capabilities = prompt_engine
         .render_worker_capabilities(
             browser_enabled,
             web_search_enabled,
+            rc.worker.load().http_request.enabled,
             opencode_enabled,
+            rc.worker.load().memory_recall,
+            rc.worker.load().tool_gating,
+            rc.worker.load().tool_permissions,
             &mcp_tool_names,
         )
         .unwrap_or_default();
@@ -565,6 +569,247 @@ pub(super) async fn set_prompt_capture(
     })))
 }
 
+// ── Mute ───────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Default)]
+pub(super) struct MuteStatusQuery {
+    agent_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetMuteRequest {
+    /// Agent to change. Omitted applies to every agent.
+    agent_id: Option<String>,
+    /// Channel to change. Omitted applies to all of the agent's channels.
+    channel_id: Option<String>,
+    muted: bool,
+    /// Post an acknowledgement in the channel. Only used with `channel_id`.
+    #[serde(default)]
+    notify: bool,
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentMuteStatus {
+    agent_id: String,
+    /// Every channel of the agent is muted.
+    muted: bool,
+    /// Channels muted on their own.
+    muted_channels: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct MuteStatusResponse {
+    agents: Vec<AgentMuteStatus>,
+}
+
+/// Report which agents and channels are muted.
+pub(super) async fn mute_status(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<MuteStatusQuery>,
+) -> Result<Json<MuteStatusResponse>, StatusCode> {
+    let configs = state.runtime_configs.load();
+    let mut agent_ids: Vec<&String> = match &query.agent_id {
+        Some(agent_id) => vec![
+            configs
+                .get_key_value(agent_id)
+                .ok_or(StatusCode::NOT_FOUND)?
+                .0,
+        ],
+        None => configs.keys().collect(),
+    };
+    agent_ids.sort();
+
+    let agents = agent_ids
+        .into_iter()
+        .map(|agent_id| agent_mute_status(agent_id, &configs[agent_id]))
+        .collect::<Result<_, _>>()?;
+    Ok(Json(MuteStatusResponse { agents }))
+}
+
+/// Mute or unmute a channel, an agent, or every agent. Channels check the
+/// mute on each inbound message, so the change applies without a restart.
+pub(super) async fn set_mute(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetMuteRequest>,
+) -> Result<Json<MuteStatusResponse>, StatusCode> {
+    if request.channel_id.is_some() && request.agent_id.is_none() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let configs = state.runtime_configs.load();
+    let mut agent_ids: Vec<&String> = match &request.agent_id {
+        Some(agent_id) => vec![
+            configs
+                .get_key_value(agent_id)
+                .ok_or(StatusCode::NOT_FOUND)?
+                .0,
+        ],
+        None => configs.keys().collect(),
+    };
+    agent_ids.sort();
+
+    for agent_id in &agent_ids {
+        let settings = configs[*agent_id].settings.load();
+        let settings = settings.as_ref().as_ref().ok_or_else(|| {
+            tracing::warn!(%agent_id, "no settings store available for mute");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        let result = match &request.channel_id {
+            Some(channel_id) => settings.set_channel_muted(channel_id, request.muted),
+            None => settings.set_muted(request.muted),
+        };
+        result.map_err(|error| {
+            tracing::warn!(%error, %agent_id, "failed to persist mute state");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        tracing::info!(
+            %agent_id,
+            channel_id = request.channel_id.as_deref().unwrap_or("*"),
+            muted = request.muted,
+            "mute state updated via API"
+        );
+    }
+
+    if request.notify
+        && let (Some(agent_id), Some(channel_id)) = (&request.agent_id, &request.channel_id)
+    {
+        notify_mute_change(&state, agent_id, channel_id, request.muted).await;
+    }
+
+    let agents = agent_ids
+        .into_iter()
+        .map(|agent_id| agent_mute_status(agent_id, &configs[agent_id]))
+        .collect::<Result<_, _>>()?;
+    Ok(Json(MuteStatusResponse { agents }))
+}
+
+fn agent_mute_status(
+    agent_id: &str,
+    runtime_config: &crate::config::RuntimeConfig,
+) -> Result<AgentMuteStatus, StatusCode> {
+    let settings = runtime_config.settings.load();
+    let Some(settings) = settings.as_ref().as_ref() else {
+        return Ok(AgentMuteStatus {
+            agent_id: agent_id.to_string(),
+            muted: false,
+            muted_channels: Vec::new(),
+        });
+    };
+    let muted_channels = settings.muted_channels().map_err(|error| {
+        tracing::warn!(%error, %agent_id, "failed to list muted channels");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(AgentMuteStatus {
+        agent_id: agent_id.to_string(),
+        muted: settings.muted(),
+        muted_channels,
+    })
+}
+
+/// Best-effort acknowledgement posted in the channel that was muted or
+/// unmuted. The mute itself already took effect, so failures only log.
+async fn notify_mute_change(state: &ApiState, agent_id: &str, channel_id: &str, muted: bool) {
+    let Some(pool) = state.agent_pools.load().get(agent_id).cloned() else {
+        return;
+    };
+    let channel = match ChannelStore::new(pool).get(channel_id).await {
+        Ok(Some(channel)) => channel,
+        Ok(None) => {
+            tracing::warn!(%agent_id, %channel_id, "cannot acknowledge mute, unknown channel");
+            return;
+        }
+        Err(error) => {
+            tracing::warn!(%error, %agent_id, %channel_id, "failed to look up muted channel");
+            return;
+        }
+    };
+    let Some(target) = crate::messaging::target::resolve_broadcast_target(&channel) else {
+        tracing::warn!(%agent_id, %channel_id, "cannot acknowledge mute, no delivery target");
+        return;
+    };
+    let Some(manager) = state.messaging_manager.read().await.clone() else {
+        return;
+    };
+
+    let text = if muted {
+        "muted by an operator. i won't respond here until unmuted."
+    } else {
+        "unmuted. i'll respond here again."
+    };
+    if let Err(error) = manager
+        .broadcast(
+            &target.adapter,
+            &target.target,
+            crate::OutboundResponse::Text(text.to_string()),
+        )
+        .await
+    {
+        tracing::warn!(%error, %agent_id, %channel_id, "failed to acknowledge mute change");
+    }
+}
+
+// ── Language ───────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+pub(super) struct ChannelLanguageQuery {
+    agent_id: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelLanguage {
+    channel_id: String,
+    language: String,
+    language_name: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelLanguageResponse {
+    agent_id: String,
+    enabled: bool,
+    default_language: String,
+    /// Channels with a detected language. Others use `default_language`.
+    channels: Vec<ChannelLanguage>,
+}
+
+/// Report the language detected for each of an agent's channels, for
+/// debugging language detection.
+pub(super) async fn channel_languages(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ChannelLanguageQuery>,
+) -> Result<Json<ChannelLanguageResponse>, StatusCode> {
+    let configs = state.runtime_configs.load();
+    let runtime_config = configs.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let config = runtime_config
+        .channel_config
+        .load()
+        .language_detection
+        .clone();
+
+    let settings = runtime_config.settings.load();
+    let detected = match settings.as_ref().as_ref() {
+        Some(settings) => settings.channel_languages().map_err(|error| {
+            tracing::warn!(%error, agent_id = %query.agent_id, "failed to list channel languages");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?,
+        None => Vec::new(),
+    };
+    let channels = detected
+        .into_iter()
+        .map(|(channel_id, language)| ChannelLanguage {
+            channel_id,
+            language_name: crate::agent::channel_language::language_name(&language).to_string(),
+            language,
+        })
+        .collect();
+
+    Ok(Json(ChannelLanguageResponse {
+        agent_id: query.agent_id,
+        enabled: config.enabled,
+        default_language: config.default_language,
+        channels,
+    }))
+}
+
 // ── Prompt Snapshot History ────────────────────────────────────────
 
 #[derive(Deserialize)]
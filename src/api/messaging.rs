@@ -1007,7 +1007,8 @@ pub(super) async fn toggle_platform(
                             webhook_config.port,
                             &webhook_config.bind,
                             webhook_config.auth_token.clone(),
-                        );
+                        )
+                        .with_signature(webhook_config.signature.clone());
                         if let Err(error) = manager.register_and_start(adapter).await {
                             tracing::error!(%error, "failed to start webhook adapter on toggle");
                         }
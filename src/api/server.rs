@@ -88,6 +88,11 @@ pub async fn start_http_server(
             get(channels::list_channels).delete(channels::delete_channel),
         )
         .route("/channels/archive", put(channels::set_channel_archive))
+        .route(
+            "/channels/mute",
+            get(channels::mute_status).put(channels::set_mute),
+        )
+        .route("/channels/language", get(channels::channel_languages))
         .route("/channels/messages", get(channels::channel_messages))
         .route("/channels/status", get(channels::channel_status))
         .route("/channels/inspect", get(channels::inspect_prompt))
@@ -274,6 +279,7 @@ pub async fn start_http_server(
         .route("/ssh/status", get(ssh::ssh_status))
         .route("/webchat/send", post(webchat::webchat_send))
         .route("/webchat/history", get(webchat::webchat_history))
+        .route("/webchat/ws", get(webchat::webchat_socket))
         .route("/links", get(links::list_links).post(links::create_link))
         .route(
             "/links/{from}/{to}",
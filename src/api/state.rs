@@ -158,6 +158,9 @@ pub enum ApiEvent {
         agent_id: String,
         channel_id: String,
         text: String,
+        /// ID the message is stored under in `conversation_messages`, when
+        /// it was logged there.
+        message_id: Option<String>,
     },
     /// Typing indicator state change.
     TypingState {
@@ -178,6 +181,7 @@ pub enum ApiEvent {
         channel_id: Option<String>,
         worker_id: String,
         task: String,
+        label: String,
         worker_type: String,
         interactive: bool,
     },
@@ -270,6 +274,13 @@ pub enum ApiEvent {
         worker_id: String,
         text: String,
     },
+    /// Streamed text from a worker response still in progress.
+    WorkerTextDelta {
+        agent_id: String,
+        worker_id: String,
+        text_delta: String,
+        aggregated_text: String,
+    },
     /// A cortex chat auto-triggered response (e.g. after a worker result was
     /// delivered). The frontend appends this as a new assistant message.
     CortexChatMessage {
@@ -394,6 +405,7 @@ impl ApiState {
                                 worker_id,
                                 channel_id,
                                 task,
+                                label,
                                 worker_type,
                                 interactive,
                                 ..
@@ -408,6 +420,7 @@ impl ApiState {
                                         channel_id: channel_id.as_deref().map(|s| s.to_string()),
                                         worker_id: worker_id.to_string(),
                                         task: task.clone(),
+                                        label: label.clone(),
                                         worker_type: worker_type.clone(),
                                         interactive: *interactive,
                                     })
@@ -651,6 +664,21 @@ impl ApiState {
                                     })
                                     .ok();
                             }
+                            ProcessEvent::WorkerTextDelta {
+                                worker_id,
+                                text_delta,
+                                aggregated_text,
+                                ..
+                            } => {
+                                api_tx
+                                    .send(ApiEvent::WorkerTextDelta {
+                                        agent_id: agent_id.clone(),
+                                        worker_id: worker_id.to_string(),
+                                        text_delta: text_delta.clone(),
+                                        aggregated_text: aggregated_text.clone(),
+                                    })
+                                    .ok();
+                            }
                             ProcessEvent::CortexChatUpdate {
                                 thread_id,
                                 content,
@@ -1,12 +1,16 @@
-use super::state::ApiState;
+use super::state::{ApiEvent, ApiState};
 use crate::{InboundMessage, MessageContent};
 
 use axum::Json;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::response::Response;
+use futures::{SinkExt as _, StreamExt as _};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Deserialize)]
 pub(super) struct WebChatSendRequest {
@@ -32,6 +36,14 @@ pub(super) async fn webchat_send(
     State(state): State<Arc<ApiState>>,
     axum::Json(request): axum::Json<WebChatSendRequest>,
 ) -> Result<Json<WebChatSendResponse>, StatusCode> {
+    inject_webchat_message(&state, request).await?;
+    Ok(Json(WebChatSendResponse { ok: true }))
+}
+
+async fn inject_webchat_message(
+    state: &ApiState,
+    request: WebChatSendRequest,
+) -> Result<(), StatusCode> {
     let manager = state
         .messaging_manager
         .read()
@@ -63,9 +75,172 @@ pub(super) async fn webchat_send(
     manager.inject_message(inbound).await.map_err(|error| {
         tracing::warn!(%error, "failed to inject webchat message");
         StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    })
+}
 
-    Ok(Json(WebChatSendResponse { ok: true }))
+#[derive(Deserialize)]
+pub(super) struct WebChatSocketQuery {
+    agent_id: String,
+    session_id: String,
+    /// The session belongs to this socket alone, so workers it started are
+    /// cancelled when the socket closes.
+    #[serde(default)]
+    dedicated: bool,
+}
+
+/// A message sent by the client over the socket.
+#[derive(Deserialize)]
+struct WebChatSocketMessage {
+    #[serde(default = "default_sender_name")]
+    sender_name: String,
+    message: String,
+}
+
+/// Frames pushed to a webchat socket.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WebChatFrame {
+    /// Text the model has generated so far for the reply in progress.
+    Delta {
+        text_delta: String,
+        text: String,
+    },
+    /// A complete reply. Deltas before it belong to this message.
+    Done {
+        message_id: String,
+        text: String,
+    },
+    Typing {
+        active: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Streaming alternative to `/webchat/send` plus the SSE event bus: the
+/// client sends messages and receives reply deltas on one socket. Clients
+/// that don't upgrade keep using `/webchat/send`.
+pub(super) async fn webchat_socket(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<WebChatSocketQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade.on_upgrade(move |socket| run_webchat_socket(state, query, socket))
+}
+
+async fn run_webchat_socket(state: Arc<ApiState>, query: WebChatSocketQuery, socket: WebSocket) {
+    let (mut sink, mut incoming) = socket.split();
+    let mut events = state.event_tx.subscribe();
+
+    loop {
+        let frame = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => frame_for_event(&event, &query.agent_id, &query.session_id),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, session_id = %query.session_id, "webchat socket lagged behind the event bus");
+                    None
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<WebChatSocketMessage>(text.as_str()) {
+                        Ok(message) => {
+                            let request = WebChatSendRequest {
+                                agent_id: query.agent_id.clone(),
+                                session_id: query.session_id.clone(),
+                                sender_name: message.sender_name,
+                                message: message.message,
+                            };
+                            inject_webchat_message(&state, request).await.err().map(|status| {
+                                WebChatFrame::Error {
+                                    message: format!("message not delivered ({status})"),
+                                }
+                            })
+                        }
+                        Err(error) => Some(WebChatFrame::Error {
+                            message: format!("invalid message: {error}"),
+                        }),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => None,
+            },
+        };
+
+        let Some(frame) = frame else {
+            continue;
+        };
+        let Ok(text) = serde_json::to_string(&frame) else {
+            continue;
+        };
+        if sink.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+
+    if query.dedicated {
+        cancel_session_workers(&state, &query.session_id).await;
+    }
+}
+
+/// The frame an API event becomes on the socket for `session_id`, if any.
+fn frame_for_event(event: &ApiEvent, agent_id: &str, session_id: &str) -> Option<WebChatFrame> {
+    match event {
+        ApiEvent::OutboundMessageDelta {
+            agent_id: event_agent,
+            channel_id,
+            text_delta,
+            aggregated_text,
+        } if event_agent == agent_id && channel_id == session_id => Some(WebChatFrame::Delta {
+            text_delta: text_delta.clone(),
+            text: aggregated_text.clone(),
+        }),
+        ApiEvent::OutboundMessage {
+            agent_id: event_agent,
+            channel_id,
+            text,
+            message_id,
+        } if event_agent == agent_id && channel_id == session_id => Some(WebChatFrame::Done {
+            // Messages that weren't logged have no stored ID to match.
+            message_id: message_id
+                .clone()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            text: text.clone(),
+        }),
+        ApiEvent::TypingState {
+            agent_id: event_agent,
+            channel_id,
+            is_typing,
+        } if event_agent == agent_id && channel_id == session_id => {
+            Some(WebChatFrame::Typing { active: *is_typing })
+        }
+        _ => None,
+    }
+}
+
+/// Cancel the workers a closed dedicated session left running.
+async fn cancel_session_workers(state: &ApiState, session_id: &str) {
+    let channel_state = state.channel_states.read().await.get(session_id).cloned();
+    let Some(channel_state) = channel_state else {
+        return;
+    };
+    let worker_ids: Vec<_> = channel_state
+        .worker_handles
+        .read()
+        .await
+        .keys()
+        .copied()
+        .collect();
+    for worker_id in worker_ids {
+        if let Err(error) = channel_state
+            .cancel_worker_with_reason(worker_id, "webchat client disconnected")
+            .await
+        {
+            tracing::debug!(%error, %worker_id, session_id, "failed to cancel webchat worker");
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -116,3 +291,42 @@ pub(super) async fn webchat_history(
 
     Ok(Json(result))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{WebChatFrame, frame_for_event};
+    use crate::api::state::ApiEvent;
+
+    #[test]
+    fn only_the_sessions_reply_events_become_frames() {
+        let delta = ApiEvent::OutboundMessageDelta {
+            agent_id: "main".into(),
+            channel_id: "session-1".into(),
+            text_delta: "lo".into(),
+            aggregated_text: "Hello".into(),
+        };
+        assert_eq!(
+            frame_for_event(&delta, "main", "session-1"),
+            Some(WebChatFrame::Delta {
+                text_delta: "lo".into(),
+                text: "Hello".into(),
+            })
+        );
+        assert_eq!(frame_for_event(&delta, "main", "session-2"), None);
+        assert_eq!(frame_for_event(&delta, "other", "session-1"), None);
+
+        let done = ApiEvent::OutboundMessage {
+            agent_id: "main".into(),
+            channel_id: "session-1".into(),
+            text: "Hello there".into(),
+            message_id: Some("msg-1".into()),
+        };
+        assert_eq!(
+            frame_for_event(&done, "main", "session-1"),
+            Some(WebChatFrame::Done {
+                message_id: "msg-1".into(),
+                text: "Hello there".into(),
+            })
+        );
+    }
+}
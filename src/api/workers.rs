@@ -34,6 +34,7 @@ pub(super) struct WorkerListResponse {
 #[derive(Serialize)]
 pub(super) struct WorkerListItem {
     id: String,
+    label: String,
     task: String,
     status: String,
     worker_type: String,
@@ -133,6 +134,7 @@ pub(super) async fn list_workers(
 
             WorkerListItem {
                 id: row.id,
+                label: row.label,
                 task: row.task,
                 status: row.status,
                 worker_type: row.worker_type,
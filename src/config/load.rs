@@ -11,16 +11,25 @@ use super::providers::{
 };
 use super::toml_schema::*;
 use super::{
-    AgentConfig, ApiConfig, ApiType, Binding, BrowserConfig, ChannelConfig, ClosePolicy,
-    CoalesceConfig, CompactionConfig, Config, CortexConfig, CronDef, DefaultsConfig, DiscordConfig,
-    DiscordInstanceConfig, EmailConfig, EmailInstanceConfig, GroupDef, HumanDef, IngestionConfig,
-    LinkDef, LlmConfig, McpServerConfig, McpTransport, MemoryPersistenceConfig, MessagingConfig,
-    MetricsConfig, OpenCodeConfig, ProjectsConfig, ProviderConfig, SignalConfig,
-    SignalInstanceConfig, SlackCommandConfig, SlackConfig, SlackInstanceConfig, TelegramConfig,
-    TelegramInstanceConfig, TelemetryConfig, TwitchConfig, TwitchInstanceConfig, WarmupConfig,
-    WebhookConfig, normalize_adapter, validate_named_messaging_adapters,
+    AgentConfig, ApiConfig, ApiType, Binding, BrowserConfig, BulletinHistoryConfig, ChannelConfig,
+    ClosePolicy, CoalesceConfig, CommandPolicyConfig, CompactionConfig, CompactionStrategy,
+    CompactorFallback, Config, CortexConfig, CronDef, DebugMirrorConfig, DefaultsConfig,
+    DiscordConfig, DiscordInstanceConfig, EmailConfig, EmailInstanceConfig, FailureLogSinkConfig,
+    FollowUpMode, GroupDef, HttpRequestConfig, HumanDef, IdentityReinforcementConfig, IdleAction,
+    IdleConfig, IngestionConfig, LanguageDetectionConfig, LinkDef, ListenOnlyTriggers, LlmConfig,
+    LogFormat, McpServerConfig, McpTransport, MemoryPersistenceConfig, MessagingConfig,
+    MetricsConfig, OpenCodeConfig, OutboundConfig, OutboundRateLimit, OutboundStage, OutputLimit,
+    OutputLimitsConfig, OutputOverflow, ProjectsConfig, ProviderConfig, RecapPriority,
+    RedactionConfig, SearchBackendKind, SignalConfig, SignalInstanceConfig, SlackCommandConfig,
+    SlackConfig, SlackInstanceConfig, StatusRefresh, TaskOverflowPolicy, TelegramConfig,
+    TelegramInstanceConfig, TelemetryConfig, ToolCallRepair, ToolErrorKind, ToolResultFormat,
+    ToolRetryPolicy, TranscriptArchiveConfig, TranscriptArchiveScope, TranscriptSinkBackend,
+    TwitchConfig, TwitchInstanceConfig, UserContentEscaping, WarmupConfig, WebSearchConfig,
+    WebhookConfig, WebhookSignatureConfig, WebhookSignatureScheme, WorkerConfig, normalize_adapter,
+    validate_named_messaging_adapters,
 };
 use crate::error::{ConfigError, Result};
+use crate::tools::ToolPermissions;
 
 use anyhow::Context as _;
 
@@ -127,6 +136,590 @@ fn parse_close_policy(value: Option<&str>) -> Option<ClosePolicy> {
     }
 }
 
+fn parse_search_backend(value: &str) -> Option<SearchBackendKind> {
+    match value {
+        "brave" => Some(SearchBackendKind::Brave),
+        "searxng" => Some(SearchBackendKind::Searxng),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown web_search.backend value, expected one of: brave, searxng"
+            );
+            None
+        }
+    }
+}
+
+fn resolve_web_search(toml: TomlWebSearchConfig, base: &WebSearchConfig) -> WebSearchConfig {
+    WebSearchConfig {
+        backend: toml
+            .backend
+            .as_deref()
+            .and_then(parse_search_backend)
+            .unwrap_or(base.backend),
+        searxng_url: toml
+            .searxng_url
+            .as_deref()
+            .and_then(resolve_env_value)
+            .or_else(|| base.searxng_url.clone()),
+    }
+}
+
+fn parse_recap_priority(tool: &str, value: &str) -> Option<RecapPriority> {
+    match value {
+        "omit" => Some(RecapPriority::Omit),
+        "normal" => Some(RecapPriority::Normal),
+        "high" => Some(RecapPriority::High),
+        other => {
+            tracing::warn!(
+                tool,
+                value = other,
+                "unknown recap_tools priority, expected one of: omit, normal, high"
+            );
+            None
+        }
+    }
+}
+
+fn parse_tool_error_kind(tool: &str, value: &str) -> Option<ToolErrorKind> {
+    match value {
+        "timeout" => Some(ToolErrorKind::Timeout),
+        "network" => Some(ToolErrorKind::Network),
+        "rate_limited" => Some(ToolErrorKind::RateLimited),
+        "server" => Some(ToolErrorKind::Server),
+        other => {
+            tracing::warn!(
+                tool,
+                value = other,
+                "unknown tool_retries error kind, expected one of: timeout, network, rate_limited, server"
+            );
+            None
+        }
+    }
+}
+
+fn parse_tool_result_format(tool: &str, value: &str) -> Option<ToolResultFormat> {
+    match value {
+        "raw" => Some(ToolResultFormat::Raw),
+        "json_pretty" => Some(ToolResultFormat::JsonPretty),
+        "table" => Some(ToolResultFormat::Table),
+        "key_value" => Some(ToolResultFormat::KeyValue),
+        other => {
+            tracing::warn!(
+                tool,
+                value = other,
+                "unknown tool_result_formats value, expected one of: raw, json_pretty, table, key_value"
+            );
+            None
+        }
+    }
+}
+
+fn parse_task_overflow_policy(value: &str) -> Option<TaskOverflowPolicy> {
+    match value {
+        "reject" => Some(TaskOverflowPolicy::Reject),
+        "attach" => Some(TaskOverflowPolicy::Attach),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown worker task_overflow policy, expected one of: reject, attach"
+            );
+            None
+        }
+    }
+}
+
+fn parse_follow_up_mode(value: &str) -> Option<FollowUpMode> {
+    match value {
+        "coalesce" => Some(FollowUpMode::Coalesce),
+        "queue" => Some(FollowUpMode::Queue),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown worker follow_up_mode, expected one of: coalesce, queue"
+            );
+            None
+        }
+    }
+}
+
+fn parse_compaction_strategy(value: &str) -> Option<CompactionStrategy> {
+    match value {
+        "recap" => Some(CompactionStrategy::Recap),
+        "llm" => Some(CompactionStrategy::Llm),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown worker compaction_strategy, expected one of: recap, llm"
+            );
+            None
+        }
+    }
+}
+
+fn parse_log_format(value: &str) -> Option<LogFormat> {
+    match value {
+        "text" => Some(LogFormat::Text),
+        "json" => Some(LogFormat::Json),
+        "both" => Some(LogFormat::Both),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown worker failure_log_format, expected one of: text, json, both"
+            );
+            None
+        }
+    }
+}
+
+fn parse_compactor_fallback(value: &str) -> Option<CompactorFallback> {
+    match value {
+        "recap" => Some(CompactorFallback::Recap),
+        "marker" => Some(CompactorFallback::Marker),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown compaction compactor_fallback, expected one of: recap, marker"
+            );
+            None
+        }
+    }
+}
+
+fn parse_idle_action(value: &str) -> Option<IdleAction> {
+    match value {
+        "hibernate" => Some(IdleAction::Hibernate),
+        "shutdown" => Some(IdleAction::Shutdown),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown idle action, expected one of: hibernate, shutdown"
+            );
+            None
+        }
+    }
+}
+
+fn parse_tool_call_repair(value: &str) -> Option<ToolCallRepair> {
+    match value {
+        "regenerate" => Some(ToolCallRepair::Regenerate),
+        "dedup" => Some(ToolCallRepair::Dedup),
+        "off" => Some(ToolCallRepair::Off),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown compaction tool_call_repair, expected one of: regenerate, dedup, off"
+            );
+            None
+        }
+    }
+}
+
+fn parse_user_content_escaping(value: &str) -> Option<UserContentEscaping> {
+    match value {
+        "off" => Some(UserContentEscaping::Off),
+        "escape" => Some(UserContentEscaping::Escape),
+        "fence" => Some(UserContentEscaping::Fence),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown channel user_content_escaping, expected one of: off, escape, fence"
+            );
+            None
+        }
+    }
+}
+
+/// Resolve `[channel.language_detection]`, inheriting unset keys from `base`.
+fn resolve_listen_only_triggers(
+    toml: Option<TomlListenOnlyTriggers>,
+    base: &ListenOnlyTriggers,
+) -> ListenOnlyTriggers {
+    let Some(t) = toml else {
+        return base.clone();
+    };
+    ListenOnlyTriggers {
+        mention: t.mention.unwrap_or(base.mention),
+        reply: t.reply.unwrap_or(base.reply),
+        command: t.command.unwrap_or(base.command),
+        keywords: t
+            .keywords
+            .map(|keywords| {
+                keywords
+                    .into_iter()
+                    .map(|keyword| keyword.trim().to_lowercase())
+                    .filter(|keyword| !keyword.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|| base.keywords.clone()),
+    }
+}
+
+fn resolve_language_detection(
+    toml: Option<TomlLanguageDetectionConfig>,
+    base: &LanguageDetectionConfig,
+) -> LanguageDetectionConfig {
+    let Some(t) = toml else {
+        return base.clone();
+    };
+    LanguageDetectionConfig {
+        enabled: t.enabled.unwrap_or(base.enabled),
+        detector: t.detector.unwrap_or_else(|| base.detector.clone()),
+        default_language: t
+            .default_language
+            .map(|language| language.trim().to_lowercase())
+            .filter(|language| !language.is_empty())
+            .unwrap_or_else(|| base.default_language.clone()),
+        // At half or below, two tied languages would both count as confident.
+        confidence_threshold: t
+            .confidence_threshold
+            .unwrap_or(base.confidence_threshold)
+            .clamp(0.51, 1.0),
+        window: t.window.unwrap_or(base.window).max(1),
+        min_chars: t.min_chars.unwrap_or(base.min_chars),
+    }
+}
+
+/// Parse `[messaging.webhook].signature_scheme`, defaulting to bare hex.
+fn parse_webhook_signature_scheme(value: Option<&str>) -> WebhookSignatureScheme {
+    match value {
+        None | Some("hex") => WebhookSignatureScheme::Hex,
+        Some("sha256") => WebhookSignatureScheme::Sha256Prefixed,
+        Some(other) => {
+            tracing::warn!(
+                scheme = other,
+                "unknown webhook signature_scheme, expected hex or sha256; using hex"
+            );
+            WebhookSignatureScheme::Hex
+        }
+    }
+}
+
+/// Parse an `[outbound]` pipeline, dropping unknown stage names.
+fn parse_outbound_pipeline(names: &[String]) -> Vec<OutboundStage> {
+    names
+        .iter()
+        .filter_map(|name| match name.as_str() {
+            // Redaction always runs after the stages now; older configs
+            // still list it.
+            "redact" => None,
+            "trim" => Some(OutboundStage::Trim),
+            "normalize_markdown" => Some(OutboundStage::NormalizeMarkdown),
+            "rewrite_links" => Some(OutboundStage::RewriteLinks),
+            "strip_markdown" => Some(OutboundStage::StripMarkdown),
+            "code_blocks" => Some(OutboundStage::CodeBlocks),
+            other => {
+                tracing::warn!(
+                    stage = other,
+                    "unknown outbound pipeline stage, expected one of: trim, normalize_markdown, \
+                     rewrite_links, strip_markdown, code_blocks"
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+fn resolve_output_limits(
+    toml: Option<TomlOutputLimitsConfig>,
+    base: &OutputLimitsConfig,
+) -> OutputLimitsConfig {
+    let Some(t) = toml else {
+        return *base;
+    };
+    OutputLimitsConfig {
+        channel: resolve_output_limit(t.channel, base.channel),
+        branch: resolve_output_limit(t.branch, base.branch),
+        worker: resolve_output_limit(t.worker, base.worker),
+    }
+}
+
+fn resolve_output_limit(toml: Option<TomlOutputLimit>, base: OutputLimit) -> OutputLimit {
+    let Some(t) = toml else {
+        return base;
+    };
+    OutputLimit {
+        max_output_tokens: t.max_output_tokens.unwrap_or(base.max_output_tokens),
+        max_chars: t.max_chars.unwrap_or(base.max_chars),
+        on_exceed: t
+            .on_exceed
+            .as_deref()
+            .and_then(parse_output_overflow)
+            .unwrap_or(base.on_exceed),
+    }
+}
+
+fn parse_output_overflow(value: &str) -> Option<OutputOverflow> {
+    match value {
+        "truncate" => Some(OutputOverflow::Truncate),
+        "reprompt" => Some(OutputOverflow::Reprompt),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown output_limits on_exceed, expected one of: truncate, reprompt"
+            );
+            None
+        }
+    }
+}
+
+fn parse_status_refresh(value: &str) -> Option<StatusRefresh> {
+    match value {
+        "per_turn" => Some(StatusRefresh::PerTurn),
+        "on_change" => Some(StatusRefresh::OnChange),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown channel status_refresh, expected one of: per_turn, on_change"
+            );
+            None
+        }
+    }
+}
+
+fn resolve_bulletin_history(
+    toml: TomlBulletinHistoryConfig,
+    base: &BulletinHistoryConfig,
+) -> BulletinHistoryConfig {
+    BulletinHistoryConfig {
+        enabled: toml.enabled.unwrap_or(base.enabled),
+        dir: toml.dir.map(PathBuf::from).or_else(|| base.dir.clone()),
+        retention: toml.retention.unwrap_or(base.retention),
+    }
+}
+
+fn resolve_debug_mirror(
+    toml: TomlDebugMirrorConfig,
+    base: &DebugMirrorConfig,
+) -> DebugMirrorConfig {
+    DebugMirrorConfig {
+        enabled: toml.enabled.unwrap_or(base.enabled),
+        log_path: toml
+            .log_path
+            .map(PathBuf::from)
+            .or_else(|| base.log_path.clone()),
+        target: toml.target.or_else(|| base.target.clone()),
+    }
+}
+
+/// Layer `[worker.recap_tools]` overrides on top of the inherited map, so
+/// configuring one tool doesn't drop the defaults for the others.
+fn resolve_recap_tools(
+    overrides: Option<HashMap<String, String>>,
+    base: &HashMap<String, RecapPriority>,
+) -> HashMap<String, RecapPriority> {
+    let mut resolved = base.clone();
+    for (tool, value) in overrides.unwrap_or_default() {
+        if let Some(priority) = parse_recap_priority(&tool, &value) {
+            resolved.insert(tool, priority);
+        }
+    }
+    resolved
+}
+
+/// Layer `[worker.tool_retries.<tool>]` overrides on top of the inherited
+/// map. Unset fields keep the tool's inherited policy, or the default policy
+/// for a tool that had none.
+fn resolve_tool_retries(
+    overrides: Option<HashMap<String, TomlToolRetryPolicy>>,
+    base: &HashMap<String, ToolRetryPolicy>,
+) -> HashMap<String, ToolRetryPolicy> {
+    let mut resolved = base.clone();
+    for (tool, toml) in overrides.unwrap_or_default() {
+        let inherited = resolved.get(&tool).cloned().unwrap_or_default();
+        let retry_on = match toml.retry_on {
+            Some(kinds) => kinds
+                .iter()
+                .filter_map(|kind| parse_tool_error_kind(&tool, kind))
+                .collect(),
+            None => inherited.retry_on,
+        };
+        let policy = ToolRetryPolicy {
+            max_retries: toml.max_retries.unwrap_or(inherited.max_retries),
+            backoff_ms: toml.backoff_ms.unwrap_or(inherited.backoff_ms),
+            retry_on,
+        };
+        resolved.insert(tool, policy);
+    }
+    resolved
+}
+
+/// Layer `[worker.tool_result_formats]` overrides on top of the inherited
+/// map.
+fn resolve_tool_result_formats(
+    overrides: Option<HashMap<String, String>>,
+    base: &HashMap<String, ToolResultFormat>,
+) -> HashMap<String, ToolResultFormat> {
+    let mut resolved = base.clone();
+    for (tool, value) in overrides.unwrap_or_default() {
+        if let Some(format) = parse_tool_result_format(&tool, &value) {
+            resolved.insert(tool, format);
+        }
+    }
+    resolved
+}
+
+/// Default request timeout for the remote failure log sink.
+const FAILURE_LOG_SINK_TIMEOUT_SECS: u64 = 5;
+
+/// Resolve `[worker.failure_log_sink]`, inheriting the parent sink when the
+/// section is absent. A sink whose URL doesn't resolve is disabled.
+fn resolve_failure_log_sink(
+    toml: Option<TomlFailureLogSinkConfig>,
+    base: &Option<FailureLogSinkConfig>,
+) -> Option<FailureLogSinkConfig> {
+    let Some(toml) = toml else {
+        return base.clone();
+    };
+
+    let Some(url) = resolve_env_value(&toml.url).filter(|url| !url.trim().is_empty()) else {
+        tracing::warn!("worker failure_log_sink url did not resolve, remote sink disabled");
+        return None;
+    };
+
+    Some(FailureLogSinkConfig {
+        url,
+        auth_token: toml.auth_token.as_deref().and_then(resolve_env_value),
+        timeout_secs: toml.timeout_secs.unwrap_or(FAILURE_LOG_SINK_TIMEOUT_SECS),
+    })
+}
+
+/// Default request timeout for remote transcript archives.
+const TRANSCRIPT_ARCHIVE_TIMEOUT_SECS: u64 = 10;
+
+fn parse_transcript_archive_scope(value: &str) -> Option<TranscriptArchiveScope> {
+    match value {
+        "failures" => Some(TranscriptArchiveScope::Failures),
+        "all" => Some(TranscriptArchiveScope::All),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown transcript_archive scope, expected one of: failures, all"
+            );
+            None
+        }
+    }
+}
+
+/// Resolve `[worker.http_request]` on top of the inherited settings.
+fn resolve_http_request(
+    toml: TomlHttpRequestConfig,
+    base: &HttpRequestConfig,
+) -> HttpRequestConfig {
+    HttpRequestConfig {
+        enabled: toml.enabled.unwrap_or(base.enabled),
+        timeout_secs: toml.timeout_secs.unwrap_or(base.timeout_secs).max(1),
+        max_response_bytes: toml
+            .max_response_bytes
+            .unwrap_or(base.max_response_bytes)
+            .max(1),
+        allowed_hosts: toml
+            .allowed_hosts
+            .unwrap_or_else(|| base.allowed_hosts.clone()),
+        denied_hosts: toml
+            .denied_hosts
+            .unwrap_or_else(|| base.denied_hosts.clone()),
+        allow_private_networks: toml
+            .allow_private_networks
+            .unwrap_or(base.allow_private_networks),
+    }
+}
+
+/// Resolve `[worker.command_policy]`; a list that is set replaces the
+/// inherited one.
+fn resolve_command_policy(
+    toml: TomlCommandPolicyConfig,
+    base: &CommandPolicyConfig,
+) -> CommandPolicyConfig {
+    CommandPolicyConfig {
+        allowed: toml.allowed.unwrap_or_else(|| base.allowed.clone()),
+        denied: toml.denied.unwrap_or_else(|| base.denied.clone()),
+    }
+}
+
+/// Resolve `[worker.tool_permissions]`; unset tools keep the inherited
+/// permission.
+fn resolve_tool_permissions(toml: TomlToolPermissions, base: ToolPermissions) -> ToolPermissions {
+    ToolPermissions {
+        shell: toml.shell.unwrap_or(base.shell),
+        file_write: toml.file_write.unwrap_or(base.file_write),
+        browser: toml.browser.unwrap_or(base.browser),
+        web_search: toml.web_search.unwrap_or(base.web_search),
+        http_request: toml.http_request.unwrap_or(base.http_request),
+        secrets: toml.secrets.unwrap_or(base.secrets),
+        mcp: toml.mcp.unwrap_or(base.mcp),
+    }
+}
+
+/// Resolve `[worker.transcript_archive]`, inheriting the parent archive when
+/// the section is absent. An archive missing a required field for its `kind`
+/// is disabled with a warning.
+fn resolve_transcript_archive(
+    toml: Option<TomlTranscriptArchiveConfig>,
+    base: &Option<TranscriptArchiveConfig>,
+) -> Option<TranscriptArchiveConfig> {
+    let Some(toml) = toml else {
+        return base.clone();
+    };
+
+    let required = |field: &str, value: &Option<String>| {
+        let resolved = value
+            .as_deref()
+            .and_then(resolve_env_value)
+            .filter(|value| !value.trim().is_empty());
+        if resolved.is_none() {
+            tracing::warn!(
+                kind = %toml.kind,
+                field,
+                "transcript_archive field missing or did not resolve, archive disabled"
+            );
+        }
+        resolved
+    };
+    let timeout_secs = toml.timeout_secs.unwrap_or(TRANSCRIPT_ARCHIVE_TIMEOUT_SECS);
+
+    let backend = match toml.kind.as_str() {
+        "jsonl" => TranscriptSinkBackend::Jsonl {
+            path: PathBuf::from(required("path", &toml.path)?),
+        },
+        "http" => TranscriptSinkBackend::Http {
+            url: required("url", &toml.url)?,
+            auth_token: toml.auth_token.as_deref().and_then(resolve_env_value),
+            timeout_secs,
+        },
+        "s3" => TranscriptSinkBackend::S3 {
+            endpoint: required("endpoint", &toml.endpoint)?,
+            bucket: required("bucket", &toml.bucket)?,
+            region: toml
+                .region
+                .as_deref()
+                .and_then(resolve_env_value)
+                .unwrap_or_else(|| "us-east-1".into()),
+            prefix: toml.prefix.clone().unwrap_or_default(),
+            access_key_id: required("access_key_id", &toml.access_key_id)?,
+            secret_access_key: required("secret_access_key", &toml.secret_access_key)?,
+            timeout_secs,
+        },
+        other => {
+            tracing::warn!(
+                kind = other,
+                "unknown transcript_archive kind, expected one of: jsonl, http, s3; archive disabled"
+            );
+            return None;
+        }
+    };
+
+    Some(TranscriptArchiveConfig {
+        scope: toml
+            .scope
+            .as_deref()
+            .and_then(parse_transcript_archive_scope)
+            .unwrap_or_default(),
+        channel_turns: toml.channel_turns.unwrap_or(false),
+        backend,
+    })
+}
+
 /// Resolve the effective close policy. When `persist_session` is enabled and no
 /// explicit `close_policy` was provided, default to `Detach` so browser tabs and
 /// cookies survive across workers.
@@ -181,6 +774,14 @@ impl CortexConfig {
             bulletin_max_turns: overrides
                 .bulletin_max_turns
                 .unwrap_or(defaults.bulletin_max_turns),
+            bulletin_gather_concurrency: overrides
+                .bulletin_gather_concurrency
+                .unwrap_or(defaults.bulletin_gather_concurrency)
+                .max(1),
+            bulletin_gather_timeout_secs: overrides
+                .bulletin_gather_timeout_secs
+                .unwrap_or(defaults.bulletin_gather_timeout_secs)
+                .max(1),
             maintenance_interval_secs,
             maintenance_decay_rate: overrides
                 .maintenance_decay_rate
@@ -840,14 +1441,20 @@ impl Config {
             context_window: None,
             compaction: None,
             memory_persistence: None,
+            identity_reinforcement: None,
+            output_limits: None,
             coalesce: None,
+            worker: None,
             ingestion: None,
             cortex: None,
+            bulletin_history: None,
+            debug_mirror: None,
             warmup: None,
             browser: None,
             channel: None,
             mcp: None,
             brave_search_key: None,
+            web_search: None,
             cron_timezone: None,
             user_timezone: None,
             sandbox: None,
@@ -888,6 +1495,10 @@ impl Config {
             bindings: Vec::new(),
             api,
             metrics: MetricsConfig::default(),
+            idle: IdleConfig::default(),
+            redaction: RedactionConfig::default(),
+            outbound: OutboundConfig::default(),
+            prompt_overrides: HashMap::new(),
             telemetry: TelemetryConfig {
                 otlp_endpoint: std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok(),
                 otlp_headers: parse_otlp_headers(std::env::var("OTEL_EXPORTER_OTLP_HEADERS").ok())?,
@@ -1426,6 +2037,23 @@ impl Config {
                     emergency_threshold: c
                         .emergency_threshold
                         .unwrap_or(base_defaults.compaction.emergency_threshold),
+                    max_recap_bytes: c
+                        .max_recap_bytes
+                        .unwrap_or(base_defaults.compaction.max_recap_bytes),
+                    compactor_fallback: c
+                        .compactor_fallback
+                        .as_deref()
+                        .and_then(parse_compactor_fallback)
+                        .unwrap_or(base_defaults.compaction.compactor_fallback),
+                    tool_call_repair: c
+                        .tool_call_repair
+                        .as_deref()
+                        .and_then(parse_tool_call_repair)
+                        .unwrap_or(base_defaults.compaction.tool_call_repair),
+                    max_message_fraction: c
+                        .max_message_fraction
+                        .unwrap_or(base_defaults.compaction.max_message_fraction)
+                        .clamp(0.05, 1.0),
                 })
                 .unwrap_or(base_defaults.compaction),
             memory_persistence: toml
@@ -1438,8 +2066,30 @@ impl Config {
                     message_interval: mp
                         .message_interval
                         .unwrap_or(base_defaults.memory_persistence.message_interval),
+                    before_compaction: mp
+                        .before_compaction
+                        .unwrap_or(base_defaults.memory_persistence.before_compaction),
                 })
                 .unwrap_or(base_defaults.memory_persistence),
+            identity_reinforcement: toml
+                .defaults
+                .identity_reinforcement
+                .map(|c| IdentityReinforcementConfig {
+                    enabled: c
+                        .enabled
+                        .unwrap_or(base_defaults.identity_reinforcement.enabled),
+                    turn_interval: c
+                        .turn_interval
+                        .unwrap_or(base_defaults.identity_reinforcement.turn_interval),
+                    usage_threshold: c
+                        .usage_threshold
+                        .unwrap_or(base_defaults.identity_reinforcement.usage_threshold),
+                })
+                .unwrap_or(base_defaults.identity_reinforcement),
+            output_limits: resolve_output_limits(
+                toml.defaults.output_limits,
+                &base_defaults.output_limits,
+            ),
             coalesce: toml
                 .defaults
                 .coalesce
@@ -1455,6 +2105,158 @@ impl Config {
                         .unwrap_or(base_defaults.coalesce.multi_user_only),
                 })
                 .unwrap_or(base_defaults.coalesce),
+            worker: toml
+                .defaults
+                .worker
+                .map(|w| WorkerConfig {
+                    max_segments: w.max_segments.unwrap_or(base_defaults.worker.max_segments),
+                    max_unfinished_segments: w
+                        .max_unfinished_segments
+                        .unwrap_or(base_defaults.worker.max_unfinished_segments),
+                    wrap_up_turns: w
+                        .wrap_up_turns
+                        .unwrap_or(base_defaults.worker.wrap_up_turns)
+                        .max(1),
+                    max_duration_secs: w
+                        .max_duration_secs
+                        .unwrap_or(base_defaults.worker.max_duration_secs),
+                    idle_timeout_secs: w
+                        .idle_timeout_secs
+                        .unwrap_or(base_defaults.worker.idle_timeout_secs),
+                    preflight_check: w
+                        .preflight_check
+                        .unwrap_or(base_defaults.worker.preflight_check),
+                    preflight_turns: w
+                        .preflight_turns
+                        .unwrap_or(base_defaults.worker.preflight_turns)
+                        .max(1),
+                    max_spawn_depth: w
+                        .max_spawn_depth
+                        .unwrap_or(base_defaults.worker.max_spawn_depth),
+                    max_task_chars: w
+                        .max_task_chars
+                        .unwrap_or(base_defaults.worker.max_task_chars),
+                    task_overflow: w
+                        .task_overflow
+                        .as_deref()
+                        .and_then(parse_task_overflow_policy)
+                        .unwrap_or(base_defaults.worker.task_overflow),
+                    allow_clarifications: w
+                        .allow_clarifications
+                        .unwrap_or(base_defaults.worker.allow_clarifications),
+                    clarification_timeout_secs: w
+                        .clarification_timeout_secs
+                        .unwrap_or(base_defaults.worker.clarification_timeout_secs),
+                    compaction_enabled: w
+                        .compaction_enabled
+                        .unwrap_or(base_defaults.worker.compaction_enabled),
+                    compaction_strategy: w
+                        .compaction_strategy
+                        .as_deref()
+                        .and_then(parse_compaction_strategy)
+                        .unwrap_or(base_defaults.worker.compaction_strategy),
+                    cancellation_notice: w
+                        .cancellation_notice
+                        .unwrap_or(base_defaults.worker.cancellation_notice),
+                    memory_recall: w
+                        .memory_recall
+                        .unwrap_or(base_defaults.worker.memory_recall),
+                    tool_gating: w.tool_gating.unwrap_or(base_defaults.worker.tool_gating),
+                    path_suggestions: w
+                        .path_suggestions
+                        .unwrap_or(base_defaults.worker.path_suggestions),
+                    max_path_suggestions: w
+                        .max_path_suggestions
+                        .unwrap_or(base_defaults.worker.max_path_suggestions),
+                    follow_up_mode: w
+                        .follow_up_mode
+                        .as_deref()
+                        .and_then(parse_follow_up_mode)
+                        .unwrap_or(base_defaults.worker.follow_up_mode),
+                    follow_up_debounce_ms: w
+                        .follow_up_debounce_ms
+                        .unwrap_or(base_defaults.worker.follow_up_debounce_ms),
+                    isolated_workdir: w
+                        .isolated_workdir
+                        .unwrap_or(base_defaults.worker.isolated_workdir),
+                    workdir_base: w
+                        .workdir_base
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| base_defaults.worker.workdir_base.clone()),
+                    retain_workdir: w
+                        .retain_workdir
+                        .unwrap_or(base_defaults.worker.retain_workdir),
+                    checkpoint_history: w
+                        .checkpoint_history
+                        .unwrap_or(base_defaults.worker.checkpoint_history),
+                    ephemeral_tools: w
+                        .ephemeral_tools
+                        .unwrap_or_else(|| base_defaults.worker.ephemeral_tools.clone()),
+                    recap_tools: resolve_recap_tools(
+                        w.recap_tools,
+                        &base_defaults.worker.recap_tools,
+                    ),
+                    tool_retries: resolve_tool_retries(
+                        w.tool_retries,
+                        &base_defaults.worker.tool_retries,
+                    ),
+                    tool_result_formats: resolve_tool_result_formats(
+                        w.tool_result_formats,
+                        &base_defaults.worker.tool_result_formats,
+                    ),
+                    failure_log_format: w
+                        .failure_log_format
+                        .as_deref()
+                        .and_then(parse_log_format)
+                        .unwrap_or(base_defaults.worker.failure_log_format),
+                    failure_log_sink: resolve_failure_log_sink(
+                        w.failure_log_sink,
+                        &base_defaults.worker.failure_log_sink,
+                    ),
+                    transcript_archive: resolve_transcript_archive(
+                        w.transcript_archive,
+                        &base_defaults.worker.transcript_archive,
+                    ),
+                    log_manifest: w.log_manifest.unwrap_or(base_defaults.worker.log_manifest),
+                    transient_retries: w
+                        .transient_retries
+                        .unwrap_or(base_defaults.worker.transient_retries),
+                    transient_backoff_secs: w
+                        .transient_backoff_secs
+                        .unwrap_or(base_defaults.worker.transient_backoff_secs),
+                    overflow_retries: w
+                        .overflow_retries
+                        .unwrap_or(base_defaults.worker.overflow_retries),
+                    overloaded_retries: w
+                        .overloaded_retries
+                        .unwrap_or(base_defaults.worker.overloaded_retries),
+                    overloaded_backoff_secs: w
+                        .overloaded_backoff_secs
+                        .unwrap_or(base_defaults.worker.overloaded_backoff_secs),
+                    result_summary_threshold_chars: w
+                        .result_summary_threshold_chars
+                        .unwrap_or(base_defaults.worker.result_summary_threshold_chars),
+                    result_summary_target_chars: w
+                        .result_summary_target_chars
+                        .unwrap_or(base_defaults.worker.result_summary_target_chars)
+                        .max(1),
+                    attach_full_result: w
+                        .attach_full_result
+                        .unwrap_or(base_defaults.worker.attach_full_result),
+                    http_request: w
+                        .http_request
+                        .map(|h| resolve_http_request(h, &base_defaults.worker.http_request))
+                        .unwrap_or_else(|| base_defaults.worker.http_request.clone()),
+                    tool_permissions: w
+                        .tool_permissions
+                        .map(|p| resolve_tool_permissions(p, base_defaults.worker.tool_permissions))
+                        .unwrap_or(base_defaults.worker.tool_permissions),
+                    command_policy: w
+                        .command_policy
+                        .map(|c| resolve_command_policy(c, &base_defaults.worker.command_policy))
+                        .unwrap_or_else(|| base_defaults.worker.command_policy.clone()),
+                })
+                .unwrap_or_else(|| base_defaults.worker.clone()),
             ingestion: toml
                 .defaults
                 .ingestion
@@ -1472,6 +2274,16 @@ impl Config {
                 .map(|c| CortexConfig::resolve(c, base_defaults.cortex))
                 .transpose()?
                 .unwrap_or(base_defaults.cortex),
+            bulletin_history: toml
+                .defaults
+                .bulletin_history
+                .map(|h| resolve_bulletin_history(h, &base_defaults.bulletin_history))
+                .unwrap_or_else(|| base_defaults.bulletin_history.clone()),
+            debug_mirror: toml
+                .defaults
+                .debug_mirror
+                .map(|d| resolve_debug_mirror(d, &base_defaults.debug_mirror))
+                .unwrap_or_else(|| base_defaults.debug_mirror.clone()),
             warmup: toml
                 .defaults
                 .warmup
@@ -1524,11 +2336,32 @@ impl Config {
                     listen_only_mode: channel_config
                         .listen_only_mode
                         .unwrap_or(base_defaults.channel.listen_only_mode),
+                    listen_only_triggers: resolve_listen_only_triggers(
+                        channel_config.listen_only_triggers,
+                        &base_defaults.channel.listen_only_triggers,
+                    ),
                     save_attachments: channel_config
                         .save_attachments
                         .unwrap_or(base_defaults.channel.save_attachments),
+                    worker_wait_timeout_secs: channel_config
+                        .worker_wait_timeout_secs
+                        .unwrap_or(base_defaults.channel.worker_wait_timeout_secs),
+                    status_refresh: channel_config
+                        .status_refresh
+                        .as_deref()
+                        .and_then(parse_status_refresh)
+                        .unwrap_or(base_defaults.channel.status_refresh),
+                    user_content_escaping: channel_config
+                        .user_content_escaping
+                        .as_deref()
+                        .and_then(parse_user_content_escaping)
+                        .unwrap_or(base_defaults.channel.user_content_escaping),
+                    language_detection: resolve_language_detection(
+                        channel_config.language_detection,
+                        &base_defaults.channel.language_detection,
+                    ),
                 })
-                .unwrap_or(base_defaults.channel),
+                .unwrap_or_else(|| base_defaults.channel.clone()),
             mcp: default_mcp,
             brave_search_key: toml
                 .defaults
@@ -1536,6 +2369,11 @@ impl Config {
                 .as_deref()
                 .and_then(resolve_env_value)
                 .or_else(|| std::env::var("BRAVE_SEARCH_API_KEY").ok()),
+            web_search: toml
+                .defaults
+                .web_search
+                .map(|w| resolve_web_search(w, &base_defaults.web_search))
+                .unwrap_or_else(|| base_defaults.web_search.clone()),
             cron_timezone: toml
                 .defaults
                 .cron_timezone
@@ -1664,13 +2502,47 @@ impl Config {
                         emergency_threshold: c
                             .emergency_threshold
                             .unwrap_or(defaults.compaction.emergency_threshold),
+                        max_recap_bytes: c
+                            .max_recap_bytes
+                            .unwrap_or(defaults.compaction.max_recap_bytes),
+                        compactor_fallback: c
+                            .compactor_fallback
+                            .as_deref()
+                            .and_then(parse_compactor_fallback)
+                            .unwrap_or(defaults.compaction.compactor_fallback),
+                        tool_call_repair: c
+                            .tool_call_repair
+                            .as_deref()
+                            .and_then(parse_tool_call_repair)
+                            .unwrap_or(defaults.compaction.tool_call_repair),
+                        max_message_fraction: c
+                            .max_message_fraction
+                            .unwrap_or(defaults.compaction.max_message_fraction)
+                            .clamp(0.05, 1.0),
                     }),
                     memory_persistence: a.memory_persistence.map(|mp| MemoryPersistenceConfig {
                         enabled: mp.enabled.unwrap_or(defaults.memory_persistence.enabled),
                         message_interval: mp
                             .message_interval
                             .unwrap_or(defaults.memory_persistence.message_interval),
+                        before_compaction: mp
+                            .before_compaction
+                            .unwrap_or(defaults.memory_persistence.before_compaction),
+                    }),
+                    identity_reinforcement: a.identity_reinforcement.map(|c| {
+                        IdentityReinforcementConfig {
+                            enabled: c.enabled.unwrap_or(defaults.identity_reinforcement.enabled),
+                            turn_interval: c
+                                .turn_interval
+                                .unwrap_or(defaults.identity_reinforcement.turn_interval),
+                            usage_threshold: c
+                                .usage_threshold
+                                .unwrap_or(defaults.identity_reinforcement.usage_threshold),
+                        }
                     }),
+                    output_limits: a
+                        .output_limits
+                        .map(|limits| resolve_output_limits(Some(limits), &defaults.output_limits)),
                     coalesce: a.coalesce.map(|c| CoalesceConfig {
                         enabled: c.enabled.unwrap_or(defaults.coalesce.enabled),
                         debounce_ms: c.debounce_ms.unwrap_or(defaults.coalesce.debounce_ms),
@@ -1680,6 +2552,148 @@ impl Config {
                             .multi_user_only
                             .unwrap_or(defaults.coalesce.multi_user_only),
                     }),
+                    worker: a.worker.map(|w| WorkerConfig {
+                        max_segments: w.max_segments.unwrap_or(defaults.worker.max_segments),
+                        max_unfinished_segments: w
+                            .max_unfinished_segments
+                            .unwrap_or(defaults.worker.max_unfinished_segments),
+                        wrap_up_turns: w
+                            .wrap_up_turns
+                            .unwrap_or(defaults.worker.wrap_up_turns)
+                            .max(1),
+                        max_duration_secs: w
+                            .max_duration_secs
+                            .unwrap_or(defaults.worker.max_duration_secs),
+                        idle_timeout_secs: w
+                            .idle_timeout_secs
+                            .unwrap_or(defaults.worker.idle_timeout_secs),
+                        preflight_check: w
+                            .preflight_check
+                            .unwrap_or(defaults.worker.preflight_check),
+                        preflight_turns: w
+                            .preflight_turns
+                            .unwrap_or(defaults.worker.preflight_turns)
+                            .max(1),
+                        max_spawn_depth: w
+                            .max_spawn_depth
+                            .unwrap_or(defaults.worker.max_spawn_depth),
+                        max_task_chars: w.max_task_chars.unwrap_or(defaults.worker.max_task_chars),
+                        task_overflow: w
+                            .task_overflow
+                            .as_deref()
+                            .and_then(parse_task_overflow_policy)
+                            .unwrap_or(defaults.worker.task_overflow),
+                        allow_clarifications: w
+                            .allow_clarifications
+                            .unwrap_or(defaults.worker.allow_clarifications),
+                        clarification_timeout_secs: w
+                            .clarification_timeout_secs
+                            .unwrap_or(defaults.worker.clarification_timeout_secs),
+                        compaction_enabled: w
+                            .compaction_enabled
+                            .unwrap_or(defaults.worker.compaction_enabled),
+                        compaction_strategy: w
+                            .compaction_strategy
+                            .as_deref()
+                            .and_then(parse_compaction_strategy)
+                            .unwrap_or(defaults.worker.compaction_strategy),
+                        cancellation_notice: w
+                            .cancellation_notice
+                            .unwrap_or(defaults.worker.cancellation_notice),
+                        memory_recall: w.memory_recall.unwrap_or(defaults.worker.memory_recall),
+                        tool_gating: w.tool_gating.unwrap_or(defaults.worker.tool_gating),
+                        path_suggestions: w
+                            .path_suggestions
+                            .unwrap_or(defaults.worker.path_suggestions),
+                        max_path_suggestions: w
+                            .max_path_suggestions
+                            .unwrap_or(defaults.worker.max_path_suggestions),
+                        follow_up_mode: w
+                            .follow_up_mode
+                            .as_deref()
+                            .and_then(parse_follow_up_mode)
+                            .unwrap_or(defaults.worker.follow_up_mode),
+                        follow_up_debounce_ms: w
+                            .follow_up_debounce_ms
+                            .unwrap_or(defaults.worker.follow_up_debounce_ms),
+                        isolated_workdir: w
+                            .isolated_workdir
+                            .unwrap_or(defaults.worker.isolated_workdir),
+                        workdir_base: w
+                            .workdir_base
+                            .map(PathBuf::from)
+                            .unwrap_or_else(|| defaults.worker.workdir_base.clone()),
+                        retain_workdir: w.retain_workdir.unwrap_or(defaults.worker.retain_workdir),
+                        checkpoint_history: w
+                            .checkpoint_history
+                            .unwrap_or(defaults.worker.checkpoint_history),
+                        ephemeral_tools: w
+                            .ephemeral_tools
+                            .unwrap_or_else(|| defaults.worker.ephemeral_tools.clone()),
+                        recap_tools: resolve_recap_tools(
+                            w.recap_tools,
+                            &defaults.worker.recap_tools,
+                        ),
+                        tool_retries: resolve_tool_retries(
+                            w.tool_retries,
+                            &defaults.worker.tool_retries,
+                        ),
+                        tool_result_formats: resolve_tool_result_formats(
+                            w.tool_result_formats,
+                            &defaults.worker.tool_result_formats,
+                        ),
+                        failure_log_format: w
+                            .failure_log_format
+                            .as_deref()
+                            .and_then(parse_log_format)
+                            .unwrap_or(defaults.worker.failure_log_format),
+                        failure_log_sink: resolve_failure_log_sink(
+                            w.failure_log_sink,
+                            &defaults.worker.failure_log_sink,
+                        ),
+                        transcript_archive: resolve_transcript_archive(
+                            w.transcript_archive,
+                            &defaults.worker.transcript_archive,
+                        ),
+                        log_manifest: w.log_manifest.unwrap_or(defaults.worker.log_manifest),
+                        transient_retries: w
+                            .transient_retries
+                            .unwrap_or(defaults.worker.transient_retries),
+                        transient_backoff_secs: w
+                            .transient_backoff_secs
+                            .unwrap_or(defaults.worker.transient_backoff_secs),
+                        overflow_retries: w
+                            .overflow_retries
+                            .unwrap_or(defaults.worker.overflow_retries),
+                        overloaded_retries: w
+                            .overloaded_retries
+                            .unwrap_or(defaults.worker.overloaded_retries),
+                        overloaded_backoff_secs: w
+                            .overloaded_backoff_secs
+                            .unwrap_or(defaults.worker.overloaded_backoff_secs),
+                        result_summary_threshold_chars: w
+                            .result_summary_threshold_chars
+                            .unwrap_or(defaults.worker.result_summary_threshold_chars),
+                        result_summary_target_chars: w
+                            .result_summary_target_chars
+                            .unwrap_or(defaults.worker.result_summary_target_chars)
+                            .max(1),
+                        attach_full_result: w
+                            .attach_full_result
+                            .unwrap_or(defaults.worker.attach_full_result),
+                        http_request: w
+                            .http_request
+                            .map(|h| resolve_http_request(h, &defaults.worker.http_request))
+                            .unwrap_or_else(|| defaults.worker.http_request.clone()),
+                        tool_permissions: w
+                            .tool_permissions
+                            .map(|p| resolve_tool_permissions(p, defaults.worker.tool_permissions))
+                            .unwrap_or(defaults.worker.tool_permissions),
+                        command_policy: w
+                            .command_policy
+                            .map(|c| resolve_command_policy(c, &defaults.worker.command_policy))
+                            .unwrap_or_else(|| defaults.worker.command_policy.clone()),
+                    }),
                     ingestion: a.ingestion.map(|ig| IngestionConfig {
                         enabled: ig.enabled.unwrap_or(defaults.ingestion.enabled),
                         poll_interval_secs: ig
@@ -1691,6 +2705,12 @@ impl Config {
                         .cortex
                         .map(|c| CortexConfig::resolve(c, defaults.cortex))
                         .transpose()?,
+                    bulletin_history: a
+                        .bulletin_history
+                        .map(|h| resolve_bulletin_history(h, &defaults.bulletin_history)),
+                    debug_mirror: a
+                        .debug_mirror
+                        .map(|d| resolve_debug_mirror(d, &defaults.debug_mirror)),
                     warmup: a.warmup.map(|w| WarmupConfig {
                         enabled: w.enabled.unwrap_or(defaults.warmup.enabled),
                         eager_embedding_load: w
@@ -1729,9 +2749,30 @@ impl Config {
                         listen_only_mode: channel_config
                             .listen_only_mode
                             .unwrap_or(defaults.channel.listen_only_mode),
+                        listen_only_triggers: resolve_listen_only_triggers(
+                            channel_config.listen_only_triggers,
+                            &defaults.channel.listen_only_triggers,
+                        ),
                         save_attachments: channel_config
                             .save_attachments
                             .unwrap_or(defaults.channel.save_attachments),
+                        worker_wait_timeout_secs: channel_config
+                            .worker_wait_timeout_secs
+                            .unwrap_or(defaults.channel.worker_wait_timeout_secs),
+                        status_refresh: channel_config
+                            .status_refresh
+                            .as_deref()
+                            .and_then(parse_status_refresh)
+                            .unwrap_or(defaults.channel.status_refresh),
+                        user_content_escaping: channel_config
+                            .user_content_escaping
+                            .as_deref()
+                            .and_then(parse_user_content_escaping)
+                            .unwrap_or(defaults.channel.user_content_escaping),
+                        language_detection: resolve_language_detection(
+                            channel_config.language_detection,
+                            &defaults.channel.language_detection,
+                        ),
                     }),
                     mcp: match a.mcp {
                         Some(mcp_servers) => Some(
@@ -1743,6 +2784,9 @@ impl Config {
                         None => None,
                     },
                     brave_search_key: a.brave_search_key.as_deref().and_then(resolve_env_value),
+                    web_search: a
+                        .web_search
+                        .map(|w| resolve_web_search(w, &defaults.web_search)),
                     cron_timezone: a.cron_timezone.as_deref().and_then(resolve_env_value),
                     user_timezone: a.user_timezone.as_deref().and_then(resolve_env_value),
                     sandbox: a.sandbox,
@@ -1789,14 +2833,20 @@ impl Config {
                 context_window: None,
                 compaction: None,
                 memory_persistence: None,
+                identity_reinforcement: None,
+                output_limits: None,
                 coalesce: None,
+                worker: None,
                 ingestion: None,
                 cortex: None,
+                bulletin_history: None,
+                debug_mirror: None,
                 warmup: None,
                 browser: None,
                 channel: None,
                 mcp: None,
                 brave_search_key: None,
+                web_search: None,
                 cron_timezone: None,
                 user_timezone: None,
                 sandbox: None,
@@ -2092,11 +3142,28 @@ impl Config {
                     instances,
                 })
             }),
-            webhook: toml.messaging.webhook.map(|w| WebhookConfig {
-                enabled: w.enabled,
-                port: w.port,
-                bind: w.bind,
-                auth_token: w.auth_token.as_deref().and_then(resolve_env_value),
+            webhook: toml.messaging.webhook.map(|w| {
+                let signature = w
+                    .signing_secret
+                    .as_deref()
+                    .and_then(resolve_env_value)
+                    .filter(|secret| !secret.is_empty())
+                    .map(|secret| WebhookSignatureConfig {
+                        secret,
+                        header: w
+                            .signature_header
+                            .as_deref()
+                            .unwrap_or("x-signature")
+                            .to_lowercase(),
+                        scheme: parse_webhook_signature_scheme(w.signature_scheme.as_deref()),
+                    });
+                WebhookConfig {
+                    enabled: w.enabled,
+                    port: w.port,
+                    bind: w.bind,
+                    auth_token: w.auth_token.as_deref().and_then(resolve_env_value),
+                    signature,
+                }
             }),
             twitch: toml.messaging.twitch.and_then(|t| {
                 let instances = t
@@ -2260,6 +3327,79 @@ impl Config {
             bind: toml.metrics.bind,
         };
 
+        let idle = {
+            let base = IdleConfig::default();
+            IdleConfig {
+                timeout_secs: toml.idle.timeout_secs.unwrap_or(base.timeout_secs),
+                action: toml
+                    .idle
+                    .action
+                    .as_deref()
+                    .and_then(parse_idle_action)
+                    .unwrap_or(base.action),
+                keep_connected: toml.idle.keep_connected.unwrap_or(base.keep_connected),
+            }
+        };
+
+        let redaction = {
+            let base = RedactionConfig::default();
+            RedactionConfig {
+                enabled: toml.redaction.enabled.unwrap_or(base.enabled),
+                patterns: toml.redaction.patterns.unwrap_or(base.patterns),
+            }
+        };
+
+        let outbound = {
+            let base = OutboundConfig::default();
+            let adapter_pipelines = toml
+                .outbound
+                .adapters
+                .into_iter()
+                .map(|(adapter, adapter_config)| {
+                    (adapter, parse_outbound_pipeline(&adapter_config.pipeline))
+                })
+                .collect();
+            let link_rewrites = toml
+                .outbound
+                .link_rewrites
+                .into_iter()
+                .filter(|rewrite| !rewrite.from.is_empty())
+                .map(|rewrite| (rewrite.from, rewrite.to))
+                .collect();
+            let mut rate_limits = base.rate_limits;
+            for (adapter, limit) in toml.outbound.rate_limits {
+                // Unset fields keep the platform's default where it has one.
+                let fallback = rate_limits
+                    .get(&adapter)
+                    .copied()
+                    .unwrap_or(OutboundRateLimit {
+                        messages: 0,
+                        per_secs: 1,
+                        per_channel: false,
+                    });
+                rate_limits.insert(
+                    adapter,
+                    OutboundRateLimit {
+                        messages: limit.messages.unwrap_or(fallback.messages),
+                        per_secs: limit.per_secs.unwrap_or(fallback.per_secs).max(1),
+                        per_channel: limit.per_channel.unwrap_or(fallback.per_channel),
+                    },
+                );
+            }
+            OutboundConfig {
+                pipeline: toml
+                    .outbound
+                    .pipeline
+                    .as_deref()
+                    .map(parse_outbound_pipeline)
+                    .unwrap_or(base.pipeline),
+                adapter_pipelines,
+                link_rewrites,
+                indent_code: toml.outbound.indent_code.unwrap_or(base.indent_code),
+                rate_limits,
+            }
+        };
+
         let telemetry = {
             // env var takes precedence over config file value
             let otlp_endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
@@ -2374,6 +3514,10 @@ impl Config {
             api,
             metrics,
             telemetry,
+            idle,
+            redaction,
+            outbound,
+            prompt_overrides: toml.prompts.overrides,
         })
     }
 }
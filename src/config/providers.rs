@@ -1,6 +1,7 @@
 use super::toml_schema::TomlRoutingConfig;
 use super::{ApiType, ProviderConfig};
-use crate::llm::routing::RoutingConfig;
+use crate::llm::routing::{RoutingConfig, SamplingParams};
+use crate::llm::tool_quirks::ToolCallQuirk;
 
 use std::collections::HashMap;
 
@@ -294,6 +295,9 @@ pub(super) fn infer_routing_from_providers(
         .map(|name| crate::llm::routing::defaults_for_provider(name))
 }
 
+/// Process type names accepted as keys under `[routing.sampling]`.
+const SAMPLING_PROCESS_TYPES: &[&str] = &["channel", "branch", "worker", "compactor", "cortex"];
+
 /// Resolve a TomlRoutingConfig against a base RoutingConfig.
 pub(super) fn resolve_routing(
     toml: Option<TomlRoutingConfig>,
@@ -309,6 +313,54 @@ pub(super) fn resolve_routing(
         None => base.fallbacks.clone(),
     };
 
+    // Sampling merges per field so an agent can override one parameter
+    // without dropping the rest of the defaults for that process type.
+    let mut sampling = base.sampling.clone();
+    for (process_type, params) in t.sampling {
+        if !SAMPLING_PROCESS_TYPES.contains(&process_type.as_str()) {
+            tracing::warn!(
+                process_type,
+                "ignoring sampling config for unknown process type"
+            );
+            continue;
+        }
+        let inherited = sampling.get(&process_type).copied().unwrap_or_default();
+        let merged = SamplingParams {
+            temperature: params.temperature.or(inherited.temperature),
+            top_p: params.top_p.or(inherited.top_p),
+            presence_penalty: params.presence_penalty.or(inherited.presence_penalty),
+            frequency_penalty: params.frequency_penalty.or(inherited.frequency_penalty),
+        }
+        .clamped(&process_type);
+        sampling.insert(process_type, merged);
+    }
+
+    let mut context_windows = base.context_windows.clone();
+    context_windows.extend(t.context_windows);
+
+    // Added to the inherited patterns, which are added to the built-in ones.
+    let mut context_overflow_patterns = base.context_overflow_patterns.clone();
+    for pattern in t.context_overflow_patterns {
+        if !context_overflow_patterns.contains(&pattern) {
+            context_overflow_patterns.push(pattern);
+        }
+    }
+
+    let mut tool_call_quirks = base.tool_call_quirks.clone();
+    for (pattern, names) in t.tool_call_quirks {
+        let quirks = names
+            .iter()
+            .filter_map(|name| {
+                let quirk = ToolCallQuirk::parse(name);
+                if quirk.is_none() {
+                    tracing::warn!(pattern, quirk = %name, "ignoring unknown tool call quirk");
+                }
+                quirk
+            })
+            .collect();
+        tool_call_quirks.insert(pattern, quirks);
+    }
+
     RoutingConfig {
         channel: t.channel.unwrap_or_else(|| base.channel.clone()),
         branch: t.branch.unwrap_or_else(|| base.branch.clone()),
@@ -321,6 +373,7 @@ pub(super) fn resolve_routing(
         rate_limit_cooldown_secs: t
             .rate_limit_cooldown_secs
             .unwrap_or(base.rate_limit_cooldown_secs),
+        request_timeout_secs: t.request_timeout_secs.unwrap_or(base.request_timeout_secs),
         channel_thinking_effort: t
             .channel_thinking_effort
             .unwrap_or_else(|| base.channel_thinking_effort.clone()),
@@ -336,5 +389,12 @@ pub(super) fn resolve_routing(
         cortex_thinking_effort: t
             .cortex_thinking_effort
             .unwrap_or_else(|| base.cortex_thinking_effort.clone()),
+        sampling,
+        tool_call_quirks,
+        text_only_models: t
+            .text_only_models
+            .unwrap_or_else(|| base.text_only_models.clone()),
+        context_windows,
+        context_overflow_patterns,
     }
 }
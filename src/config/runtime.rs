@@ -4,12 +4,16 @@ use std::sync::Arc;
 use arc_swap::ArcSwap;
 
 use super::{
-    BrowserConfig, ChannelConfig, CoalesceConfig, CompactionConfig, Config, CortexConfig,
-    DefaultsConfig, IngestionConfig, McpServerConfig, MemoryPersistenceConfig, OpenCodeConfig,
-    ResolvedAgentConfig, WarmupConfig, WarmupStatus, WorkReadiness, evaluate_work_readiness,
+    BrowserConfig, BulletinHistoryConfig, ChannelConfig, CoalesceConfig, CompactionConfig, Config,
+    CortexConfig, DebugMirrorConfig, DefaultsConfig, IdentityReinforcementConfig, IngestionConfig,
+    McpServerConfig, MemoryPersistenceConfig, OpenCodeConfig, OutputLimitsConfig,
+    ResolvedAgentConfig, SearchBackendKind, WarmupConfig, WarmupStatus, WebSearchConfig,
+    WorkReadiness, WorkerConfig, evaluate_work_readiness,
 };
 use crate::llm::routing::RoutingConfig;
+use crate::secrets::provider::SecretProviderDyn;
 use crate::tools::browser::SharedBrowserHandle;
+use crate::tools::{BraveBackend, SearchBackendDyn, SearxngBackend};
 
 /// Live configuration that can be hot-reloaded without restarting.
 ///
@@ -28,7 +32,10 @@ pub struct RuntimeConfig {
     pub routing: ArcSwap<RoutingConfig>,
     pub compaction: ArcSwap<CompactionConfig>,
     pub memory_persistence: ArcSwap<MemoryPersistenceConfig>,
+    pub identity_reinforcement: ArcSwap<IdentityReinforcementConfig>,
+    pub output_limits: ArcSwap<OutputLimitsConfig>,
     pub coalesce: ArcSwap<CoalesceConfig>,
+    pub worker: ArcSwap<WorkerConfig>,
     pub ingestion: ArcSwap<IngestionConfig>,
     pub channel_config: ArcSwap<ChannelConfig>,
     pub max_turns: ArcSwap<usize>,
@@ -39,10 +46,15 @@ pub struct RuntimeConfig {
     pub browser_config: ArcSwap<BrowserConfig>,
     pub mcp: ArcSwap<Vec<McpServerConfig>>,
     pub history_backfill_count: ArcSwap<usize>,
-    pub brave_search_key: ArcSwap<Option<String>>,
+    /// Handle for the Brave key, built from its config reference at load and
+    /// on reload so a remote store's cache outlives individual searches.
+    pub brave_search_key: ArcSwap<Option<Arc<dyn SecretProviderDyn>>>,
+    pub web_search: ArcSwap<WebSearchConfig>,
     pub cron_timezone: ArcSwap<Option<String>>,
     pub user_timezone: ArcSwap<Option<String>>,
     pub cortex: ArcSwap<CortexConfig>,
+    pub bulletin_history: ArcSwap<BulletinHistoryConfig>,
+    pub debug_mirror: ArcSwap<DebugMirrorConfig>,
     pub warmup: ArcSwap<WarmupConfig>,
     /// Current warmup lifecycle status for API and observability.
     pub warmup_status: ArcSwap<WarmupStatus>,
@@ -109,9 +121,12 @@ impl RuntimeConfig {
             routing: ArcSwap::from_pointee(agent_config.routing.clone()),
             compaction: ArcSwap::from_pointee(agent_config.compaction),
             memory_persistence: ArcSwap::from_pointee(agent_config.memory_persistence),
+            identity_reinforcement: ArcSwap::from_pointee(agent_config.identity_reinforcement),
+            output_limits: ArcSwap::from_pointee(agent_config.output_limits),
             coalesce: ArcSwap::from_pointee(agent_config.coalesce),
+            worker: ArcSwap::from_pointee(agent_config.worker.clone()),
             ingestion: ArcSwap::from_pointee(agent_config.ingestion),
-            channel_config: ArcSwap::from_pointee(agent_config.channel),
+            channel_config: ArcSwap::from_pointee(agent_config.channel.clone()),
             max_turns: ArcSwap::from_pointee(agent_config.max_turns),
             branch_max_turns: ArcSwap::from_pointee(agent_config.branch_max_turns),
             context_window: ArcSwap::from_pointee(agent_config.context_window),
@@ -120,10 +135,18 @@ impl RuntimeConfig {
             browser_config: ArcSwap::from_pointee(agent_config.browser.clone()),
             mcp: ArcSwap::from_pointee(agent_config.mcp.clone()),
             history_backfill_count: ArcSwap::from_pointee(agent_config.history_backfill_count),
-            brave_search_key: ArcSwap::from_pointee(agent_config.brave_search_key.clone()),
+            brave_search_key: ArcSwap::from_pointee(
+                agent_config
+                    .brave_search_key
+                    .as_deref()
+                    .map(crate::secrets::provider::from_reference),
+            ),
+            web_search: ArcSwap::from_pointee(agent_config.web_search.clone()),
             cron_timezone: ArcSwap::from_pointee(agent_config.cron_timezone.clone()),
             user_timezone: ArcSwap::from_pointee(agent_config.user_timezone.clone()),
             cortex: ArcSwap::from_pointee(agent_config.cortex),
+            bulletin_history: ArcSwap::from_pointee(agent_config.bulletin_history.clone()),
+            debug_mirror: ArcSwap::from_pointee(agent_config.debug_mirror.clone()),
             warmup: ArcSwap::from_pointee(agent_config.warmup),
             warmup_status: ArcSwap::from_pointee(WarmupStatus::default()),
             warmup_lock: Arc::new(tokio::sync::Mutex::new(())),
@@ -172,7 +195,7 @@ impl RuntimeConfig {
             match settings.channel_listen_only_mode() {
                 Ok(Some(enabled)) => {
                     self.channel_config.rcu(move |current| {
-                        let mut next = **current;
+                        let mut next = (**current).clone();
                         next.listen_only_mode = enabled;
                         Arc::new(next)
                     });
@@ -202,11 +225,51 @@ impl RuntimeConfig {
         self.work_readiness().ready
     }
 
+    /// Persisted memory bulletin snapshots, oldest first. Empty until bulletin
+    /// history is enabled and a synthesis has run.
+    pub fn memory_bulletin_history(
+        &self,
+    ) -> std::io::Result<Vec<crate::agent::bulletin_history::BulletinSnapshot>> {
+        match &self.bulletin_history.load().dir {
+            Some(dir) => crate::agent::bulletin_history::load_history(dir),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Path to the saved attachments directory for persisted channel files.
     pub fn saved_dir(&self) -> std::path::PathBuf {
         self.workspace_dir.join("saved")
     }
 
+    /// The configured web search backend. `None` when the selected backend
+    /// is missing what it needs (a Brave key, or a SearXNG URL). The Brave
+    /// key is a handle resolved on each use.
+    pub fn search_backend(&self) -> Option<Arc<dyn SearchBackendDyn>> {
+        match self.web_search.load().backend {
+            SearchBackendKind::Brave => self
+                .brave_search_key
+                .load()
+                .as_ref()
+                .as_ref()
+                .map(|key| Arc::new(BraveBackend::new(key.clone())) as _),
+            SearchBackendKind::Searxng => self
+                .web_search
+                .load()
+                .searxng_url
+                .as_deref()
+                .map(|url| Arc::new(SearxngBackend::new(url)) as _),
+        }
+    }
+
+    /// Whether workers get the `web_search` tool.
+    pub fn web_search_enabled(&self) -> bool {
+        let web_search = self.web_search.load();
+        match web_search.backend {
+            SearchBackendKind::Brave => self.brave_search_key.load().is_some(),
+            SearchBackendKind::Searxng => web_search.searxng_url.is_some(),
+        }
+    }
+
     /// Reload tunable config values from a freshly parsed Config.
     ///
     /// Finds the matching agent by ID, re-resolves it against defaults, and
@@ -233,10 +296,17 @@ impl RuntimeConfig {
         self.compaction.store(Arc::new(resolved.compaction));
         self.memory_persistence
             .store(Arc::new(resolved.memory_persistence));
+        self.identity_reinforcement
+            .store(Arc::new(resolved.identity_reinforcement));
+        self.output_limits.store(Arc::new(resolved.output_limits));
         self.coalesce.store(Arc::new(resolved.coalesce));
+        self.worker.store(Arc::new(resolved.worker));
         self.ingestion.store(Arc::new(resolved.ingestion));
         let resolved_channel = resolved.channel;
-        let configured_listen_only = agent.channel.map(|channel| channel.listen_only_mode);
+        let configured_listen_only = agent
+            .channel
+            .as_ref()
+            .map(|channel| channel.listen_only_mode);
         self.channel_listen_only_explicit
             .store(Arc::new(configured_listen_only));
         let persisted_listen_only = self.settings.load().as_ref().as_ref().and_then(|settings| {
@@ -252,7 +322,7 @@ impl RuntimeConfig {
             }
         });
         self.channel_config.rcu(move |current| {
-            let mut next = resolved_channel;
+            let mut next = resolved_channel.clone();
             next.listen_only_mode = configured_listen_only
                 .or(persisted_listen_only)
                 .unwrap_or(current.as_ref().listen_only_mode);
@@ -281,11 +351,19 @@ impl RuntimeConfig {
         self.mcp.store(Arc::new(new_mcp.clone()));
         self.history_backfill_count
             .store(Arc::new(resolved.history_backfill_count));
-        self.brave_search_key
-            .store(Arc::new(resolved.brave_search_key));
+        self.brave_search_key.store(Arc::new(
+            resolved
+                .brave_search_key
+                .as_deref()
+                .map(crate::secrets::provider::from_reference),
+        ));
+        self.web_search.store(Arc::new(resolved.web_search));
         self.cron_timezone.store(Arc::new(resolved.cron_timezone));
         self.user_timezone.store(Arc::new(resolved.user_timezone));
         self.cortex.store(Arc::new(resolved.cortex));
+        self.bulletin_history
+            .store(Arc::new(resolved.bulletin_history));
+        self.debug_mirror.store(Arc::new(resolved.debug_mirror));
         self.warmup.store(Arc::new(resolved.warmup));
         // Preserve project_paths from the current sandbox config when
         // reloading — the resolved config only has user-configured paths.
@@ -27,6 +27,63 @@ pub(super) struct TomlConfig {
     pub(super) metrics: TomlMetricsConfig,
     #[serde(default)]
     pub(super) telemetry: TomlTelemetryConfig,
+    #[serde(default)]
+    pub(super) idle: TomlIdleConfig,
+    #[serde(default)]
+    pub(super) redaction: TomlRedactionConfig,
+    #[serde(default)]
+    pub(super) outbound: TomlOutboundConfig,
+    #[serde(default)]
+    pub(super) prompts: TomlPromptsConfig,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlPromptsConfig {
+    #[serde(default)]
+    pub(super) overrides: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlIdleConfig {
+    pub(super) timeout_secs: Option<u64>,
+    pub(super) action: Option<String>,
+    pub(super) keep_connected: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlRedactionConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) patterns: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlOutboundConfig {
+    pub(super) pipeline: Option<Vec<String>>,
+    #[serde(default)]
+    pub(super) adapters: HashMap<String, TomlOutboundAdapterConfig>,
+    #[serde(default)]
+    pub(super) link_rewrites: Vec<TomlLinkRewrite>,
+    pub(super) indent_code: Option<Vec<String>>,
+    #[serde(default)]
+    pub(super) rate_limits: HashMap<String, TomlOutboundRateLimit>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOutboundRateLimit {
+    pub(super) messages: Option<u32>,
+    pub(super) per_secs: Option<u64>,
+    pub(super) per_channel: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOutboundAdapterConfig {
+    pub(super) pipeline: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlLinkRewrite {
+    pub(super) from: String,
+    pub(super) to: String,
 }
 
 #[derive(Deserialize)]
@@ -280,15 +337,21 @@ pub(super) struct TomlDefaultsConfig {
     pub(super) context_window: Option<usize>,
     pub(super) compaction: Option<TomlCompactionConfig>,
     pub(super) memory_persistence: Option<TomlMemoryPersistenceConfig>,
+    pub(super) identity_reinforcement: Option<TomlIdentityReinforcementConfig>,
+    pub(super) output_limits: Option<TomlOutputLimitsConfig>,
     pub(super) coalesce: Option<TomlCoalesceConfig>,
+    pub(super) worker: Option<TomlWorkerConfig>,
     pub(super) ingestion: Option<TomlIngestionConfig>,
     pub(super) cortex: Option<TomlCortexConfig>,
+    pub(super) bulletin_history: Option<TomlBulletinHistoryConfig>,
+    pub(super) debug_mirror: Option<TomlDebugMirrorConfig>,
     pub(super) warmup: Option<TomlWarmupConfig>,
     pub(super) browser: Option<TomlBrowserConfig>,
     pub(super) channel: Option<TomlChannelConfig>,
     #[serde(default)]
     pub(super) mcp: Vec<TomlMcpServerConfig>,
     pub(super) brave_search_key: Option<String>,
+    pub(super) web_search: Option<TomlWebSearchConfig>,
     pub(super) cron_timezone: Option<String>,
     pub(super) user_timezone: Option<String>,
     pub(super) opencode: Option<TomlOpenCodeConfig>,
@@ -305,6 +368,7 @@ pub(super) struct TomlRoutingConfig {
     pub(super) cortex: Option<String>,
     pub(super) voice: Option<String>,
     pub(super) rate_limit_cooldown_secs: Option<u64>,
+    pub(super) request_timeout_secs: Option<u64>,
     pub(super) channel_thinking_effort: Option<String>,
     pub(super) branch_thinking_effort: Option<String>,
     pub(super) worker_thinking_effort: Option<String>,
@@ -313,12 +377,51 @@ pub(super) struct TomlRoutingConfig {
     #[serde(default)]
     pub(super) task_overrides: HashMap<String, String>,
     pub(super) fallbacks: Option<HashMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub(super) sampling: HashMap<String, TomlSamplingParams>,
+    #[serde(default)]
+    pub(super) tool_call_quirks: HashMap<String, Vec<String>>,
+    pub(super) text_only_models: Option<Vec<String>>,
+    #[serde(default)]
+    pub(super) context_windows: HashMap<String, usize>,
+    #[serde(default)]
+    pub(super) context_overflow_patterns: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlSamplingParams {
+    pub(super) temperature: Option<f64>,
+    pub(super) top_p: Option<f64>,
+    pub(super) presence_penalty: Option<f64>,
+    pub(super) frequency_penalty: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOutputLimitsConfig {
+    pub(super) channel: Option<TomlOutputLimit>,
+    pub(super) branch: Option<TomlOutputLimit>,
+    pub(super) worker: Option<TomlOutputLimit>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlOutputLimit {
+    pub(super) max_output_tokens: Option<u64>,
+    pub(super) max_chars: Option<usize>,
+    pub(super) on_exceed: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlIdentityReinforcementConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) turn_interval: Option<usize>,
+    pub(super) usage_threshold: Option<f32>,
 }
 
 #[derive(Deserialize)]
 pub(super) struct TomlMemoryPersistenceConfig {
     pub(super) enabled: Option<bool>,
     pub(super) message_interval: Option<usize>,
+    pub(super) before_compaction: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -330,6 +433,112 @@ pub(super) struct TomlCoalesceConfig {
     pub(super) multi_user_only: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlWorkerConfig {
+    pub(super) max_segments: Option<usize>,
+    pub(super) max_unfinished_segments: Option<usize>,
+    pub(super) wrap_up_turns: Option<usize>,
+    pub(super) max_duration_secs: Option<u64>,
+    pub(super) idle_timeout_secs: Option<u64>,
+    pub(super) preflight_check: Option<bool>,
+    pub(super) preflight_turns: Option<usize>,
+    pub(super) max_spawn_depth: Option<usize>,
+    pub(super) max_task_chars: Option<usize>,
+    pub(super) task_overflow: Option<String>,
+    pub(super) allow_clarifications: Option<bool>,
+    pub(super) clarification_timeout_secs: Option<u64>,
+    pub(super) compaction_enabled: Option<bool>,
+    pub(super) compaction_strategy: Option<String>,
+    pub(super) cancellation_notice: Option<bool>,
+    pub(super) memory_recall: Option<bool>,
+    pub(super) tool_gating: Option<bool>,
+    pub(super) path_suggestions: Option<bool>,
+    pub(super) max_path_suggestions: Option<usize>,
+    pub(super) follow_up_mode: Option<String>,
+    pub(super) follow_up_debounce_ms: Option<u64>,
+    pub(super) isolated_workdir: Option<bool>,
+    pub(super) workdir_base: Option<String>,
+    pub(super) retain_workdir: Option<bool>,
+    pub(super) checkpoint_history: Option<bool>,
+    pub(super) ephemeral_tools: Option<Vec<String>>,
+    pub(super) recap_tools: Option<HashMap<String, String>>,
+    pub(super) tool_retries: Option<HashMap<String, TomlToolRetryPolicy>>,
+    pub(super) tool_result_formats: Option<HashMap<String, String>>,
+    pub(super) failure_log_format: Option<String>,
+    pub(super) failure_log_sink: Option<TomlFailureLogSinkConfig>,
+    pub(super) transcript_archive: Option<TomlTranscriptArchiveConfig>,
+    pub(super) log_manifest: Option<bool>,
+    pub(super) transient_retries: Option<usize>,
+    pub(super) transient_backoff_secs: Option<u64>,
+    pub(super) overflow_retries: Option<usize>,
+    pub(super) overloaded_retries: Option<usize>,
+    pub(super) overloaded_backoff_secs: Option<u64>,
+    pub(super) result_summary_threshold_chars: Option<usize>,
+    pub(super) result_summary_target_chars: Option<usize>,
+    pub(super) attach_full_result: Option<bool>,
+    pub(super) http_request: Option<TomlHttpRequestConfig>,
+    pub(super) tool_permissions: Option<TomlToolPermissions>,
+    pub(super) command_policy: Option<TomlCommandPolicyConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlCommandPolicyConfig {
+    pub(super) allowed: Option<Vec<String>>,
+    pub(super) denied: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlToolPermissions {
+    pub(super) shell: Option<bool>,
+    pub(super) file_write: Option<bool>,
+    pub(super) browser: Option<bool>,
+    pub(super) web_search: Option<bool>,
+    pub(super) http_request: Option<bool>,
+    pub(super) secrets: Option<bool>,
+    pub(super) mcp: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlHttpRequestConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) timeout_secs: Option<u64>,
+    pub(super) max_response_bytes: Option<usize>,
+    pub(super) allowed_hosts: Option<Vec<String>>,
+    pub(super) denied_hosts: Option<Vec<String>>,
+    pub(super) allow_private_networks: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlToolRetryPolicy {
+    pub(super) max_retries: Option<u32>,
+    pub(super) backoff_ms: Option<u64>,
+    pub(super) retry_on: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlFailureLogSinkConfig {
+    pub(super) url: String,
+    pub(super) auth_token: Option<String>,
+    pub(super) timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlTranscriptArchiveConfig {
+    pub(super) kind: String,
+    pub(super) scope: Option<String>,
+    pub(super) channel_turns: Option<bool>,
+    pub(super) path: Option<String>,
+    pub(super) url: Option<String>,
+    pub(super) auth_token: Option<String>,
+    pub(super) endpoint: Option<String>,
+    pub(super) bucket: Option<String>,
+    pub(super) region: Option<String>,
+    pub(super) prefix: Option<String>,
+    pub(super) access_key_id: Option<String>,
+    pub(super) secret_access_key: Option<String>,
+    pub(super) timeout_secs: Option<u64>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlIngestionConfig {
     pub(super) enabled: Option<bool>,
@@ -342,6 +551,24 @@ pub(super) struct TomlCompactionConfig {
     pub(super) background_threshold: Option<f32>,
     pub(super) aggressive_threshold: Option<f32>,
     pub(super) emergency_threshold: Option<f32>,
+    pub(super) max_recap_bytes: Option<usize>,
+    pub(super) compactor_fallback: Option<String>,
+    pub(super) tool_call_repair: Option<String>,
+    pub(super) max_message_fraction: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlBulletinHistoryConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) dir: Option<String>,
+    pub(super) retention: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlDebugMirrorConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) log_path: Option<String>,
+    pub(super) target: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -355,6 +582,8 @@ pub(super) struct TomlCortexConfig {
     pub(super) bulletin_interval_secs: Option<u64>,
     pub(super) bulletin_max_words: Option<usize>,
     pub(super) bulletin_max_turns: Option<usize>,
+    pub(super) bulletin_gather_concurrency: Option<usize>,
+    pub(super) bulletin_gather_timeout_secs: Option<u64>,
     pub(super) maintenance_interval_secs: Option<u64>,
     pub(super) maintenance_decay_rate: Option<f32>,
     pub(super) maintenance_prune_threshold: Option<f32>,
@@ -385,10 +614,39 @@ pub(super) struct TomlBrowserConfig {
     pub(super) close_policy: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlWebSearchConfig {
+    pub(super) backend: Option<String>,
+    pub(super) searxng_url: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlChannelConfig {
     pub(super) listen_only_mode: Option<bool>,
+    pub(super) listen_only_triggers: Option<TomlListenOnlyTriggers>,
     pub(super) save_attachments: Option<bool>,
+    pub(super) worker_wait_timeout_secs: Option<u64>,
+    pub(super) status_refresh: Option<String>,
+    pub(super) user_content_escaping: Option<String>,
+    pub(super) language_detection: Option<TomlLanguageDetectionConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlListenOnlyTriggers {
+    pub(super) mention: Option<bool>,
+    pub(super) reply: Option<bool>,
+    pub(super) command: Option<bool>,
+    pub(super) keywords: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlLanguageDetectionConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) detector: Option<String>,
+    pub(super) default_language: Option<String>,
+    pub(super) confidence_threshold: Option<f32>,
+    pub(super) window: Option<usize>,
+    pub(super) min_chars: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -456,14 +714,20 @@ pub(super) struct TomlAgentConfig {
     pub(super) context_window: Option<usize>,
     pub(super) compaction: Option<TomlCompactionConfig>,
     pub(super) memory_persistence: Option<TomlMemoryPersistenceConfig>,
+    pub(super) identity_reinforcement: Option<TomlIdentityReinforcementConfig>,
+    pub(super) output_limits: Option<TomlOutputLimitsConfig>,
     pub(super) coalesce: Option<TomlCoalesceConfig>,
+    pub(super) worker: Option<TomlWorkerConfig>,
     pub(super) ingestion: Option<TomlIngestionConfig>,
     pub(super) cortex: Option<TomlCortexConfig>,
+    pub(super) bulletin_history: Option<TomlBulletinHistoryConfig>,
+    pub(super) debug_mirror: Option<TomlDebugMirrorConfig>,
     pub(super) warmup: Option<TomlWarmupConfig>,
     pub(super) browser: Option<TomlBrowserConfig>,
     pub(super) channel: Option<TomlChannelConfig>,
     pub(super) mcp: Option<Vec<TomlMcpServerConfig>>,
     pub(super) brave_search_key: Option<String>,
+    pub(super) web_search: Option<TomlWebSearchConfig>,
     pub(super) cron_timezone: Option<String>,
     pub(super) user_timezone: Option<String>,
     pub(super) sandbox: Option<crate::sandbox::SandboxConfig>,
@@ -659,6 +923,9 @@ pub(super) struct TomlWebhookConfig {
     #[serde(default = "default_webhook_bind")]
     pub(super) bind: String,
     pub(super) auth_token: Option<String>,
+    pub(super) signing_secret: Option<String>,
+    pub(super) signature_header: Option<String>,
+    pub(super) signature_scheme: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -59,6 +59,15 @@ pub struct Config {
     pub metrics: MetricsConfig,
     /// OpenTelemetry export configuration.
     pub telemetry: TelemetryConfig,
+    /// Idle shutdown / hibernation.
+    pub idle: IdleConfig,
+    /// Secret redaction on outbound messages.
+    pub redaction: RedactionConfig,
+    /// Post-processing applied to outbound message text.
+    pub outbound: OutboundConfig,
+    /// Prompt template sources keyed by template name, replacing the
+    /// embedded templates of the same name.
+    pub prompt_overrides: HashMap<String, String>,
 }
 
 impl Config {
@@ -172,6 +181,164 @@ impl Default for MetricsConfig {
     }
 }
 
+/// Idle shutdown configuration.
+///
+/// When nothing has been received or sent for `timeout_secs` and no worker is
+/// running, the bot either exits through the normal graceful shutdown or
+/// disconnects its messaging adapters until the next activity.
+#[derive(Debug, Clone)]
+pub struct IdleConfig {
+    /// Seconds of inactivity before acting. 0 disables idle tracking.
+    pub timeout_secs: u64,
+    pub action: IdleAction,
+    /// Adapters (by name or platform) left connected while hibernating, so
+    /// an inbound message on them wakes the bot.
+    pub keep_connected: Vec<String>,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 0,
+            action: IdleAction::default(),
+            keep_connected: vec!["webhook".into(), "webchat".into()],
+        }
+    }
+}
+
+/// What happens when the idle timeout elapses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdleAction {
+    /// Disconnect messaging adapters and reconnect on the next inbound
+    /// message or outbound send (e.g. a cron delivery).
+    #[default]
+    Hibernate,
+    /// Exit the process through the same graceful shutdown as a stop signal.
+    Shutdown,
+}
+
+/// Outbound redaction configuration.
+///
+/// Applied by the messaging layer to everything sent to a chat platform, as
+/// a last line of defense after the per-path scrubbing of worker results and
+/// branch conclusions. Uses the same API key and private key patterns as
+/// leak detection.
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    /// Scrub known secret formats from outbound messages.
+    pub enabled: bool,
+    /// Extra regexes to scrub, in addition to the built-in patterns.
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// A named transform in the outbound post-processing pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboundStage {
+    /// Trim surrounding whitespace and collapse runs of blank lines.
+    Trim,
+    /// Normalize list markers to `-` and close an unterminated code fence.
+    NormalizeMarkdown,
+    /// Apply the configured `link_rewrites`.
+    RewriteLinks,
+    /// Reduce markdown to plain text, for platforms that don't render it.
+    StripMarkdown,
+    /// Format fenced code for the target platform: keep language hints where
+    /// the platform renders them, drop them where it doesn't, and indent code
+    /// for the platforms in `indent_code`.
+    CodeBlocks,
+}
+
+/// Ordered post-processing of outbound message text. Secret redaction is
+/// configured by [`RedactionConfig`] and always runs after these stages.
+///
+/// Runs in the messaging layer before a response reaches an adapter, so it
+/// covers channel replies, worker results relayed by the channel, and
+/// proactive sends alike. Adapter-specific markup conversion (Telegram HTML,
+/// Slack mrkdwn) still happens inside the adapter, after the pipeline.
+#[derive(Debug, Clone)]
+pub struct OutboundConfig {
+    /// Stages applied to every adapter without its own pipeline, in order.
+    pub pipeline: Vec<OutboundStage>,
+    /// Pipelines for specific adapters, keyed by platform (`"email"`) or
+    /// named instance (`"email:support"`). A named instance falls back to
+    /// its platform's pipeline, then to `pipeline`.
+    pub adapter_pipelines: HashMap<String, Vec<OutboundStage>>,
+    /// `(from, to)` replacements applied by [`OutboundStage::RewriteLinks`].
+    pub link_rewrites: Vec<(String, String)>,
+    /// Platforms or named adapters where [`OutboundStage::CodeBlocks`] turns
+    /// fenced code into indented blocks, for targets that show raw text.
+    pub indent_code: Vec<String>,
+    /// Send budgets keyed by platform or named instance, with the same
+    /// fallback as `adapter_pipelines`. Adapters without one are unlimited.
+    pub rate_limits: HashMap<String, OutboundRateLimit>,
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        Self {
+            pipeline: Vec::new(),
+            adapter_pipelines: HashMap::new(),
+            link_rewrites: Vec::new(),
+            indent_code: Vec::new(),
+            rate_limits: OutboundRateLimit::platform_defaults(),
+        }
+    }
+}
+
+/// How many messages an adapter may send per window before further sends
+/// wait their turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboundRateLimit {
+    /// Messages allowed per window (0 = unlimited).
+    pub messages: u32,
+    /// Window length in seconds.
+    pub per_secs: u64,
+    /// Budget each channel separately instead of sharing one across the
+    /// adapter.
+    pub per_channel: bool,
+}
+
+impl OutboundRateLimit {
+    /// Limits that keep clear of each platform's documented send limits.
+    pub fn platform_defaults() -> HashMap<String, Self> {
+        HashMap::from([
+            (
+                "telegram".to_string(),
+                Self {
+                    messages: 30,
+                    per_secs: 1,
+                    per_channel: false,
+                },
+            ),
+            (
+                "discord".to_string(),
+                Self {
+                    messages: 5,
+                    per_secs: 5,
+                    per_channel: true,
+                },
+            ),
+            (
+                "slack".to_string(),
+                Self {
+                    messages: 1,
+                    per_secs: 1,
+                    per_channel: true,
+                },
+            ),
+        ])
+    }
+}
+
 /// API types supported by LLM providers.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiType {
@@ -530,15 +697,22 @@ pub struct DefaultsConfig {
     pub context_window: usize,
     pub compaction: CompactionConfig,
     pub memory_persistence: MemoryPersistenceConfig,
+    pub identity_reinforcement: IdentityReinforcementConfig,
+    pub output_limits: OutputLimitsConfig,
     pub coalesce: CoalesceConfig,
+    pub worker: WorkerConfig,
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
+    pub bulletin_history: BulletinHistoryConfig,
+    pub debug_mirror: DebugMirrorConfig,
     pub warmup: WarmupConfig,
     pub browser: BrowserConfig,
     pub channel: ChannelConfig,
     pub mcp: Vec<McpServerConfig>,
     /// Brave Search API key for web search tool. Supports "env:VAR_NAME" references.
     pub brave_search_key: Option<String>,
+    /// Which search backend the web search tool uses.
+    pub web_search: WebSearchConfig,
     /// Default timezone used when evaluating cron active hours.
     pub cron_timezone: Option<String>,
     /// Default timezone for channel/worker temporal context.
@@ -563,9 +737,14 @@ impl std::fmt::Debug for DefaultsConfig {
             .field("context_window", &self.context_window)
             .field("compaction", &self.compaction)
             .field("memory_persistence", &self.memory_persistence)
+            .field("identity_reinforcement", &self.identity_reinforcement)
+            .field("output_limits", &self.output_limits)
             .field("coalesce", &self.coalesce)
+            .field("worker", &self.worker)
             .field("ingestion", &self.ingestion)
             .field("cortex", &self.cortex)
+            .field("bulletin_history", &self.bulletin_history)
+            .field("debug_mirror", &self.debug_mirror)
             .field("warmup", &self.warmup)
             .field("browser", &self.browser)
             .field("channel", &self.channel)
@@ -574,6 +753,7 @@ impl std::fmt::Debug for DefaultsConfig {
                 "brave_search_key",
                 &self.brave_search_key.as_ref().map(|_| "[REDACTED]"),
             )
+            .field("web_search", &self.web_search)
             .field("cron_timezone", &self.cron_timezone)
             .field("user_timezone", &self.user_timezone)
             .field("history_backfill_count", &self.history_backfill_count)
@@ -636,6 +816,45 @@ pub struct CompactionConfig {
     pub background_threshold: f32,
     pub aggressive_threshold: f32,
     pub emergency_threshold: f32,
+    /// Upper bound on the tool-call recap written when a worker compacts its
+    /// history. The oldest actions are elided first once the recap exceeds it.
+    pub max_recap_bytes: usize,
+    /// What replaces the compacted messages when the compactor model fails.
+    pub compactor_fallback: CompactorFallback,
+    /// How duplicate tool-call IDs are repaired before each prompt and after
+    /// compaction.
+    pub tool_call_repair: ToolCallRepair,
+    /// Largest share of the context window a single message may take during
+    /// overflow recovery. Bigger messages are truncated in place, since
+    /// dropping other messages can't make room for them.
+    pub max_message_fraction: f32,
+}
+
+/// Summary used when channel compaction can't reach the compactor model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompactorFallback {
+    /// Build the same programmatic recap workers use: tool calls, their
+    /// results, and assistant notes from the removed messages.
+    #[default]
+    Recap,
+    /// Insert a bare marker noting how many messages were removed.
+    Marker,
+}
+
+/// Repair applied to tool-call IDs in history that would otherwise fail
+/// call/result correlation.
+///
+/// Orphans (a call with no result, or a result with no call) are dropped in
+/// every mode except `Off`; the modes differ in how duplicate IDs are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToolCallRepair {
+    /// Give each repeated call a fresh ID and re-point its result at it.
+    #[default]
+    Regenerate,
+    /// Drop repeated calls along with their results, keeping the first.
+    Dedup,
+    /// Leave history untouched.
+    Off,
 }
 
 /// Auto-branching memory persistence configuration.
@@ -649,6 +868,10 @@ pub struct MemoryPersistenceConfig {
     pub enabled: bool,
     /// Number of user messages between automatic memory persistence branches.
     pub message_interval: usize,
+    /// Also run a persistence branch right before channel compaction drops
+    /// or summarizes history, so facts are saved while they are still in
+    /// context.
+    pub before_compaction: bool,
 }
 
 impl Default for MemoryPersistenceConfig {
@@ -656,16 +879,103 @@ impl Default for MemoryPersistenceConfig {
         Self {
             enabled: true,
             message_interval: 50,
+            before_compaction: false,
+        }
+    }
+}
+
+/// Periodic identity reinforcement for long channel conversations.
+///
+/// As history grows, the system prompt carries less relative weight and the
+/// model can drift from its configured persona. When enabled, a condensed
+/// identity reminder is appended to channel history on a turn cadence and
+/// once each time context usage crosses the threshold.
+#[derive(Debug, Clone, Copy)]
+pub struct IdentityReinforcementConfig {
+    /// Whether identity reminders are injected.
+    pub enabled: bool,
+    /// Number of channel turns between reminders (0 disables the cadence).
+    pub turn_interval: usize,
+    /// Context usage fraction (0.0-1.0) that triggers a reminder (0.0 disables).
+    pub usage_threshold: f32,
+}
+
+impl Default for IdentityReinforcementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            turn_interval: 25,
+            usage_threshold: 0.6,
         }
     }
 }
 
+/// Per-process caps on how much the model says in one turn, independent of
+/// platform message limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputLimitsConfig {
+    /// Caps on each channel reply.
+    pub channel: OutputLimit,
+    /// Caps on a branch's conclusion.
+    pub branch: OutputLimit,
+    /// Caps on a worker's final result.
+    pub worker: OutputLimit,
+}
+
+impl OutputLimitsConfig {
+    /// The limit for `process_type`. Compactors and the cortex are never
+    /// capped.
+    pub fn for_process(&self, process_type: crate::ProcessType) -> OutputLimit {
+        match process_type {
+            crate::ProcessType::Channel => self.channel,
+            crate::ProcessType::Branch => self.branch,
+            crate::ProcessType::Worker => self.worker,
+            crate::ProcessType::Compactor | crate::ProcessType::Cortex => OutputLimit::default(),
+        }
+    }
+}
+
+/// How much one process may say in a single turn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputLimit {
+    /// Soft cap, sent as the max output tokens of each completion request.
+    /// It also bounds tool-call arguments, so keep it generous. 0 leaves the
+    /// provider default.
+    pub max_output_tokens: u64,
+    /// Hard cap in characters on the finished answer. 0 disables.
+    pub max_chars: usize,
+    /// What to do with an answer over `max_chars`.
+    pub on_exceed: OutputOverflow,
+}
+
+impl OutputLimit {
+    /// Whether `text` is over the hard cap.
+    pub fn exceeded_by(&self, text: &str) -> bool {
+        self.max_chars > 0 && text.chars().count() > self.max_chars
+    }
+}
+
+/// What happens to an answer over its `max_chars` cap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputOverflow {
+    /// Cut it at the last sentence boundary that fits.
+    #[default]
+    Truncate,
+    /// Ask the model once for a shorter answer, then truncate if it is still
+    /// too long.
+    Reprompt,
+}
+
 impl Default for CompactionConfig {
     fn default() -> Self {
         Self {
             background_threshold: 0.80,
             aggressive_threshold: 0.85,
             emergency_threshold: 0.95,
+            max_recap_bytes: 6_000,
+            compactor_fallback: CompactorFallback::default(),
+            tool_call_repair: ToolCallRepair::default(),
+            max_message_fraction: 0.5,
         }
     }
 }
@@ -701,6 +1011,567 @@ impl Default for CoalesceConfig {
     }
 }
 
+/// Worker execution limits.
+///
+/// Bounds how long a worker may keep running when the LLM never converges on
+/// a final answer. Each segment is a fixed number of tool-call turns followed
+/// by a context check.
+#[derive(Debug, Clone)]
+pub struct WorkerConfig {
+    /// Maximum segments a worker may run before it is failed with
+    /// "max segments reached".
+    pub max_segments: usize,
+    /// Consecutive segments that exhaust their turn budget without a final
+    /// answer before the worker is told to wrap up. A segment in which the
+    /// worker raises its progress with `set_progress` restarts the count, so
+    /// a worker that keeps reporting progress runs until `max_segments`.
+    /// 0 disables the check.
+    pub max_unfinished_segments: usize,
+    /// Turn budget for the wrap-up segment. A worker that still has no
+    /// final answer after it is failed.
+    pub wrap_up_turns: usize,
+    /// Wall-clock limit on a worker's run, in seconds; each interactive
+    /// follow-up gets a fresh budget. Checked between segments, so an LLM
+    /// call in flight gets to finish. 0 disables the limit.
+    pub max_duration_secs: u64,
+    /// How long an interactive worker waits for a follow-up before it
+    /// finishes on its own with status "idle timeout". Resets after every
+    /// follow-up. 0 waits forever.
+    pub idle_timeout_secs: u64,
+    /// Run a short capability check before the task, where the worker
+    /// confirms it understands the task and has the tools it needs. A
+    /// reported blocker fails the worker before the real work begins.
+    pub preflight_check: bool,
+    /// Turn budget for the capability check.
+    pub preflight_turns: usize,
+    /// How many levels of nested worker spawns are allowed. A worker spawned
+    /// directly by a channel or the cortex is at depth 1; spawns that would go
+    /// past this depth are refused.
+    pub max_spawn_depth: usize,
+    /// Longest task description, in characters, placed inline in a worker's
+    /// initial prompt.
+    pub max_task_chars: usize,
+    /// What to do with a task longer than `max_task_chars`.
+    pub task_overflow: TaskOverflowPolicy,
+    /// Let channel-spawned workers pause and ask the user a clarifying
+    /// question when their task is underspecified.
+    pub allow_clarifications: bool,
+    /// How long a worker waits for an answer before continuing on its own
+    /// best assumption.
+    pub clarification_timeout_secs: u64,
+    /// Whether workers compact their history as it fills the context window.
+    /// When false, a context overflow fails the worker immediately.
+    pub compaction_enabled: bool,
+    /// How compacted-away history is condensed into the marker message.
+    pub compaction_strategy: CompactionStrategy,
+    /// Relay a notice with a summary of completed work to the channel when a
+    /// worker is cancelled, instead of a bare "Worker cancelled" result.
+    pub cancellation_notice: bool,
+    /// Let the channel give individual builtin workers a read-only
+    /// `memory_recall` tool by spawning them with `memory_recall: true`.
+    /// Workers never get memory access unless both are set.
+    pub memory_recall: bool,
+    /// Infer which optional tools (shell, browser, web search) a worker gets
+    /// from its task text when the spawn request doesn't name them.
+    pub tool_gating: bool,
+    /// On a not-found error, file tools list similarly named entries from the
+    /// same directory and the shell tool lists similar executables on `PATH`.
+    pub path_suggestions: bool,
+    /// Most suggestions included in a single not-found error.
+    pub max_path_suggestions: usize,
+    /// How an interactive worker handles follow-ups that arrive while it is
+    /// still busy with an earlier one.
+    pub follow_up_mode: FollowUpMode,
+    /// In [`FollowUpMode::Coalesce`], how long the worker waits after a
+    /// follow-up for more to arrive before starting the turn. 0 (the
+    /// default) starts right away with whatever is already queued.
+    pub follow_up_debounce_ms: u64,
+    /// Give each worker its own working directory, named by worker ID under
+    /// `workdir_base`. Shell commands start there and file tools cannot
+    /// leave it, so concurrent workers don't clobber each other's files.
+    pub isolated_workdir: bool,
+    /// Parent of the per-worker working directories. Relative paths resolve
+    /// from the agent workspace.
+    pub workdir_base: PathBuf,
+    /// Keep a worker's working directory after it finishes instead of
+    /// deleting it.
+    pub retain_workdir: bool,
+    /// Write a worker's live history to `logs_dir/checkpoints` after each
+    /// segment so [`Worker::resume`](crate::agent::worker::Worker::resume)
+    /// can pick up after a crash or restart.
+    pub checkpoint_history: bool,
+    /// Tools whose results are elided from history once the model has
+    /// responded to them. The call itself stays visible; only the bulky
+    /// output is replaced with a placeholder.
+    pub ephemeral_tools: Vec<String>,
+    /// Per-tool detail level in the recap written when worker history is
+    /// compacted. Tools not listed use [`RecapPriority::Normal`].
+    pub recap_tools: HashMap<String, RecapPriority>,
+    /// Per-tool retry of transient failures, keyed by tool name. Tools not
+    /// listed return their first failure to the model.
+    pub tool_retries: HashMap<String, ToolRetryPolicy>,
+    /// Per-tool formatting of results before they enter history, keyed by
+    /// tool name. Tools not listed use [`ToolResultFormat::Raw`].
+    pub tool_result_formats: HashMap<String, ToolResultFormat>,
+    /// File formats written for each failure log.
+    pub failure_log_format: LogFormat,
+    /// Optional remote destination that also receives failure logs.
+    pub failure_log_sink: Option<FailureLogSinkConfig>,
+    /// Optional archive that receives structured transcripts.
+    pub transcript_archive: Option<TranscriptArchiveConfig>,
+    /// Record every written worker log in a per-run manifest file
+    /// (`manifest_<run>.jsonl`) in the logs directory.
+    pub log_manifest: bool,
+    /// Retries of a segment that failed with a transient provider error
+    /// (rate limits, 5xx, timeouts, dropped connections) before the worker
+    /// gives up. Errors that aren't transient still fail immediately.
+    pub transient_retries: usize,
+    /// Base backoff before a transient retry, doubled on each retry and
+    /// capped at a minute. A `Retry-After` hint of up to 30 seconds takes
+    /// precedence.
+    pub transient_backoff_secs: u64,
+    /// Context overflow recoveries (prune stale tool results, force-compact,
+    /// retry) before the worker fails as unrecoverable. Models with small
+    /// context windows may need more; 0 fails on the first overflow.
+    pub overflow_retries: usize,
+    /// Retries of a segment that failed because the provider was overloaded
+    /// (503/529), counted separately from other transient errors.
+    pub overloaded_retries: usize,
+    /// Base backoff before retrying an overloaded provider, doubled on each
+    /// retry and capped at a minute. A `Retry-After` hint of up to 30
+    /// seconds takes precedence.
+    pub overloaded_backoff_secs: u64,
+    /// Results longer than this many characters are condensed by the
+    /// compactor model before they reach the channel. 0 disables.
+    pub result_summary_threshold_chars: usize,
+    /// Length the condensed result aims for, in characters.
+    pub result_summary_target_chars: usize,
+    /// Save the full text of a condensed result under `saved/` and point to
+    /// it from the summary.
+    pub attach_full_result: bool,
+    /// Limits and host policy for the `http_request` tool.
+    pub http_request: HttpRequestConfig,
+    /// Which tools workers may have at all. Spawns can narrow this further
+    /// but never widen it.
+    pub tool_permissions: crate::tools::ToolPermissions,
+    /// Programs the `shell` tool may run.
+    pub command_policy: CommandPolicyConfig,
+}
+
+impl WorkerConfig {
+    /// The isolated working directory for `worker_id`, or `None` when
+    /// workers share the workspace.
+    pub fn workdir_for(&self, workspace: &Path, worker_id: uuid::Uuid) -> Option<PathBuf> {
+        self.isolated_workdir.then(|| {
+            workspace
+                .join(&self.workdir_base)
+                .join(worker_id.to_string())
+        })
+    }
+
+    /// How many not-found suggestions tools should include, zero when
+    /// suggestions are disabled.
+    pub fn path_suggestion_limit(&self) -> usize {
+        if self.path_suggestions {
+            self.max_path_suggestions
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            max_segments: 50,
+            max_unfinished_segments: 10,
+            wrap_up_turns: 3,
+            max_duration_secs: 0,
+            idle_timeout_secs: 0,
+            preflight_check: false,
+            preflight_turns: 2,
+            max_spawn_depth: 3,
+            max_task_chars: 20_000,
+            task_overflow: TaskOverflowPolicy::default(),
+            allow_clarifications: false,
+            clarification_timeout_secs: 1800,
+            compaction_enabled: true,
+            compaction_strategy: CompactionStrategy::default(),
+            cancellation_notice: true,
+            memory_recall: false,
+            tool_gating: false,
+            path_suggestions: true,
+            max_path_suggestions: 3,
+            follow_up_mode: FollowUpMode::default(),
+            follow_up_debounce_ms: 0,
+            isolated_workdir: false,
+            workdir_base: PathBuf::from("workers"),
+            retain_workdir: false,
+            checkpoint_history: false,
+            ephemeral_tools: Vec::new(),
+            recap_tools: HashMap::from([
+                ("set_status".to_string(), RecapPriority::Omit),
+                ("get_status".to_string(), RecapPriority::Omit),
+                ("set_progress".to_string(), RecapPriority::Omit),
+                ("file_write".to_string(), RecapPriority::High),
+                ("file_edit".to_string(), RecapPriority::High),
+                ("shell".to_string(), RecapPriority::High),
+            ]),
+            // Read-only network and browser calls are safe to repeat. Shell
+            // and input tools are not, since a retry repeats side effects.
+            tool_retries: [
+                "browser_launch",
+                "browser_navigate",
+                "browser_snapshot",
+                "browser_screenshot",
+                "web_search",
+            ]
+            .into_iter()
+            .map(|tool| (tool.to_string(), ToolRetryPolicy::default()))
+            .collect(),
+            tool_result_formats: HashMap::new(),
+            failure_log_format: LogFormat::default(),
+            failure_log_sink: None,
+            transcript_archive: None,
+            log_manifest: false,
+            transient_retries: 5,
+            transient_backoff_secs: 5,
+            overflow_retries: 2,
+            overloaded_retries: 5,
+            overloaded_backoff_secs: 10,
+            result_summary_threshold_chars: 0,
+            result_summary_target_chars: 1500,
+            attach_full_result: true,
+            http_request: HttpRequestConfig::default(),
+            tool_permissions: crate::tools::ToolPermissions::ALL,
+            command_policy: CommandPolicyConfig::default(),
+        }
+    }
+}
+
+/// The worker `http_request` tool.
+///
+/// Host patterns match case-insensitively, with `*` as a wildcard
+/// (`*.example.com`). The deny list wins over the allow list.
+#[derive(Debug, Clone)]
+pub struct HttpRequestConfig {
+    /// Whether builtin workers get the tool.
+    pub enabled: bool,
+    /// Limit on each request, connection through the last body byte.
+    pub timeout_secs: u64,
+    /// Response bodies are cut off after this many bytes.
+    pub max_response_bytes: usize,
+    /// Hosts the tool may contact. Empty allows every host not denied.
+    pub allowed_hosts: Vec<String>,
+    /// Hosts the tool may never contact.
+    pub denied_hosts: Vec<String>,
+    /// Allow loopback, private, link-local and other non-public addresses.
+    /// Off by default so a worker can't reach internal services or cloud
+    /// metadata endpoints.
+    pub allow_private_networks: bool,
+}
+
+impl Default for HttpRequestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: 30,
+            max_response_bytes: 256 * 1024,
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            allow_private_networks: false,
+        }
+    }
+}
+
+/// Allow and deny lists for the programs a worker's `shell` tool runs.
+///
+/// A rule is a program pattern with optional argument patterns after it
+/// (`git push *--force*`), each matching one argument, case-insensitive with
+/// `*` as a wildcard and `|` between alternatives. The deny list wins over
+/// the allow list. See `crate::tools::command_policy`.
+#[derive(Debug, Clone)]
+pub struct CommandPolicyConfig {
+    /// Programs the shell may run. Empty allows every program not denied.
+    pub allowed: Vec<String>,
+    /// Programs the shell may never run.
+    pub denied: Vec<String>,
+}
+
+impl Default for CommandPolicyConfig {
+    fn default() -> Self {
+        Self {
+            allowed: Vec::new(),
+            denied: [
+                // A recursive delete of the root or the home directory.
+                // Deletes below them, like a worker's own workdir, run.
+                "rm -r|--recursive /|/\\*|~|~/|$home|$home/",
+                "rm *--no-preserve-root*",
+                "mkfs*",
+                "dd *of=/dev/*",
+                "shutdown",
+                "reboot",
+                "halt",
+                "poweroff",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
+
+/// HTTP endpoint that receives worker failure logs as JSON.
+///
+/// Delivery happens alongside the local log write and is best-effort, so
+/// failures stay inspectable on hosts whose log directory doesn't outlive the
+/// container.
+#[derive(Clone)]
+pub struct FailureLogSinkConfig {
+    /// Endpoint the log is POSTed to.
+    pub url: String,
+    /// Sent as a bearer token when set. Supports `env:` and `secret:` references.
+    pub auth_token: Option<String>,
+    /// Request timeout. Kept short because delivery is never retried.
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for FailureLogSinkConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailureLogSinkConfig")
+            .field("url", &self.url)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
+/// Archiving of structured worker (and optionally channel) transcripts.
+///
+/// Unlike the failure log sink, which carries a rendered text log of failed
+/// workers, this hands the full transcript steps and metadata to a
+/// [`TranscriptSink`](crate::conversation::transcript_sink::TranscriptSink)
+/// for long-term storage. Delivery is best-effort and runs in the background.
+#[derive(Debug, Clone)]
+pub struct TranscriptArchiveConfig {
+    pub scope: TranscriptArchiveScope,
+    /// Also archive every channel turn, not just worker runs.
+    pub channel_turns: bool,
+    pub backend: TranscriptSinkBackend,
+}
+
+/// Which outcomes are archived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptArchiveScope {
+    /// Only failed or cancelled runs.
+    Failures,
+    /// Every run, whatever the outcome.
+    #[default]
+    All,
+}
+
+/// Where archived transcripts go.
+#[derive(Clone)]
+pub enum TranscriptSinkBackend {
+    /// One JSON record per line, appended to a local file.
+    Jsonl { path: PathBuf },
+    /// Each record POSTed as JSON.
+    Http {
+        url: String,
+        /// Sent as a bearer token when set.
+        auth_token: Option<String>,
+        timeout_secs: u64,
+    },
+    /// Each record PUT as an object in an S3-compatible bucket, signed
+    /// with AWS Signature Version 4.
+    S3 {
+        /// Base URL of the service, e.g. `https://s3.us-east-1.amazonaws.com`
+        /// or a MinIO endpoint. Requests use path-style addressing.
+        endpoint: String,
+        bucket: String,
+        region: String,
+        /// Key prefix, e.g. `spacebot/transcripts/`.
+        prefix: String,
+        access_key_id: String,
+        secret_access_key: String,
+        timeout_secs: u64,
+    },
+}
+
+impl std::fmt::Debug for TranscriptSinkBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Jsonl { path } => f.debug_struct("Jsonl").field("path", path).finish(),
+            Self::Http {
+                url,
+                auth_token,
+                timeout_secs,
+            } => f
+                .debug_struct("Http")
+                .field("url", url)
+                .field("auth_token", &auth_token.as_ref().map(|_| "[REDACTED]"))
+                .field("timeout_secs", timeout_secs)
+                .finish(),
+            Self::S3 {
+                endpoint,
+                bucket,
+                region,
+                prefix,
+                timeout_secs,
+                ..
+            } => f
+                .debug_struct("S3")
+                .field("endpoint", endpoint)
+                .field("bucket", bucket)
+                .field("region", region)
+                .field("prefix", prefix)
+                .field("access_key_id", &"[REDACTED]")
+                .field("secret_access_key", &"[REDACTED]")
+                .field("timeout_secs", timeout_secs)
+                .finish(),
+        }
+    }
+}
+
+/// Handling for worker tasks that exceed the configured character budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskOverflowPolicy {
+    /// Refuse to spawn the worker.
+    Reject,
+    /// Save the full task to a reference file in the workspace and give the
+    /// worker a truncated task that points at it.
+    #[default]
+    Attach,
+}
+
+/// Handling for follow-up input queued on a busy interactive worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FollowUpMode {
+    /// Merge every follow-up waiting at the start of a turn, including any
+    /// that arrive during the debounce window or while history is being
+    /// compacted, into a single prompt.
+    #[default]
+    Coalesce,
+    /// Process follow-ups one turn each, in arrival order, and report how
+    /// many are still queued in the worker status.
+    Queue,
+}
+
+/// File formats of a worker failure log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// The human-readable `.log` file.
+    #[default]
+    Text,
+    /// A `.json` file with the same content as structured fields, for
+    /// loading into dashboards.
+    Json,
+    /// Both files, side by side under the same name.
+    Both,
+}
+
+impl LogFormat {
+    pub fn writes_text(self) -> bool {
+        matches!(self, Self::Text | Self::Both)
+    }
+
+    pub fn writes_json(self) -> bool {
+        matches!(self, Self::Json | Self::Both)
+    }
+}
+
+/// How a worker condenses the messages it compacts away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompactionStrategy {
+    /// A programmatic recap of the removed tool calls and results. No LLM
+    /// call, but intermediate conclusions only survive as truncated text.
+    #[default]
+    Recap,
+    /// A summary written by the compactor model. Falls back to the recap
+    /// when the call fails, times out or comes back blank.
+    Llm,
+}
+
+/// How much of a tool's activity survives into the compaction recap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecapPriority {
+    /// Leave the tool's calls and results out of the recap entirely.
+    Omit,
+    /// Include calls and results, truncated to a short preview.
+    #[default]
+    Normal,
+    /// Include calls and results with a much larger preview.
+    High,
+}
+
+impl RecapPriority {
+    /// Max bytes kept from a call's arguments or a tool result.
+    pub fn preview_bytes(self) -> usize {
+        match self {
+            Self::Omit => 0,
+            Self::Normal => 200,
+            Self::High => 1000,
+        }
+    }
+}
+
+/// How the tool server retries a failing tool call before handing the
+/// failure to the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolRetryPolicy {
+    /// Retries after the first attempt. Zero disables retrying.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after.
+    pub backoff_ms: u64,
+    /// Failure kinds worth retrying. Anything else fails immediately.
+    pub retry_on: Vec<ToolErrorKind>,
+}
+
+impl Default for ToolRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            backoff_ms: 500,
+            retry_on: vec![
+                ToolErrorKind::Timeout,
+                ToolErrorKind::Network,
+                ToolErrorKind::RateLimited,
+                ToolErrorKind::Server,
+            ],
+        }
+    }
+}
+
+/// Transient failure classes a [`ToolRetryPolicy`] can retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolErrorKind {
+    /// The operation or request timed out.
+    Timeout,
+    /// Connection, DNS, or transport failure.
+    Network,
+    /// The remote service rate limited the request.
+    RateLimited,
+    /// The remote service returned a 5xx error.
+    Server,
+}
+
+/// How a tool's result is rendered for the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToolResultFormat {
+    /// The tool's output as compact JSON, unchanged.
+    #[default]
+    Raw,
+    /// Indented JSON.
+    JsonPretty,
+    /// A markdown table, for results that are a list of records.
+    Table,
+    /// One `key: value` line per field, nested fields as dotted paths.
+    KeyValue,
+}
+
 /// File-based memory ingestion configuration.
 ///
 /// Watches a directory in the agent workspace for text files, chunks them, and
@@ -795,15 +1666,148 @@ impl Default for BrowserConfig {
     }
 }
 
+/// The service behind the `web_search` tool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchBackendKind {
+    /// Brave Search API, authenticated with `brave_search_key`.
+    #[default]
+    Brave,
+    /// A SearXNG instance's JSON API at `searxng_url`.
+    Searxng,
+}
+
+/// Web search backend selection.
+#[derive(Debug, Clone, Default)]
+pub struct WebSearchConfig {
+    pub backend: SearchBackendKind,
+    /// Base URL of the SearXNG instance, e.g. `https://search.example.com`.
+    pub searxng_url: Option<String>,
+}
+
 /// Channel behavior configuration.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone)]
 pub struct ChannelConfig {
-    /// When true, unsolicited chat messages are ignored unless command/mention/reply.
+    /// When true, the channel observes: unsolicited chat messages are added to
+    /// history and feed memory persistence, but only messages matching
+    /// `listen_only_triggers` get a reply.
     pub listen_only_mode: bool,
+    /// What counts as addressing the bot while `listen_only_mode` is on.
+    pub listen_only_triggers: ListenOnlyTriggers,
     /// When true, file attachments received in the channel are saved to
     /// `workspace/saved/` and tracked in the `saved_attachments` table so
     /// they can be recalled on later turns.
     pub save_attachments: bool,
+    /// Longest the channel holds user messages for a worker spawned with
+    /// `wait: true` before giving up and processing them anyway.
+    pub worker_wait_timeout_secs: u64,
+    /// When the status block shown to the channel model is refreshed.
+    pub status_refresh: StatusRefresh,
+    /// How user message text and sender names are neutralized before they
+    /// are embedded in the channel history.
+    pub user_content_escaping: UserContentEscaping,
+    /// Sticky detection of the language the channel is conversing in.
+    pub language_detection: LanguageDetectionConfig,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            listen_only_mode: false,
+            listen_only_triggers: ListenOnlyTriggers::default(),
+            save_attachments: false,
+            worker_wait_timeout_secs: 600,
+            status_refresh: StatusRefresh::default(),
+            user_content_escaping: UserContentEscaping::default(),
+            language_detection: LanguageDetectionConfig::default(),
+        }
+    }
+}
+
+/// Messages that invoke the bot in listen-only mode. Anything else is
+/// observed without a reply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListenOnlyTriggers {
+    /// An @mention of the bot.
+    pub mention: bool,
+    /// A reply to one of the bot's messages.
+    pub reply: bool,
+    /// A message starting with `/`.
+    pub command: bool,
+    /// Words or phrases that invoke the bot when they appear in a message,
+    /// matched case-insensitively as whole words.
+    pub keywords: Vec<String>,
+}
+
+impl Default for ListenOnlyTriggers {
+    fn default() -> Self {
+        Self {
+            mention: true,
+            reply: true,
+            command: true,
+            keywords: Vec::new(),
+        }
+    }
+}
+
+/// How a channel detects the language it is conversing in.
+///
+/// Detector scores are pooled over the last `window` messages, and the
+/// channel only switches language when one language holds at least
+/// `confidence_threshold` of the pooled evidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageDetectionConfig {
+    /// When false, no detection runs and prompts carry no language hint.
+    pub enabled: bool,
+    /// Name of the registered detector to use. `"builtin"` is always
+    /// available.
+    pub detector: String,
+    /// Language used before detection is confident, and when content stays
+    /// mixed across a full window.
+    pub default_language: String,
+    /// Share of pooled evidence a language needs to become current.
+    pub confidence_threshold: f32,
+    /// Number of recent messages pooled as evidence.
+    pub window: usize,
+    /// Messages shorter than this many characters are not used as evidence.
+    pub min_chars: usize,
+}
+
+impl Default for LanguageDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            detector: "builtin".into(),
+            default_language: "en".into(),
+            confidence_threshold: 0.6,
+            window: 5,
+            min_chars: 20,
+        }
+    }
+}
+
+/// Refresh cadence for the channel's status block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusRefresh {
+    /// Render the status block once, when the turn's system prompt is built.
+    #[default]
+    PerTurn,
+    /// Also inject an updated status block into a turn that is still running
+    /// when a worker or branch changes state.
+    OnChange,
+}
+
+/// Prompt-injection hardening for user content in the channel history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserContentEscaping {
+    /// Embed user content verbatim.
+    Off,
+    /// Escape the prompts' own section tags and chat-template tokens, and
+    /// quote lines that imitate system markers.
+    #[default]
+    Escape,
+    /// Escape, then wrap each message in `<user_message>` tags the system
+    /// prompt tells the model to treat as data.
+    Fence,
 }
 
 /// OpenCode subprocess worker configuration.
@@ -837,6 +1841,45 @@ impl Default for OpenCodeConfig {
     }
 }
 
+/// Persisted snapshots of the cortex memory bulletin.
+///
+/// When enabled, every successful bulletin synthesis is written to `dir` with
+/// a timestamp so operators can see how the bulletin changes between runs.
+#[derive(Debug, Clone)]
+pub struct BulletinHistoryConfig {
+    pub enabled: bool,
+    /// Snapshot directory. Resolves to `<agent data dir>/bulletins` when unset.
+    pub dir: Option<PathBuf>,
+    /// Number of most recent snapshots kept; older ones are deleted.
+    pub retention: usize,
+}
+
+impl Default for BulletinHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            retention: 48,
+        }
+    }
+}
+
+/// Debug mirror of model internals.
+///
+/// When enabled, tool calls, tool results, and workers' intermediate text are
+/// copied to a JSONL log and optionally a messaging destination, so they can
+/// be inspected without appearing in the user-facing channel.
+#[derive(Debug, Clone, Default)]
+pub struct DebugMirrorConfig {
+    pub enabled: bool,
+    /// JSONL file each mirrored event is appended to. Resolves to
+    /// `<agent data dir>/debug_mirror.jsonl` when unset.
+    pub log_path: Option<PathBuf>,
+    /// Optional `adapter:target` destination (e.g. `discord:123456789`) that
+    /// also receives a one-line summary of each event.
+    pub target: Option<String>,
+}
+
 /// Cortex configuration.
 #[derive(Debug, Clone, Copy)]
 pub struct CortexConfig {
@@ -852,6 +1895,11 @@ pub struct CortexConfig {
     pub bulletin_max_words: usize,
     /// Max LLM turns for bulletin generation.
     pub bulletin_max_turns: usize,
+    /// How many bulletin sources are queried at once.
+    pub bulletin_gather_concurrency: usize,
+    /// Seconds to wait for bulletin sources before synthesizing from the ones
+    /// that finished.
+    pub bulletin_gather_timeout_secs: u64,
     /// Interval in seconds between memory maintenance passes.
     pub maintenance_interval_secs: u64,
     /// Per-day decay applied to memory importance during maintenance.
@@ -884,6 +1932,8 @@ impl Default for CortexConfig {
             bulletin_interval_secs: 3600,
             bulletin_max_words: 1500,
             bulletin_max_turns: 15,
+            bulletin_gather_concurrency: 4,
+            bulletin_gather_timeout_secs: 30,
             maintenance_interval_secs: 3600,
             maintenance_decay_rate: 0.05,
             maintenance_prune_threshold: 0.1,
@@ -1121,15 +2171,21 @@ pub struct AgentConfig {
     pub context_window: Option<usize>,
     pub compaction: Option<CompactionConfig>,
     pub memory_persistence: Option<MemoryPersistenceConfig>,
+    pub identity_reinforcement: Option<IdentityReinforcementConfig>,
+    pub output_limits: Option<OutputLimitsConfig>,
     pub coalesce: Option<CoalesceConfig>,
+    pub worker: Option<WorkerConfig>,
     pub ingestion: Option<IngestionConfig>,
     pub cortex: Option<CortexConfig>,
+    pub bulletin_history: Option<BulletinHistoryConfig>,
+    pub debug_mirror: Option<DebugMirrorConfig>,
     pub warmup: Option<WarmupConfig>,
     pub browser: Option<BrowserConfig>,
     pub channel: Option<ChannelConfig>,
     pub mcp: Option<Vec<McpServerConfig>>,
     /// Per-agent Brave Search API key override. None inherits from defaults.
     pub brave_search_key: Option<String>,
+    pub web_search: Option<WebSearchConfig>,
     /// Optional timezone override for cron active-hours evaluation.
     pub cron_timezone: Option<String>,
     /// Optional timezone override for channel/worker temporal context.
@@ -1184,14 +2240,20 @@ pub struct ResolvedAgentConfig {
     pub context_window: usize,
     pub compaction: CompactionConfig,
     pub memory_persistence: MemoryPersistenceConfig,
+    pub identity_reinforcement: IdentityReinforcementConfig,
+    pub output_limits: OutputLimitsConfig,
     pub coalesce: CoalesceConfig,
+    pub worker: WorkerConfig,
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
+    pub bulletin_history: BulletinHistoryConfig,
+    pub debug_mirror: DebugMirrorConfig,
     pub warmup: WarmupConfig,
     pub browser: BrowserConfig,
     pub channel: ChannelConfig,
     pub mcp: Vec<McpServerConfig>,
     pub brave_search_key: Option<String>,
+    pub web_search: WebSearchConfig,
     pub cron_timezone: Option<String>,
     pub user_timezone: Option<String>,
     /// Sandbox configuration for process containment.
@@ -1214,14 +2276,20 @@ impl Default for DefaultsConfig {
             context_window: 128_000,
             compaction: CompactionConfig::default(),
             memory_persistence: MemoryPersistenceConfig::default(),
+            identity_reinforcement: IdentityReinforcementConfig::default(),
+            output_limits: OutputLimitsConfig::default(),
             coalesce: CoalesceConfig::default(),
+            worker: WorkerConfig::default(),
             ingestion: IngestionConfig::default(),
             cortex: CortexConfig::default(),
+            bulletin_history: BulletinHistoryConfig::default(),
+            debug_mirror: DebugMirrorConfig::default(),
             warmup: WarmupConfig::default(),
             browser: BrowserConfig::default(),
             channel: ChannelConfig::default(),
             mcp: Vec::new(),
             brave_search_key: None,
+            web_search: WebSearchConfig::default(),
             cron_timezone: None,
             user_timezone: None,
             history_backfill_count: 50,
@@ -1248,6 +2316,20 @@ impl AgentConfig {
             defaults.user_timezone.as_deref(),
             resolved_cron_timezone.as_deref(),
         );
+        let mut bulletin_history = self
+            .bulletin_history
+            .clone()
+            .unwrap_or_else(|| defaults.bulletin_history.clone());
+        bulletin_history
+            .dir
+            .get_or_insert_with(|| agent_root.join("data").join("bulletins"));
+        let mut debug_mirror = self
+            .debug_mirror
+            .clone()
+            .unwrap_or_else(|| defaults.debug_mirror.clone());
+        debug_mirror
+            .log_path
+            .get_or_insert_with(|| agent_root.join("data").join("debug_mirror.jsonl"));
 
         ResolvedAgentConfig {
             id: self.id.clone(),
@@ -1279,20 +2361,37 @@ impl AgentConfig {
             memory_persistence: self
                 .memory_persistence
                 .unwrap_or(defaults.memory_persistence),
+            identity_reinforcement: self
+                .identity_reinforcement
+                .unwrap_or(defaults.identity_reinforcement),
+            output_limits: self.output_limits.unwrap_or(defaults.output_limits),
             coalesce: self.coalesce.unwrap_or(defaults.coalesce),
+            worker: self
+                .worker
+                .clone()
+                .unwrap_or_else(|| defaults.worker.clone()),
             ingestion: self.ingestion.unwrap_or(defaults.ingestion),
             cortex: self.cortex.unwrap_or(defaults.cortex),
+            bulletin_history,
+            debug_mirror,
             warmup: self.warmup.unwrap_or(defaults.warmup),
             browser: self
                 .browser
                 .clone()
                 .unwrap_or_else(|| defaults.browser.clone()),
-            channel: self.channel.unwrap_or(defaults.channel),
+            channel: self
+                .channel
+                .clone()
+                .unwrap_or_else(|| defaults.channel.clone()),
             mcp: resolve_mcp_configs(&defaults.mcp, self.mcp.as_deref()),
             brave_search_key: self
                 .brave_search_key
                 .clone()
                 .or_else(|| defaults.brave_search_key.clone()),
+            web_search: self
+                .web_search
+                .clone()
+                .unwrap_or_else(|| defaults.web_search.clone()),
             cron_timezone: resolved_cron_timezone,
             user_timezone: resolved_user_timezone,
             sandbox: self.sandbox.clone().unwrap_or_default(),
@@ -2460,12 +3559,59 @@ impl SystemSecrets for TwitchConfig {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct WebhookConfig {
     pub enabled: bool,
     pub port: u16,
     pub bind: String,
     pub auth_token: Option<String>,
+    /// HMAC verification of inbound `/send` bodies, when a signing secret is
+    /// configured.
+    pub signature: Option<WebhookSignatureConfig>,
+}
+
+impl std::fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("enabled", &self.enabled)
+            .field("port", &self.port)
+            .field("bind", &self.bind)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+/// How senders sign webhook requests: an HMAC-SHA256 of the raw body, keyed
+/// with a shared secret, in a request header.
+#[derive(Clone)]
+pub struct WebhookSignatureConfig {
+    pub secret: String,
+    /// Header carrying the signature, lowercased.
+    pub header: String,
+    pub scheme: WebhookSignatureScheme,
+}
+
+impl std::fmt::Debug for WebhookSignatureConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookSignatureConfig")
+            .field("secret", &"[REDACTED]")
+            .field("header", &self.header)
+            .field("scheme", &self.scheme)
+            .finish()
+    }
+}
+
+/// How the signature header value is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookSignatureScheme {
+    /// The bare hex digest.
+    Hex,
+    /// `sha256=` followed by the hex digest, as GitHub sends it.
+    Sha256Prefixed,
 }
 
 /// Signal messaging via signal-cli JSON-RPC daemon.
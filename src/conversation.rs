@@ -3,6 +3,7 @@
 pub mod channels;
 pub mod context;
 pub mod history;
+pub mod transcript_sink;
 pub mod worker_transcript;
 
 pub use channels::ChannelStore;
@@ -69,9 +69,10 @@ impl ConversationLogger {
         });
     }
 
-    /// Log a bot (assistant) message. Fire-and-forget.
-    pub fn log_bot_message(&self, channel_id: &ChannelId, content: &str) {
-        self.log_bot_message_with_name(channel_id, content, None);
+    /// Log a bot (assistant) message. Fire-and-forget. Returns the ID the
+    /// message is stored under.
+    pub fn log_bot_message(&self, channel_id: &ChannelId, content: &str) -> String {
+        self.log_bot_message_with_name(channel_id, content, None)
     }
 
     /// Log a system message (e.g. task delegation audit record). Fire-and-forget.
@@ -102,14 +103,30 @@ impl ConversationLogger {
     }
 
     /// Log a bot (assistant) message with an agent display name. Fire-and-forget.
+    /// Returns the ID the message is stored under.
     pub fn log_bot_message_with_name(
         &self,
         channel_id: &ChannelId,
         content: &str,
         sender_name: Option<&str>,
+    ) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.log_bot_message_with_id(&id, channel_id, content, sender_name);
+        id
+    }
+
+    /// Log a bot (assistant) message under an ID chosen by the caller, for
+    /// messages whose ID goes out with them before they are logged.
+    /// Fire-and-forget.
+    pub fn log_bot_message_with_id(
+        &self,
+        id: &str,
+        channel_id: &ChannelId,
+        content: &str,
+        sender_name: Option<&str>,
     ) {
         let pool = self.pool.clone();
-        let id = uuid::Uuid::new_v4().to_string();
+        let id = id.to_string();
         let channel_id = channel_id.to_string();
         let content = content.to_string();
         let sender_name = sender_name.map(String::from);
@@ -339,6 +356,7 @@ impl ProcessRunLogger {
         channel_id: Option<&ChannelId>,
         worker_id: WorkerId,
         task: &str,
+        label: &str,
         worker_type: &str,
         agent_id: &crate::AgentId,
         interactive: bool,
@@ -348,18 +366,20 @@ impl ProcessRunLogger {
         let id = worker_id.to_string();
         let channel_id = channel_id.map(|c| c.to_string());
         let task = task.to_string();
+        let label = label.to_string();
         let worker_type = worker_type.to_string();
         let agent_id = agent_id.to_string();
         let directory = directory.map(|d| d.to_string_lossy().to_string());
 
         tokio::spawn(async move {
             if let Err(error) = sqlx::query(
-                "INSERT OR IGNORE INTO worker_runs (id, channel_id, task, worker_type, agent_id, interactive, directory) \
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                "INSERT OR IGNORE INTO worker_runs (id, channel_id, task, label, worker_type, agent_id, interactive, directory) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .bind(&id)
             .bind(&channel_id)
             .bind(&task)
+            .bind(&label)
             .bind(&worker_type)
             .bind(&agent_id)
             .bind(interactive)
@@ -564,10 +584,13 @@ impl ProcessRunLogger {
     ///
     /// Idle interactive workers are intentionally left alone — they will be
     /// resumed by `get_idle_interactive_workers()` + the reconnection logic.
+    /// So are the running workers listed in `resumable`, which startup picks
+    /// up again with `get_interrupted_workers()`.
     pub async fn reconcile_running_workers_for_agent(
         &self,
         agent_id: &str,
         failure_message: &str,
+        resumable: &[String],
     ) -> crate::error::Result<u64> {
         let result = sqlx::query(
             "UPDATE worker_runs \
@@ -577,10 +600,12 @@ impl ProcessRunLogger {
                      WHEN result IS NULL OR result = '' THEN ? \
                      ELSE result \
                  END \
-             WHERE status = 'running' AND (agent_id = ? OR agent_id IS NULL)",
+             WHERE status = 'running' AND (agent_id = ? OR agent_id IS NULL) \
+                   AND id NOT IN (SELECT value FROM json_each(?))",
         )
         .bind(failure_message)
         .bind(agent_id)
+        .bind(serde_json::to_string(resumable).map_err(|error| anyhow::anyhow!(error))?)
         .execute(&self.pool)
         .await
         .map_err(|error| anyhow::anyhow!(error))?;
@@ -598,9 +623,9 @@ impl ProcessRunLogger {
         agent_id: &str,
     ) -> crate::error::Result<Vec<IdleWorkerRow>> {
         let rows = sqlx::query_as::<_, IdleWorkerRow>(
-            "SELECT id, task, channel_id, worker_type, transcript, \
+            "SELECT id, task, label, channel_id, worker_type, transcript, \
                     COALESCE(tool_calls, 0) AS tool_calls, \
-                    opencode_session_id, opencode_port, directory \
+                    opencode_session_id, opencode_port, directory, interactive \
              FROM worker_runs \
              WHERE status = 'idle' AND interactive = TRUE \
                    AND (agent_id = ? OR agent_id IS NULL)",
@@ -613,6 +638,58 @@ impl ProcessRunLogger {
         Ok(rows)
     }
 
+    /// Load the builtin fire-and-forget workers still marked running for an
+    /// agent, i.e. interrupted by the last shutdown.
+    ///
+    /// Only channel workers are returned: a resumed worker reports back to
+    /// the channel that spawned it. Whether one can resume depends on the
+    /// history checkpoint it left under the agent's logs directory.
+    pub async fn get_interrupted_workers(
+        &self,
+        agent_id: &str,
+    ) -> crate::error::Result<Vec<IdleWorkerRow>> {
+        let rows = sqlx::query_as::<_, IdleWorkerRow>(
+            "SELECT id, task, label, channel_id, worker_type, transcript, \
+                    COALESCE(tool_calls, 0) AS tool_calls, \
+                    opencode_session_id, opencode_port, directory, interactive \
+             FROM worker_runs \
+             WHERE status = 'running' AND interactive = FALSE \
+                   AND worker_type = 'builtin' AND channel_id IS NOT NULL \
+                   AND (agent_id = ? OR agent_id IS NULL)",
+        )
+        .bind(agent_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+
+        Ok(rows)
+    }
+
+    /// Mark an interrupted worker as failed (used when resuming it fails at
+    /// startup).
+    pub async fn fail_interrupted_worker(
+        &self,
+        worker_id: &str,
+        reason: &str,
+    ) -> crate::error::Result<()> {
+        sqlx::query(
+            "UPDATE worker_runs \
+             SET status = 'failed', \
+                 completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP), \
+                 result = CASE \
+                     WHEN result IS NULL OR result = '' THEN ? \
+                     ELSE result \
+                 END \
+             WHERE id = ? AND status = 'running'",
+        )
+        .bind(reason)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|error| anyhow::anyhow!(error))?;
+        Ok(())
+    }
+
     /// Mark an idle worker as failed (used when reconnection fails at startup).
     pub async fn fail_idle_worker(
         &self,
@@ -832,7 +909,7 @@ impl ProcessRunLogger {
         let count_query =
             format!("SELECT COUNT(*) as total FROM worker_runs w {count_where_clause}");
         let list_query = format!(
-            "SELECT w.id, w.task, w.status, w.worker_type, w.channel_id, w.started_at, \
+            "SELECT w.id, w.task, w.label, w.status, w.worker_type, w.channel_id, w.started_at, \
                     w.completed_at, w.transcript IS NOT NULL as has_transcript, \
                     w.tool_calls, w.opencode_port, w.interactive, \
                     c.display_name as channel_name \
@@ -868,27 +945,32 @@ impl ProcessRunLogger {
 
         let items = rows
             .into_iter()
-            .map(|row| WorkerRunRow {
-                id: row.try_get("id").unwrap_or_default(),
-                task: row.try_get("task").unwrap_or_default(),
-                status: row.try_get("status").unwrap_or_default(),
-                worker_type: row
-                    .try_get("worker_type")
-                    .unwrap_or_else(|_| "builtin".into()),
-                channel_id: row.try_get("channel_id").ok(),
-                channel_name: row.try_get("channel_name").ok(),
-                started_at: row
-                    .try_get::<chrono::DateTime<chrono::Utc>, _>("started_at")
-                    .map(|t| t.to_rfc3339())
-                    .unwrap_or_default(),
-                completed_at: row
-                    .try_get::<chrono::DateTime<chrono::Utc>, _>("completed_at")
-                    .ok()
-                    .map(|t| t.to_rfc3339()),
-                has_transcript: row.try_get::<bool, _>("has_transcript").unwrap_or(false),
-                tool_calls: row.try_get::<i64, _>("tool_calls").unwrap_or(0),
-                opencode_port: row.try_get::<i32, _>("opencode_port").ok(),
-                interactive: row.try_get::<bool, _>("interactive").unwrap_or(false),
+            .map(|row| {
+                let task: String = row.try_get("task").unwrap_or_default();
+                let label = row.try_get::<Option<String>, _>("label").unwrap_or(None);
+                WorkerRunRow {
+                    id: row.try_get("id").unwrap_or_default(),
+                    label: label.unwrap_or_else(|| crate::agent::worker::worker_label(&task)),
+                    task,
+                    status: row.try_get("status").unwrap_or_default(),
+                    worker_type: row
+                        .try_get("worker_type")
+                        .unwrap_or_else(|_| "builtin".into()),
+                    channel_id: row.try_get("channel_id").ok(),
+                    channel_name: row.try_get("channel_name").ok(),
+                    started_at: row
+                        .try_get::<chrono::DateTime<chrono::Utc>, _>("started_at")
+                        .map(|t| t.to_rfc3339())
+                        .unwrap_or_default(),
+                    completed_at: row
+                        .try_get::<chrono::DateTime<chrono::Utc>, _>("completed_at")
+                        .ok()
+                        .map(|t| t.to_rfc3339()),
+                    has_transcript: row.try_get::<bool, _>("has_transcript").unwrap_or(false),
+                    tool_calls: row.try_get::<i64, _>("tool_calls").unwrap_or(0),
+                    opencode_port: row.try_get::<i32, _>("opencode_port").ok(),
+                    interactive: row.try_get::<bool, _>("interactive").unwrap_or(false),
+                }
             })
             .collect();
 
@@ -950,6 +1032,9 @@ impl ProcessRunLogger {
 #[derive(Debug, Clone, Serialize)]
 pub struct WorkerRunRow {
     pub id: String,
+    /// Human-readable label; a slug of the task for runs recorded before
+    /// labels were stored.
+    pub label: String,
     pub task: String,
     pub status: String,
     pub worker_type: String,
@@ -963,11 +1048,13 @@ pub struct WorkerRunRow {
     pub interactive: bool,
 }
 
-/// A worker that was idle at shutdown, loaded for reconnection at startup.
+/// A worker that was idle or interrupted at shutdown, loaded for
+/// reconnection at startup.
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct IdleWorkerRow {
     pub id: String,
     pub task: String,
+    pub label: Option<String>,
     pub channel_id: Option<String>,
     pub worker_type: String,
     pub transcript: Option<Vec<u8>>,
@@ -975,6 +1062,8 @@ pub struct IdleWorkerRow {
     pub opencode_session_id: Option<String>,
     pub opencode_port: Option<i32>,
     pub directory: Option<String>,
+    /// False for a fire-and-forget worker interrupted mid-task.
+    pub interactive: bool,
 }
 
 /// A worker run row with full detail including the transcript blob.
@@ -1012,6 +1101,16 @@ mod tests {
             "CREATE TABLE worker_runs (
                 id TEXT PRIMARY KEY,
                 channel_id TEXT,
+                agent_id TEXT,
+                task TEXT NOT NULL DEFAULT '',
+                label TEXT,
+                worker_type TEXT NOT NULL DEFAULT 'builtin',
+                interactive BOOLEAN NOT NULL DEFAULT FALSE,
+                transcript BLOB,
+                tool_calls INTEGER,
+                opencode_session_id TEXT,
+                opencode_port INTEGER,
+                directory TEXT,
                 status TEXT NOT NULL,
                 result TEXT,
                 completed_at TIMESTAMP
@@ -1082,4 +1181,37 @@ mod tests {
         let status: String = sqlx::Row::try_get(&row, "status").expect("missing status");
         assert_eq!(status, "running");
     }
+
+    #[tokio::test]
+    async fn reconcile_leaves_resumable_workers_running() {
+        let pool = setup_worker_runs_table().await;
+        let logger = ProcessRunLogger::new(pool.clone());
+        let resumable = uuid::Uuid::new_v4().to_string();
+        let orphaned = uuid::Uuid::new_v4().to_string();
+
+        for id in [&resumable, &orphaned] {
+            sqlx::query(
+                "INSERT INTO worker_runs (id, channel_id, agent_id, status, result) \
+                 VALUES (?, 'channel-1', 'agent-1', 'running', '')",
+            )
+            .bind(id)
+            .execute(&pool)
+            .await
+            .expect("failed to insert worker row");
+        }
+
+        let failed = logger
+            .reconcile_running_workers_for_agent("agent-1", "restarted", &[resumable.clone()])
+            .await
+            .expect("reconcile should succeed");
+        assert_eq!(failed, 1);
+
+        let interrupted = logger
+            .get_interrupted_workers("agent-1")
+            .await
+            .expect("query should succeed");
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, resumable);
+        assert!(!interrupted[0].interactive);
+    }
 }
@@ -0,0 +1,472 @@
+//! Archiving of structured transcripts to external storage.
+//!
+//! Workers (and, when configured, channel turns) hand a [`TranscriptRecord`]
+//! to [`archive`] when they finish. The record carries the same transcript
+//! steps persisted to `worker_runs.transcript`, plus enough metadata to file
+//! it: agent, channel, outcome, and timestamp. A [`TranscriptSink`] delivers
+//! it to a local JSONL file, an HTTP endpoint, or an S3-compatible bucket.
+//! Delivery runs on a detached task and failures are only logged, so
+//! archiving never delays the agent.
+
+use crate::config::{TranscriptArchiveConfig, TranscriptArchiveScope, TranscriptSinkBackend};
+use crate::conversation::worker_transcript::TranscriptStep;
+use crate::secrets::sigv4;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What produced a transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptKind {
+    Worker,
+    ChannelTurn,
+}
+
+/// How the run that produced a transcript ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TranscriptOutcome {
+    Success,
+    Failure { error: String },
+}
+
+/// One archived transcript with its metadata.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptRecord {
+    pub kind: TranscriptKind,
+    /// Worker ID, or a generated ID for a channel turn.
+    pub id: String,
+    pub agent_id: String,
+    pub channel_id: Option<String>,
+    /// The worker's task. Absent for channel turns.
+    pub task: Option<String>,
+    pub outcome: TranscriptOutcome,
+    pub timestamp: String,
+    pub steps: Vec<TranscriptStep>,
+}
+
+impl TranscriptRecord {
+    /// Build a record from a Rig history, stamped with the current time.
+    pub fn new(
+        kind: TranscriptKind,
+        id: impl Into<String>,
+        agent_id: impl Into<String>,
+        channel_id: Option<String>,
+        task: Option<String>,
+        outcome: TranscriptOutcome,
+        history: &[rig::message::Message],
+    ) -> Self {
+        Self {
+            kind,
+            id: id.into(),
+            agent_id: agent_id.into(),
+            channel_id,
+            task,
+            outcome,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            steps: crate::conversation::worker_transcript::convert_history(history),
+        }
+    }
+
+    fn is_failure(&self) -> bool {
+        matches!(self.outcome, TranscriptOutcome::Failure { .. })
+    }
+
+    /// Object key under an S3 prefix: `{prefix}{agent}/{date}/{kind}_{id}.json`.
+    fn object_key(&self, prefix: &str) -> String {
+        let date = self.timestamp.get(..10).unwrap_or("unknown");
+        let kind = match self.kind {
+            TranscriptKind::Worker => "worker",
+            TranscriptKind::ChannelTurn => "channel_turn",
+        };
+        format!(
+            "{prefix}{}/{date}/{kind}_{}.json",
+            sanitize_key_segment(&self.agent_id),
+            sanitize_key_segment(&self.id)
+        )
+    }
+}
+
+/// Destination for archived transcripts.
+pub trait TranscriptSink: Send + Sync {
+    /// Deliver one record. Called from a background task; errors are logged
+    /// by the caller and never retried.
+    fn archive<'a>(
+        &'a self,
+        record: &'a TranscriptRecord,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>>;
+}
+
+/// Build the sink for `config`. Relative JSONL paths resolve from
+/// `instance_dir`.
+pub fn build_sink(
+    config: &TranscriptArchiveConfig,
+    http_client: &reqwest::Client,
+    instance_dir: &Path,
+) -> Arc<dyn TranscriptSink> {
+    match &config.backend {
+        TranscriptSinkBackend::Jsonl { path } => Arc::new(JsonlSink {
+            path: instance_dir.join(path),
+        }),
+        TranscriptSinkBackend::Http {
+            url,
+            auth_token,
+            timeout_secs,
+        } => Arc::new(HttpSink {
+            client: http_client.clone(),
+            url: url.clone(),
+            auth_token: auth_token.clone(),
+            timeout: Duration::from_secs(*timeout_secs),
+        }),
+        TranscriptSinkBackend::S3 {
+            endpoint,
+            bucket,
+            region,
+            prefix,
+            access_key_id,
+            secret_access_key,
+            timeout_secs,
+        } => Arc::new(S3Sink {
+            client: http_client.clone(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.clone(),
+            region: region.clone(),
+            prefix: prefix.clone(),
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret_access_key.clone(),
+            timeout: Duration::from_secs(*timeout_secs),
+        }),
+    }
+}
+
+/// Whether `config` wants a record with this outcome.
+pub fn in_scope(config: &TranscriptArchiveConfig, record: &TranscriptRecord) -> bool {
+    match config.scope {
+        TranscriptArchiveScope::All => true,
+        TranscriptArchiveScope::Failures => record.is_failure(),
+    }
+}
+
+/// Archive `record` in the background if it falls within the configured
+/// scope. Returns immediately.
+pub fn archive(
+    config: &TranscriptArchiveConfig,
+    http_client: &reqwest::Client,
+    instance_dir: &Path,
+    record: TranscriptRecord,
+) {
+    if !in_scope(config, &record) {
+        return;
+    }
+    let sink = build_sink(config, http_client, instance_dir);
+    tokio::spawn(async move {
+        match sink.archive(&record).await {
+            Ok(()) => {
+                tracing::debug!(id = %record.id, kind = ?record.kind, "transcript archived");
+            }
+            Err(error) => {
+                tracing::warn!(
+                    id = %record.id,
+                    kind = ?record.kind,
+                    %error,
+                    "failed to archive transcript"
+                );
+            }
+        }
+    });
+}
+
+/// Appends one JSON record per line to a local file.
+struct JsonlSink {
+    path: PathBuf,
+}
+
+impl TranscriptSink for JsonlSink {
+    fn archive<'a>(
+        &'a self,
+        record: &'a TranscriptRecord,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            let path = self.path.clone();
+            // One `write_all` on an append-mode file per record keeps
+            // concurrent archives from interleaving in practice.
+            tokio::task::spawn_blocking(move || {
+                use std::io::Write as _;
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)?
+                    .write_all(&line)
+            })
+            .await?
+            .with_context(|| format!("failed to append to {}", self.path.display()))
+        })
+    }
+}
+
+/// POSTs each record as JSON.
+struct HttpSink {
+    client: reqwest::Client,
+    url: String,
+    auth_token: Option<String>,
+    timeout: Duration,
+}
+
+impl TranscriptSink for HttpSink {
+    fn archive<'a>(
+        &'a self,
+        record: &'a TranscriptRecord,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut request = self
+                .client
+                .post(&self.url)
+                .timeout(self.timeout)
+                .json(record);
+            if let Some(token) = &self.auth_token {
+                request = request.bearer_auth(token);
+            }
+            request.send().await?.error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+/// PUTs each record as an object in an S3-compatible bucket.
+struct S3Sink {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+    timeout: Duration,
+}
+
+impl TranscriptSink for S3Sink {
+    fn archive<'a>(
+        &'a self,
+        record: &'a TranscriptRecord,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(record)?;
+            let key = record.object_key(&self.prefix);
+            let url = reqwest::Url::parse(&format!("{}/{}/{key}", self.endpoint, self.bucket))
+                .context("invalid S3 endpoint")?;
+            let host = match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{host}:{port}"),
+                (Some(host), None) => host.to_string(),
+                (None, _) => anyhow::bail!("S3 endpoint has no host"),
+            };
+            let signed = sign_v4_put(
+                &SigningParams {
+                    access_key_id: &self.access_key_id,
+                    secret_access_key: &self.secret_access_key,
+                    region: &self.region,
+                    host: &host,
+                    path: url.path(),
+                },
+                &body,
+                chrono::Utc::now(),
+            );
+
+            self.client
+                .put(url)
+                .timeout(self.timeout)
+                .header("content-type", "application/json")
+                .header("x-amz-date", signed.amz_date)
+                .header("x-amz-content-sha256", signed.payload_hash)
+                .header("authorization", signed.authorization)
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+struct SigningParams<'a> {
+    access_key_id: &'a str,
+    secret_access_key: &'a str,
+    region: &'a str,
+    host: &'a str,
+    /// Already URI-encoded request path.
+    path: &'a str,
+}
+
+struct SignedHeaders {
+    amz_date: String,
+    payload_hash: String,
+    authorization: String,
+}
+
+/// Sign a PUT with no query string using AWS Signature Version 4.
+fn sign_v4_put(
+    params: &SigningParams<'_>,
+    body: &[u8],
+    now: chrono::DateTime<chrono::Utc>,
+) -> SignedHeaders {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let payload_hash = sigv4::payload_hash(body);
+    let authorization = sigv4::authorization(&sigv4::SigV4Request {
+        method: "PUT",
+        service: "s3",
+        region: params.region,
+        path: params.path,
+        headers: &[
+            ("host", params.host),
+            ("x-amz-content-sha256", &payload_hash),
+            ("x-amz-date", &amz_date),
+        ],
+        payload_hash: &payload_hash,
+        access_key: params.access_key_id,
+        secret_key: params.secret_access_key,
+        amz_date: &amz_date,
+    });
+
+    SignedHeaders {
+        amz_date,
+        payload_hash,
+        authorization,
+    }
+}
+
+/// Keep object key segments to characters that need no URI encoding.
+fn sanitize_key_segment(segment: &str) -> String {
+    segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SigningParams, TranscriptKind, TranscriptOutcome, TranscriptRecord, archive, build_sink,
+        in_scope, sign_v4_put,
+    };
+    use crate::config::{TranscriptArchiveConfig, TranscriptArchiveScope, TranscriptSinkBackend};
+
+    fn record(outcome: TranscriptOutcome) -> TranscriptRecord {
+        TranscriptRecord::new(
+            TranscriptKind::Worker,
+            "w1",
+            "main",
+            Some("discord:1".into()),
+            Some("do the thing".into()),
+            outcome,
+            &[rig::message::Message::user("do the thing")],
+        )
+    }
+
+    fn jsonl_config(
+        path: std::path::PathBuf,
+        scope: TranscriptArchiveScope,
+    ) -> TranscriptArchiveConfig {
+        TranscriptArchiveConfig {
+            scope,
+            channel_turns: false,
+            backend: TranscriptSinkBackend::Jsonl { path },
+        }
+    }
+
+    #[tokio::test]
+    async fn jsonl_sink_appends_one_line_per_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = jsonl_config(
+            "archive/transcripts.jsonl".into(),
+            TranscriptArchiveScope::All,
+        );
+        let sink = build_sink(&config, &reqwest::Client::new(), dir.path());
+
+        sink.archive(&record(TranscriptOutcome::Success))
+            .await
+            .unwrap();
+        let failure = TranscriptOutcome::Failure {
+            error: "boom".into(),
+        };
+        sink.archive(&record(failure)).await.unwrap();
+
+        let contents =
+            std::fs::read_to_string(dir.path().join("archive/transcripts.jsonl")).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["outcome"]["status"], "success");
+        assert_eq!(lines[1]["outcome"]["error"], "boom");
+        assert_eq!(lines[1]["steps"][0]["type"], "user_text");
+    }
+
+    #[tokio::test]
+    async fn failures_scope_skips_successful_runs() {
+        let config = jsonl_config("unused.jsonl".into(), TranscriptArchiveScope::Failures);
+        assert!(!in_scope(&config, &record(TranscriptOutcome::Success)));
+        assert!(in_scope(
+            &config,
+            &record(TranscriptOutcome::Failure { error: "x".into() })
+        ));
+
+        // Out-of-scope records never touch the sink.
+        let dir = tempfile::tempdir().unwrap();
+        archive(
+            &config,
+            &reqwest::Client::new(),
+            dir.path(),
+            record(TranscriptOutcome::Success),
+        );
+        tokio::task::yield_now().await;
+        assert!(!dir.path().join("unused.jsonl").exists());
+    }
+
+    #[test]
+    fn s3_signature_covers_payload_and_scope() {
+        let params = SigningParams {
+            access_key_id: "AKIDEXAMPLE",
+            secret_access_key: "secret",
+            region: "eu-west-1",
+            host: "minio.local:9000",
+            path: "/bucket/main/2026-01-01/worker_w1.json",
+        };
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let signed = sign_v4_put(&params, b"{}", now);
+        assert_eq!(signed.amz_date, "20260101T120000Z");
+        assert_eq!(
+            signed.payload_hash,
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert_eq!(
+            signed.authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260101/eu-west-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, \
+             Signature=1841736fea3ec21bdea8afad7adf3d4457f9b1dc241a0cf0bd4b04f2d5e72059"
+        );
+        assert_ne!(
+            sign_v4_put(&params, b"[]", now).authorization,
+            signed.authorization
+        );
+    }
+}
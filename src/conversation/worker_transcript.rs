@@ -384,7 +384,7 @@ pub fn transcript_to_history(steps: &[TranscriptStep]) -> Vec<rig::message::Mess
 }
 
 /// Convert Rig `Vec<Message>` to `Vec<TranscriptStep>`.
-fn convert_history(history: &[rig::message::Message]) -> Vec<TranscriptStep> {
+pub fn convert_history(history: &[rig::message::Message]) -> Vec<TranscriptStep> {
     let mut steps = Vec::new();
 
     for message in history {
@@ -187,6 +187,14 @@ pub enum AgentError {
     #[error("max concurrent workers ({max}) reached for channel {channel_id}")]
     WorkerLimitReached { channel_id: String, max: usize },
 
+    #[error("max spawn depth ({max}) reached: a process at depth {depth} cannot spawn workers")]
+    SpawnDepthExceeded { depth: usize, max: usize },
+
+    #[error(
+        "worker task is {length} characters, over the {max} character limit; shorten the task or move the details into a file the worker can read"
+    )]
+    TaskTooLong { length: usize, max: usize },
+
     #[error(
         "duplicate worker task on channel {channel_id}: worker {existing_worker_id} is already running this task"
     )]
@@ -204,6 +212,15 @@ pub enum AgentError {
     #[error("process cancelled: {reason}")]
     Cancelled { reason: String },
 
+    #[error("worker timed out after {limit_secs}s ({segments} segments run)")]
+    TimedOut { limit_secs: u64, segments: usize },
+
+    /// The history no longer fits the context window and compaction can't
+    /// shrink it enough. `attempts` is the number of recoveries tried, 0 when
+    /// compaction is disabled. A larger-context model may still succeed.
+    #[error("context overflow unrecoverable after {attempts} compaction attempts")]
+    ContextOverflowUnrecoverable { attempts: usize },
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -226,6 +243,12 @@ pub enum SecretsError {
     #[error("secret store is locked — unlock with master key first")]
     StoreLocked,
 
+    #[error("failed to resolve secret from {provider}: {details}")]
+    Provider {
+        provider: &'static str,
+        details: String,
+    },
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
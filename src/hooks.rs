@@ -2,8 +2,10 @@
 
 pub mod cortex;
 pub mod loop_guard;
+pub mod provenance;
 pub mod spacebot;
 
 pub use cortex::CortexHook;
 pub use loop_guard::{LoopGuard, LoopGuardConfig, LoopGuardVerdict};
-pub use spacebot::{SpacebotHook, ToolNudgePolicy};
+pub use provenance::TouchedResources;
+pub use spacebot::{SpacebotHook, TokenUsage, ToolNudgePolicy};
@@ -0,0 +1,151 @@
+//! Provenance tracking: which files and URLs a process touched through its tools.
+
+use std::collections::BTreeSet;
+
+/// Files, URLs, and commands touched by successful tool calls.
+///
+/// Populated by `SpacebotHook::on_tool_result` so only calls that actually
+/// executed are recorded. Paths and URLs are stored exactly as the model
+/// passed them to the tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TouchedResources {
+    /// Paths read via `file_read`.
+    pub files_read: BTreeSet<String>,
+    /// Paths created or changed via `file_write` / `file_edit`.
+    pub files_modified: BTreeSet<String>,
+    /// Pages opened via `browser_navigate` / `browser_tab_open`.
+    pub urls_visited: BTreeSet<String>,
+    /// Number of shell commands executed.
+    pub shell_commands: usize,
+    /// Number of web searches performed.
+    pub web_searches: usize,
+}
+
+impl TouchedResources {
+    /// Record a successful tool call from its name and raw JSON arguments.
+    pub fn record(&mut self, tool_name: &str, args: &str) {
+        let string_arg = |key: &str| -> Option<String> {
+            let parsed: serde_json::Value = serde_json::from_str(args).ok()?;
+            parsed
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToOwned::to_owned)
+        };
+
+        match tool_name {
+            "file_read" => {
+                if let Some(path) = string_arg("path") {
+                    self.files_read.insert(path);
+                }
+            }
+            "file_write" | "file_edit" => {
+                if let Some(path) = string_arg("path") {
+                    self.files_modified.insert(path);
+                }
+            }
+            "browser_navigate" | "browser_tab_open" => {
+                if let Some(url) = string_arg("url") {
+                    self.urls_visited.insert(url);
+                }
+            }
+            "shell" => self.shell_commands += 1,
+            "web_search" => self.web_searches += 1,
+            _ => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files_read.is_empty()
+            && self.files_modified.is_empty()
+            && self.urls_visited.is_empty()
+            && self.shell_commands == 0
+            && self.web_searches == 0
+    }
+
+    /// Render a compact, human-readable summary for the channel, or `None`
+    /// when nothing was touched.
+    pub fn summary(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut lines = Vec::new();
+        if !self.files_modified.is_empty() {
+            lines.push(format!("- Modified: {}", join_code(&self.files_modified)));
+        }
+        if !self.files_read.is_empty() {
+            lines.push(format!("- Read: {}", join_code(&self.files_read)));
+        }
+        if !self.urls_visited.is_empty() {
+            lines.push(format!(
+                "- Visited {} page(s): {}",
+                self.urls_visited.len(),
+                self.urls_visited
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if self.shell_commands > 0 {
+            lines.push(format!("- Ran {} shell command(s)", self.shell_commands));
+        }
+        if self.web_searches > 0 {
+            lines.push(format!("- Ran {} web search(es)", self.web_searches));
+        }
+
+        Some(lines.join("\n"))
+    }
+}
+
+fn join_code(values: &BTreeSet<String>) -> String {
+    values
+        .iter()
+        .map(|value| format!("`{value}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TouchedResources;
+
+    #[test]
+    fn records_paths_urls_and_counts() {
+        let mut touched = TouchedResources::default();
+        touched.record("file_write", r#"{"path": "config.toml", "content": "x"}"#);
+        touched.record("file_edit", r#"{"path": "src/main.rs", "old_string": "a"}"#);
+        touched.record("file_edit", r#"{"path": "src/main.rs", "old_string": "b"}"#);
+        touched.record("file_read", r#"{"path": "README.md"}"#);
+        touched.record("browser_navigate", r#"{"url": "https://example.com"}"#);
+        touched.record("shell", r#"{"command": "ls"}"#);
+        touched.record("set_status", r#"{"status": "working"}"#);
+
+        assert_eq!(touched.files_modified.len(), 2);
+        assert!(touched.files_read.contains("README.md"));
+        assert!(touched.urls_visited.contains("https://example.com"));
+        assert_eq!(touched.shell_commands, 1);
+        assert_eq!(touched.web_searches, 0);
+    }
+
+    #[test]
+    fn ignores_malformed_arguments() {
+        let mut touched = TouchedResources::default();
+        touched.record("file_write", "not json");
+        touched.record("browser_navigate", r#"{"url": "   "}"#);
+        assert!(touched.is_empty());
+        assert_eq!(touched.summary(), None);
+    }
+
+    #[test]
+    fn summary_lists_modified_files_first() {
+        let mut touched = TouchedResources::default();
+        touched.record("file_read", r#"{"path": "a.txt"}"#);
+        touched.record("file_write", r#"{"path": "b.txt"}"#);
+
+        let summary = touched.summary().expect("summary");
+        assert_eq!(summary, "- Modified: `b.txt`\n- Read: `a.txt`");
+    }
+}
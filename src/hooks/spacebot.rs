@@ -1,8 +1,12 @@
 //! SpacebotHook: Prompt hook for channels, branches, and workers.
 
+use crate::agent::tool_call_repair::repair_and_log;
+use crate::config::{OutputLimit, OutputOverflow, ToolCallRepair};
 use crate::hooks::loop_guard::{LoopGuard, LoopGuardConfig, LoopGuardVerdict};
+use crate::hooks::provenance::TouchedResources;
+use crate::prompts::PromptEngine;
 use crate::tools::{MemoryPersistenceContractState, MemoryPersistenceTerminalOutcome};
-use crate::{AgentId, ChannelId, ProcessEvent, ProcessId, ProcessType};
+use crate::{AgentId, ChannelId, ProcessEvent, ProcessId, ProcessType, WorkerEvent};
 use rig::agent::{HookAction, PromptHook, ToolCallHookAction};
 use rig::completion::{CompletionModel, CompletionResponse, Message, Prompt, PromptError};
 use std::sync::Arc;
@@ -29,6 +33,28 @@ impl ToolNudgePolicy {
     }
 }
 
+/// Token counts reported by the provider across completion calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Whether nothing was reported, e.g. by a provider that omits usage.
+    pub fn is_empty(&self) -> bool {
+        self.input_tokens == 0 && self.output_tokens == 0
+    }
+
+    /// Usage accumulated since the `earlier` snapshot.
+    pub fn since(&self, earlier: TokenUsage) -> TokenUsage {
+        TokenUsage {
+            input_tokens: self.input_tokens.saturating_sub(earlier.input_tokens),
+            output_tokens: self.output_tokens.saturating_sub(earlier.output_tokens),
+        }
+    }
+}
+
 /// Hook for observing agent behavior and sending events.
 #[derive(Clone)]
 pub struct SpacebotHook {
@@ -61,6 +87,13 @@ pub struct SpacebotHook {
     /// append the messages to history before re-prompting.
     injected_messages: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
     memory_persistence_contract: Option<Arc<MemoryPersistenceContractState>>,
+    /// Files and URLs touched by successful worker tool calls, surfaced in
+    /// the worker outcome so the channel can report provenance.
+    touched_resources: Arc<std::sync::Mutex<TouchedResources>>,
+    /// Repair applied to tool-call IDs in history before each prompt.
+    tool_call_repair: ToolCallRepair,
+    /// Provider-reported usage summed over every completion response.
+    token_usage: Arc<std::sync::Mutex<TokenUsage>>,
 }
 
 impl SpacebotHook {
@@ -115,6 +148,9 @@ impl SpacebotHook {
             inject_rx: None,
             injected_messages: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
             memory_persistence_contract: None,
+            touched_resources: Arc::new(std::sync::Mutex::new(TouchedResources::default())),
+            tool_call_repair: ToolCallRepair::default(),
+            token_usage: Arc::new(std::sync::Mutex::new(TokenUsage::default())),
         }
     }
 
@@ -124,6 +160,12 @@ impl SpacebotHook {
         self
     }
 
+    /// Override how duplicate tool-call IDs in history are repaired.
+    pub fn with_tool_call_repair(mut self, mode: ToolCallRepair) -> Self {
+        self.tool_call_repair = mode;
+        self
+    }
+
     pub fn with_memory_persistence_contract(
         mut self,
         contract_state: Arc<MemoryPersistenceContractState>,
@@ -132,6 +174,22 @@ impl SpacebotHook {
         self
     }
 
+    /// Snapshot of the files and URLs touched by tool calls so far.
+    pub fn touched_resources(&self) -> TouchedResources {
+        self.touched_resources
+            .lock()
+            .map(|touched| touched.clone())
+            .unwrap_or_default()
+    }
+
+    /// Token usage the provider has reported so far.
+    pub fn token_usage(&self) -> TokenUsage {
+        self.token_usage
+            .lock()
+            .map(|usage| *usage)
+            .unwrap_or_default()
+    }
+
     /// Attach a context injection receiver to this hook.
     ///
     /// When set, `on_completion_call` will drain pending messages from the
@@ -206,7 +264,7 @@ impl SpacebotHook {
         &self,
         agent: &rig::agent::Agent<M>,
         history: &mut Vec<Message>,
-        prompt: &str,
+        prompt: impl Into<Message> + Send,
     ) -> std::result::Result<String, PromptError>
     where
         M: CompletionModel,
@@ -215,13 +273,14 @@ impl SpacebotHook {
         self.set_tool_nudge_request_active(true);
         self.set_completion_contract_request_active(false);
 
-        let mut current_prompt = std::borrow::Cow::Borrowed(prompt);
+        let mut current_prompt: Message = prompt.into();
         let mut using_tool_nudge_prompt = false;
 
         loop {
+            repair_and_log(history, self.tool_call_repair, &self.process_id);
             let history_len_before_attempt = history.len();
             let result = agent
-                .prompt(current_prompt.as_ref())
+                .prompt(current_prompt.clone())
                 .with_history(history)
                 .with_hook(self.clone())
                 .await;
@@ -255,7 +314,7 @@ impl SpacebotHook {
                     }
 
                     // Re-prompt asking the worker to incorporate the new context.
-                    current_prompt = std::borrow::Cow::Borrowed(
+                    current_prompt = Message::from(
                         "New context has been provided above. Incorporate this \
                          information and continue working on your task. Do not \
                          repeat completed work.",
@@ -290,7 +349,7 @@ impl SpacebotHook {
                         attempt = attempts + 1,
                         "text-only response without outcome signal, nudging tool usage"
                     );
-                    current_prompt = std::borrow::Cow::Borrowed(Self::TOOL_NUDGE_PROMPT);
+                    current_prompt = Message::from(Self::TOOL_NUDGE_PROMPT);
                     using_tool_nudge_prompt = true;
                     continue;
                 }
@@ -366,13 +425,14 @@ impl SpacebotHook {
         &self,
         agent: &rig::agent::Agent<M>,
         history: &mut Vec<Message>,
-        prompt: &str,
+        prompt: impl Into<Message> + Send,
     ) -> std::result::Result<String, PromptError>
     where
         M: CompletionModel,
     {
         self.reset_tool_nudge_state();
         self.set_tool_nudge_request_active(false);
+        repair_and_log(history, self.tool_call_repair, &self.process_id);
         agent
             .prompt(prompt)
             .with_history(history)
@@ -380,6 +440,50 @@ impl SpacebotHook {
             .await
     }
 
+    /// Hold `text` to `limit`. With `reprompt`, the model is asked once for
+    /// a shorter version; whatever is still too long is cut at a sentence
+    /// boundary.
+    pub async fn enforce_output_limit<M>(
+        &self,
+        agent: &rig::agent::Agent<M>,
+        history: &mut Vec<Message>,
+        prompts: &PromptEngine,
+        limit: OutputLimit,
+        text: String,
+    ) -> String
+    where
+        M: CompletionModel,
+    {
+        if !limit.exceeded_by(&text) {
+            return text;
+        }
+        let length = text.chars().count();
+        tracing::info!(
+            process_id = %self.process_id,
+            length,
+            max_chars = limit.max_chars,
+            on_exceed = ?limit.on_exceed,
+            "output over limit"
+        );
+
+        let mut text = text;
+        if limit.on_exceed == OutputOverflow::Reprompt {
+            match prompts.render_system_output_limit_retry(length, limit.max_chars) {
+                Ok(prompt) => match self.prompt_once(agent, history, prompt).await {
+                    Ok(shorter) if !shorter.trim().is_empty() => text = shorter,
+                    Ok(_) => {}
+                    Err(error) => {
+                        tracing::warn!(process_id = %self.process_id, %error, "output limit retry failed");
+                    }
+                },
+                Err(error) => {
+                    tracing::warn!(process_id = %self.process_id, %error, "failed to render output limit retry");
+                }
+            }
+        }
+        crate::tools::truncate_at_sentence(&text, limit.max_chars)
+    }
+
     /// Send a status update event.
     pub fn send_status(&self, status: impl Into<String>) {
         let event = ProcessEvent::StatusUpdate {
@@ -390,6 +494,45 @@ impl SpacebotHook {
         self.event_tx.send(event).ok();
     }
 
+    /// Publish streamed response text: as a `TextDelta` for a channel with a
+    /// channel ID, as a `WorkerTextDelta` for a worker. Other processes don't
+    /// publish their deltas.
+    pub fn send_text_delta(&self, text_delta: &str, aggregated_text: &str) {
+        let event = match (&self.process_type, &self.process_id) {
+            (ProcessType::Channel, _) => {
+                let Some(channel_id) = self.channel_id.clone() else {
+                    return;
+                };
+                ProcessEvent::TextDelta {
+                    agent_id: self.agent_id.clone(),
+                    process_id: self.process_id.clone(),
+                    channel_id: Some(channel_id),
+                    text_delta: text_delta.to_string(),
+                    aggregated_text: aggregated_text.to_string(),
+                }
+            }
+            (ProcessType::Worker, ProcessId::Worker(worker_id)) => ProcessEvent::WorkerTextDelta {
+                agent_id: self.agent_id.clone(),
+                worker_id: *worker_id,
+                channel_id: self.channel_id.clone(),
+                text_delta: text_delta.to_string(),
+                aggregated_text: aggregated_text.to_string(),
+            },
+            _ => return,
+        };
+        self.event_tx.send(event).ok();
+    }
+
+    /// A [`TextDeltaSink`](crate::llm::model::TextDeltaSink) that publishes a model's streamed text through
+    /// [`send_text_delta`](Self::send_text_delta), for
+    /// `SpacebotModel::with_text_deltas`.
+    pub fn text_delta_sink(&self) -> crate::llm::model::TextDeltaSink {
+        let hook = self.clone();
+        Arc::new(move |text_delta: &str, aggregated_text: &str| {
+            hook.send_text_delta(text_delta, aggregated_text);
+        })
+    }
+
     /// Send a worker idle event. Only valid for worker processes.
     pub fn send_worker_idle(&self) {
         if let ProcessId::Worker(worker_id) = &self.process_id {
@@ -402,6 +545,19 @@ impl SpacebotHook {
         }
     }
 
+    /// Send a worker lifecycle event. Only valid for worker processes.
+    pub fn send_worker_event(&self, event: WorkerEvent) {
+        if let ProcessId::Worker(worker_id) = &self.process_id {
+            let event = ProcessEvent::WorkerLifecycle {
+                agent_id: self.agent_id.clone(),
+                worker_id: *worker_id,
+                channel_id: self.channel_id.clone(),
+                event,
+            };
+            self.event_tx.send(event).ok();
+        }
+    }
+
     /// Scan content for potential secret leaks, including encoded forms.
     ///
     /// Delegates to the shared implementation in `secrets::scrub`.
@@ -712,6 +868,12 @@ where
             "completion response received"
         );
 
+        // Counted before any nudge or rejection: the call was paid for.
+        if let Ok(mut usage) = self.token_usage.lock() {
+            usage.input_tokens += response.usage.input_tokens;
+            usage.output_tokens += response.usage.output_tokens;
+        }
+
         if self.should_nudge_tool_usage::<M>(response) {
             return HookAction::Terminate {
                 reason: Self::TOOL_NUDGE_REASON.into(),
@@ -762,19 +924,7 @@ where
     }
 
     async fn on_text_delta(&self, text_delta: &str, aggregated_text: &str) -> HookAction {
-        if self.process_type == ProcessType::Channel
-            && let Some(channel_id) = self.channel_id.clone()
-        {
-            let event = ProcessEvent::TextDelta {
-                agent_id: self.agent_id.clone(),
-                process_id: self.process_id.clone(),
-                channel_id: Some(channel_id),
-                text_delta: text_delta.to_string(),
-                aggregated_text: aggregated_text.to_string(),
-            };
-            self.event_tx.send(event).ok();
-        }
-
+        self.send_text_delta(text_delta, aggregated_text);
         HookAction::Continue
     }
 
@@ -918,6 +1068,13 @@ where
 
         let is_tool_error = result.starts_with("Toolset error:");
 
+        if !is_tool_error
+            && self.process_type == ProcessType::Worker
+            && let Ok(mut touched) = self.touched_resources.lock()
+        {
+            touched.record(tool_name, _args);
+        }
+
         if !is_tool_error
             && tool_name == "memory_persistence_complete"
             && let Some(contract_state) = &self.memory_persistence_contract
@@ -1022,6 +1179,7 @@ mod tests {
             usage: Usage::default(),
             raw_response: RawResponse {
                 body: serde_json::json!({}),
+                served_by: None,
             },
         }
     }
@@ -1037,6 +1195,7 @@ mod tests {
             usage: Usage::default(),
             raw_response: RawResponse {
                 body: serde_json::json!({}),
+                served_by: None,
             },
         }
     }
@@ -1487,6 +1646,35 @@ mod tests {
         assert!(matches!(history[base_len], Message::Assistant { .. }));
     }
 
+    #[tokio::test]
+    async fn worker_text_delta_sink_emits_worker_events() {
+        let (event_tx, mut event_rx) = tokio::sync::broadcast::channel(8);
+        let worker_id = uuid::Uuid::new_v4();
+        let hook = SpacebotHook::new(
+            std::sync::Arc::<str>::from("agent"),
+            ProcessId::Worker(worker_id),
+            ProcessType::Worker,
+            Some(std::sync::Arc::<str>::from("channel")),
+            event_tx,
+        );
+
+        let sink = hook.text_delta_sink();
+        sink("Reading ", "Reading ");
+        sink("config", "Reading config");
+
+        for expected in ["Reading ", "Reading config"] {
+            let event = event_rx.recv().await.expect("worker text delta event");
+            assert!(matches!(
+                event,
+                ProcessEvent::WorkerTextDelta {
+                    worker_id: event_worker,
+                    ref aggregated_text,
+                    ..
+                } if event_worker == worker_id && aggregated_text == expected
+            ));
+        }
+    }
+
     #[tokio::test]
     async fn channel_text_delta_emits_process_event() {
         let (event_tx, mut event_rx) = tokio::sync::broadcast::channel(8);
@@ -1591,6 +1779,7 @@ mod tests {
             usage: Usage::default(),
             raw_response: RawResponse {
                 body: serde_json::json!({}),
+                served_by: None,
             },
         };
 
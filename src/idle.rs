@@ -0,0 +1,173 @@
+//! Idle tracking and idle shutdown.
+//!
+//! The messaging manager records the time of every inbound message and
+//! outbound send in an [`ActivityTracker`], and per-agent event watchers keep
+//! a count of running workers. Once nothing has happened for
+//! `idle.timeout_secs` and no worker is running, the idle monitor either
+//! signals the main loop to shut down gracefully or hibernates the messaging
+//! adapters until the next activity.
+
+use crate::ProcessEvent;
+use crate::config::{IdleAction, IdleConfig};
+use crate::messaging::MessagingManager;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{Notify, broadcast, watch};
+
+/// Last-activity timestamp and running worker count for the whole instance.
+#[derive(Debug)]
+pub struct ActivityTracker {
+    /// Unix milliseconds of the most recent inbound or outbound message.
+    last_activity_ms: watch::Sender<i64>,
+    active_workers: AtomicUsize,
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        let (last_activity_ms, _) = watch::channel(chrono::Utc::now().timestamp_millis());
+        Self {
+            last_activity_ms,
+            active_workers: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ActivityTracker {
+    /// Record activity now.
+    pub fn touch(&self) {
+        self.last_activity_ms
+            .send_replace(chrono::Utc::now().timestamp_millis());
+    }
+
+    /// Time since the last recorded activity.
+    pub fn idle_for(&self) -> Duration {
+        let elapsed = chrono::Utc::now().timestamp_millis() - *self.last_activity_ms.borrow();
+        Duration::from_millis(elapsed.max(0) as u64)
+    }
+
+    /// Receiver that changes on every [`touch`](Self::touch).
+    pub fn subscribe(&self) -> watch::Receiver<i64> {
+        self.last_activity_ms.subscribe()
+    }
+
+    pub fn active_workers(&self) -> usize {
+        self.active_workers.load(Ordering::Relaxed)
+    }
+
+    /// Whether the idle timeout has elapsed with no worker running.
+    pub fn is_idle(&self, timeout: Duration) -> bool {
+        self.active_workers() == 0 && self.idle_for() >= timeout
+    }
+
+    fn worker_started(&self) {
+        self.active_workers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn worker_finished(&self) {
+        // The closure always returns `Some`, so the update can't fail. The
+        // count saturates at zero in case a WorkerComplete arrives without
+        // its WorkerStarted (a lagged receiver).
+        self.active_workers
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count.saturating_sub(1))
+            })
+            .ok();
+    }
+}
+
+/// Keep `tracker`'s worker count in step with one agent's event bus.
+pub fn spawn_worker_counter(
+    tracker: Arc<ActivityTracker>,
+    mut event_rx: broadcast::Receiver<ProcessEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match event_rx.recv().await {
+                Ok(ProcessEvent::WorkerStarted { .. }) => tracker.worker_started(),
+                Ok(ProcessEvent::WorkerComplete { .. }) => tracker.worker_finished(),
+                Ok(_) => {}
+                // Missed events can't be recovered, so the count may drift;
+                // treat the gap itself as activity to avoid acting on it.
+                Err(broadcast::error::RecvError::Lagged(_)) => tracker.touch(),
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+/// Spawn the idle monitor, or return `None` when idle tracking is disabled.
+///
+/// For [`IdleAction::Shutdown`] the monitor notifies `shutdown` once and
+/// exits; the main loop treats that like a stop signal. For
+/// [`IdleAction::Hibernate`] it disconnects adapters and reconnects them on
+/// the next activity, indefinitely.
+pub fn spawn_idle_monitor(
+    config: IdleConfig,
+    messaging: Arc<MessagingManager>,
+    shutdown: Arc<Notify>,
+) -> Option<tokio::task::JoinHandle<()>> {
+    if config.timeout_secs == 0 {
+        return None;
+    }
+    let timeout = Duration::from_secs(config.timeout_secs);
+    let poll_interval = (timeout / 4).clamp(Duration::from_secs(1), Duration::from_secs(60));
+    let tracker = messaging.activity();
+
+    Some(tokio::spawn(async move {
+        let mut activity_rx = tracker.subscribe();
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            if !tracker.is_idle(timeout) {
+                continue;
+            }
+
+            match config.action {
+                IdleAction::Shutdown => {
+                    tracing::info!(
+                        idle_secs = tracker.idle_for().as_secs(),
+                        "idle timeout reached, shutting down"
+                    );
+                    shutdown.notify_one();
+                    return;
+                }
+                IdleAction::Hibernate => {
+                    // Mark current activity as seen first so anything from
+                    // here on, including during the disconnect, wakes us.
+                    activity_rx.borrow_and_update();
+                    tracing::info!(
+                        idle_secs = tracker.idle_for().as_secs(),
+                        "idle timeout reached, hibernating messaging adapters"
+                    );
+                    messaging.hibernate(&config.keep_connected).await;
+
+                    if activity_rx.changed().await.is_err() {
+                        return;
+                    }
+                    messaging.wake().await;
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActivityTracker;
+    use std::time::Duration;
+
+    #[test]
+    fn running_workers_keep_the_instance_awake() {
+        let tracker = ActivityTracker::default();
+        assert!(tracker.is_idle(Duration::ZERO));
+
+        tracker.worker_started();
+        assert!(!tracker.is_idle(Duration::ZERO));
+
+        tracker.worker_finished();
+        tracker.worker_finished();
+        assert_eq!(tracker.active_workers(), 0);
+        assert!(!tracker.is_idle(Duration::from_secs(3600)));
+    }
+}
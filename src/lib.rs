@@ -13,6 +13,7 @@ pub mod factory;
 pub mod github_copilot_auth;
 pub mod hooks;
 pub mod identity;
+pub mod idle;
 pub mod links;
 pub mod llm;
 pub mod mcp;
@@ -78,7 +79,7 @@ impl std::fmt::Display for ProcessId {
 }
 
 /// Process types in the system.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum ProcessType {
     Channel,
@@ -145,6 +146,43 @@ pub fn classify_broadcast_recv_result<T>(
     }
 }
 
+/// Milestones in a worker's run, carried by [`ProcessEvent::WorkerLifecycle`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorkerEvent {
+    /// A segment is starting. `index` counts from 1.
+    SegmentStarted {
+        index: usize,
+    },
+    /// Older history was compacted into a marker.
+    Compacted {
+        removed: usize,
+        remaining: usize,
+    },
+    /// The context overflowed; history was compacted and the segment retried.
+    OverflowRetry {
+        attempt: usize,
+    },
+    /// A transient provider error; the segment is retried after a delay.
+    TransientRetry {
+        attempt: usize,
+        delay_secs: u64,
+    },
+    /// The provider was overloaded; the segment is retried after a delay.
+    OverloadedRetry {
+        attempt: usize,
+        delay_secs: u64,
+    },
+    /// The worker was told to wrap up after failing to converge.
+    WrapUp,
+    Completed,
+    Failed {
+        reason: String,
+    },
+    Cancelled,
+    TimedOut,
+}
+
 /// Events sent between processes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -167,6 +205,9 @@ pub enum ProcessEvent {
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
         task: String,
+        /// Human-readable label shown next to the worker ID. Cosmetic; the ID
+        /// stays the key.
+        label: String,
         worker_type: String,
         interactive: bool,
         /// Working directory for the worker (used by OpenCode workers to
@@ -187,6 +228,23 @@ pub enum ProcessEvent {
         worker_id: WorkerId,
         channel_id: Option<ChannelId>,
     },
+    /// A typed milestone in a worker's run, sent alongside the free-form
+    /// `StatusUpdate` strings so consumers don't have to parse them.
+    WorkerLifecycle {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        event: WorkerEvent,
+    },
+    /// A worker decided its task is underspecified and is waiting for the
+    /// originating channel to relay a question to the user. The answer comes
+    /// back through the same input path as interactive follow-ups.
+    WorkerClarification {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        question: String,
+    },
     WorkerComplete {
         agent_id: AgentId,
         worker_id: WorkerId,
@@ -308,6 +366,15 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         text: String,
     },
+    /// Streamed text from a worker's response in progress. Deltas repeat
+    /// what `WorkerText` sends once the response completes.
+    WorkerTextDelta {
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        text_delta: String,
+        aggregated_text: String,
+    },
 }
 
 /// Default broadcast capacity for the per-agent control event bus.
@@ -409,6 +476,10 @@ pub struct AgentDeps {
     /// Sender for injecting messages into channels from outside the normal
     /// inbound message flow (e.g. cross-agent task completion notifications).
     pub injection_tx: tokio::sync::mpsc::Sender<ChannelInjection>,
+    /// How many spawns deep this process sits. Channels, branches, and the
+    /// cortex run at depth 0; every worker runs one level below whatever
+    /// spawned it.
+    pub spawn_depth: usize,
 }
 
 impl AgentDeps {
@@ -423,6 +494,15 @@ impl AgentDeps {
     pub fn routing(&self) -> arc_swap::Guard<Arc<llm::RoutingConfig>> {
         self.runtime_config.routing.load()
     }
+
+    /// Clone of these deps for a worker spawned by this process, one level
+    /// deeper.
+    pub fn for_spawned_worker(&self) -> Self {
+        Self {
+            spawn_depth: self.spawn_depth + 1,
+            ..self.clone()
+        }
+    }
 }
 
 /// A running agent instance with all its isolated resources.
@@ -600,6 +680,9 @@ pub struct Attachment {
 pub struct RoutedResponse {
     pub response: OutboundResponse,
     pub target: InboundMessage,
+    /// ID the response is stored under in `conversation_messages`, when it
+    /// is logged there.
+    pub message_id: Option<String>,
 }
 
 /// A sender that automatically pairs outbound responses with a captured
@@ -616,14 +699,30 @@ impl RoutedSender {
         Self { inner, target }
     }
 
+    /// The message responses are routed back to.
+    pub fn target(&self) -> &InboundMessage {
+        &self.target
+    }
+
     pub async fn send(
         &self,
         response: OutboundResponse,
+    ) -> std::result::Result<(), mpsc::error::SendError<RoutedResponse>> {
+        self.send_logged(response, None).await
+    }
+
+    /// Like [`send`](Self::send), for a response logged to the conversation
+    /// under `message_id`.
+    pub async fn send_logged(
+        &self,
+        response: OutboundResponse,
+        message_id: Option<String>,
     ) -> std::result::Result<(), mpsc::error::SendError<RoutedResponse>> {
         self.inner
             .send(RoutedResponse {
                 response,
                 target: self.target.clone(),
+                message_id,
             })
             .await
     }
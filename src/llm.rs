@@ -2,10 +2,12 @@
 
 pub mod anthropic;
 pub mod manager;
+pub mod marker;
 pub mod model;
 pub mod pricing;
 pub mod providers;
 pub mod routing;
+pub mod tool_quirks;
 
 pub use manager::LlmManager;
 pub use model::SpacebotModel;
@@ -4,6 +4,8 @@ use super::auth::{self, AnthropicAuthPath};
 use super::cache;
 use super::tools;
 
+use crate::llm::routing::SamplingParams;
+
 use reqwest::RequestBuilder;
 use rig::completion::CompletionRequest;
 
@@ -51,6 +53,12 @@ fn messages_url(base_url: &str) -> String {
 ///
 /// `thinking_effort` controls adaptive thinking: "auto" picks max for Opus /
 /// high for others, or pass "max", "high", "medium", "low" explicitly.
+///
+/// `sampling` carries the configured per-process sampling parameters.
+/// Anthropic has no presence/frequency penalties, accepts only one of
+/// temperature and top_p, and requires default sampling with adaptive
+/// thinking, so unsupported values are dropped here.
+#[allow(clippy::too_many_arguments)]
 pub fn build_anthropic_request(
     http_client: &reqwest::Client,
     api_key: &str,
@@ -58,6 +66,7 @@ pub fn build_anthropic_request(
     model_name: &str,
     request: &CompletionRequest,
     thinking_effort: &str,
+    sampling: &SamplingParams,
     force_bearer: bool,
 ) -> AnthropicRequest {
     let is_oauth = auth::detect_auth_path(api_key, force_bearer) == AnthropicAuthPath::OAuthToken;
@@ -80,6 +89,17 @@ pub fn build_anthropic_request(
 
     if let Some(temperature) = request.temperature {
         body["temperature"] = serde_json::json!(temperature);
+    } else if !adaptive_thinking {
+        if let Some(temperature) = sampling.temperature {
+            body["temperature"] = serde_json::json!(temperature.min(1.0));
+        } else if let Some(top_p) = sampling.top_p {
+            body["top_p"] = serde_json::json!(top_p);
+        }
+    } else if sampling.temperature.is_some() || sampling.top_p.is_some() {
+        tracing::debug!(
+            model = model_name,
+            "skipping configured sampling, adaptive thinking requires provider defaults"
+        );
     }
 
     if adaptive_thinking {
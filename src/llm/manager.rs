@@ -449,13 +449,27 @@ impl LlmManager {
         }
     }
 
-    /// Record that a model hit a rate limit.
+    /// Check that `model_name` can be called, i.e. its provider is
+    /// configured. Fails with [`LlmError::UnknownModel`] otherwise.
+    pub fn check_model(&self, model_name: &str) -> Result<()> {
+        let (provider_id, _) = self.resolve_model(model_name)?;
+        if self.get_provider(&provider_id).is_err() {
+            return Err(LlmError::UnknownModel(format!(
+                "{model_name} (provider '{provider_id}' is not configured)"
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Record that a model hit a rate limit, or stayed overloaded through its
+    /// retries, so calls skip it until the cooldown expires.
     pub async fn record_rate_limit(&self, model_name: &str) {
         self.rate_limited
             .write()
             .await
             .insert(model_name.to_string(), Instant::now());
-        tracing::warn!(model = %model_name, "model rate limited, entering cooldown");
+        tracing::warn!(model = %model_name, "model rate limited or overloaded, entering cooldown");
     }
 
     /// Check if a model is currently in rate limit cooldown.
@@ -476,3 +490,24 @@ impl LlmManager {
             .retain(|_, limited_at| limited_at.elapsed().as_secs() < cooldown_secs);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LlmManager;
+
+    #[tokio::test]
+    async fn models_on_unconfigured_providers_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[llm]\nanthropic_key = \"sk-test\"\n").unwrap();
+        let config = crate::config::Config::load_from_path(&path).unwrap();
+        let manager = LlmManager::new(config.llm).await.unwrap();
+
+        manager.check_model("anthropic/claude-sonnet-4").unwrap();
+        let error = manager.check_model("nope/model-x").unwrap_err().to_string();
+        assert!(
+            error.contains("nope/model-x (provider 'nope' is not configured)"),
+            "{error}"
+        );
+    }
+}
@@ -1,7 +1,12 @@
 //! Model routing configuration and resolution.
 
 use crate::ProcessType;
+use crate::llm::tool_quirks::{ToolCallQuirk, pattern_matches};
+
+use chrono::{DateTime, Utc};
+
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Model routing configuration. Lives on the agent config (via defaults).
 /// Determines which LLM model each process type uses, with task-type
@@ -24,14 +29,149 @@ pub struct RoutingConfig {
     /// try the next model in its chain.
     pub fallbacks: HashMap<String, Vec<String>>,
 
-    /// How long to deprioritize a rate-limited model (seconds).
+    /// How long to deprioritize a rate-limited or overloaded model (seconds).
     pub rate_limit_cooldown_secs: u64,
 
+    /// Upper bound on a single provider request (seconds), separate from any
+    /// process-level timeout. A request that stalls past this fails with a
+    /// retriable timeout error. 0 disables the limit.
+    pub request_timeout_secs: u64,
+
     pub channel_thinking_effort: String,
     pub branch_thinking_effort: String,
     pub worker_thinking_effort: String,
     pub compactor_thinking_effort: String,
     pub cortex_thinking_effort: String,
+
+    /// Sampling parameters keyed by process type name ("channel", "branch",
+    /// "worker", "compactor", "cortex"). Process types without an entry use
+    /// provider defaults.
+    pub sampling: HashMap<String, SamplingParams>,
+
+    /// Tool-call quirks keyed by model pattern (`*` matches anything). A
+    /// matching pattern replaces the built-in quirk table for that model.
+    pub tool_call_quirks: HashMap<String, Vec<ToolCallQuirk>>,
+
+    /// Model patterns that can't take image input (`*` matches anything).
+    /// Images sent to a channel on one of these models reach it as a text
+    /// description instead.
+    pub text_only_models: Vec<String>,
+
+    /// Extra phrasings that mark a context overflow error, matched like the
+    /// built-in ones (case-insensitive, anywhere in the error, `*` matches
+    /// anything). For local servers and proxies with their own wording.
+    pub context_overflow_patterns: Vec<String>,
+
+    /// Context windows in tokens keyed by model pattern (`*` matches
+    /// anything), checked before the built-in table. Models matched by
+    /// neither use the agent's `context_window`.
+    pub context_windows: HashMap<String, usize>,
+}
+
+/// Context windows of common models, most specific pattern first; the first
+/// match wins. Local runtimes may be configured with less than the model
+/// supports, which `context_windows` can record.
+const BUILTIN_CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("*claude*", 200_000),
+    ("*gpt-4.1*", 1_047_576),
+    ("*gpt-5*", 400_000),
+    ("*gpt-4o*", 128_000),
+    ("*gpt-4-turbo*", 128_000),
+    ("*gpt-3.5-turbo*", 16_385),
+    ("*/o1*", 200_000),
+    ("*/o3*", 200_000),
+    ("*/o4-mini*", 200_000),
+    ("*gpt-oss*", 131_072),
+    ("*gemini-2.5*", 1_048_576),
+    ("*gemini-2.0*", 1_048_576),
+    ("*deepseek*", 128_000),
+    ("*llama3.1*", 131_072),
+    ("*llama-3.1*", 131_072),
+    ("*llama3.2*", 131_072),
+    ("*llama3.3*", 131_072),
+    ("*llama-3.3*", 131_072),
+    ("*llama3*", 8_192),
+    ("*qwen3*", 32_768),
+    ("*qwen2.5*", 32_768),
+    ("*mistral-large*", 131_072),
+    ("*mistral*", 32_768),
+    ("*mixtral*", 32_768),
+    ("*gemma3*", 131_072),
+    ("*gemma2*", 8_192),
+    ("*phi3*", 4_096),
+];
+
+/// Popular models without image input.
+const TEXT_ONLY_MODELS: &[&str] = &[
+    "deepseek/*",
+    "*deepseek-chat*",
+    "*deepseek-r1*",
+    "*deepseek-v3*",
+    "*gpt-oss*",
+    "*codestral*",
+    "*qwen3-coder*",
+    "*kimi-k2*",
+    "*minimax-m2*",
+];
+
+/// Optional sampling parameters applied to every completion request a process
+/// makes. Unset values leave the provider default in place, and providers
+/// that don't accept a parameter never receive it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SamplingParams {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub presence_penalty: Option<f64>,
+    pub frequency_penalty: Option<f64>,
+}
+
+impl SamplingParams {
+    /// Clamp each set value into the range providers accept, warning about
+    /// every value that had to move. `label` names the config entry in the
+    /// warning.
+    pub fn clamped(self, label: &str) -> Self {
+        Self {
+            temperature: clamp_sampling(label, "temperature", self.temperature, 0.0, 2.0),
+            top_p: clamp_sampling(label, "top_p", self.top_p, 0.0, 1.0),
+            presence_penalty: clamp_sampling(
+                label,
+                "presence_penalty",
+                self.presence_penalty,
+                -2.0,
+                2.0,
+            ),
+            frequency_penalty: clamp_sampling(
+                label,
+                "frequency_penalty",
+                self.frequency_penalty,
+                -2.0,
+                2.0,
+            ),
+        }
+    }
+}
+
+fn clamp_sampling(label: &str, name: &str, value: Option<f64>, min: f64, max: f64) -> Option<f64> {
+    let value = value?;
+    if value.is_nan() {
+        tracing::warn!(
+            process_type = label,
+            parameter = name,
+            "sampling value is NaN, ignoring"
+        );
+        return None;
+    }
+    let clamped = value.clamp(min, max);
+    if clamped != value {
+        tracing::warn!(
+            process_type = label,
+            parameter = name,
+            configured = value,
+            clamped,
+            "sampling value out of range, clamping"
+        );
+    }
+    Some(clamped)
 }
 
 impl Default for RoutingConfig {
@@ -53,16 +193,27 @@ impl RoutingConfig {
             task_overrides: HashMap::new(),
             fallbacks: HashMap::new(),
             rate_limit_cooldown_secs: 60,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
             channel_thinking_effort: "auto".into(),
             branch_thinking_effort: "auto".into(),
             worker_thinking_effort: "auto".into(),
             compactor_thinking_effort: "auto".into(),
             cortex_thinking_effort: "auto".into(),
+            sampling: HashMap::new(),
+            tool_call_quirks: HashMap::new(),
+            text_only_models: TEXT_ONLY_MODELS.iter().map(|&p| p.to_string()).collect(),
+            context_windows: HashMap::new(),
+            context_overflow_patterns: Vec::new(),
         }
     }
 }
 
 impl RoutingConfig {
+    /// Per-request timeout, or `None` when disabled.
+    pub fn request_timeout(&self) -> Option<Duration> {
+        (self.request_timeout_secs > 0).then(|| Duration::from_secs(self.request_timeout_secs))
+    }
+
     /// Resolve the model name for a process type and optional task type.
     pub fn resolve(&self, process_type: ProcessType, task_type: Option<&str>) -> &str {
         // Check task-type override first (only for workers and branches)
@@ -101,6 +252,93 @@ impl RoutingConfig {
         "auto"
     }
 
+    /// Sampling parameters for a process type name, empty when none are
+    /// configured.
+    pub fn sampling_for(&self, process_type: &str) -> SamplingParams {
+        self.sampling.get(process_type).copied().unwrap_or_default()
+    }
+
+    /// Whether `model_name` accepts images, going by `text_only_models`.
+    pub fn supports_vision(&self, model_name: &str) -> bool {
+        !self
+            .text_only_models
+            .iter()
+            .any(|pattern| pattern_matches(pattern, model_name))
+    }
+
+    /// Context window of `model_name` in tokens: a configured
+    /// `context_windows` entry (the longest matching pattern), else the
+    /// built-in table, else `default`.
+    pub fn context_window_for(&self, model_name: &str, default: usize) -> usize {
+        let configured = self
+            .context_windows
+            .iter()
+            .filter(|(pattern, _)| pattern_matches(pattern, model_name))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, &window)| window);
+        configured
+            .or_else(|| {
+                BUILTIN_CONTEXT_WINDOWS
+                    .iter()
+                    .find(|(pattern, _)| pattern_matches(pattern, model_name))
+                    .map(|&(_, window)| window)
+            })
+            .filter(|&window| window > 0)
+            .unwrap_or(default)
+    }
+
+    /// Smallest context window among `model_name` and the fallbacks that may
+    /// serve its calls. History sized to this fits whichever model in the
+    /// chain ends up answering.
+    pub fn chain_context_window(&self, model_name: &str, default: usize) -> usize {
+        std::iter::once(model_name)
+            .chain(
+                self.get_fallbacks(model_name)
+                    .iter()
+                    .take(MAX_FALLBACK_ATTEMPTS)
+                    .map(String::as_str),
+            )
+            .map(|model| self.context_window_for(model, default))
+            .min()
+            .unwrap_or(default)
+    }
+
+    /// Whether `error_message` means the request overflowed the context
+    /// window, counting `context_overflow_patterns`.
+    pub fn is_context_overflow(&self, error_message: &str) -> bool {
+        is_context_overflow_error_with(error_message, &self.context_overflow_patterns)
+    }
+
+    /// Whether a completion that failed on `failed_model` should move on to
+    /// `next_model` in the fallback chain. Errors caused by the request
+    /// (context overflow, counting `context_overflow_patterns`, or a
+    /// malformed request) would fail the same way on any model, so they end
+    /// the chain; context overflow has its own recovery in the caller.
+    /// Account errors (auth, billing) end it only when `next_model` is on
+    /// the same provider. Everything else, such as availability errors or a
+    /// missing model, falls back.
+    pub fn should_fall_back(
+        &self,
+        error_message: &str,
+        failed_model: &str,
+        next_model: &str,
+    ) -> bool {
+        let lower = error_message.to_lowercase();
+        let request_error = self.is_context_overflow(error_message)
+            || lower.contains("400 bad request")
+            || lower.contains("invalid_request_error");
+        if request_error {
+            return false;
+        }
+        let account_error = lower.contains("401 unauthorized")
+            || lower.contains("402 payment required")
+            || lower.contains("403 forbidden")
+            || lower.contains("authentication_error")
+            || lower.contains("insufficient credits")
+            || lower.contains("billing");
+        !account_error || provider_from_model(failed_model) != provider_from_model(next_model)
+    }
+
     /// Get the fallback chain for a model, if any.
     pub fn get_fallbacks(&self, model_name: &str) -> &[String] {
         self.fallbacks
@@ -112,7 +350,7 @@ impl RoutingConfig {
 
 /// Whether an HTTP status code should trigger a fallback to the next model.
 pub fn is_retriable_status(status: u16) -> bool {
-    matches!(status, 429 | 502 | 503 | 504)
+    matches!(status, 429 | 502 | 503 | 504 | 529)
 }
 
 /// Whether a completion error message indicates a retriable failure.
@@ -124,6 +362,7 @@ pub fn is_retriable_error(error_message: &str) -> bool {
         || lower.contains("502")
         || lower.contains("503")
         || lower.contains("504")
+        || lower.contains("529")
         || lower.contains("rate limit")
         || lower.contains("overloaded")
         || lower.contains("timeout")
@@ -137,23 +376,77 @@ pub fn is_retriable_error(error_message: &str) -> bool {
         || lower.contains("empty response")
         || lower.contains("failed to read response body")
         || lower.contains("error decoding response body")
+        // Streams cut off mid-response carry no usable content
+        || lower.contains("incomplete response")
+        || lower.contains("stream read failed")
 }
 
+/// Whether a completion error means the provider is overloaded (503, or
+/// Anthropic's 529 `overloaded_error`).
+///
+/// Distinct from rate limits: the request itself was fine and nothing about
+/// our usage needs to change, the provider just can't take it right now.
+/// Workers retry these on their own counter with a longer backoff, and a
+/// model that stays overloaded through its retries is put in cooldown so
+/// the fallback chain takes over.
+pub fn is_overloaded_error(error_message: &str) -> bool {
+    let lower = error_message.to_lowercase();
+    lower.contains("overloaded")
+        || lower.contains("503")
+        || lower.contains("529")
+        || lower.contains("service unavailable")
+        || lower.contains("temporarily unavailable")
+}
+
+/// Phrasings providers use for a request over the model's context limit.
+/// Each is matched case-insensitively anywhere in the error, with `*`
+/// matching any run of characters.
+const BUILTIN_CONTEXT_OVERFLOW_PATTERNS: &[&str] = &[
+    // OpenAI, vLLM, Mistral: "maximum context length is ...",
+    // "context_length_exceeded"
+    "context length",
+    "context_length",
+    "maximum context",
+    "context window",
+    // Anthropic: "prompt is too long: 208391 tokens > 200000 maximum"
+    "prompt is too long",
+    "prompt too long",
+    "input is too long",
+    // Ollama: "input length exceeds the context length"
+    "input length exceeds",
+    // llama.cpp: "the request exceeds the available context size"
+    "context size",
+    "token limit",
+    "too many tokens",
+    "request too large",
+    "content_too_large",
+    "max_tokens",
+    // Gemini: "exceeds the maximum number of tokens allowed"
+    "maximum*tokens",
+];
+
 /// Whether a completion error indicates context window overflow.
 ///
 /// Providers return 400 with various phrasings when the request exceeds
 /// the model's context limit. Checking for these lets workers compact
 /// and retry instead of dying.
 pub fn is_context_overflow_error(error_message: &str) -> bool {
-    let lower = error_message.to_lowercase();
-    lower.contains("context length")
-        || lower.contains("maximum context")
-        || lower.contains("token limit")
-        || lower.contains("too many tokens")
-        || lower.contains("request too large")
-        || lower.contains("content_too_large")
-        || lower.contains("max_tokens")
-        || (lower.contains("maximum") && lower.contains("tokens"))
+    is_context_overflow_error_with(error_message, &[])
+}
+
+/// [`is_context_overflow_error`] with `extra_patterns` (from
+/// `context_overflow_patterns`) checked alongside the built-in ones. Empty
+/// patterns are ignored rather than matching every error.
+pub fn is_context_overflow_error_with(error_message: &str, extra_patterns: &[String]) -> bool {
+    let overflow_pattern_matches = |pattern: &str| {
+        !pattern.is_empty() && pattern_matches(&format!("*{pattern}*"), error_message)
+    };
+    BUILTIN_CONTEXT_OVERFLOW_PATTERNS
+        .iter()
+        .any(|pattern| overflow_pattern_matches(pattern))
+        || extra_patterns
+            .iter()
+            .any(|pattern| overflow_pattern_matches(pattern.trim()))
 }
 
 /// Returns routing defaults appropriate for a given provider.
@@ -463,6 +756,15 @@ pub const MAX_RETRIES_PER_MODEL: usize = 3;
 /// Base delay for exponential backoff between retries (milliseconds).
 pub const RETRY_BASE_DELAY_MS: u64 = 500;
 
+/// Default upper bound on a single provider request (seconds). Also applied
+/// to models called without a routing config.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 600;
+
+/// Longest provider-requested `Retry-After` wait honored in place. Longer
+/// waits skip the remaining retries for that model so the fallback chain
+/// gets a chance instead of stalling the caller.
+pub const MAX_RETRY_AFTER_WAIT: Duration = Duration::from_secs(30);
+
 /// Whether an error indicates an actual rate limit (429) vs other transient failures.
 /// Only rate-limit errors should trigger cooldown — timeouts and 5xx errors are
 /// momentary and shouldn't lock out a model for the full cooldown period.
@@ -470,3 +772,319 @@ pub fn is_rate_limit_error(error_message: &str) -> bool {
     let lower = error_message.to_lowercase();
     lower.contains("429") || lower.contains("rate limit")
 }
+
+/// Extract a provider-requested retry delay from a `Retry-After` header value
+/// or from an error message that embeds one (e.g. `"... (retry-after: 30)"`).
+///
+/// Handles both delta-seconds (`"30"`, `"1.5"`) and HTTP-date
+/// (`"Wed, 21 Oct 2015 07:28:00 GMT"`) formats. Dates in the past yield a
+/// zero delay. Returns `None` when no retry hint is present.
+pub fn parse_retry_after(error_or_header: &str) -> Option<Duration> {
+    parse_retry_after_at(error_or_header, Utc::now())
+}
+
+fn parse_retry_after_at(text: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let text = text.trim();
+    if let Some(delay) = parse_retry_after_value(text, now) {
+        return Some(delay);
+    }
+
+    // ASCII lowercasing keeps byte offsets aligned with the original text.
+    let lower = text.to_ascii_lowercase();
+    ["retry-after", "retry_after", "retry after"]
+        .iter()
+        .filter_map(|marker| {
+            let index = lower.find(marker)?;
+            let rest = text[index + marker.len()..].trim_start_matches(|c: char| {
+                c == ':' || c == '=' || c == '"' || c == '\'' || c.is_whitespace()
+            });
+            extract_retry_after_value(rest, now)
+        })
+        .next()
+}
+
+/// Parse the value at the start of `rest`, ignoring whatever follows it.
+fn extract_retry_after_value(rest: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let seconds_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+    if seconds_len > 0 {
+        return parse_retry_after_value(&rest[..seconds_len], now);
+    }
+
+    let date_end = rest.find("GMT")? + "GMT".len();
+    parse_retry_after_value(&rest[..date_end], now)
+}
+
+fn parse_retry_after_value(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    if let Ok(seconds) = value.parse::<f64>() {
+        // Rejects negative, non-finite and out-of-range values rather than
+        // panicking on a hostile header.
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_overflow_is_detected_across_providers() {
+        for error in [
+            r#"OpenAI API error (400 Bad Request): {"error":{"message":"This model's maximum context length is 128000 tokens. However, your messages resulted in 131245 tokens. Please reduce the length of the messages.","type":"invalid_request_error","param":"messages","code":"context_length_exceeded"}}"#,
+            r#"Anthropic API error (400 Bad Request): {"type":"error","error":{"type":"invalid_request_error","message":"prompt is too long: 208391 tokens > 200000 maximum"}}"#,
+            r#"Mistral API error (400 Bad Request): {"object":"error","message":"Prompt contains 40213 tokens and 0 draft tokens, too large for model with 32768 maximum context length","type":"invalid_request_message_error"}"#,
+            r#"Ollama API error (400 Bad Request): {"error":"input length exceeds the context length"}"#,
+            r#"llama.cpp API error (400 Bad Request): {"error":{"code":400,"message":"the request exceeds the available context size, try increasing it","type":"exceed_context_size_error","n_prompt_tokens":9012,"n_ctx":8192}}"#,
+            "Gemini API error (400 Bad Request): The input token count (1523422) exceeds the maximum number of tokens allowed (1048576).",
+        ] {
+            assert!(is_context_overflow_error(error), "{error}");
+        }
+        for error in [
+            "",
+            r#"Anthropic API error (529 <unknown status code>): {"type":"overloaded_error","message":"Overloaded"}"#,
+            "OpenAI API error (401 Unauthorized): Incorrect API key provided",
+            "provider request timeout: no response after 120s",
+            "OpenAI API error (429 Too Many Requests): Rate limit reached for gpt-4o on requests per min (RPM): Limit 500",
+        ] {
+            assert!(!is_context_overflow_error(error), "{error}");
+        }
+    }
+
+    #[test]
+    fn configured_overflow_patterns_extend_the_built_in_ones() {
+        let error = "proxy error (413): upstream rejected body: KV cache full";
+        let mut routing = RoutingConfig::default();
+        assert!(!routing.is_context_overflow(error));
+
+        routing.context_overflow_patterns = vec!["".into(), "  ".into(), "kv * FULL".into()];
+        assert!(routing.is_context_overflow(error));
+        assert!(routing.is_context_overflow("prompt is too long: 9000 tokens"));
+        assert!(!routing.is_context_overflow("upstream connect error"));
+    }
+
+    #[test]
+    fn request_errors_end_the_fallback_chain() {
+        let routing = RoutingConfig::default();
+        for error in [
+            "Anthropic API error (400 Bad Request): prompt is too long: 210000 tokens > 200000 maximum",
+            "OpenAI API error (400 Bad Request): Invalid 'messages[3].content'",
+            "invalid_request_error: tools.0.name: String should match pattern",
+        ] {
+            assert!(
+                !routing.should_fall_back(error, "anthropic/claude-sonnet-4", "openai/gpt-4.1"),
+                "{error}"
+            );
+        }
+        for error in [
+            "Anthropic API error (529 <unknown status code>): overloaded",
+            "OpenRouter API error (404 Not Found): no endpoints found for model",
+            "provider request timeout: no response after 120s",
+        ] {
+            assert!(
+                routing.should_fall_back(
+                    error,
+                    "anthropic/claude-sonnet-4",
+                    "anthropic/claude-haiku-4.5"
+                ),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn configured_overflow_patterns_end_the_fallback_chain() {
+        let error = "proxy error (413): upstream rejected body: KV cache full";
+        let mut routing = RoutingConfig::default();
+        assert!(routing.should_fall_back(error, "openai/gpt-4.1", "anthropic/claude-sonnet-4"));
+
+        routing.context_overflow_patterns = vec!["kv * full".into()];
+        assert!(!routing.should_fall_back(error, "openai/gpt-4.1", "anthropic/claude-sonnet-4"));
+    }
+
+    #[test]
+    fn account_errors_fall_back_only_to_another_provider() {
+        let routing = RoutingConfig::default();
+        for error in [
+            "OpenAI API error (401 Unauthorized): invalid api key",
+            "OpenRouter API error (402 Payment Required): insufficient credits",
+            "Anthropic API error (401): authentication_error",
+            "Anthropic API error: your credit balance is too low, check billing",
+        ] {
+            assert!(
+                !routing.should_fall_back(error, "openai/gpt-4.1", "openai/gpt-4.1-mini"),
+                "{error}"
+            );
+            assert!(
+                routing.should_fall_back(error, "openai/gpt-4.1", "anthropic/claude-sonnet-4"),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn sampling_values_are_clamped_into_range() {
+        let sampling = SamplingParams {
+            temperature: Some(3.5),
+            top_p: Some(0.9),
+            presence_penalty: Some(-4.0),
+            frequency_penalty: Some(f64::NAN),
+        }
+        .clamped("channel");
+
+        assert_eq!(
+            sampling,
+            SamplingParams {
+                temperature: Some(2.0),
+                top_p: Some(0.9),
+                presence_penalty: Some(-2.0),
+                frequency_penalty: None,
+            }
+        );
+    }
+
+    #[test]
+    fn context_windows_come_from_config_then_table_then_default() {
+        let mut routing = RoutingConfig::default();
+        assert_eq!(
+            routing.context_window_for("anthropic/claude-sonnet-4", 128_000),
+            200_000
+        );
+        assert_eq!(routing.context_window_for("openai/gpt-4o-mini", 1), 128_000);
+        assert_eq!(routing.context_window_for("ollama/llama3.1:8b", 1), 131_072);
+        assert_eq!(routing.context_window_for("ollama/llama3:8b", 1), 8_192);
+        assert_eq!(
+            routing.context_window_for("custom/in-house", 64_000),
+            64_000
+        );
+
+        routing.context_windows = HashMap::from([
+            ("ollama/*".to_string(), 16_384),
+            ("ollama/llama3.1*".to_string(), 32_768),
+        ]);
+        assert_eq!(routing.context_window_for("ollama/llama3.1:8b", 1), 32_768);
+        assert_eq!(routing.context_window_for("ollama/qwen2.5", 1), 16_384);
+        assert_eq!(
+            routing.context_window_for("anthropic/claude-sonnet-4", 1),
+            200_000
+        );
+    }
+
+    #[test]
+    fn a_chain_is_measured_by_its_smallest_window() {
+        let mut routing = RoutingConfig::default();
+        assert_eq!(
+            routing.chain_context_window("anthropic/claude-sonnet-4", 1),
+            200_000
+        );
+
+        routing.fallbacks = HashMap::from([(
+            "anthropic/claude-sonnet-4".to_string(),
+            vec![
+                "openai/gpt-4o-mini".to_string(),
+                "ollama/llama3:8b".to_string(),
+            ],
+        )]);
+        assert_eq!(
+            routing.chain_context_window("anthropic/claude-sonnet-4", 1),
+            8_192
+        );
+        assert_eq!(
+            routing.chain_context_window("openai/gpt-4o-mini", 1),
+            128_000
+        );
+    }
+
+    #[test]
+    fn text_only_models_are_matched_by_pattern() {
+        let mut routing = RoutingConfig::default();
+        assert!(routing.supports_vision("anthropic/claude-sonnet-4"));
+        assert!(!routing.supports_vision("deepseek/deepseek-chat"));
+        assert!(!routing.supports_vision("openrouter/openai/gpt-oss-120b"));
+
+        routing.text_only_models = vec!["ollama/*".into()];
+        assert!(routing.supports_vision("deepseek/deepseek-chat"));
+        assert!(!routing.supports_vision("ollama/llama3.1"));
+    }
+
+    fn fixed_now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .expect("valid date")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_delta_seconds() {
+        assert_eq!(
+            parse_retry_after_at("120", fixed_now()),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after_at("1.5", fixed_now()),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_accepts_http_date() {
+        assert_eq!(
+            parse_retry_after_at("Wed, 21 Oct 2015 07:28:45 GMT", fixed_now()),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(
+            parse_retry_after_at("Wed, 21 Oct 2015 07:27:00 GMT", fixed_now()),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_extracts_from_error_strings() {
+        let seconds = "Anthropic API error (429 Too Many Requests): rate limited (retry-after: 20)";
+        assert_eq!(
+            parse_retry_after_at(seconds, fixed_now()),
+            Some(Duration::from_secs(20))
+        );
+
+        let date = "OpenAI API error (429) (Retry-After: Wed, 21 Oct 2015 07:29:00 GMT)";
+        assert_eq!(
+            parse_retry_after_at(date, fixed_now()),
+            Some(Duration::from_secs(60))
+        );
+
+        let json = r#"{"error": {"retry_after": 7, "message": "slow down"}}"#;
+        assert_eq!(
+            parse_retry_after_at(json, fixed_now()),
+            Some(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_returns_none_without_hint() {
+        assert_eq!(
+            parse_retry_after_at("Anthropic API error (429): rate limited", fixed_now()),
+            None
+        );
+        assert_eq!(parse_retry_after_at("", fixed_now()), None);
+        assert_eq!(parse_retry_after_at("retry-after: soon", fixed_now()), None);
+    }
+
+    #[test]
+    fn parse_retry_after_rejects_out_of_range_seconds() {
+        assert_eq!(
+            parse_retry_after_at("99999999999999999999999", fixed_now()),
+            None
+        );
+        assert_eq!(
+            parse_retry_after_at("retry-after: 99999999999999999999999", fixed_now()),
+            None
+        );
+        assert_eq!(parse_retry_after_at("-5", fixed_now()), None);
+    }
+}
@@ -0,0 +1,457 @@
+//! Repairs for models that emit tool calls in nonstandard shapes.
+//!
+//! Older and local models often get tool calling almost right: the arguments
+//! arrive as a JSON string instead of an object, the call is written into the
+//! text as `<tool_call>` tags, or the tool name carries a namespace. Rig only
+//! dispatches well-formed [`ToolCall`] content, so these calls would silently
+//! never run. Each [`ToolCallQuirk`] names one such shape, and
+//! [`normalize_tool_calls`] rewrites a response's content before rig sees it.
+//! Which quirks apply to a model comes from a built-in table that
+//! `[routing.tool_call_quirks]` can extend or override.
+
+use rig::message::{AssistantContent, Text, ToolCall, ToolFunction};
+use rig::one_or_many::OneOrMany;
+use std::collections::HashMap;
+
+/// A known way a model's tool calls deviate from the standard format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolCallQuirk {
+    /// Arguments are a JSON-encoded string, possibly encoded twice, instead
+    /// of an object.
+    StringifiedArguments,
+    /// Calls are written into the text as `<tool_call>{...}</tool_call>` or
+    /// `<function=name>{...}</function>` instead of native tool calls.
+    XmlToolTags,
+    /// The whole text reply is a `{"name": ..., "arguments": ...}` object,
+    /// optionally in a code fence.
+    JsonInText,
+    /// Tool names carry a namespace or channel suffix, like
+    /// `functions.shell` or `shell<|channel|>commentary`.
+    NamespacedNames,
+}
+
+impl ToolCallQuirk {
+    /// Parse a quirk from its config name.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stringified_arguments" => Some(Self::StringifiedArguments),
+            "xml_tool_tags" => Some(Self::XmlToolTags),
+            "json_in_text" => Some(Self::JsonInText),
+            "namespaced_names" => Some(Self::NamespacedNames),
+            _ => None,
+        }
+    }
+}
+
+/// Model patterns with quirks they are known to need. `*` matches any run of
+/// characters, and matching ignores case.
+const BUILTIN_QUIRKS: &[(&str, &[ToolCallQuirk])] = &[
+    ("ollama/*", &[ToolCallQuirk::StringifiedArguments]),
+    ("*qwen*", &[ToolCallQuirk::XmlToolTags]),
+    ("*hermes*", &[ToolCallQuirk::XmlToolTags]),
+    (
+        "*llama3*",
+        &[ToolCallQuirk::XmlToolTags, ToolCallQuirk::JsonInText],
+    ),
+    (
+        "*llama-3*",
+        &[ToolCallQuirk::XmlToolTags, ToolCallQuirk::JsonInText],
+    ),
+    ("*gpt-oss*", &[ToolCallQuirk::NamespacedNames]),
+];
+
+/// Quirks to repair for `model`. Configured patterns that match take the
+/// place of the built-in table, so an empty list turns repairs off.
+pub fn quirks_for(
+    model: &str,
+    overrides: Option<&HashMap<String, Vec<ToolCallQuirk>>>,
+) -> Vec<ToolCallQuirk> {
+    let mut quirks = Vec::new();
+    let mut overridden = false;
+    for (pattern, configured) in overrides.into_iter().flatten() {
+        if pattern_matches(pattern, model) {
+            overridden = true;
+            quirks.extend(configured.iter().copied());
+        }
+    }
+    if !overridden {
+        for (pattern, builtin) in BUILTIN_QUIRKS {
+            if pattern_matches(pattern, model) {
+                quirks.extend(builtin.iter().copied());
+            }
+        }
+    }
+    quirks.sort_by_key(|quirk| *quirk as u8);
+    quirks.dedup();
+    quirks
+}
+
+/// Case-insensitive match of `name` against a pattern where `*` matches any
+/// run of characters.
+pub(crate) fn pattern_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Rewrite `choice` so calls in a quirky shape become standard tool calls.
+/// Calls recovered from text are only kept when they name one of
+/// `tool_names`, so ordinary prose that happens to look like a call stays
+/// text. Returns how many calls were repaired.
+pub fn normalize_tool_calls(
+    choice: &mut OneOrMany<AssistantContent>,
+    quirks: &[ToolCallQuirk],
+    tool_names: &[String],
+) -> usize {
+    if quirks.is_empty() {
+        return 0;
+    }
+    let mut repaired = 0;
+    let mut content = Vec::new();
+
+    for item in choice.iter() {
+        match item {
+            AssistantContent::ToolCall(call) => {
+                let mut call = call.clone();
+                if quirks.contains(&ToolCallQuirk::StringifiedArguments)
+                    && let Some(arguments) = decode_stringified(&call.function.arguments)
+                {
+                    call.function.arguments = arguments;
+                    repaired += 1;
+                }
+                if quirks.contains(&ToolCallQuirk::NamespacedNames)
+                    && let Some(name) = strip_namespace(&call.function.name, tool_names)
+                {
+                    call.function.name = name;
+                    repaired += 1;
+                }
+                content.push(AssistantContent::ToolCall(call));
+            }
+            AssistantContent::Text(text) => {
+                let (remaining, calls) = extract_text_calls(&text.text, quirks, tool_names);
+                if calls.is_empty() {
+                    content.push(item.clone());
+                    continue;
+                }
+                repaired += calls.len();
+                if !remaining.trim().is_empty() {
+                    content.push(AssistantContent::Text(Text {
+                        text: remaining.trim().to_string(),
+                    }));
+                }
+                content.extend(calls.into_iter().map(AssistantContent::ToolCall));
+            }
+            other => content.push(other.clone()),
+        }
+    }
+
+    if repaired > 0
+        && let Ok(normalized) = OneOrMany::many(content)
+    {
+        *choice = normalized;
+    }
+    repaired
+}
+
+/// Decode arguments that arrived as a JSON string into the object inside.
+fn decode_stringified(arguments: &serde_json::Value) -> Option<serde_json::Value> {
+    let mut value = arguments.clone();
+    // Two rounds covers arguments that were encoded twice.
+    for _ in 0..2 {
+        let serde_json::Value::String(raw) = &value else {
+            break;
+        };
+        value = serde_json::from_str(raw).ok()?;
+    }
+    (value.is_object() && &value != arguments).then_some(value)
+}
+
+/// Known tool name hidden behind a namespace prefix or channel suffix.
+fn strip_namespace(name: &str, tool_names: &[String]) -> Option<String> {
+    if tool_names.iter().any(|tool| tool == name) {
+        return None;
+    }
+    let unsuffixed = name.split("<|").next().unwrap_or(name);
+    let bare = unsuffixed.rsplit('.').next().unwrap_or(unsuffixed).trim();
+    tool_names
+        .iter()
+        .any(|tool| tool == bare)
+        .then(|| bare.to_string())
+}
+
+/// Pull tool calls out of reply text, returning the text that's left.
+fn extract_text_calls(
+    text: &str,
+    quirks: &[ToolCallQuirk],
+    tool_names: &[String],
+) -> (String, Vec<ToolCall>) {
+    let mut remaining = text.to_string();
+    let mut calls = Vec::new();
+
+    if quirks.contains(&ToolCallQuirk::XmlToolTags) {
+        remaining = extract_tagged_calls(&remaining, "<tool_call>", "</tool_call>", &mut calls);
+        remaining = extract_function_tag_calls(&remaining, &mut calls);
+    }
+    if quirks.contains(&ToolCallQuirk::JsonInText)
+        && calls.is_empty()
+        && let Some(call) = parse_call_object(strip_code_fence(&remaining))
+    {
+        calls.push(call);
+        remaining.clear();
+    }
+
+    // Anything that doesn't name a real tool was prose after all.
+    if calls
+        .iter()
+        .any(|call| !tool_names.contains(&call.function.name))
+    {
+        return (text.to_string(), Vec::new());
+    }
+    (remaining, calls)
+}
+
+/// Replace every `open{json}close` block that parses as a call.
+fn extract_tagged_calls(text: &str, open: &str, close: &str, calls: &mut Vec<ToolCall>) -> String {
+    let mut remaining = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(open) {
+        let body_start = start + open.len();
+        let Some(body_len) = rest[body_start..].find(close) else {
+            break;
+        };
+        match parse_call_object(&rest[body_start..body_start + body_len]) {
+            Some(call) => {
+                remaining.push_str(&rest[..start]);
+                calls.push(call);
+            }
+            None => remaining.push_str(&rest[..body_start + body_len + close.len()]),
+        }
+        rest = &rest[body_start + body_len + close.len()..];
+    }
+    remaining.push_str(rest);
+    remaining
+}
+
+/// Replace every `<function=name>{json}</function>` block.
+fn extract_function_tag_calls(text: &str, calls: &mut Vec<ToolCall>) -> String {
+    const OPEN: &str = "<function=";
+    const CLOSE: &str = "</function>";
+    let mut remaining = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        let name_start = start + OPEN.len();
+        let (Some(name_len), Some(end)) = (rest[name_start..].find('>'), rest.find(CLOSE)) else {
+            break;
+        };
+        let body_start = name_start + name_len + 1;
+        if end < body_start {
+            break;
+        }
+        let name = rest[name_start..name_start + name_len].trim();
+        let arguments = serde_json::from_str::<serde_json::Value>(rest[body_start..end].trim())
+            .ok()
+            .filter(serde_json::Value::is_object);
+        match arguments {
+            Some(arguments) if !name.is_empty() => {
+                remaining.push_str(&rest[..start]);
+                calls.push(new_tool_call(name, arguments));
+            }
+            _ => remaining.push_str(&rest[..end + CLOSE.len()]),
+        }
+        rest = &rest[end + CLOSE.len()..];
+    }
+    remaining.push_str(rest);
+    remaining
+}
+
+/// Parse `{"name": ..., "arguments": {...}}` (or `"parameters"`).
+fn parse_call_object(raw: &str) -> Option<ToolCall> {
+    let value: serde_json::Value = serde_json::from_str(raw.trim()).ok()?;
+    let name = value.get("name")?.as_str()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let arguments = value
+        .get("arguments")
+        .or_else(|| value.get("parameters"))
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    let arguments = decode_stringified(&arguments).unwrap_or(arguments);
+    Some(new_tool_call(name, arguments))
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(inner) = trimmed
+        .strip_prefix("```")
+        .and_then(|inner| inner.strip_suffix("```"))
+    else {
+        return trimmed;
+    };
+    // Drop the language tag on the opening fence line.
+    inner
+        .split_once('\n')
+        .map_or(inner, |(_, body)| body)
+        .trim()
+}
+
+fn new_tool_call(name: &str, arguments: serde_json::Value) -> ToolCall {
+    ToolCall {
+        id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+        call_id: None,
+        function: ToolFunction {
+            name: name.to_string(),
+            arguments,
+        },
+        signature: None,
+        additional_params: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ToolCallQuirk, normalize_tool_calls, quirks_for};
+    use rig::message::{AssistantContent, ToolCall, ToolFunction};
+    use rig::one_or_many::OneOrMany;
+    use std::collections::HashMap;
+
+    fn tools() -> Vec<String> {
+        vec!["shell".to_string(), "file_read".to_string()]
+    }
+
+    fn call(name: &str, arguments: serde_json::Value) -> AssistantContent {
+        AssistantContent::ToolCall(ToolCall {
+            id: "call_1".to_string(),
+            call_id: None,
+            function: ToolFunction {
+                name: name.to_string(),
+                arguments,
+            },
+            signature: None,
+            additional_params: None,
+        })
+    }
+
+    fn tool_calls(choice: &OneOrMany<AssistantContent>) -> Vec<(String, serde_json::Value)> {
+        choice
+            .iter()
+            .filter_map(|item| match item {
+                AssistantContent::ToolCall(call) => {
+                    Some((call.function.name.clone(), call.function.arguments.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stringified_arguments_are_decoded() {
+        let double = serde_json::json!("\"{\\\"command\\\":\\\"ls\\\"}\"");
+        let mut choice = OneOrMany::one(call("shell", double));
+        let quirks = [ToolCallQuirk::StringifiedArguments];
+        assert_eq!(normalize_tool_calls(&mut choice, &quirks, &tools()), 1);
+        assert_eq!(
+            tool_calls(&choice),
+            vec![("shell".to_string(), serde_json::json!({"command": "ls"}))]
+        );
+
+        // Well-formed arguments are left alone.
+        let mut choice = OneOrMany::one(call("shell", serde_json::json!({"command": "ls"})));
+        assert_eq!(normalize_tool_calls(&mut choice, &quirks, &tools()), 0);
+    }
+
+    #[test]
+    fn xml_tool_tags_become_tool_calls() {
+        let text = "Let me look.\n<tool_call>\n{\"name\": \"shell\", \"arguments\": \
+                    {\"command\": \"ls\"}}\n</tool_call>\n\
+                    <function=file_read>{\"path\": \"a.txt\"}</function>";
+        let mut choice = OneOrMany::one(AssistantContent::text(text));
+        let quirks = [ToolCallQuirk::XmlToolTags];
+        assert_eq!(normalize_tool_calls(&mut choice, &quirks, &tools()), 2);
+        assert_eq!(
+            tool_calls(&choice),
+            vec![
+                ("shell".to_string(), serde_json::json!({"command": "ls"})),
+                (
+                    "file_read".to_string(),
+                    serde_json::json!({"path": "a.txt"})
+                ),
+            ]
+        );
+        assert!(matches!(
+            choice.first(),
+            AssistantContent::Text(text) if text.text == "Let me look."
+        ));
+    }
+
+    #[test]
+    fn json_reply_becomes_a_tool_call_only_for_known_tools() {
+        let fenced = "```json\n{\"name\": \"shell\", \"parameters\": {\"command\": \"pwd\"}}\n```";
+        let mut choice = OneOrMany::one(AssistantContent::text(fenced));
+        let quirks = [ToolCallQuirk::JsonInText];
+        assert_eq!(normalize_tool_calls(&mut choice, &quirks, &tools()), 1);
+        assert_eq!(
+            tool_calls(&choice),
+            vec![("shell".to_string(), serde_json::json!({"command": "pwd"}))]
+        );
+
+        let prose = "{\"name\": \"Alice\", \"arguments\": {}}";
+        let mut choice = OneOrMany::one(AssistantContent::text(prose));
+        assert_eq!(normalize_tool_calls(&mut choice, &quirks, &tools()), 0);
+        assert!(matches!(choice.first(), AssistantContent::Text(text) if text.text == prose));
+    }
+
+    #[test]
+    fn namespaced_names_are_stripped_to_known_tools() {
+        let mut choice = OneOrMany::many(vec![
+            call("functions.shell", serde_json::json!({})),
+            call("file_read<|channel|>commentary", serde_json::json!({})),
+            call("functions.unknown", serde_json::json!({})),
+        ])
+        .unwrap();
+        let quirks = [ToolCallQuirk::NamespacedNames];
+        assert_eq!(normalize_tool_calls(&mut choice, &quirks, &tools()), 2);
+        let names: Vec<String> = tool_calls(&choice)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["shell", "file_read", "functions.unknown"]);
+    }
+
+    #[test]
+    fn configured_patterns_replace_the_builtin_table() {
+        assert_eq!(
+            quirks_for("ollama/qwen2.5-coder:7b", None),
+            [
+                ToolCallQuirk::StringifiedArguments,
+                ToolCallQuirk::XmlToolTags
+            ]
+        );
+        assert!(quirks_for("anthropic/claude-sonnet-4", None).is_empty());
+
+        let overrides = HashMap::from([
+            ("ollama/qwen*".to_string(), Vec::new()),
+            ("local/*".to_string(), vec![ToolCallQuirk::NamespacedNames]),
+        ]);
+        assert!(quirks_for("ollama/qwen2.5-coder:7b", Some(&overrides)).is_empty());
+        assert_eq!(
+            quirks_for("local/my-model", Some(&overrides)),
+            [ToolCallQuirk::NamespacedNames]
+        );
+    }
+}
@@ -43,6 +43,12 @@ enum Command {
     },
     /// Show status of the running daemon
     Status,
+    /// Chat with an agent from the terminal (stdin/stdout, no messaging platform)
+    Repl {
+        /// Agent ID to talk to (defaults to binding resolution / default agent)
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
     /// Manage skills
     #[command(subcommand)]
     Skill(SkillCommand),
@@ -272,6 +278,7 @@ fn forward_sse_event(
     agent_id: &str,
     channel_id: &str,
     response: &spacebot::OutboundResponse,
+    message_id: Option<&str>,
 ) {
     match response {
         spacebot::OutboundResponse::Text(text)
@@ -282,6 +289,7 @@ fn forward_sse_event(
                     agent_id: agent_id.to_string(),
                     channel_id: channel_id.to_string(),
                     text: text.clone(),
+                    message_id: message_id.map(ToString::to_string),
                 })
                 .ok();
         }
@@ -344,6 +352,7 @@ fn main() -> anyhow::Result<()> {
             cmd_start(cli.config, cli.debug, foreground)
         }
         Command::Status => cmd_status(),
+        Command::Repl { agent } => cmd_repl(cli.config, cli.debug, agent),
         Command::Skill(skill_cmd) => cmd_skill(cli.config, skill_cmd),
         Command::Auth(auth_cmd) => cmd_auth(cli.config, auth_cmd),
         Command::Secrets(secrets_cmd) => cmd_secrets(cli.config, secrets_cmd),
@@ -417,7 +426,59 @@ fn cmd_start(
             spacebot::daemon::init_background_tracing(&paths, debug, &config.telemetry)
         };
 
-        run(config, foreground, otel_provider, bootstrapped_store).await
+        run(config, foreground, otel_provider, bootstrapped_store, None).await
+    })
+}
+
+/// Run the full runtime in the foreground with a stdin/stdout adapter
+/// registered, so a local terminal can talk to a channel agent directly.
+///
+/// Logs go to the instance log directory instead of the terminal to keep the
+/// conversation readable.
+fn cmd_repl(
+    config_path: Option<std::path::PathBuf>,
+    debug: bool,
+    agent: Option<String>,
+) -> anyhow::Result<()> {
+    let instance_dir = resolve_instance_dir(&config_path);
+    let paths = spacebot::daemon::DaemonPaths::new(&instance_dir);
+
+    if let Some(pid) = spacebot::daemon::is_running(&paths) {
+        eprintln!("spacebot is already running (pid {pid}), stop it before starting a repl");
+        std::process::exit(1);
+    }
+
+    let resolved_config_path = if config_path.is_some() {
+        config_path.clone()
+    } else if spacebot::config::Config::needs_onboarding() {
+        spacebot::config::run_onboarding().with_context(|| "onboarding failed")?
+    } else {
+        None
+    };
+
+    let bootstrapped_store = bootstrap_secrets_store(&resolved_config_path);
+    let config = load_config(&resolved_config_path)?;
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("failed to build Tokio runtime")?;
+
+    runtime.block_on(async {
+        let paths = spacebot::daemon::DaemonPaths::new(&config.instance_dir);
+        let otel_provider =
+            spacebot::daemon::init_background_tracing(&paths, debug, &config.telemetry);
+        eprintln!("logs: {}", paths.log_dir.display());
+
+        let repl_adapter = Arc::new(spacebot::messaging::repl::ReplAdapter::new(agent));
+        run(
+            config,
+            true,
+            otel_provider,
+            bootstrapped_store,
+            Some(repl_adapter),
+        )
+        .await
     })
 }
 
@@ -1480,6 +1541,7 @@ async fn run(
     foreground: bool,
     otel_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
     bootstrapped_store: Option<Arc<spacebot::secrets::store::SecretsStore>>,
+    repl_adapter: Option<Arc<spacebot::messaging::repl::ReplAdapter>>,
 ) -> anyhow::Result<()> {
     let paths = spacebot::daemon::DaemonPaths::new(&config.instance_dir);
 
@@ -1595,9 +1657,11 @@ async fn run(
     // Initialize the language for all text lookups (must happen before PromptEngine/tools)
     spacebot::prompts::text::init("en").with_context(|| "failed to initialize language")?;
 
-    // Create the PromptEngine with bundled templates (no file watching, no user overrides)
-    let prompt_engine = spacebot::prompts::PromptEngine::new("en")
-        .with_context(|| "failed to initialize prompt engine")?;
+    // Create the PromptEngine with bundled templates plus any configured
+    // overrides (no file watching)
+    let prompt_engine =
+        spacebot::prompts::PromptEngine::with_overrides("en", &config.prompt_overrides)
+            .with_context(|| "failed to initialize prompt engine")?;
 
     // Parse config links into shared agent links (hot-reloadable via ArcSwap)
     let agent_links = Arc::new(ArcSwap::from_pointee(
@@ -1621,6 +1685,8 @@ async fn run(
     let mut cron_schedulers_for_shutdown: Vec<Arc<spacebot::cron::Scheduler>> = Vec::new();
     let mut _ingestion_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
     let mut _cortex_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+    // Notified by the idle monitor when `[idle] action = "shutdown"` fires.
+    let idle_shutdown = Arc::new(tokio::sync::Notify::new());
     let bindings: Arc<ArcSwap<Vec<spacebot::config::Binding>>> =
         Arc::new(ArcSwap::from_pointee(config.bindings.clone()));
     api_state.set_bindings(bindings.clone()).await;
@@ -1675,6 +1741,8 @@ async fn run(
             injection_tx.clone(),
             task_store_registry.clone(),
             &bootstrapped_store,
+            repl_adapter.as_ref(),
+            idle_shutdown.clone(),
         )
         .await?;
         agents_initialized = true;
@@ -1726,13 +1794,14 @@ async fn run(
     // Active conversation channels: conversation_id -> ActiveChannel
     let mut active_channels: HashMap<String, ActiveChannel> = HashMap::new();
 
-    // Resume idle interactive workers that survived the restart.
-    // For each idle worker, pre-create the channel if needed and spawn
-    // the resumed worker into its state so follow-ups route correctly.
+    // Resume idle interactive workers that survived the restart, and the
+    // interrupted fire-and-forget workers left running by reconciliation.
+    // For each worker, pre-create the channel if needed and spawn the
+    // resumed worker into its state so follow-ups route correctly.
     if agents_initialized {
         for (agent_id, agent) in agents.iter() {
             let run_logger = spacebot::conversation::ProcessRunLogger::new(agent.db.sqlite.clone());
-            let idle_workers = match run_logger
+            let mut idle_workers = match run_logger
                 .get_idle_interactive_workers(&agent.config.id)
                 .await
             {
@@ -1742,13 +1811,21 @@ async fn run(
                     continue;
                 }
             };
+            let idle_count = idle_workers.len();
+            match run_logger.get_interrupted_workers(&agent.config.id).await {
+                Ok(workers) => idle_workers.extend(workers),
+                Err(error) => {
+                    tracing::warn!(agent_id = %agent_id, %error, "failed to query interrupted workers");
+                }
+            }
             if idle_workers.is_empty() {
                 continue;
             }
             tracing::info!(
                 agent_id = %agent_id,
-                idle_count = idle_workers.len(),
-                "found idle interactive workers to resume"
+                idle_count,
+                interrupted_count = idle_workers.len() - idle_count,
+                "found workers to resume"
             );
 
             // Group idle workers by channel_id
@@ -1895,6 +1972,29 @@ async fn run(
                                     "resumed idle worker"
                                 );
                             }
+                            Err(reason) if !idle_worker.interactive => {
+                                // Interrupted workers that can't resume failed
+                                // their task.
+                                if let Err(error) = run_logger
+                                    .fail_interrupted_worker(
+                                        &idle_worker.id,
+                                        "Worker interrupted: Spacebot restarted before completion.",
+                                    )
+                                    .await
+                                {
+                                    tracing::warn!(
+                                        worker_id = %idle_worker.id,
+                                        %error,
+                                        "failed to mark interrupted worker failed"
+                                    );
+                                }
+                                tracing::info!(
+                                    worker_id = %idle_worker.id,
+                                    channel_id = %conversation_id,
+                                    %reason,
+                                    "could not resume interrupted worker"
+                                );
+                            }
                             Err(reason) => {
                                 // Resume failed at runtime (e.g. OpenCode disabled,
                                 // transcript corrupt). Retire the worker.
@@ -1944,12 +2044,17 @@ async fn run(
                     let sse_channel_id = conversation_id.clone();
                     let outbound_handle = tokio::spawn(async move {
                         while let Some(routed) = response_rx.recv().await {
-                            let spacebot::RoutedResponse { response, target } = routed;
+                            let spacebot::RoutedResponse {
+                                response,
+                                target,
+                                message_id,
+                            } = routed;
                             forward_sse_event(
                                 &api_event_tx,
                                 &sse_agent_id,
                                 &sse_channel_id,
                                 &response,
+                                message_id.as_deref(),
                             );
                             route_outbound(&messaging_for_outbound, &target, response).await;
                         }
@@ -2114,8 +2219,18 @@ async fn run(
                     let sse_channel_id = conversation_id.clone();
                     let outbound_handle = tokio::spawn(async move {
                         while let Some(routed) = response_rx.recv().await {
-                            let spacebot::RoutedResponse { response, target } = routed;
-                            forward_sse_event(&api_event_tx, &sse_agent_id, &sse_channel_id, &response);
+                            let spacebot::RoutedResponse {
+                                response,
+                                target,
+                                message_id,
+                            } = routed;
+                            forward_sse_event(
+                                &api_event_tx,
+                                &sse_agent_id,
+                                &sse_channel_id,
+                                &response,
+                                message_id.as_deref(),
+                            );
                             route_outbound(&messaging_for_outbound, &target, response).await;
                         }
                         tracing::debug!(
@@ -2266,6 +2381,8 @@ async fn run(
                                     injection_tx.clone(),
                                     task_store_registry.clone(),
                                     &bootstrapped_store,
+                                    repl_adapter.as_ref(),
+                                    idle_shutdown.clone(),
                                 ).await {
                                     Ok(()) => {
                                         agents_initialized = true;
@@ -2313,6 +2430,19 @@ async fn run(
                 tracing::info!("shutdown signal received");
                 break;
             }
+            _ = idle_shutdown.notified() => {
+                tracing::info!("idle timeout reached, shutting down");
+                break;
+            }
+            _ = async {
+                match repl_adapter.as_ref() {
+                    Some(adapter) => adapter.closed().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                tracing::info!("repl session ended");
+                break;
+            }
         }
     }
 
@@ -2409,6 +2539,8 @@ async fn initialize_agents(
         ArcSwap<std::collections::HashMap<String, Arc<spacebot::tasks::TaskStore>>>,
     >,
     bootstrapped_store: &Option<Arc<spacebot::secrets::store::SecretsStore>>,
+    repl_adapter: Option<&Arc<spacebot::messaging::repl::ReplAdapter>>,
+    idle_shutdown: Arc<tokio::sync::Notify>,
 ) -> anyhow::Result<()> {
     let resolved_agents = config.resolve_agents();
 
@@ -2475,10 +2607,29 @@ async fn initialize_agents(
             })?;
 
         let run_logger = spacebot::conversation::ProcessRunLogger::new(db.sqlite.clone());
+        // Interrupted workers that checkpointed their history stay running
+        // so startup can resume them; the rest are marked failed.
+        let resumable_workers: Vec<String> = run_logger
+            .get_interrupted_workers(&agent_config.id)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to load interrupted workers for agent '{}'",
+                    agent_config.id
+                )
+            })?
+            .into_iter()
+            .filter(|worker| {
+                spacebot::agent::worker::checkpoint_path(&agent_config.logs_dir(), &worker.id)
+                    .exists()
+            })
+            .map(|worker| worker.id)
+            .collect();
         let orphaned_workers = run_logger
             .reconcile_running_workers_for_agent(
                 &agent_config.id,
                 "Worker interrupted: Spacebot restarted before completion.",
+                &resumable_workers,
             )
             .await
             .with_context(|| {
@@ -2585,7 +2736,12 @@ async fn initialize_agents(
             .agents
             .iter()
             .find(|agent| agent.id == agent_config.id)
-            .and_then(|agent| agent.channel.map(|channel| channel.listen_only_mode));
+            .and_then(|agent| {
+                agent
+                    .channel
+                    .as_ref()
+                    .map(|channel| channel.listen_only_mode)
+            });
         runtime_config.set_settings(settings_store.clone(), explicit_listen_only);
         runtime_config
             .prompt_snapshots
@@ -2650,6 +2806,7 @@ async fn initialize_agents(
                 spacebot::agent::process_control::ProcessControlRegistry::new(),
             ),
             injection_tx: injection_tx.clone(),
+            spawn_depth: 0,
         };
 
         let agent = spacebot::Agent {
@@ -2801,7 +2958,8 @@ async fn initialize_agents(
     }
 
     // Initialize messaging adapters
-    let new_messaging_manager = spacebot::messaging::MessagingManager::new();
+    let new_messaging_manager = spacebot::messaging::MessagingManager::new()
+        .with_outbound(&config.outbound, &config.redaction);
 
     // Shared Discord permissions (hot-reloadable via file watcher)
     *discord_permissions = config.messaging.discord.as_ref().map(|discord_config| {
@@ -3019,7 +3177,8 @@ async fn initialize_agents(
             webhook_config.port,
             &webhook_config.bind,
             webhook_config.auth_token.clone(),
-        );
+        )
+        .with_signature(webhook_config.signature.clone());
         new_messaging_manager.register(adapter).await;
     }
 
@@ -3167,6 +3326,12 @@ async fn initialize_agents(
         .await;
     api_state.set_webchat_adapter(webchat_adapter);
 
+    if let Some(repl_adapter) = repl_adapter {
+        new_messaging_manager
+            .register_shared(repl_adapter.clone())
+            .await;
+    }
+
     *messaging_manager = Arc::new(new_messaging_manager);
     api_state
         .set_messaging_manager(messaging_manager.clone())
@@ -3181,6 +3346,19 @@ async fn initialize_agents(
 
     tracing::info!("messaging adapters started");
 
+    if let Some(handle) = spacebot::idle::spawn_idle_monitor(
+        config.idle.clone(),
+        messaging_manager.clone(),
+        idle_shutdown,
+    ) {
+        cortex_handles.push(handle);
+        tracing::info!(
+            timeout_secs = config.idle.timeout_secs,
+            action = ?config.idle.action,
+            "idle monitor started"
+        );
+    }
+
     // Initialize cron schedulers for each agent
     let mut cron_stores_map = std::collections::HashMap::new();
     let mut cron_schedulers_map = std::collections::HashMap::new();
@@ -3304,6 +3482,14 @@ async fn initialize_agents(
         );
         cortex_handles.push(ready_task_handle);
         tracing::info!(agent_id = %agent_id, "cortex ready-task loop started");
+
+        cortex_handles.push(spacebot::agent::debug_mirror::spawn_debug_mirror(
+            agent.deps.clone(),
+        ));
+        cortex_handles.push(spacebot::idle::spawn_worker_counter(
+            messaging_manager.activity(),
+            agent.deps.event_tx.subscribe(),
+        ));
     }
 
     // Create cortex chat sessions for each agent
@@ -3311,7 +3497,7 @@ async fn initialize_agents(
         let mut sessions = std::collections::HashMap::new();
         for (agent_id, agent) in agents.iter() {
             let browser_config = (**agent.deps.runtime_config.browser_config.load()).clone();
-            let brave_search_key = (**agent.deps.runtime_config.brave_search_key.load()).clone();
+            let search_backend = agent.deps.runtime_config.search_backend();
             let conversation_logger =
                 spacebot::conversation::history::ConversationLogger::new(agent.db.sqlite.clone());
             let channel_store = spacebot::conversation::ChannelStore::new(agent.db.sqlite.clone());
@@ -3328,7 +3514,7 @@ async fn initialize_agents(
                 run_logger,
                 browser_config,
                 agent.config.screenshot_dir(),
-                brave_search_key,
+                search_backend,
                 agent.deps.runtime_config.workspace_dir.clone(),
                 agent.deps.sandbox.clone(),
                 agent.deps.runtime_config.clone(),
@@ -1,8 +1,11 @@
-//! Messaging adapters (Discord, Slack, Telegram, Twitch, Signal, Email, Webhook, WebChat).
+//! Messaging adapters (Discord, Slack, Telegram, Twitch, Signal, Email, Webhook, WebChat, REPL).
 
 pub mod discord;
 pub mod email;
 pub mod manager;
+pub mod pipeline;
+pub mod rate_limit;
+pub mod repl;
 pub mod signal;
 pub mod slack;
 pub mod target;
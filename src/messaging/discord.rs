@@ -2,7 +2,7 @@
 
 use crate::config::DiscordPermissions;
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
-use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
+use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging, split_message};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
 use anyhow::Context as _;
@@ -984,42 +984,6 @@ async fn build_metadata(
     (metadata, formatted_author)
 }
 
-/// Split a message into chunks that fit within Discord's 2000 char limit.
-/// Tries to split at newlines, then spaces, then hard-cuts.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        let safe_max = {
-            let mut i = max_len.min(remaining.len());
-            while !remaining.is_char_boundary(i) {
-                i -= 1;
-            }
-            i
-        };
-
-        let split_at = remaining[..safe_max]
-            .rfind('\n')
-            .or_else(|| remaining[..safe_max].rfind(' '))
-            .unwrap_or(safe_max);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
-
 // --- Rich Message Builders ---
 
 fn build_embed(card: &crate::Card) -> CreateEmbed {
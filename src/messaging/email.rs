@@ -1511,9 +1511,9 @@ fn extract_text_and_attachments(
     );
 
     let mut body_text = if !plain_text_parts.is_empty() {
-        plain_text_parts.join("\n\n")
+        strip_quoted_reply(&plain_text_parts.join("\n\n"))
     } else if !html_parts.is_empty() {
-        html_to_text(&html_parts.join("\n\n"))
+        html_to_text(&strip_html_quotes(&html_parts.join("\n\n")))
     } else {
         parsed.get_body().unwrap_or_default()
     };
@@ -1594,6 +1594,79 @@ fn html_to_text(html: &str) -> String {
     decoded.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+/// Drop the quoted history a mail client appends to a reply: everything
+/// from the attribution line ("On ... wrote:", "-----Original Message-----",
+/// Outlook's underscore rule) down, plus any `>`-quoted lines. Replies keep
+/// the whole thread in the channel history already, so the quotes only
+/// repeat it. A message that is nothing but quotes is kept as-is.
+fn strip_quoted_reply(text: &str) -> String {
+    let text = text.replace("\r\n", "\n");
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if is_reply_attribution(trimmed) {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        kept.push(line);
+    }
+    let stripped = kept.join("\n");
+    if stripped.trim().is_empty() {
+        text
+    } else {
+        stripped
+    }
+}
+
+fn is_reply_attribution(line: &str) -> bool {
+    (line.starts_with("On ") && line.ends_with("wrote:"))
+        || line.eq_ignore_ascii_case("-----original message-----")
+        || (line.len() >= 20 && line.chars().all(|c| c == '_'))
+}
+
+/// Remove `<blockquote>` sections (how HTML clients quote replies) before
+/// the markup is flattened to text. Tags are matched by nesting depth, so
+/// nested quotes go with their outermost one and replies written between
+/// two quotes are kept. An unclosed quote runs to the end.
+fn strip_html_quotes(html: &str) -> String {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = REGEX.get_or_init(|| {
+        Regex::new(r"(?i)<(/?)blockquote\b[^>]*>").expect("valid blockquote regex")
+    });
+
+    let mut stripped = String::with_capacity(html.len());
+    let mut depth = 0usize;
+    let mut kept_to = 0;
+    for tag in regex.captures_iter(html) {
+        let whole = tag.get(0).expect("match has a whole group");
+        if tag[1].is_empty() {
+            if depth == 0 {
+                stripped.push_str(&html[kept_to..whole.start()]);
+            }
+            depth += 1;
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 {
+                stripped.push(' ');
+                kept_to = whole.end();
+            }
+        }
+    }
+    if depth == 0 {
+        stripped.push_str(&html[kept_to..]);
+    } else {
+        stripped.push(' ');
+    }
+
+    if html_to_text(&stripped).is_empty() {
+        html.to_string()
+    } else {
+        stripped
+    }
+}
+
 fn html_tag_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| Regex::new(r"(?is)<[^>]+>").expect("valid HTML tag regex"))
@@ -1758,8 +1831,35 @@ mod tests {
         EmailSearchHit, EmailSearchQuery, build_imap_search_criterion, derive_thread_key,
         extract_message_ids, is_local_mail_host, normalize_email_target, normalize_reply_subject,
         normalize_search_folders, parse_primary_mailbox, sort_and_limit_search_hits,
+        strip_html_quotes, strip_quoted_reply,
     };
 
+    #[test]
+    fn quoted_reply_history_is_stripped() {
+        let reply = "Sounds good, ship it.\n\nOn Tue, 3 Mar 2026 at 10:02, Ops <ops@example.com> wrote:\n> Ready to deploy?\n> -- ops";
+        assert_eq!(strip_quoted_reply(reply).trim(), "Sounds good, ship it.");
+
+        let inline = "> Which region?\neu-west-1\n> And the size?\nLarge";
+        assert_eq!(strip_quoted_reply(inline), "eu-west-1\nLarge");
+
+        let outlook = "Approved.\n\n-----Original Message-----\nFrom: Ops\nPlease approve.";
+        assert_eq!(strip_quoted_reply(outlook).trim(), "Approved.");
+
+        let only_quotes = "> forwarded text";
+        assert_eq!(strip_quoted_reply(only_quotes), only_quotes);
+
+        let html =
+            "<p>Yes</p><blockquote><p>Deploy?</p><blockquote>older</blockquote></blockquote>";
+        assert_eq!(strip_html_quotes(html), "<p>Yes</p> ");
+
+        let interleaved = "<blockquote>Which region?</blockquote><p>eu-west-1</p>\
+                           <blockquote>And the size?</blockquote><p>Large</p>";
+        assert_eq!(
+            strip_html_quotes(interleaved),
+            " <p>eu-west-1</p> <p>Large</p>"
+        );
+    }
+
     #[test]
     fn parse_primary_mailbox_parses_display_name() {
         let parsed = parse_primary_mailbox("Alice Example <alice@example.com>");
@@ -1,5 +1,8 @@
 //! MessagingManager: Fan-in and routing for all adapters.
 
+use crate::idle::ActivityTracker;
+use crate::messaging::pipeline::OutboundPipeline;
+use crate::messaging::rate_limit::OutboundRateLimiter;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging, MessagingDyn};
 use crate::{InboundMessage, OutboundResponse, StatusUpdate};
 
@@ -19,6 +22,14 @@ pub struct MessagingManager {
     fan_in_tx: mpsc::Sender<InboundMessage>,
     /// Receiver side, taken once by `start()`.
     fan_in_rx: RwLock<Option<mpsc::Receiver<InboundMessage>>>,
+    /// Touched on every inbound message and outbound send, for idle tracking.
+    activity: Arc<ActivityTracker>,
+    /// Adapters disconnected by `hibernate()`, restarted by `wake()`.
+    hibernated: RwLock<Vec<String>>,
+    /// Post-processing (redaction first of all) applied to every outbound response.
+    pipeline: OutboundPipeline,
+    /// Paces outbound sends to each platform's budget.
+    rate_limiter: OutboundRateLimiter,
 }
 
 impl MessagingManager {
@@ -28,9 +39,32 @@ impl MessagingManager {
             adapters: RwLock::new(HashMap::new()),
             fan_in_tx,
             fan_in_rx: RwLock::new(Some(fan_in_rx)),
+            activity: Arc::new(ActivityTracker::default()),
+            hibernated: RwLock::new(Vec::new()),
+            pipeline: OutboundPipeline::default(),
+            rate_limiter: OutboundRateLimiter::new(
+                &crate::config::OutboundConfig::default().rate_limits,
+            ),
         }
     }
 
+    /// Replace the default outbound pipeline and rate limits with ones built
+    /// from config.
+    pub fn with_outbound(
+        mut self,
+        outbound: &crate::config::OutboundConfig,
+        redaction: &crate::config::RedactionConfig,
+    ) -> Self {
+        self.pipeline = OutboundPipeline::new(outbound, redaction);
+        self.rate_limiter = OutboundRateLimiter::new(&outbound.rate_limits);
+        self
+    }
+
+    /// Activity tracker shared with the idle monitor.
+    pub fn activity(&self) -> Arc<ActivityTracker> {
+        self.activity.clone()
+    }
+
     /// Register an adapter (before start). Use `register_and_start` for runtime addition.
     pub async fn register(&self, adapter: impl Messaging) {
         let name = adapter.name().to_string();
@@ -82,9 +116,11 @@ impl MessagingManager {
             .take()
             .context("start() already called")?;
 
-        Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(
-            receiver,
-        )))
+        let activity = self.activity.clone();
+        Ok(Box::pin(
+            tokio_stream::wrappers::ReceiverStream::new(receiver)
+                .inspect(move |_| activity.touch()),
+        ))
     }
 
     /// Register and start a new adapter at runtime.
@@ -220,8 +256,15 @@ impl MessagingManager {
         message: &InboundMessage,
         response: OutboundResponse,
     ) -> crate::Result<()> {
-        let adapters = self.adapters.read().await;
+        self.activity.touch();
+        let response = self.pipeline.process(response, message.adapter_key());
         let adapter_key = message.adapter_key();
+        if counts_against_rate_limit(&response) {
+            self.rate_limiter
+                .acquire(adapter_key, &message.conversation_id)
+                .await;
+        }
+        let adapters = self.adapters.read().await;
         let adapter = adapters
             .get(adapter_key)
             .with_context(|| format!("no messaging adapter named '{}'", adapter_key))?;
@@ -249,6 +292,16 @@ impl MessagingManager {
         target: &str,
         response: OutboundResponse,
     ) -> crate::Result<()> {
+        self.activity.touch();
+        // Proactive sends (cron deliveries, cross-channel messages) can fire
+        // while hibernating; reconnect first so the adapter can deliver.
+        if self.is_hibernating().await {
+            self.wake().await;
+        }
+        let response = self.pipeline.process(response, adapter_name);
+        if counts_against_rate_limit(&response) {
+            self.rate_limiter.acquire(adapter_name, target).await;
+        }
         let adapters = self.adapters.read().await;
         let adapter = adapters
             .get(adapter_name)
@@ -300,6 +353,62 @@ impl MessagingManager {
         Ok(())
     }
 
+    /// Whether adapters are currently disconnected by `hibernate()`.
+    pub async fn is_hibernating(&self) -> bool {
+        !self.hibernated.read().await.is_empty()
+    }
+
+    /// Disconnect every adapter except those named (or on a platform named)
+    /// in `keep_connected`. Adapters stay registered so `wake()` can start
+    /// them again.
+    pub async fn hibernate(&self, keep_connected: &[String]) {
+        let mut hibernated = self.hibernated.write().await;
+        let adapters = self.adapters.read().await;
+        for (name, adapter) in adapters.iter() {
+            let kept = keep_connected.iter().any(|keep| {
+                name == keep
+                    || name
+                        .strip_prefix(keep.as_str())
+                        .is_some_and(|rest| rest.starts_with(':'))
+            });
+            if kept || hibernated.contains(name) {
+                continue;
+            }
+            if let Err(error) = adapter.shutdown().await {
+                tracing::warn!(adapter = %name, %error, "failed to disconnect adapter for hibernation");
+            }
+            hibernated.push(name.clone());
+        }
+        tracing::info!(adapters = ?*hibernated, "messaging adapters hibernated");
+    }
+
+    /// Reconnect adapters disconnected by `hibernate()`. Adapters that fail
+    /// to start are retried in the background like at startup.
+    pub async fn wake(&self) {
+        let names = std::mem::take(&mut *self.hibernated.write().await);
+        if names.is_empty() {
+            return;
+        }
+        let adapters = self.adapters.read().await;
+        for name in &names {
+            let Some(adapter) = adapters.get(name) else {
+                continue;
+            };
+            match adapter.start().await {
+                Ok(stream) => Self::spawn_forwarder(name.clone(), stream, self.fan_in_tx.clone()),
+                Err(error) => {
+                    tracing::warn!(adapter = %name, %error, "adapter failed to wake, will retry in background");
+                    Self::spawn_retry_task(
+                        name.clone(),
+                        Arc::clone(adapter),
+                        self.fan_in_tx.clone(),
+                    );
+                }
+            }
+        }
+        tracing::info!(adapters = ?names, "messaging adapters woken");
+    }
+
     /// Shut down all adapters gracefully.
     pub async fn shutdown(&self) {
         let adapters = self.adapters.read().await;
@@ -311,6 +420,15 @@ impl MessagingManager {
     }
 }
 
+/// Streamed chunks and the stream's end are edits of the message
+/// `StreamStart` posted, and adapters throttle those edits themselves.
+fn counts_against_rate_limit(response: &OutboundResponse) -> bool {
+    !matches!(
+        response,
+        OutboundResponse::StreamChunk(_) | OutboundResponse::StreamEnd
+    )
+}
+
 impl Default for MessagingManager {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,559 @@
+//! Ordered post-processing of outbound message text.
+//!
+//! Every response the messaging layer delivers passes through an
+//! [`OutboundPipeline`] first: a list of [`OutboundStage`]s applied in order
+//! to each user-visible text field. Operators pick and order the stages in
+//! `[outbound]`, globally or per adapter. The default pipeline is empty.
+//!
+//! Secret redaction is not a stage: while `[redaction]` is enabled it runs
+//! after the stages on every response, streamed chunks included, so no
+//! pipeline override can leave it out. The stages look at whole messages
+//! (fences, blank-line runs, list markers), so streamed chunks skip them;
+//! applying them to an arbitrary slice of a reply would mangle it.
+//!
+//! Adapters split long messages with
+//! [`split_message`](crate::messaging::traits::split_message), which builds
+//! on [`split_preserving_code_blocks`] so a code block cut across two
+//! messages is closed and reopened instead of leaking its fence into the
+//! next message.
+
+use crate::OutboundResponse;
+use crate::config::{OutboundConfig, OutboundStage, RedactionConfig};
+use crate::secrets::scrub::OutboundRedactor;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Configured outbound stages plus the state they need.
+#[derive(Debug)]
+pub struct OutboundPipeline {
+    pipeline: Vec<OutboundStage>,
+    adapter_pipelines: HashMap<String, Vec<OutboundStage>>,
+    link_rewrites: Vec<(String, String)>,
+    indent_code: Vec<String>,
+    redactor: OutboundRedactor,
+}
+
+/// How a platform shows fenced code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeStyle {
+    /// Fences with their language hint, for syntax highlighting.
+    Hinted,
+    /// Fences without a language hint, which the platform would show as text.
+    Bare,
+    /// No fences, code indented four spaces, for raw-text targets.
+    Indented,
+}
+
+impl Default for OutboundPipeline {
+    fn default() -> Self {
+        Self::new(&OutboundConfig::default(), &RedactionConfig::default())
+    }
+}
+
+impl OutboundPipeline {
+    pub fn new(config: &OutboundConfig, redaction: &RedactionConfig) -> Self {
+        Self {
+            pipeline: config.pipeline.clone(),
+            adapter_pipelines: config.adapter_pipelines.clone(),
+            link_rewrites: config.link_rewrites.clone(),
+            indent_code: config.indent_code.clone(),
+            redactor: OutboundRedactor::new(redaction),
+        }
+    }
+
+    /// Stages for `adapter` (e.g. `"email:support"`): its own pipeline, its
+    /// platform's, or the global one.
+    pub fn stages_for(&self, adapter: &str) -> &[OutboundStage] {
+        let platform = adapter.split(':').next().unwrap_or(adapter);
+        self.adapter_pipelines
+            .get(adapter)
+            .or_else(|| self.adapter_pipelines.get(platform))
+            .unwrap_or(&self.pipeline)
+    }
+
+    /// Run every user-visible text field of `response` through the stages
+    /// configured for `adapter`, then redact it.
+    pub fn process(&self, mut response: OutboundResponse, adapter: &str) -> OutboundResponse {
+        let stages: &[OutboundStage] = match response {
+            OutboundResponse::StreamChunk(_) => &[],
+            _ => self.stages_for(adapter),
+        };
+        let mut redacted = false;
+        for text in text_fields(&mut response) {
+            for stage in stages {
+                let Cow::Owned(next) = self.apply(*stage, text, adapter) else {
+                    continue;
+                };
+                *text = next;
+            }
+            redacted |= self.redactor.redact_in_place(text);
+        }
+        if redacted {
+            tracing::warn!(adapter, "redacted secret from outbound message");
+        }
+        response
+    }
+
+    fn apply<'a>(&self, stage: OutboundStage, text: &'a str, adapter: &str) -> Cow<'a, str> {
+        match stage {
+            OutboundStage::Trim => trim(text),
+            OutboundStage::NormalizeMarkdown => normalize_markdown(text),
+            OutboundStage::RewriteLinks => self.rewrite_links(text),
+            OutboundStage::StripMarkdown => strip_markdown(text),
+            OutboundStage::CodeBlocks => format_code_blocks(text, self.code_style(adapter)),
+        }
+    }
+
+    fn code_style(&self, adapter: &str) -> CodeStyle {
+        let platform = adapter.split(':').next().unwrap_or(adapter);
+        if self
+            .indent_code
+            .iter()
+            .any(|target| target == adapter || target == platform)
+        {
+            return CodeStyle::Indented;
+        }
+        match platform {
+            // Slack's markdown blocks render fences but not language hints,
+            // which would show up as a stray first line.
+            "slack" => CodeStyle::Bare,
+            _ => CodeStyle::Hinted,
+        }
+    }
+
+    fn rewrite_links<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut result = Cow::Borrowed(text);
+        for (from, to) in &self.link_rewrites {
+            if result.contains(from.as_str()) {
+                result = Cow::Owned(result.replace(from.as_str(), to));
+            }
+        }
+        result
+    }
+}
+
+/// Mutable references to every user-visible text field of `response`.
+fn text_fields(response: &mut OutboundResponse) -> Vec<&mut String> {
+    match response {
+        OutboundResponse::Text(text)
+        | OutboundResponse::StreamChunk(text)
+        | OutboundResponse::Ephemeral { text, .. }
+        | OutboundResponse::ScheduledMessage { text, .. } => vec![text],
+        OutboundResponse::ThreadReply { thread_name, text } => vec![thread_name, text],
+        OutboundResponse::File { caption, .. } => caption.iter_mut().collect(),
+        OutboundResponse::RichMessage { text, cards, .. } => {
+            let mut fields = vec![text];
+            for card in cards {
+                fields.extend(
+                    [&mut card.title, &mut card.description, &mut card.footer]
+                        .into_iter()
+                        .flatten(),
+                );
+                for field in &mut card.fields {
+                    fields.push(&mut field.name);
+                    fields.push(&mut field.value);
+                }
+            }
+            fields
+        }
+        OutboundResponse::Reaction(_)
+        | OutboundResponse::RemoveReaction(_)
+        | OutboundResponse::StreamStart
+        | OutboundResponse::StreamEnd
+        | OutboundResponse::Status(_) => Vec::new(),
+    }
+}
+
+/// Whether `line` opens or closes a fenced code block.
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Split `text` with an adapter's own `split` function so that no chunk leaves
+/// a code block open. A chunk that ends inside a block gets a closing fence,
+/// and the next chunk reopens it with the same language hint. Room for the
+/// added fences is taken off `max_len` up front, so chunks still fit.
+pub fn split_preserving_code_blocks(
+    text: &str,
+    max_len: usize,
+    split: impl Fn(&str, usize) -> Vec<String>,
+) -> Vec<String> {
+    let Some(longest_fence) = text
+        .lines()
+        .filter(|line| is_fence(line))
+        .map(|line| line.trim().len())
+        .max()
+    else {
+        return split(text, max_len);
+    };
+    // A reopening fence plus newline in front, "\n```" behind.
+    let reserve = longest_fence + 1 + 4;
+    let chunks = split(text, max_len.saturating_sub(reserve).max(1));
+
+    let mut balanced = Vec::with_capacity(chunks.len());
+    let mut open_fence: Option<String> = None;
+    for chunk in chunks {
+        let mut fixed = String::with_capacity(chunk.len() + reserve);
+        if let Some(fence) = &open_fence {
+            fixed.push_str(fence);
+            fixed.push('\n');
+        }
+        for line in chunk.lines().filter(|line| is_fence(line)) {
+            open_fence = match open_fence {
+                Some(_) => None,
+                None => Some(line.trim().to_string()),
+            };
+        }
+        fixed.push_str(&chunk);
+        if open_fence.is_some() {
+            fixed.push_str("\n```");
+        }
+        balanced.push(fixed);
+    }
+    balanced
+}
+
+/// Rewrite fenced code blocks in `style`. An unterminated block is closed.
+fn format_code_blocks(text: &str, style: CodeStyle) -> Cow<'_, str> {
+    if !text.contains("```") {
+        return Cow::Borrowed(text);
+    }
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_code = false;
+    // Indented code needs blank lines around it to read as a block.
+    let mut after_block = false;
+    for line in text.split('\n') {
+        if is_fence(line) {
+            if in_code {
+                in_code = false;
+                match style {
+                    CodeStyle::Indented => after_block = true,
+                    CodeStyle::Hinted | CodeStyle::Bare => lines.push("```".to_string()),
+                }
+            } else {
+                in_code = true;
+                let hint = line.trim().trim_start_matches('`').trim();
+                match style {
+                    CodeStyle::Hinted => lines.push(format!("```{hint}")),
+                    CodeStyle::Bare => lines.push("```".to_string()),
+                    CodeStyle::Indented => {
+                        if lines.last().is_some_and(|last| !last.trim().is_empty()) {
+                            lines.push(String::new());
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+        if in_code && style == CodeStyle::Indented {
+            lines.push(if line.is_empty() {
+                String::new()
+            } else {
+                format!("    {line}")
+            });
+            continue;
+        }
+        if after_block && !line.trim().is_empty() {
+            lines.push(String::new());
+        }
+        after_block = false;
+        lines.push(line.to_string());
+    }
+    if in_code && style != CodeStyle::Indented {
+        lines.push("```".to_string());
+    }
+    let formatted = lines.join("\n");
+    if formatted == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(formatted)
+    }
+}
+
+/// Trim the message and collapse runs of blank lines outside code blocks.
+fn trim(text: &str) -> Cow<'_, str> {
+    let trimmed = text.trim();
+    if !trimmed.contains("\n\n\n") {
+        return if trimmed.len() == text.len() {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(trimmed.to_string())
+        };
+    }
+    let mut out = String::with_capacity(trimmed.len());
+    let mut in_code = false;
+    let mut blank_run = 0;
+    for line in trimmed.lines() {
+        if is_fence(line) {
+            in_code = !in_code;
+        }
+        if !in_code && line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.pop();
+    Cow::Owned(out)
+}
+
+/// Use `-` for list items and close a code block the model left open.
+fn normalize_markdown(text: &str) -> Cow<'_, str> {
+    let mut out = String::with_capacity(text.len() + 4);
+    let mut changed = false;
+    let mut in_code = false;
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        if is_fence(line) {
+            in_code = !in_code;
+            out.push_str(line);
+            continue;
+        }
+        let content = line.trim_start();
+        let indent = &line[..line.len() - content.len()];
+        match content
+            .strip_prefix("* ")
+            .or_else(|| content.strip_prefix("+ "))
+        {
+            Some(item) if !in_code => {
+                out.push_str(indent);
+                out.push_str("- ");
+                out.push_str(item);
+                changed = true;
+            }
+            _ => out.push_str(line),
+        }
+    }
+    if in_code {
+        out.push_str("\n```");
+        changed = true;
+    }
+    if changed {
+        Cow::Owned(out)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Reduce markdown to plain text: drop bold, inline code, heading markers and
+/// code fences, and render links as `text (url)`.
+fn strip_markdown(text: &str) -> Cow<'_, str> {
+    if !text.contains(['*', '`', '#', '[']) {
+        return Cow::Borrowed(text);
+    }
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in text.split('\n') {
+        if is_fence(line) {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(line.to_string());
+            continue;
+        }
+        let line = line
+            .trim_start_matches('#')
+            .strip_prefix(' ')
+            .filter(|_| line.starts_with('#'))
+            .unwrap_or(line);
+        lines.push(strip_inline_markdown(line));
+    }
+    let out = lines.join("\n");
+    if out == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+fn strip_inline_markdown(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[')
+            && let Some(close) = after.find("](")
+            && let Some(end) = after[close + 2..].find(')')
+        {
+            let label = &after[..close];
+            let url = &after[close + 2..close + 2 + end];
+            if label == url {
+                out.push_str(url);
+            } else {
+                out.push_str(&format!("{label} ({url})"));
+            }
+            rest = &after[close + 2 + end + 1..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            rest = after;
+            continue;
+        }
+        let mut chars = rest.chars();
+        let c = chars.next().unwrap_or_default();
+        if c != '`' {
+            out.push(c);
+        }
+        rest = chars.as_str();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutboundPipeline, split_preserving_code_blocks};
+    use crate::OutboundResponse;
+    use crate::config::{OutboundConfig, OutboundStage, RedactionConfig};
+    use std::collections::HashMap;
+
+    fn text(response: OutboundResponse) -> String {
+        match response {
+            OutboundResponse::Text(text) | OutboundResponse::StreamChunk(text) => text,
+            other => panic!("unexpected response {other:?}"),
+        }
+    }
+
+    #[test]
+    fn default_pipeline_only_redacts() {
+        let pipeline =
+            OutboundPipeline::new(&OutboundConfig::default(), &RedactionConfig::default());
+        let response =
+            OutboundResponse::Text("  key sk-ant-REDACTED\n\n\n* a  ".into());
+        assert_eq!(
+            text(pipeline.process(response, "discord")),
+            "  key [LEAKED_SECRET_REDACTED]\n\n\n* a  "
+        );
+    }
+
+    #[test]
+    fn stages_run_in_order_with_adapter_overrides() {
+        let config = OutboundConfig {
+            pipeline: vec![
+                OutboundStage::Trim,
+                OutboundStage::NormalizeMarkdown,
+                OutboundStage::RewriteLinks,
+            ],
+            adapter_pipelines: HashMap::from([(
+                "email".to_string(),
+                vec![OutboundStage::RewriteLinks, OutboundStage::StripMarkdown],
+            )]),
+            link_rewrites: vec![(
+                "http://dash.internal".to_string(),
+                "https://dash.example.com".to_string(),
+            )],
+            indent_code: Vec::new(),
+            rate_limits: HashMap::new(),
+        };
+        let pipeline = OutboundPipeline::new(&config, &RedactionConfig::default());
+        let reply = "\n## Done\n\n\n\n* see [the dashboard](http://dash.internal/x)\n```sh\nls";
+
+        assert_eq!(
+            text(pipeline.process(OutboundResponse::Text(reply.into()), "discord")),
+            "## Done\n\n- see [the dashboard](https://dash.example.com/x)\n```sh\nls\n```"
+        );
+        assert_eq!(
+            text(pipeline.process(OutboundResponse::Text(reply.into()), "email:support")),
+            "\nDone\n\n\n\n* see the dashboard (https://dash.example.com/x)\nls"
+        );
+    }
+
+    #[test]
+    fn streaming_chunks_are_only_redacted() {
+        let config = OutboundConfig {
+            pipeline: vec![OutboundStage::Trim],
+            ..OutboundConfig::default()
+        };
+        let pipeline = OutboundPipeline::new(&config, &RedactionConfig::default());
+        let chunk = OutboundResponse::StreamChunk(" token sk-ant-REDACTED ".into());
+        assert_eq!(
+            text(pipeline.process(chunk, "webchat")),
+            " token [LEAKED_SECRET_REDACTED] "
+        );
+    }
+
+    #[test]
+    fn overrides_cannot_turn_off_redaction() {
+        let config = OutboundConfig {
+            adapter_pipelines: HashMap::from([(
+                "email".to_string(),
+                vec![OutboundStage::StripMarkdown],
+            )]),
+            ..OutboundConfig::default()
+        };
+        let reply = "**key** sk-ant-REDACTED";
+
+        let pipeline = OutboundPipeline::new(&config, &RedactionConfig::default());
+        assert_eq!(
+            text(pipeline.process(OutboundResponse::Text(reply.into()), "email:support")),
+            "key [LEAKED_SECRET_REDACTED]"
+        );
+
+        let disabled = RedactionConfig {
+            enabled: false,
+            ..RedactionConfig::default()
+        };
+        let pipeline = OutboundPipeline::new(&config, &disabled);
+        assert_eq!(
+            text(pipeline.process(OutboundResponse::Text(reply.into()), "email:support")),
+            "key sk-ant-REDACTED"
+        );
+    }
+
+    #[test]
+    fn code_blocks_are_formatted_per_platform() {
+        let config = OutboundConfig {
+            pipeline: vec![OutboundStage::CodeBlocks],
+            indent_code: vec!["webhook".to_string()],
+            ..OutboundConfig::default()
+        };
+        let pipeline = OutboundPipeline::new(&config, &RedactionConfig::default());
+        let reply = "Run this:\n``` rust\nfn main() {}\n```\nThen check.";
+        let run =
+            |adapter: &str| text(pipeline.process(OutboundResponse::Text(reply.into()), adapter));
+
+        assert_eq!(
+            run("discord"),
+            "Run this:\n```rust\nfn main() {}\n```\nThen check."
+        );
+        assert_eq!(
+            run("slack"),
+            "Run this:\n```\nfn main() {}\n```\nThen check."
+        );
+        assert_eq!(
+            run("webhook"),
+            "Run this:\n\n    fn main() {}\n\nThen check."
+        );
+    }
+
+    #[test]
+    fn split_chunks_never_leave_a_code_block_open() {
+        let naive_split = |text: &str, max_len: usize| {
+            text.as_bytes()
+                .chunks(max_len)
+                .map(|chunk| String::from_utf8(chunk.to_vec()).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let code: String = (0..20).map(|i| format!("let x{i} = {i};\n")).collect();
+        let reply = format!("Here:\n```rust\n{code}```\nDone.");
+        let chunks = split_preserving_code_blocks(&reply, 120, naive_split);
+
+        assert!(chunks.len() > 2);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 120, "chunk over limit: {chunk:?}");
+            let fences = chunk.lines().filter(|line| line.starts_with("```")).count();
+            assert_eq!(fences % 2, 0, "unbalanced chunk: {chunk:?}");
+        }
+        assert!(chunks[1].starts_with("```rust\n"));
+
+        let plain = split_preserving_code_blocks("no code here", 5, naive_split);
+        assert_eq!(plain, naive_split("no code here", 5));
+    }
+}
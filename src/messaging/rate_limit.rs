@@ -0,0 +1,240 @@
+//! Outbound send pacing.
+//!
+//! Platforms reject bursts of sends with 429s, which used to surface as
+//! failed replies when an agent posted several messages back to back. The
+//! [`OutboundRateLimiter`] keeps a token bucket per adapter (or per adapter
+//! and channel, for platforms that limit each channel) and makes a send over
+//! budget wait for its slot instead of failing. Budgets come from
+//! `[outbound.rate_limits]`.
+
+use crate::config::OutboundRateLimit;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How often `acquire` drops buckets that have refilled since their last
+/// send.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A send budget: `messages` per `window`, refilled continuously.
+#[derive(Debug, Clone, Copy)]
+struct Budget {
+    messages: u32,
+    window: Duration,
+    per_channel: bool,
+}
+
+impl Budget {
+    fn tokens_per_sec(&self) -> f64 {
+        f64::from(self.messages) / self.window.as_secs_f64()
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Goes negative while sends are queued; each queued send owns one
+    /// token of the debt.
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Tokens available at `now`, capped at the budget.
+    fn tokens_at(&self, budget: Budget, now: Instant) -> f64 {
+        let refilled = now.duration_since(self.updated).as_secs_f64() * budget.tokens_per_sec();
+        (self.tokens + refilled).min(f64::from(budget.messages))
+    }
+}
+
+/// Buckets keyed by `(adapter, channel)`.
+#[derive(Debug, Default)]
+struct Buckets {
+    buckets: HashMap<(String, String), Bucket>,
+    /// When full buckets were last dropped.
+    swept: Option<Instant>,
+}
+
+/// Token buckets for every adapter with a configured budget.
+#[derive(Debug, Default)]
+pub struct OutboundRateLimiter {
+    budgets: HashMap<String, Budget>,
+    buckets: Mutex<Buckets>,
+}
+
+impl OutboundRateLimiter {
+    pub fn new(limits: &HashMap<String, OutboundRateLimit>) -> Self {
+        let budgets = limits
+            .iter()
+            .filter(|(_, limit)| limit.messages > 0)
+            .map(|(adapter, limit)| {
+                (
+                    adapter.clone(),
+                    Budget {
+                        messages: limit.messages,
+                        window: Duration::from_secs(limit.per_secs.max(1)),
+                        per_channel: limit.per_channel,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            budgets,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// The budget for `adapter` (e.g. `"discord:ops"`): its own, its
+    /// platform's, or none.
+    fn budget_for(&self, adapter: &str) -> Option<Budget> {
+        let platform = adapter.split(':').next().unwrap_or(adapter);
+        self.budgets
+            .get(adapter)
+            .or_else(|| self.budgets.get(platform))
+            .copied()
+    }
+
+    /// Wait until `adapter` may send another message to `channel`. Returns
+    /// at once while the budget has room; otherwise the send takes the next
+    /// free slot, so queued sends drain in the order they arrived. Named
+    /// instances sharing a platform's budget still get their own buckets.
+    ///
+    /// Channels come and go (an email thread, a webchat session), so every
+    /// [`SWEEP_INTERVAL`] buckets that have refilled are dropped. A full
+    /// bucket is the same as a missing one, so no pacing is lost.
+    pub async fn acquire(&self, adapter: &str, channel: &str) {
+        let Some(budget) = self.budget_for(adapter) else {
+            return;
+        };
+        let channel = if budget.per_channel { channel } else { "" };
+        let wait = {
+            let mut state = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            if state
+                .swept
+                .is_none_or(|swept| now.duration_since(swept) >= SWEEP_INTERVAL)
+            {
+                self.drop_full_buckets(&mut state.buckets, now);
+                state.swept = Some(now);
+            }
+            let bucket = state
+                .buckets
+                .entry((adapter.to_string(), channel.to_string()))
+                .or_insert(Bucket {
+                    tokens: f64::from(budget.messages),
+                    updated: now,
+                });
+            bucket.tokens = bucket.tokens_at(budget, now) - 1.0;
+            bucket.updated = now;
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / budget.tokens_per_sec())
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tracing::debug!(
+                adapter,
+                channel,
+                wait_ms = wait.as_millis() as u64,
+                "outbound send queued for rate limit"
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Drop the buckets that have refilled to their budget by `now`.
+    fn drop_full_buckets(&self, buckets: &mut HashMap<(String, String), Bucket>, now: Instant) {
+        buckets.retain(|(adapter, _), bucket| {
+            self.budget_for(adapter)
+                .is_some_and(|budget| bucket.tokens_at(budget, now) < f64::from(budget.messages))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, OutboundRateLimiter, SWEEP_INTERVAL};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[tokio::test(start_paused = true)]
+    async fn sends_over_budget_are_paced_not_dropped() {
+        let limiter = Arc::new(OutboundRateLimiter {
+            budgets: HashMap::from([(
+                "discord".to_string(),
+                Budget {
+                    messages: 10,
+                    window: Duration::from_millis(200),
+                    per_channel: true,
+                },
+            )]),
+            ..OutboundRateLimiter::default()
+        });
+
+        let start = Instant::now();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sends = (0..50).map(|_| {
+            let limiter = limiter.clone();
+            let sent = sent.clone();
+            tokio::spawn(async move {
+                limiter.acquire("discord:ops", "channel-1").await;
+                sent.lock().unwrap().push(start.elapsed());
+            })
+        });
+        for send in sends.collect::<Vec<_>>() {
+            send.await.unwrap();
+        }
+
+        let mut sent = sent.lock().unwrap().clone();
+        sent.sort();
+        assert_eq!(sent.len(), 50);
+        // A full bucket goes out at once, the other 40 drain one every 20ms.
+        let expected: Vec<_> = (0..50u64)
+            .map(|i| Duration::from_millis(i.saturating_sub(9) * 20))
+            .collect();
+        assert_eq!(sent, expected);
+
+        // Another channel has its own bucket, and unlimited adapters never wait.
+        let before = Instant::now();
+        limiter.acquire("discord:ops", "channel-2").await;
+        limiter.acquire("webhook", "channel-1").await;
+        assert_eq!(before.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_channel_buckets_are_dropped() {
+        let limiter = Arc::new(OutboundRateLimiter {
+            budgets: HashMap::from([(
+                "email".to_string(),
+                Budget {
+                    messages: 2,
+                    window: Duration::from_secs(10),
+                    per_channel: true,
+                },
+            )]),
+            ..OutboundRateLimiter::default()
+        });
+        let bucket_count = || limiter.buckets.lock().unwrap().buckets.len();
+
+        for thread in 0..20 {
+            limiter.acquire("email", &format!("thread-{thread}")).await;
+        }
+        // 20 sends queued on one thread leave its bucket 90s in debt.
+        for _ in 0..20 {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire("email", "busy").await });
+        }
+        tokio::task::yield_now().await;
+        assert_eq!(bucket_count(), 21);
+
+        // After the sweep interval the idle threads have refilled and are
+        // dropped, while the busy thread still owes tokens.
+        tokio::time::advance(SWEEP_INTERVAL).await;
+        limiter.acquire("email", "thread-0").await;
+        assert_eq!(bucket_count(), 2);
+    }
+}
@@ -0,0 +1,214 @@
+//! Terminal REPL adapter for local development.
+//!
+//! Reads lines from stdin and routes them to a single local conversation, then
+//! prints responses and status updates to stdout. Used by `spacebot repl` to
+//! iterate on prompts, skills, and tools without a messaging platform. Worker
+//! spawning, compaction, and every other channel feature behave exactly as
+//! they would on a real platform because the channel never knows the
+//! difference.
+
+use crate::messaging::traits::{InboundStream, Messaging};
+use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
+
+use std::collections::HashMap;
+use std::io::Write as _;
+use tokio::io::AsyncBufReadExt as _;
+use tokio::sync::{mpsc, watch};
+
+/// Conversation ID used for REPL sessions. Fixed so history carries over
+/// between runs through the regular conversation log backfill.
+const REPL_CONVERSATION_ID: &str = "repl:local";
+
+/// Sender identity attached to typed lines.
+const REPL_SENDER: &str = "local";
+
+/// Stdin/stdout messaging adapter.
+pub struct ReplAdapter {
+    /// Agent to route typed lines to. Falls back to binding resolution
+    /// (and therefore the default agent) when unset.
+    agent_id: Option<String>,
+    /// Flipped to `true` when the user quits or stdin hits EOF.
+    closed_tx: watch::Sender<bool>,
+}
+
+impl ReplAdapter {
+    pub fn new(agent_id: Option<String>) -> Self {
+        let (closed_tx, _) = watch::channel(false);
+        Self {
+            agent_id,
+            closed_tx,
+        }
+    }
+
+    /// Resolves once the user has ended the session (`/quit` or EOF).
+    pub async fn closed(&self) {
+        let mut closed_rx = self.closed_tx.subscribe();
+        closed_rx.wait_for(|closed| *closed).await.ok();
+    }
+
+    fn build_message(agent_id: Option<&str>, text: String) -> InboundMessage {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "sender_display_name".into(),
+            serde_json::Value::String(REPL_SENDER.into()),
+        );
+        metadata.insert(
+            crate::metadata_keys::CHANNEL_NAME.into(),
+            serde_json::Value::String("repl".into()),
+        );
+
+        InboundMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: "repl".into(),
+            adapter: Some("repl".into()),
+            conversation_id: REPL_CONVERSATION_ID.into(),
+            sender_id: REPL_SENDER.into(),
+            agent_id: agent_id.map(Into::into),
+            content: MessageContent::Text(text),
+            timestamp: chrono::Utc::now(),
+            metadata,
+            formatted_author: Some(REPL_SENDER.into()),
+        }
+    }
+}
+
+/// Render a status update as a single dim progress line, or `None` for
+/// updates that carry no useful information in a terminal.
+fn format_status(status: &StatusUpdate) -> Option<String> {
+    match status {
+        StatusUpdate::Thinking => Some("thinking…".into()),
+        StatusUpdate::StopTyping => None,
+        StatusUpdate::ToolStarted { tool_name } => Some(format!("tool {tool_name} started")),
+        StatusUpdate::ToolCompleted { tool_name } => Some(format!("tool {tool_name} finished")),
+        StatusUpdate::BranchStarted { branch_id } => Some(format!("branch {branch_id} started")),
+        StatusUpdate::WorkerStarted { worker_id, task } => {
+            Some(format!("worker {worker_id} started: {task}"))
+        }
+        StatusUpdate::WorkerCompleted { worker_id, .. } => {
+            Some(format!("worker {worker_id} completed"))
+        }
+    }
+}
+
+fn print_line(text: &str) {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{text}").ok();
+    stdout.flush().ok();
+}
+
+impl Messaging for ReplAdapter {
+    fn name(&self) -> &str {
+        "repl"
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        let (inbound_tx, inbound_rx) = mpsc::channel(32);
+        let agent_id = self.agent_id.clone();
+        let closed_tx = self.closed_tx.clone();
+
+        eprintln!("spacebot repl — type a message and press enter, /quit to exit");
+
+        tokio::spawn(async move {
+            let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+            loop {
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break,
+                    Err(error) => {
+                        tracing::warn!(%error, "failed to read repl input");
+                        break;
+                    }
+                };
+
+                let text = line.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                if text == "/quit" || text == "/exit" {
+                    break;
+                }
+
+                let message = Self::build_message(agent_id.as_deref(), text.to_string());
+                if inbound_tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+
+            closed_tx.send_replace(true);
+        });
+
+        Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(
+            inbound_rx,
+        )))
+    }
+
+    async fn respond(
+        &self,
+        _message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
+                print_line(&format!("\n{text}\n"));
+            }
+            OutboundResponse::File {
+                filename,
+                data,
+                caption,
+                ..
+            } => {
+                let caption = caption
+                    .map(|caption| format!(": {caption}"))
+                    .unwrap_or_default();
+                print_line(&format!("[file {filename}, {} bytes{caption}]", data.len()));
+            }
+            OutboundResponse::Reaction(emoji) => print_line(&format!("[reacted {emoji}]")),
+            OutboundResponse::RemoveReaction(_) => {}
+            OutboundResponse::StreamStart => print_line(""),
+            OutboundResponse::StreamChunk(chunk) => {
+                let mut stdout = std::io::stdout().lock();
+                write!(stdout, "{chunk}").ok();
+                stdout.flush().ok();
+            }
+            OutboundResponse::StreamEnd => print_line("\n"),
+            OutboundResponse::Status(status) => {
+                if let Some(line) = format_status(&status) {
+                    print_line(&format!("  · {line}"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_status(
+        &self,
+        _message: &InboundMessage,
+        status: StatusUpdate,
+    ) -> crate::Result<()> {
+        if let Some(line) = format_status(&status) {
+            print_line(&format!("  · {line}"));
+        }
+        Ok(())
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
+        if let OutboundResponse::Text(text) | OutboundResponse::RichMessage { text, .. } = response
+        {
+            print_line(&format!("\n[broadcast to {target}]\n{text}\n"));
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> crate::Result<()> {
+        self.closed_tx.send_replace(true);
+        Ok(())
+    }
+}
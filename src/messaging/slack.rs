@@ -23,7 +23,7 @@
 
 use crate::config::{SlackCommandConfig, SlackPermissions};
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
-use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
+use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging, split_message};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
 use anyhow::Context as _;
@@ -1643,39 +1643,6 @@ fn variant_name(response: &OutboundResponse) -> &'static str {
     }
 }
 
-/// Split a message into UTF-8-safe chunks at line/word boundaries.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Walk back to a valid char boundary before slicing
-        let mut safe_max = max_len.min(remaining.len());
-        while !remaining.is_char_boundary(safe_max) {
-            safe_max -= 1;
-        }
-
-        let split_at = remaining[..safe_max]
-            .rfind('\n')
-            .or_else(|| remaining[..safe_max].rfind(' '))
-            .unwrap_or(safe_max);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
-
 /// Convert an emoji input to a Slack reaction short-code name.
 ///
 /// Handles three input forms:
@@ -2,7 +2,7 @@
 
 use crate::config::TelegramPermissions;
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
-use crate::messaging::traits::{InboundStream, Messaging};
+use crate::messaging::traits::{InboundStream, Messaging, split_message};
 use crate::{Attachment, InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
 use anyhow::Context as _;
@@ -1000,34 +1000,6 @@ async fn send_poll(bot: &Bot, chat_id: ChatId, poll: &crate::Poll) -> anyhow::Re
     Ok(())
 }
 
-/// Split a message into chunks that fit within Telegram's character limit.
-/// Tries to split at newlines, then spaces, then hard-cuts.
-fn split_message(text: &str, max_len: usize) -> Vec<String> {
-    if text.len() <= max_len {
-        return vec![text.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        if remaining.len() <= max_len {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        let split_at = remaining[..max_len]
-            .rfind('\n')
-            .or_else(|| remaining[..max_len].rfind(' '))
-            .unwrap_or(max_len);
-
-        chunks.push(remaining[..split_at].to_string());
-        remaining = remaining[split_at..].trim_start();
-    }
-
-    chunks
-}
-
 /// Return true when Telegram rejected rich text entities and a plain-caption retry is safe.
 fn should_retry_plain_caption(error: &RequestError) -> bool {
     matches!(error, RequestError::Api(ApiError::CantParseEntities(_)))
@@ -1,6 +1,7 @@
 //! Messaging trait and dynamic dispatch companion.
 
 use crate::error::Result;
+use crate::messaging::pipeline::split_preserving_code_blocks;
 use crate::{InboundMessage, OutboundResponse, StatusUpdate};
 use futures::Stream;
 use std::pin::Pin;
@@ -192,3 +193,144 @@ pub fn apply_runtime_adapter_to_conversation_id(
         format!("{runtime_key}:{remainder}")
     }
 }
+
+/// Largest file a platform accepts as an `OutboundResponse::File`, or `None`
+/// for platforms that can't receive files at all (Twitch chat, webhooks and
+/// the web chat only see the filename). Platforms not listed here get 25 MiB.
+///
+/// | Platform | Limit |
+/// |----------|-------|
+/// | Discord | 10 MiB, the limit for servers without boosts |
+/// | Telegram | 50 MB, the Bot API upload limit |
+/// | Slack | 1 GB |
+/// | Signal | 100 MiB |
+/// | Email | 20 MiB, under what most mail servers accept once encoded |
+pub fn max_attachment_bytes(platform: &str) -> Option<u64> {
+    const MIB: u64 = 1024 * 1024;
+    match platform {
+        "discord" => Some(10 * MIB),
+        "telegram" => Some(50 * 1000 * 1000),
+        "slack" => Some(1000 * 1000 * 1000),
+        "signal" => Some(100 * MIB),
+        "email" => Some(20 * MIB),
+        "twitch" | "webhook" | "webchat" => None,
+        _ => Some(25 * MIB),
+    }
+}
+
+/// Split an outgoing message into chunks of at most `max_len` bytes, the
+/// platform's message limit, to be sent in order.
+///
+/// Splits prefer paragraph breaks, then line breaks, then spaces, so words
+/// stay whole unless one is longer than the limit. A code block that spans a
+/// split is closed at the end of its chunk and reopened, with its language
+/// hint, at the start of the next, so every chunk is valid markdown.
+pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    split_preserving_code_blocks(text, max_len, split_at_boundaries)
+}
+
+/// Split plain text at the last paragraph break, line break or space that
+/// fits in `max_len` bytes. Breaks in the first half of a chunk are only
+/// used when nothing later fits, so chunks don't come out tiny. Never
+/// splits inside a character, and keeps indentation at the start of a
+/// chunk.
+pub fn split_at_boundaries(text: &str, max_len: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut remaining = text;
+
+    while !remaining.is_empty() {
+        if remaining.len() <= max_len {
+            chunks.push(remaining.to_string());
+            break;
+        }
+
+        let mut window_len = remaining.floor_char_boundary(max_len);
+        if window_len == 0 {
+            // The limit is smaller than the first character; send it alone.
+            window_len = remaining
+                .char_indices()
+                .nth(1)
+                .map_or(remaining.len(), |(index, _)| index);
+        }
+        let window = &remaining[..window_len];
+        let late = |index: &usize| *index >= window_len / 2;
+        let split_at = window
+            .rfind("\n\n")
+            .filter(late)
+            .or_else(|| window.rfind('\n').filter(late))
+            .or_else(|| window.rfind(' ').filter(late))
+            .or_else(|| window.rfind('\n'))
+            .or_else(|| window.rfind(' '))
+            .filter(|index| *index > 0)
+            .unwrap_or(window_len);
+
+        let chunk = remaining[..split_at].trim_end();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        let rest = &remaining[split_at..];
+        remaining = match rest.strip_prefix(' ') {
+            Some(rest) => rest,
+            None => rest.trim_start_matches(['\r', '\n']),
+        };
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_at_boundaries, split_message};
+
+    #[test]
+    fn long_message_with_a_code_block_splits_into_valid_chunks() {
+        let prose = "The deploy finished and every health check passed. ".repeat(40);
+        let code: String = (0..60)
+            .map(|i| format!("    let replica_{i} = connect(\"db-{i}\").await?;\n"))
+            .collect();
+        let text = format!("{prose}\n\n```rust\n{code}```\n\n{prose}");
+        assert!(text.len() >= 5000);
+
+        let chunks = split_message(&text, 2000);
+        assert!(chunks.len() >= 3);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 2000, "chunk over limit: {}", chunk.len());
+            let fences = chunk.lines().filter(|line| line.starts_with("```")).count();
+            assert_eq!(fences % 2, 0, "unbalanced chunk: {chunk:?}");
+        }
+
+        // No word was cut: every word of every chunk is a word of the text,
+        // and the words come back in order.
+        let original: Vec<&str> = text.split_whitespace().collect();
+        let rejoined: Vec<&str> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.split_whitespace())
+            .filter(|word| !word.starts_with("```"))
+            .collect();
+        let original: Vec<&str> = original
+            .into_iter()
+            .filter(|word| !word.starts_with("```"))
+            .collect();
+        assert_eq!(rejoined, original);
+
+        // Reopened blocks keep their language hint and their indentation.
+        assert!(
+            chunks[1..]
+                .iter()
+                .any(|chunk| chunk.starts_with("```rust\n    let replica_"))
+        );
+    }
+
+    #[test]
+    fn boundary_split_handles_multibyte_text_and_oversized_words() {
+        let text = "héllo wörld ".repeat(10);
+        for chunk in split_at_boundaries(&text, 7) {
+            assert!(chunk.len() <= 7);
+        }
+        assert_eq!(
+            split_at_boundaries("abcdefghij", 4),
+            vec!["abcd", "efgh", "ij"]
+        );
+        assert_eq!(split_at_boundaries("short", 100), vec!["short"]);
+    }
+}
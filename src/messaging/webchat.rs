@@ -96,6 +96,7 @@ impl Messaging for WebChatAdapter {
             agent_id: agent_id.to_string(),
             channel_id: target.to_string(),
             text,
+            message_id: None,
         })
         .ok();
 
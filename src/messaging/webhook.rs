@@ -4,19 +4,27 @@
 //! delivers responses via a per-conversation polling endpoint. This is
 //! the integration point for scripts, CI pipelines, and other programs
 //! that need to interact with Spacebot programmatically.
+//!
+//! Requests are authenticated with a bearer token, an HMAC signature of the
+//! body, or both. With a signing secret configured, a `/send` whose
+//! signature header doesn't match the body is rejected before it is parsed.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::Context as _;
 use axum::Router;
+use axum::body::Bytes;
 use axum::extract::{Json, State};
 use axum::http::header::AUTHORIZATION;
 use axum::http::{HeaderMap, StatusCode};
 use axum::routing::{get, post};
+use hmac::{Hmac, Mac as _};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use tokio::sync::{RwLock, mpsc};
 
+use crate::config::{WebhookSignatureConfig, WebhookSignatureScheme};
 use crate::messaging::traits::{InboundStream, Messaging};
 use crate::{InboundMessage, MessageContent, OutboundResponse};
 
@@ -25,6 +33,7 @@ pub struct WebhookAdapter {
     port: u16,
     bind: String,
     auth_token: Option<String>,
+    signature: Option<Arc<WebhookSignatureConfig>>,
     inbound_tx: Arc<RwLock<Option<mpsc::Sender<InboundMessage>>>>,
     /// Buffered responses per conversation_id, waiting to be polled.
     response_buffers: Arc<RwLock<HashMap<String, Vec<WebhookResponse>>>>,
@@ -37,6 +46,7 @@ struct AppState {
     inbound_tx: Arc<RwLock<Option<mpsc::Sender<InboundMessage>>>>,
     response_buffers: Arc<RwLock<HashMap<String, Vec<WebhookResponse>>>>,
     auth_token: Option<String>,
+    signature: Option<Arc<WebhookSignatureConfig>>,
     runtime_key: String,
 }
 
@@ -81,11 +91,18 @@ impl WebhookAdapter {
             port,
             bind: bind.into(),
             auth_token,
+            signature: None,
             inbound_tx: Arc::new(RwLock::new(None)),
             response_buffers: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
         }
     }
+
+    /// Require `/send` bodies to carry a valid HMAC signature.
+    pub fn with_signature(mut self, signature: Option<WebhookSignatureConfig>) -> Self {
+        self.signature = signature.map(Arc::new);
+        self
+    }
 }
 
 impl Messaging for WebhookAdapter {
@@ -104,12 +121,13 @@ impl Messaging for WebhookAdapter {
             inbound_tx: self.inbound_tx.clone(),
             response_buffers: self.response_buffers.clone(),
             auth_token: self.auth_token.clone(),
+            signature: self.signature.clone(),
             runtime_key: self.name().to_string(),
         };
 
-        if self.auth_token.is_none() {
+        if self.auth_token.is_none() && self.signature.is_none() {
             tracing::warn!(
-                "webhook authentication is disabled because no auth token is configured"
+                "webhook authentication is disabled because no auth token or signing secret is configured"
             );
         }
 
@@ -241,11 +259,23 @@ impl Messaging for WebhookAdapter {
 async fn handle_send(
     headers: HeaderMap,
     State(state): State<AppState>,
-    Json(request): Json<WebhookRequest>,
+    body: Bytes,
 ) -> Result<StatusCode, (StatusCode, String)> {
     if !is_authorized(&headers, state.auth_token.as_deref()) {
         return Err((StatusCode::UNAUTHORIZED, "unauthorized".into()));
     }
+    if let Some(signature) = &state.signature
+        && !has_valid_signature(&headers, &body, signature)
+    {
+        tracing::warn!("rejected webhook request with a missing or invalid signature");
+        return Err((StatusCode::UNAUTHORIZED, "invalid signature".into()));
+    }
+    let request: WebhookRequest = serde_json::from_slice(&body).map_err(|error| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("invalid request body: {error}"),
+        )
+    })?;
 
     let tx = state.inbound_tx.read().await;
     let Some(tx) = tx.as_ref() else {
@@ -338,3 +368,86 @@ fn is_authorized(headers: &HeaderMap, expected_token: Option<&str>) -> bool {
         .and_then(|value| value.strip_prefix("Bearer "))
         .is_some_and(|token| token == expected_token)
 }
+
+/// Check the configured signature header against an HMAC-SHA256 of `body`.
+/// The comparison is constant-time.
+fn has_valid_signature(
+    headers: &HeaderMap,
+    body: &[u8],
+    signature: &WebhookSignatureConfig,
+) -> bool {
+    let Some(value) = headers
+        .get(signature.header.as_str())
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let digest = match signature.scheme {
+        WebhookSignatureScheme::Hex => Some(value.trim()),
+        WebhookSignatureScheme::Sha256Prefixed => value.trim().strip_prefix("sha256="),
+    };
+    let Some(Ok(digest)) = digest.map(hex::decode) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(signature.secret.as_bytes())
+        .expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&digest).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::has_valid_signature;
+    use crate::config::{WebhookSignatureConfig, WebhookSignatureScheme};
+
+    use axum::http::HeaderMap;
+    use hmac::{Hmac, Mac as _};
+    use sha2::Sha256;
+
+    const BODY: &[u8] = br#"{"conversation_id":"ci","content":"deploy finished"}"#;
+
+    fn config(scheme: WebhookSignatureScheme) -> WebhookSignatureConfig {
+        WebhookSignatureConfig {
+            secret: "shared-secret".into(),
+            header: "x-hub-signature-256".into(),
+            scheme,
+        }
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"shared-secret").unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Hub-Signature-256", value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn valid_signatures_are_accepted() {
+        let hex = config(WebhookSignatureScheme::Hex);
+        assert!(has_valid_signature(&headers(&sign(BODY)), BODY, &hex));
+
+        let github = config(WebhookSignatureScheme::Sha256Prefixed);
+        let prefixed = format!("sha256={}", sign(BODY));
+        assert!(has_valid_signature(&headers(&prefixed), BODY, &github));
+    }
+
+    #[test]
+    fn tampered_or_missing_signatures_are_rejected() {
+        let hex = config(WebhookSignatureScheme::Hex);
+        let signature = sign(BODY);
+        let tampered = br#"{"conversation_id":"ci","content":"rm -rf /"}"#;
+
+        assert!(!has_valid_signature(&headers(&signature), tampered, &hex));
+        assert!(!has_valid_signature(&headers("not-hex"), BODY, &hex));
+        assert!(!has_valid_signature(&HeaderMap::new(), BODY, &hex));
+
+        // A bare digest doesn't satisfy the prefixed scheme.
+        let github = config(WebhookSignatureScheme::Sha256Prefixed);
+        assert!(!has_valid_signature(&headers(&signature), BODY, &github));
+    }
+}
@@ -1,5 +1,7 @@
+pub mod assembly;
 pub mod engine;
 pub mod text;
 
+pub use assembly::{PromptInputs, render_system_prompts};
 pub use engine::{PromptEngine, SkillInfo};
 pub use text::{get as get_text, init as init_language};
@@ -0,0 +1,196 @@
+//! Assembly of complete system prompts outside a running process.
+//!
+//! Channels, workers and branches build their system prompts from live state
+//! when they start. [`render_system_prompts`] builds the same prompts from a
+//! [`PromptInputs`] snapshot instead, through the same convenience methods,
+//! so prompt assembly can be reviewed or snapshot-tested without running the
+//! bot.
+
+use crate::ProcessType;
+use crate::config::RuntimeConfig;
+use crate::error::Result;
+use crate::prompts::PromptEngine;
+use crate::sandbox::Sandbox;
+use crate::skills::SkillSet;
+use crate::tools::ToolPermissions;
+
+use std::collections::BTreeMap;
+
+/// Everything the system prompts depend on besides the templates and skills.
+///
+/// Live-only context (status block, conversation, coalesce hints, available
+/// channels, org and project context) is left out: those sections come and go
+/// with activity, and leaving them out keeps the rendered prompts stable.
+#[derive(Debug, Clone, Default)]
+pub struct PromptInputs {
+    pub identity_context: Option<String>,
+    pub memory_bulletin: Option<String>,
+    pub instance_dir: String,
+    pub workspace_dir: String,
+    pub browser_enabled: bool,
+    pub browser_persist_session: bool,
+    pub web_search_enabled: bool,
+    pub http_request_enabled: bool,
+    pub opencode_enabled: bool,
+    pub memory_recall_enabled: bool,
+    pub tool_gating_enabled: bool,
+    pub tool_permissions: ToolPermissions,
+    pub mcp_tool_names: Vec<String>,
+    pub sandbox_enabled: bool,
+    pub sandbox_containment_active: bool,
+    pub sandbox_read_allowlist: Vec<String>,
+    pub sandbox_write_allowlist: Vec<String>,
+    pub tool_secret_names: Vec<String>,
+}
+
+impl PromptInputs {
+    /// Gather inputs from an agent's runtime config. MCP tool names come from
+    /// the MCP manager, not the config, so they start empty.
+    pub fn from_runtime_config(rc: &RuntimeConfig, sandbox: &Sandbox) -> Self {
+        let non_empty = |text: String| (!text.is_empty()).then_some(text);
+        let browser_config = rc.browser_config.load();
+        let worker_config = rc.worker.load();
+        let secrets_guard = rc.secrets.load();
+        let tool_secret_names = match (*secrets_guard).as_ref() {
+            Some(store) => store.tool_secret_names(),
+            None => Vec::new(),
+        };
+        Self {
+            identity_context: non_empty(rc.identity.load().render()),
+            memory_bulletin: non_empty(rc.memory_bulletin.load().to_string()),
+            instance_dir: rc.instance_dir.display().to_string(),
+            workspace_dir: rc.workspace_dir.display().to_string(),
+            browser_enabled: browser_config.enabled,
+            browser_persist_session: browser_config.persist_session,
+            web_search_enabled: rc.web_search_enabled(),
+            http_request_enabled: worker_config.http_request.enabled,
+            opencode_enabled: rc.opencode.load().enabled,
+            memory_recall_enabled: worker_config.memory_recall,
+            tool_gating_enabled: worker_config.tool_gating,
+            tool_permissions: worker_config.tool_permissions,
+            mcp_tool_names: Vec::new(),
+            sandbox_enabled: sandbox.mode_enabled(),
+            sandbox_containment_active: sandbox.containment_active(),
+            sandbox_read_allowlist: sandbox.prompt_read_allowlist(),
+            sandbox_write_allowlist: sandbox.prompt_write_allowlist(),
+            tool_secret_names,
+        }
+    }
+}
+
+/// Append the skills listing to a rendered worker prompt, flagging
+/// `suggested` skills. A listing that fails to render is logged and left out
+/// rather than failing the worker.
+pub fn append_worker_skills(
+    worker_prompt: String,
+    skills: &SkillSet,
+    suggested: &[&str],
+    prompt_engine: &PromptEngine,
+) -> String {
+    match skills.render_worker_skills(suggested, prompt_engine) {
+        Ok(skills_prompt) if !skills_prompt.is_empty() => {
+            format!("{worker_prompt}\n\n{skills_prompt}")
+        }
+        Ok(_) => worker_prompt,
+        Err(error) => {
+            tracing::warn!(%error, "failed to render worker skills listing, spawning without skills context");
+            worker_prompt
+        }
+    }
+}
+
+/// Render the complete system prompt of every process type.
+pub fn render_system_prompts(
+    prompt_engine: &PromptEngine,
+    skills: &SkillSet,
+    inputs: &PromptInputs,
+) -> Result<BTreeMap<ProcessType, String>> {
+    let skills_prompt = skills.render_channel_prompt(prompt_engine)?;
+    let worker_capabilities = prompt_engine.render_worker_capabilities(
+        inputs.browser_enabled,
+        inputs.web_search_enabled,
+        inputs.http_request_enabled,
+        inputs.opencode_enabled,
+        inputs.memory_recall_enabled,
+        inputs.tool_gating_enabled,
+        inputs.tool_permissions,
+        &inputs.mcp_tool_names,
+    )?;
+    let channel = prompt_engine.render_channel_prompt(
+        inputs.identity_context.clone(),
+        inputs.memory_bulletin.clone(),
+        (!skills_prompt.is_empty()).then_some(skills_prompt),
+        worker_capabilities,
+        None,
+        None,
+        None,
+        None,
+        inputs.sandbox_containment_active,
+    )?;
+
+    let worker = prompt_engine.render_worker_prompt(
+        &inputs.instance_dir,
+        &inputs.workspace_dir,
+        inputs.sandbox_enabled,
+        inputs.sandbox_containment_active,
+        inputs.sandbox_read_allowlist.clone(),
+        inputs.sandbox_write_allowlist.clone(),
+        &inputs.tool_secret_names,
+        inputs.browser_persist_session,
+        None,
+    )?;
+    let worker = append_worker_skills(worker, skills, &[], prompt_engine);
+
+    let branch = prompt_engine.render_branch_prompt(&inputs.instance_dir, &inputs.workspace_dir)?;
+
+    Ok(BTreeMap::from([
+        (ProcessType::Channel, channel),
+        (ProcessType::Branch, branch),
+        (ProcessType::Worker, worker),
+        (
+            ProcessType::Compactor,
+            prompt_engine.render_static("compactor")?,
+        ),
+        (ProcessType::Cortex, prompt_engine.render_static("cortex")?),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PromptInputs, render_system_prompts};
+    use crate::ProcessType;
+    use crate::prompts::PromptEngine;
+    use crate::skills::SkillSet;
+
+    #[test]
+    fn every_process_type_gets_a_prompt_built_from_the_inputs() {
+        let engine = PromptEngine::new("en").unwrap();
+        let inputs = PromptInputs {
+            identity_context: Some("You are Sprocket, the ops team's agent.".into()),
+            instance_dir: "/srv/spacebot".into(),
+            workspace_dir: "/srv/spacebot/agents/main/workspace".into(),
+            browser_enabled: true,
+            ..PromptInputs::default()
+        };
+        let prompts = render_system_prompts(&engine, &SkillSet::default(), &inputs).unwrap();
+
+        assert_eq!(
+            prompts.keys().copied().collect::<Vec<_>>(),
+            [
+                ProcessType::Channel,
+                ProcessType::Branch,
+                ProcessType::Worker,
+                ProcessType::Compactor,
+                ProcessType::Cortex,
+            ]
+        );
+        assert!(prompts[&ProcessType::Channel].contains("You are Sprocket"));
+        assert!(prompts[&ProcessType::Worker].contains("/srv/spacebot/agents/main/workspace"));
+        assert!(prompts[&ProcessType::Branch].contains("/srv/spacebot/agents/main/workspace"));
+        assert!(prompts.values().all(|prompt| !prompt.trim().is_empty()));
+
+        // Nothing time- or activity-dependent, so the output is a stable snapshot.
+        let again = render_system_prompts(&engine, &SkillSet::default(), &inputs).unwrap();
+        assert_eq!(prompts, again);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::error::Result;
+use crate::tools::ToolPermissions;
 use anyhow::Context;
+use minijinja::value::Rest;
 use minijinja::{Environment, Value, context};
 use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// A completed background process result, passed to the retrigger template.
 #[derive(Clone, Debug, Serialize)]
@@ -18,151 +20,382 @@ pub struct RetriggerResult {
     pub result: String,
 }
 
+/// Every template the engine registers, by name. Each can be replaced with
+/// [`PromptEngine::with_overrides`].
+const TEMPLATE_NAMES: &[&str] = &[
+    "channel",
+    "branch",
+    "worker",
+    "cortex",
+    "cortex_bulletin",
+    "compactor",
+    "memory_persistence",
+    "ingestion",
+    "cortex_chat",
+    "cortex_profile",
+    "factory",
+    "adapters/email",
+    "adapters/cron",
+    "adapters/signal",
+    "fragments/worker_capabilities",
+    "fragments/conversation_context",
+    "fragments/skills_channel",
+    "fragments/skills_worker",
+    "fragments/available_channels",
+    "fragments/org_context",
+    "fragments/worker_clarifications",
+    "fragments/user_content_fence",
+    "fragments/channel_language",
+    "fragments/worker_direct_answer",
+    "fragments/worker_plan_mode",
+    "fragments/worker_workdir",
+    "fragments/worker_tool_restrictions",
+    "fragments/projects_context",
+    "fragments/system/retrigger",
+    "fragments/system/truncation",
+    "fragments/system/task_truncation",
+    "fragments/system/worker_overflow",
+    "fragments/system/output_limit_retry",
+    "fragments/system/worker_result_summary",
+    "fragments/system/worker_result_attached",
+    "fragments/system/worker_empty_result",
+    "fragments/system/worker_wrap_up",
+    "fragments/system/worker_cancelled",
+    "fragments/system/status_refresh",
+    "fragments/system/worker_follow_ups",
+    "fragments/system/worker_preflight",
+    "fragments/system/worker_preflight_proceed",
+    "fragments/system/worker_continue_in_background",
+    "fragments/system/worker_compact",
+    "fragments/system/worker_resume",
+    "fragments/system/memory_persistence",
+    "fragments/system/memory_persistence_contract_retry",
+    "fragments/system/cortex_synthesis",
+    "fragments/system/profile_synthesis",
+    "fragments/system/ingestion_chunk",
+    "fragments/system/history_backfill",
+    "fragments/system/tool_syntax_correction",
+    "fragments/system/command_refused",
+    "fragments/system/identity_reminder",
+    "fragments/coalesce_hint",
+];
+
+/// Context keys each render site supplies, by template. Templates not listed
+/// are rendered with `render_static` and get no variables. Keep an entry in
+/// step with the `context!` of its `render_*` method: startup validation
+/// rejects any template that references a variable not listed here.
+const TEMPLATE_VARIABLES: &[(&str, &[&str])] = &[
+    (
+        "channel",
+        &[
+            "identity_context",
+            "memory_bulletin",
+            "skills_prompt",
+            "worker_capabilities",
+            "conversation_context",
+            "status_text",
+            "coalesce_hint",
+            "available_channels",
+            "sandbox_enabled",
+            "org_context",
+            "adapter_prompt",
+            "project_context",
+            "backfill_transcript",
+        ],
+    ),
+    ("branch", &["instance_dir", "workspace_dir"]),
+    (
+        "worker",
+        &[
+            "instance_dir",
+            "workspace_dir",
+            "sandbox_enabled",
+            "sandbox_containment_active",
+            "sandbox_read_allowlist",
+            "sandbox_write_allowlist",
+            "tool_secret_names",
+            "browser_persist_session",
+            "status_text",
+        ],
+    ),
+    (
+        "cortex_chat",
+        &[
+            "identity_context",
+            "memory_bulletin",
+            "channel_transcript",
+            "agents_manifest",
+            "changelog_highlights",
+            "runtime_config_snapshot",
+            "worker_capabilities",
+            "factory_enabled",
+        ],
+    ),
+    ("factory", &["identity_context", "memory_bulletin"]),
+    (
+        "fragments/worker_capabilities",
+        &[
+            "shell_enabled",
+            "file_write_enabled",
+            "browser_enabled",
+            "web_search_enabled",
+            "http_request_enabled",
+            "opencode_enabled",
+            "memory_recall_enabled",
+            "tool_gating_enabled",
+            "mcp_tool_names",
+        ],
+    ),
+    (
+        "fragments/conversation_context",
+        &["platform", "server_name", "channel_name"],
+    ),
+    ("fragments/skills_channel", &["skills"]),
+    ("fragments/skills_worker", &["skills"]),
+    ("fragments/available_channels", &["channels"]),
+    ("fragments/org_context", &["org_context"]),
+    ("fragments/channel_language", &["language", "language_name"]),
+    ("fragments/worker_workdir", &["workdir", "retained"]),
+    ("fragments/worker_tool_restrictions", &["denied_tools"]),
+    ("fragments/projects_context", &["projects"]),
+    ("fragments/system/retrigger", &["results"]),
+    ("fragments/system/truncation", &["remove_count"]),
+    (
+        "fragments/system/task_truncation",
+        &["max_chars", "length", "path"],
+    ),
+    (
+        "fragments/system/output_limit_retry",
+        &["length", "max_chars"],
+    ),
+    ("fragments/system/worker_result_summary", &["target_chars"]),
+    (
+        "fragments/system/worker_result_attached",
+        &["path", "length"],
+    ),
+    (
+        "fragments/system/worker_cancelled",
+        &["reason", "summary", "last_output"],
+    ),
+    ("fragments/system/status_refresh", &["status"]),
+    ("fragments/system/command_refused", &["refusal"]),
+    ("fragments/system/worker_follow_ups", &["messages"]),
+    (
+        "fragments/system/worker_preflight",
+        &["task", "can_clarify"],
+    ),
+    (
+        "fragments/system/worker_continue_in_background",
+        &["message"],
+    ),
+    (
+        "fragments/system/worker_compact",
+        &["remove_count", "recap"],
+    ),
+    (
+        "fragments/system/cortex_synthesis",
+        &["max_words", "raw_sections"],
+    ),
+    (
+        "fragments/system/profile_synthesis",
+        &["identity_context", "memory_bulletin"],
+    ),
+    (
+        "fragments/system/ingestion_chunk",
+        &["filename", "chunk_number", "total_chunks", "chunk"],
+    ),
+    ("fragments/system/history_backfill", &["transcript"]),
+    ("fragments/system/identity_reminder", &["identity_context"]),
+    (
+        "fragments/coalesce_hint",
+        &["message_count", "elapsed", "unique_senders"],
+    ),
+];
+
+/// Names MiniJinja resolves itself, which templates may use without a
+/// render site supplying them.
+const BUILTIN_NAMES: &[&str] = &["range", "dict", "namespace", "debug", "loop"];
+
+/// A function templates can call, registered with
+/// [`PromptEngine::with_functions`]. It receives the call's arguments in
+/// order.
+pub type PromptFunction =
+    Arc<dyn Fn(&[Value]) -> std::result::Result<Value, minijinja::Error> + Send + Sync>;
+
+/// `value` cut to at most `max` characters, with an ellipsis when anything was
+/// cut. `{{ description | truncate_chars(200) }}`
+fn truncate_chars(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        return value.to_string();
+    }
+    let mut truncated = value
+        .chars()
+        .take(max.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+/// One `- item` line per element. `{{ tool_names | join_bullets }}`
+fn join_bullets(items: Vec<Value>) -> String {
+    items
+        .iter()
+        .map(|item| format!("- {item}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The singular word for a count of one, otherwise the plural, which
+/// defaults to the singular plus "s".
+/// `{{ count }} {{ count | pluralize("entry", "entries") }}`
+fn pluralize(count: i64, singular: &str, plural: Option<&str>) -> String {
+    if count == 1 {
+        singular.to_string()
+    } else {
+        plural.map_or_else(|| format!("{singular}s"), str::to_string)
+    }
+}
+
+/// Filters available to every template.
+fn register_filters(env: &mut Environment<'_>) {
+    env.add_filter("truncate_chars", truncate_chars);
+    env.add_filter("join_bullets", join_bullets);
+    env.add_filter("pluralize", pluralize);
+}
+
+/// Check every registered template against the variables its render site
+/// supplies, failing with one line per template that references anything
+/// else. `functions` are the names registered with the engine, which any
+/// template may call. Parse errors already surfaced when the template was
+/// added.
+fn validate_templates(env: &Environment<'_>, functions: &[&str]) -> anyhow::Result<()> {
+    let mut problems = Vec::new();
+    for name in TEMPLATE_NAMES {
+        let template = env
+            .get_template(name)
+            .with_context(|| format!("template '{name}' is not registered"))?;
+        let supplied = TEMPLATE_VARIABLES
+            .iter()
+            .find(|(template_name, _)| template_name == name)
+            .map_or(&[][..], |(_, variables)| *variables);
+        let mut unknown = template
+            .undeclared_variables(false)
+            .into_iter()
+            .filter(|variable| {
+                !supplied.contains(&variable.as_str())
+                    && !BUILTIN_NAMES.contains(&variable.as_str())
+                    && !functions.contains(&variable.as_str())
+            })
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            unknown.sort();
+            problems.push(format!(
+                "'{name}' references {}, which its render site doesn't supply",
+                unknown.join(", ")
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("invalid prompt templates:\n  {}", problems.join("\n  "));
+    }
+    Ok(())
+}
+
 /// Template engine for rendering system prompts with dynamic variables.
 ///
-/// Prompts are bundled in the binary as `include_str!` embedded templates.
-/// Language selection is done at initialization and templates are not
-/// reloadable at runtime (no file watching, no hot reload).
+/// Prompts are bundled in the binary as `include_str!` embedded templates,
+/// optionally replaced one by one from `[prompts.overrides]`. Language
+/// selection is done at initialization and templates are not reloadable at
+/// runtime (no file watching, no hot reload).
 #[derive(Clone)]
 pub struct PromptEngine {
     /// The MiniJinja environment holding all templates for the configured language.
     /// Wrapped in Arc to make PromptEngine Clone.
     env: Arc<Environment<'static>>,
-    /// Selected language code (e.g., "en").
+    /// Language the templates were loaded for (e.g., "en"). Unsupported
+    /// requests resolve to "en".
     language: String,
+    /// Output of `render_static` by template name. Shared by clones, which
+    /// hold the same templates; an engine built after a reload starts empty.
+    /// `None` for engines with injected functions, whose output can change
+    /// between calls.
+    static_cache: Option<Arc<Mutex<HashMap<String, String>>>>,
 }
 
 impl PromptEngine {
     /// Create a new engine with templates for the given language.
     ///
-    /// Currently only "en" (English) is fully implemented.
-    /// The language parameter exists for future i18n expansion.
+    /// Templates come from `prompts/{language}/`, falling back to the English
+    /// template for each one the language doesn't translate. An unsupported
+    /// language loads English throughout.
     pub fn new(language: &str) -> anyhow::Result<Self> {
-        if language != "en" {
-            tracing::warn!(
-                language = language,
-                "non-English language requested, falling back to English"
-            );
-        }
+        Self::with_overrides(language, &HashMap::new())
+    }
+
+    /// Create an engine whose templates named in `overrides` use the given
+    /// source instead of the embedded one. Every other template stays
+    /// embedded. Fails, naming the template, when an override doesn't
+    /// compile or names no template, or when any template references a
+    /// variable its render site doesn't supply.
+    pub fn with_overrides(
+        language: &str,
+        overrides: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        Self::with_functions(language, overrides, &[])
+    }
+
+    /// Like [`with_overrides`](Self::with_overrides), also registering
+    /// `functions` for templates to call by name, alongside the built-in
+    /// `truncate_chars`, `join_bullets` and `pluralize` filters.
+    pub fn with_functions(
+        language: &str,
+        overrides: &HashMap<String, String>,
+        functions: &[(&str, PromptFunction)],
+    ) -> anyhow::Result<Self> {
+        let language = crate::prompts::text::resolve_language(language);
 
         let mut env = Environment::new();
+        register_filters(&mut env);
+        for (name, function) in functions {
+            let function = function.clone();
+            env.add_function(name.to_string(), move |args: Rest<Value>| function(&args));
+        }
 
         // Register all templates from the central text registry
-        // Process prompts
-        env.add_template("channel", crate::prompts::text::get("channel"))?;
-        env.add_template("branch", crate::prompts::text::get("branch"))?;
-        env.add_template("worker", crate::prompts::text::get("worker"))?;
-        env.add_template("cortex", crate::prompts::text::get("cortex"))?;
-        env.add_template(
-            "cortex_bulletin",
-            crate::prompts::text::get("cortex_bulletin"),
-        )?;
-        env.add_template("compactor", crate::prompts::text::get("compactor"))?;
-        env.add_template(
-            "memory_persistence",
-            crate::prompts::text::get("memory_persistence"),
-        )?;
-        env.add_template("ingestion", crate::prompts::text::get("ingestion"))?;
-        env.add_template("cortex_chat", crate::prompts::text::get("cortex_chat"))?;
-        env.add_template(
-            "cortex_profile",
-            crate::prompts::text::get("cortex_profile"),
-        )?;
-        env.add_template("factory", crate::prompts::text::get("factory"))?;
-
-        // Adapter-specific prompt fragments
-        env.add_template(
-            "adapters/email",
-            crate::prompts::text::get("adapters/email"),
-        )?;
-        env.add_template("adapters/cron", crate::prompts::text::get("adapters/cron"))?;
-        env.add_template(
-            "adapters/signal",
-            crate::prompts::text::get("adapters/signal"),
-        )?;
-
-        // Fragment templates
-        env.add_template(
-            "fragments/worker_capabilities",
-            crate::prompts::text::get("fragments/worker_capabilities"),
-        )?;
-        env.add_template(
-            "fragments/conversation_context",
-            crate::prompts::text::get("fragments/conversation_context"),
-        )?;
-        env.add_template(
-            "fragments/skills_channel",
-            crate::prompts::text::get("fragments/skills_channel"),
-        )?;
-        env.add_template(
-            "fragments/skills_worker",
-            crate::prompts::text::get("fragments/skills_worker"),
-        )?;
-        env.add_template(
-            "fragments/available_channels",
-            crate::prompts::text::get("fragments/available_channels"),
-        )?;
-        env.add_template(
-            "fragments/org_context",
-            crate::prompts::text::get("fragments/org_context"),
-        )?;
-        env.add_template(
-            "fragments/projects_context",
-            crate::prompts::text::get("fragments/projects_context"),
-        )?;
+        for name in TEMPLATE_NAMES {
+            env.add_template(name, crate::prompts::text::get_in(language, name))?;
+        }
 
-        // System message fragments
-        env.add_template(
-            "fragments/system/retrigger",
-            crate::prompts::text::get("fragments/system/retrigger"),
-        )?;
-        env.add_template(
-            "fragments/system/truncation",
-            crate::prompts::text::get("fragments/system/truncation"),
-        )?;
-        env.add_template(
-            "fragments/system/worker_overflow",
-            crate::prompts::text::get("fragments/system/worker_overflow"),
-        )?;
-        env.add_template(
-            "fragments/system/worker_compact",
-            crate::prompts::text::get("fragments/system/worker_compact"),
-        )?;
-        env.add_template(
-            "fragments/system/memory_persistence",
-            crate::prompts::text::get("fragments/system/memory_persistence"),
-        )?;
-        env.add_template(
-            "fragments/system/cortex_synthesis",
-            crate::prompts::text::get("fragments/system/cortex_synthesis"),
-        )?;
-        env.add_template(
-            "fragments/system/profile_synthesis",
-            crate::prompts::text::get("fragments/system/profile_synthesis"),
-        )?;
-        env.add_template(
-            "fragments/system/ingestion_chunk",
-            crate::prompts::text::get("fragments/system/ingestion_chunk"),
-        )?;
-        env.add_template(
-            "fragments/system/history_backfill",
-            crate::prompts::text::get("fragments/system/history_backfill"),
-        )?;
-        env.add_template(
-            "fragments/system/tool_syntax_correction",
-            crate::prompts::text::get("fragments/system/tool_syntax_correction"),
-        )?;
-        env.add_template(
-            "fragments/coalesce_hint",
-            crate::prompts::text::get("fragments/coalesce_hint"),
-        )?;
+        // Overrides replace the embedded source. Compiling them here means a
+        // broken override fails startup instead of the first render.
+        for (name, source) in overrides {
+            if !TEMPLATE_NAMES.contains(&name.as_str()) {
+                anyhow::bail!(
+                    "prompt override '{name}' doesn't name a template; see \
+                     PromptEngine::overridable_templates"
+                );
+            }
+            env.add_template_owned(name.clone(), source.clone())
+                .with_context(|| format!("prompt override '{name}' doesn't compile"))?;
+        }
+
+        // A variable no render site supplies renders as empty or fails deep
+        // inside a process; refuse it here instead.
+        let function_names = functions.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        validate_templates(&env, &function_names)?;
 
         Ok(Self {
             env: Arc::new(env),
             language: language.to_string(),
+            static_cache: functions.is_empty().then(Arc::default),
         })
     }
 
+    /// Names of the templates `with_overrides` can replace.
+    pub fn overridable_templates() -> &'static [&'static str] {
+        TEMPLATE_NAMES
+    }
+
     /// Render a template by name with the given context variables.
     ///
     /// # Arguments
@@ -199,24 +432,57 @@ impl PromptEngine {
     }
 
     /// Convenience method for rendering simple templates with no variables.
+    ///
+    /// Without injected functions the output can't change for the life of
+    /// the engine, so each template is rendered once and served from a cache
+    /// after that. Engines built with [`with_functions`](Self::with_functions)
+    /// render every time, since a function may return something new on each
+    /// call. Failed renders aren't cached.
     pub fn render_static(&self, template_name: &str) -> Result<String> {
-        self.render(template_name, Value::UNDEFINED)
+        let Some(static_cache) = &self.static_cache else {
+            return self.render(template_name, Value::UNDEFINED);
+        };
+        if let Some(rendered) = static_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(template_name)
+        {
+            return Ok(rendered.clone());
+        }
+        let rendered = self.render(template_name, Value::UNDEFINED)?;
+        static_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(template_name.to_string(), rendered.clone());
+        Ok(rendered)
     }
 
-    /// Convenience method for rendering worker capabilities fragment.
+    /// Convenience method for rendering worker capabilities fragment. Tools
+    /// `permissions` deny are left out, however they're configured.
+    #[allow(clippy::too_many_arguments)]
     pub fn render_worker_capabilities(
         &self,
         browser_enabled: bool,
         web_search_enabled: bool,
+        http_request_enabled: bool,
         opencode_enabled: bool,
+        memory_recall_enabled: bool,
+        tool_gating_enabled: bool,
+        permissions: ToolPermissions,
         mcp_tool_names: &[String],
     ) -> Result<String> {
+        let mcp_tool_names = if permissions.mcp { mcp_tool_names } else { &[] };
         self.render(
             "fragments/worker_capabilities",
             context! {
-                browser_enabled => browser_enabled,
-                web_search_enabled => web_search_enabled,
+                shell_enabled => permissions.shell,
+                file_write_enabled => permissions.file_write,
+                browser_enabled => browser_enabled && permissions.browser,
+                web_search_enabled => web_search_enabled && permissions.web_search,
+                http_request_enabled => http_request_enabled && permissions.http_request,
                 opencode_enabled => opencode_enabled,
+                memory_recall_enabled => memory_recall_enabled,
+                tool_gating_enabled => tool_gating_enabled,
                 mcp_tool_names => mcp_tool_names,
             },
         )
@@ -241,6 +507,9 @@ impl PromptEngine {
 
     /// Convenience method for rendering skills channel fragment.
     pub fn render_skills_channel(&self, skills: Vec<SkillInfo>) -> Result<String> {
+        if skills.is_empty() {
+            return Ok(String::new());
+        }
         self.render(
             "fragments/skills_channel",
             context! {
@@ -275,7 +544,7 @@ impl PromptEngine {
                 sandbox_write_allowlist => sandbox_write_allowlist,
                 tool_secret_names => tool_secret_names,
                 browser_persist_session => browser_persist_session,
-                status_text => status_text,
+                status_text => present(status_text),
             },
         )
     }
@@ -306,6 +575,9 @@ impl PromptEngine {
     /// Workers see all available skills with suggestions from the channel flagged.
     /// They read whichever skills they need via the read_skill tool.
     pub fn render_skills_worker(&self, skills: Vec<SkillInfo>) -> Result<String> {
+        if skills.is_empty() {
+            return Ok(String::new());
+        }
         self.render(
             "fragments/skills_worker",
             context! {
@@ -332,6 +604,38 @@ impl PromptEngine {
         self.render_static("fragments/system/tool_syntax_correction")
     }
 
+    /// Error returned to a worker whose shell command the command policy
+    /// refused.
+    pub fn render_system_command_refused(&self, refusal: &str) -> Result<String> {
+        self.render(
+            "fragments/system/command_refused",
+            context! {
+                refusal => refusal,
+            },
+        )
+    }
+
+    /// Refreshed status block injected into a channel turn that is still in
+    /// flight when background work changes.
+    pub fn render_system_status_refresh(&self, status: &str) -> Result<String> {
+        self.render(
+            "fragments/system/status_refresh",
+            context! {
+                status => status,
+            },
+        )
+    }
+
+    /// Condensed identity reminder re-injected into long channel conversations.
+    pub fn render_system_identity_reminder(&self, identity_context: &str) -> Result<String> {
+        self.render(
+            "fragments/system/identity_reminder",
+            context! {
+                identity_context => identity_context,
+            },
+        )
+    }
+
     /// Convenience method for rendering truncation marker.
     pub fn render_system_truncation(&self, remove_count: usize) -> Result<String> {
         self.render(
@@ -342,11 +646,176 @@ impl PromptEngine {
         )
     }
 
+    /// Marker appended to a worker task cut down to `max_chars`, pointing at
+    /// the file that holds the full text.
+    pub fn render_system_task_truncation(
+        &self,
+        max_chars: usize,
+        length: usize,
+        path: &str,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/task_truncation",
+            context! {
+                max_chars => max_chars,
+                length => length,
+                path => path,
+            },
+        )
+    }
+
+    /// Convenience method for rendering the worker prompt section that
+    /// allows clarifying questions.
+    pub fn render_worker_clarifications(&self) -> Result<String> {
+        self.render_static("fragments/worker_clarifications")
+    }
+
+    /// Convenience method for rendering the channel prompt section that
+    /// explains fenced user messages.
+    pub fn render_user_content_fence(&self) -> Result<String> {
+        self.render_static("fragments/user_content_fence")
+    }
+
+    /// Render the note telling the channel which language to reply in.
+    pub fn render_channel_language(&self, language: &str, language_name: &str) -> Result<String> {
+        self.render(
+            "fragments/channel_language",
+            context! {
+                language => language,
+                language_name => language_name,
+            },
+        )
+    }
+
+    /// Convenience method for rendering the worker prompt section for
+    /// direct-answer workers that run without tools.
+    pub fn render_worker_direct_answer(&self) -> Result<String> {
+        self.render_static("fragments/worker_direct_answer")
+    }
+
+    /// Convenience method for rendering the worker prompt section for
+    /// plan-only workers whose side-effecting tools don't run.
+    pub fn render_worker_plan_mode(&self) -> Result<String> {
+        self.render_static("fragments/worker_plan_mode")
+    }
+
+    /// Render the worker prompt section describing its isolated working
+    /// directory.
+    pub fn render_worker_workdir(&self, workdir: &str, retained: bool) -> Result<String> {
+        self.render(
+            "fragments/worker_workdir",
+            context! {
+                workdir => workdir,
+                retained => retained,
+            },
+        )
+    }
+
+    /// Render the worker prompt section listing the tools its permissions
+    /// deny.
+    pub fn render_worker_tool_restrictions(&self, denied_tools: &[&str]) -> Result<String> {
+        self.render(
+            "fragments/worker_tool_restrictions",
+            context! {
+                denied_tools => denied_tools,
+            },
+        )
+    }
+
     /// Convenience method for rendering worker overflow recovery message.
     pub fn render_system_worker_overflow(&self) -> Result<String> {
         self.render_static("fragments/system/worker_overflow")
     }
 
+    /// Follow-up asking for a `length`-character answer to fit in `max_chars`.
+    pub fn render_system_output_limit_retry(
+        &self,
+        length: usize,
+        max_chars: usize,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/output_limit_retry",
+            context! {
+                length => length,
+                max_chars => max_chars,
+            },
+        )
+    }
+
+    /// Preamble for condensing an over-long worker result to `target_chars`.
+    pub fn render_system_worker_result_summary(&self, target_chars: usize) -> Result<String> {
+        self.render(
+            "fragments/system/worker_result_summary",
+            context! {
+                target_chars => target_chars,
+            },
+        )
+    }
+
+    /// Note appended to a condensed worker result naming the file that holds
+    /// the full `length`-character output.
+    pub fn render_system_worker_result_attached(
+        &self,
+        path: &str,
+        length: usize,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/worker_result_attached",
+            context! {
+                path => path,
+                length => length,
+            },
+        )
+    }
+
+    /// Re-prompt sent once when a worker's final response comes back blank.
+    pub fn render_system_worker_empty_result(&self) -> Result<String> {
+        self.render_static("fragments/system/worker_empty_result")
+    }
+
+    /// Directive sent once a worker has used `max_unfinished_segments` turn
+    /// budgets in a row without a final answer or progress update.
+    pub fn render_system_worker_wrap_up(&self) -> Result<String> {
+        self.render_static("fragments/system/worker_wrap_up")
+    }
+
+    /// Combined prompt for several follow-ups coalesced into one worker turn.
+    pub fn render_system_worker_follow_ups(&self, messages: &[String]) -> Result<String> {
+        self.render(
+            "fragments/system/worker_follow_ups",
+            context! {
+                messages => messages,
+            },
+        )
+    }
+
+    /// Capability check a worker runs before starting its task.
+    pub fn render_system_worker_preflight(&self, task: &str, can_clarify: bool) -> Result<String> {
+        self.render(
+            "fragments/system/worker_preflight",
+            context! {
+                task => task,
+                can_clarify => can_clarify,
+            },
+        )
+    }
+
+    /// Sent after a capability check that found no blocker.
+    pub fn render_system_worker_preflight_proceed(&self) -> Result<String> {
+        self.render_static("fragments/system/worker_preflight_proceed")
+    }
+
+    /// Final instruction for an interactive worker handed off to finish in
+    /// the background, with the user's last direction if any.
+    pub fn render_system_worker_continue_in_background(&self, message: &str) -> Result<String> {
+        self.render(
+            "fragments/system/worker_continue_in_background",
+            context! {
+                message => message,
+            },
+        )
+    }
+
     /// Convenience method for rendering worker compaction message.
     pub fn render_system_worker_compact(&self, remove_count: usize, recap: &str) -> Result<String> {
         self.render(
@@ -358,6 +827,28 @@ impl PromptEngine {
         )
     }
 
+    /// Sent in place of the task when a worker resumes from a checkpoint.
+    pub fn render_system_worker_resume(&self) -> Result<String> {
+        self.render_static("fragments/system/worker_resume")
+    }
+
+    /// Render the notice relayed to the user when a worker is cancelled.
+    pub fn render_system_worker_cancelled(
+        &self,
+        reason: &str,
+        summary: Option<&str>,
+        last_output: Option<&str>,
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/worker_cancelled",
+            context! {
+                reason => reason,
+                summary => summary,
+                last_output => last_output,
+            },
+        )
+    }
+
     /// Convenience method for rendering memory persistence prompt.
     pub fn render_system_memory_persistence(&self) -> Result<String> {
         self.render_static("fragments/system/memory_persistence")
@@ -512,12 +1003,12 @@ impl PromptEngine {
         self.render(
             "cortex_chat",
             context! {
-                identity_context => identity_context,
-                memory_bulletin => memory_bulletin,
-                channel_transcript => channel_transcript,
-                agents_manifest => agents_manifest,
-                changelog_highlights => changelog_highlights,
-                runtime_config_snapshot => runtime_config_snapshot,
+                identity_context => present(identity_context),
+                memory_bulletin => present(memory_bulletin),
+                channel_transcript => present(channel_transcript),
+                agents_manifest => present(agents_manifest),
+                changelog_highlights => present(changelog_highlights),
+                runtime_config_snapshot => present(runtime_config_snapshot),
                 worker_capabilities => worker_capabilities,
                 factory_enabled => factory_enabled,
             },
@@ -536,8 +1027,8 @@ impl PromptEngine {
         self.render(
             "factory",
             context! {
-                identity_context => identity_context,
-                memory_bulletin => memory_bulletin,
+                identity_context => present(identity_context),
+                memory_bulletin => present(memory_bulletin),
             },
         )
     }
@@ -583,29 +1074,36 @@ impl PromptEngine {
         self.render(
             "channel",
             context! {
-                identity_context => identity_context,
-                memory_bulletin => memory_bulletin,
-                skills_prompt => skills_prompt,
+                identity_context => present(identity_context),
+                memory_bulletin => present(memory_bulletin),
+                skills_prompt => present(skills_prompt),
                 worker_capabilities => worker_capabilities,
-                conversation_context => conversation_context,
-                status_text => status_text,
-                coalesce_hint => coalesce_hint,
-                available_channels => available_channels,
+                conversation_context => present(conversation_context),
+                status_text => present(status_text),
+                coalesce_hint => present(coalesce_hint),
+                available_channels => present(available_channels),
                 sandbox_enabled => sandbox_enabled,
-                org_context => org_context,
-                adapter_prompt => adapter_prompt,
-                project_context => project_context,
-                backfill_transcript => backfill_transcript,
+                org_context => present(org_context),
+                adapter_prompt => present(adapter_prompt),
+                project_context => present(project_context),
+                backfill_transcript => present(backfill_transcript),
             },
         )
     }
 
-    /// Get the configured language code.
+    /// Get the language the templates were loaded for, after fallback.
     pub fn language(&self) -> &str {
         &self.language
     }
 }
 
+/// Drop an optional prompt section that has nothing in it. Templates guard
+/// sections with `{% if %}`, which only skips a missing or empty string, so
+/// whitespace-only content would otherwise still render the section heading.
+fn present(section: Option<String>) -> Option<String> {
+    section.filter(|text| !text.trim().is_empty())
+}
+
 /// Organizational context for an agent — grouped by relationship.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct OrgContext {
@@ -639,6 +1137,10 @@ pub struct SkillInfo {
     /// Whether the spawning channel suggested this skill for the current task.
     /// Workers should prioritise suggested skills but may read others too.
     pub suggested: bool,
+    /// Precedence from the skill manifest; higher is more specific.
+    pub priority: i32,
+    /// Full skill instructions, inlined for suggested skills in worker prompts.
+    pub content: Option<String>,
 }
 
 /// Information about a channel for template rendering.
@@ -680,3 +1182,202 @@ pub struct ProjectWorktreeContext {
 
 // All templates are now loaded from the centralized text registry (src/prompts/text.rs)
 // to support multiple languages at compile time.
+
+#[cfg(test)]
+mod tests {
+    use super::{PromptEngine, PromptFunction};
+    use minijinja::Value;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn overrides_replace_single_templates() {
+        let overrides = HashMap::from([(
+            "fragments/coalesce_hint".to_string(),
+            "Batched messages: {{ message_count }}".to_string(),
+        )]);
+        let engine = PromptEngine::with_overrides("en", &overrides).unwrap();
+        let embedded = PromptEngine::new("en").unwrap();
+
+        assert_eq!(
+            engine
+                .render(
+                    "fragments/coalesce_hint",
+                    minijinja::context! { message_count => 3 }
+                )
+                .unwrap(),
+            "Batched messages: 3"
+        );
+        assert_eq!(
+            engine.render_static("worker").unwrap(),
+            embedded.render_static("worker").unwrap()
+        );
+        assert!(PromptEngine::overridable_templates().contains(&"fragments/system/retrigger"));
+    }
+
+    #[test]
+    fn translations_fall_back_to_english_per_template() {
+        let spanish = PromptEngine::new("es").unwrap();
+        let english = PromptEngine::new("en").unwrap();
+        assert_eq!(spanish.language(), "es");
+
+        let truncation = minijinja::context! { remove_count => 4 };
+        assert!(
+            spanish
+                .render("fragments/system/truncation", truncation.clone())
+                .unwrap()
+                .starts_with("[Sistema: se truncaron 4 mensajes")
+        );
+        assert_ne!(
+            spanish
+                .render("fragments/system/truncation", truncation.clone())
+                .unwrap(),
+            english
+                .render("fragments/system/truncation", truncation)
+                .unwrap()
+        );
+        // Untranslated templates come from prompts/en/.
+        assert_eq!(
+            spanish.render_static("worker").unwrap(),
+            english.render_static("worker").unwrap()
+        );
+
+        assert_eq!(PromptEngine::new("xx").unwrap().language(), "en");
+    }
+
+    #[test]
+    fn static_renders_are_cached_per_engine() {
+        let engine = PromptEngine::new("en").unwrap();
+        let first = engine
+            .render_static("fragments/system/worker_overflow")
+            .unwrap();
+        let clone = engine.clone();
+        assert_eq!(
+            clone
+                .render_static("fragments/system/worker_overflow")
+                .unwrap(),
+            first
+        );
+        assert_eq!(
+            clone.static_cache.as_ref().unwrap().lock().unwrap().len(),
+            1
+        );
+
+        // A rebuilt engine, as after a reload, starts with an empty cache and
+        // renders its own templates.
+        let overrides = HashMap::from([(
+            "fragments/system/worker_overflow".to_string(),
+            "[System: trimmed.]".to_string(),
+        )]);
+        let rebuilt = PromptEngine::with_overrides("en", &overrides).unwrap();
+        assert_eq!(
+            rebuilt
+                .render_static("fragments/system/worker_overflow")
+                .unwrap(),
+            "[System: trimmed.]"
+        );
+        assert!(engine.render_static("missing/template").is_err());
+        assert_eq!(
+            engine.static_cache.as_ref().unwrap().lock().unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn built_in_filters_render() {
+        let overrides = HashMap::from([(
+            "fragments/system/worker_follow_ups".to_string(),
+            "{{ messages[0] | truncate_chars(6) }}|{{ messages[1] | truncate_chars(20) }}\n\
+             {{ messages | join_bullets }}\n\
+             {{ 1 | pluralize(\"message\") }} {{ 3 | pluralize(\"message\") }} \
+             {{ 2 | pluralize(\"entry\", \"entries\") }}"
+                .to_string(),
+        )]);
+        let engine = PromptEngine::with_overrides("en", &overrides).unwrap();
+        let rendered = engine
+            .render_system_worker_follow_ups(&["deploy the fix".to_string(), "thanks".to_string()])
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "deplo…|thanks\n- deploy the fix\n- thanks\nmessage messages entries"
+        );
+    }
+
+    #[test]
+    fn injected_functions_are_callable_and_validated() {
+        let shout: PromptFunction = Arc::new(|args: &[Value]| {
+            Ok(Value::from(
+                args.first()
+                    .map(|value| value.to_string().to_uppercase())
+                    .unwrap_or_default(),
+            ))
+        });
+        let overrides = HashMap::from([(
+            "fragments/system/status_refresh".to_string(),
+            "{{ shout(status) }}".to_string(),
+        )]);
+        let engine = PromptEngine::with_functions("en", &overrides, &[("shout", shout)]).unwrap();
+        assert_eq!(engine.render_system_status_refresh("idle").unwrap(), "IDLE");
+
+        // Without the function the call is an unsupplied variable.
+        assert!(PromptEngine::with_overrides("en", &overrides).is_err());
+    }
+
+    #[test]
+    fn static_renders_call_injected_functions_every_time() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let tick: PromptFunction = Arc::new(move |_: &[Value]| {
+            Ok(Value::from(counter.fetch_add(1, Ordering::Relaxed) + 1))
+        });
+        let overrides = HashMap::from([(
+            "fragments/system/worker_overflow".to_string(),
+            "[System: attempt {{ tick() }}.]".to_string(),
+        )]);
+        let engine = PromptEngine::with_functions("en", &overrides, &[("tick", tick)]).unwrap();
+        for attempt in 1..=2 {
+            assert_eq!(
+                engine
+                    .render_static("fragments/system/worker_overflow")
+                    .unwrap(),
+                format!("[System: attempt {attempt}.]")
+            );
+        }
+        assert!(engine.static_cache.is_none());
+    }
+
+    #[test]
+    fn broken_or_unknown_overrides_name_the_template() {
+        let broken = HashMap::from([(
+            "fragments/system/retrigger".to_string(),
+            "{% for result in results %}unterminated".to_string(),
+        )]);
+        let error = PromptEngine::with_overrides("en", &broken)
+            .err()
+            .expect("override should fail to compile")
+            .to_string();
+        assert!(error.contains("fragments/system/retrigger"), "{error}");
+
+        let unsupplied = HashMap::from([(
+            "fragments/system/truncation".to_string(),
+            "{{ remove_count }} dropped, {{ removed_count }} lost".to_string(),
+        )]);
+        let error = PromptEngine::with_overrides("en", &unsupplied)
+            .err()
+            .expect("unsupplied variable should be rejected")
+            .to_string();
+        assert!(
+            error.contains("'fragments/system/truncation' references removed_count"),
+            "{error}"
+        );
+
+        let unknown = HashMap::from([("fragments/nope".to_string(), "hi".to_string())]);
+        let error = PromptEngine::with_overrides("en", &unknown)
+            .err()
+            .expect("unknown template should be rejected")
+            .to_string();
+        assert!(error.contains("fragments/nope"), "{error}");
+    }
+}
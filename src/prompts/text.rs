@@ -18,21 +18,31 @@ use std::sync::OnceLock;
 
 static LANGUAGE: OnceLock<String> = OnceLock::new();
 
+/// Languages with a directory under `prompts/`. English is complete; every
+/// other language may translate only some keys, and the rest fall back to
+/// English one by one.
+pub const SUPPORTED_LANGUAGES: &[&str] = &["en", "es"];
+
+/// `language` if it has a prompts directory, otherwise English.
+pub fn resolve_language(language: &str) -> &'static str {
+    match SUPPORTED_LANGUAGES.iter().find(|&&lang| lang == language) {
+        Some(lang) => lang,
+        None => {
+            tracing::warn!(
+                language = language,
+                "unsupported language requested, falling back to English"
+            );
+            "en"
+        }
+    }
+}
+
 /// Initialize the language for text lookups.
 /// Must be called once at startup before any text lookups occur.
-/// Returns Err if the language code is not supported.
+/// Unsupported languages fall back to English.
 pub fn init(language: &str) -> anyhow::Result<()> {
-    // Currently only "en" is supported, but we validate the format
-    // and set up the infrastructure for future languages
-    if language != "en" {
-        tracing::warn!(
-            language = language,
-            "non-English language requested, falling back to English"
-        );
-    }
-
     LANGUAGE
-        .set(language.to_string())
+        .set(resolve_language(language).to_string())
         .map_err(|_| anyhow::anyhow!("language already initialized"))?;
 
     Ok(())
@@ -49,6 +59,12 @@ pub fn get(key: &str) -> &'static str {
     lookup(language(), key)
 }
 
+/// Get text for the given key in `language`, regardless of the configured
+/// one. Keys the language doesn't translate come back in English.
+pub fn get_in(language: &str, key: &str) -> &'static str {
+    lookup(language, key)
+}
+
 /// Lookup function generated by the macro.
 /// Matches on (language, key) pairs.
 fn lookup(lang: &str, key: &str) -> &'static str {
@@ -95,15 +111,59 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/system/truncation") => {
             include_str!("../../prompts/en/fragments/system/truncation.md.j2")
         }
+        ("en", "fragments/system/task_truncation") => {
+            include_str!("../../prompts/en/fragments/system/task_truncation.md.j2")
+        }
         ("en", "fragments/system/worker_overflow") => {
             include_str!("../../prompts/en/fragments/system/worker_overflow.md.j2")
         }
+        ("en", "fragments/system/worker_cancelled") => {
+            include_str!("../../prompts/en/fragments/system/worker_cancelled.md.j2")
+        }
+        ("en", "fragments/system/status_refresh") => {
+            include_str!("../../prompts/en/fragments/system/status_refresh.md.j2")
+        }
+        ("en", "fragments/system/worker_follow_ups") => {
+            include_str!("../../prompts/en/fragments/system/worker_follow_ups.md.j2")
+        }
+        ("en", "fragments/system/worker_preflight") => {
+            include_str!("../../prompts/en/fragments/system/worker_preflight.md.j2")
+        }
+        ("en", "fragments/system/worker_preflight_proceed") => {
+            include_str!("../../prompts/en/fragments/system/worker_preflight_proceed.md.j2")
+        }
+        ("en", "fragments/system/worker_continue_in_background") => {
+            include_str!("../../prompts/en/fragments/system/worker_continue_in_background.md.j2")
+        }
+        ("en", "fragments/system/output_limit_retry") => {
+            include_str!("../../prompts/en/fragments/system/output_limit_retry.md.j2")
+        }
+        ("en", "fragments/system/worker_result_summary") => {
+            include_str!("../../prompts/en/fragments/system/worker_result_summary.md.j2")
+        }
+        ("en", "fragments/system/worker_result_attached") => {
+            include_str!("../../prompts/en/fragments/system/worker_result_attached.md.j2")
+        }
+        ("en", "fragments/system/worker_empty_result") => {
+            include_str!("../../prompts/en/fragments/system/worker_empty_result.md.j2")
+        }
+        ("en", "fragments/system/worker_wrap_up") => {
+            include_str!("../../prompts/en/fragments/system/worker_wrap_up.md.j2")
+        }
         ("en", "fragments/system/worker_compact") => {
             include_str!("../../prompts/en/fragments/system/worker_compact.md.j2")
         }
+        ("en", "fragments/system/worker_resume") => {
+            include_str!("../../prompts/en/fragments/system/worker_resume.md.j2")
+        }
         ("en", "fragments/system/memory_persistence") => {
             include_str!("../../prompts/en/fragments/system/memory_persistence.md.j2")
         }
+        ("en", "fragments/system/memory_persistence_contract_retry") => {
+            include_str!(
+                "../../prompts/en/fragments/system/memory_persistence_contract_retry.md.j2"
+            )
+        }
         ("en", "fragments/system/cortex_synthesis") => {
             include_str!("../../prompts/en/fragments/system/cortex_synthesis.md.j2")
         }
@@ -119,6 +179,12 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/system/tool_syntax_correction") => {
             include_str!("../../prompts/en/fragments/system/tool_syntax_correction.md.j2")
         }
+        ("en", "fragments/system/command_refused") => {
+            include_str!("../../prompts/en/fragments/system/command_refused.md.j2")
+        }
+        ("en", "fragments/system/identity_reminder") => {
+            include_str!("../../prompts/en/fragments/system/identity_reminder.md.j2")
+        }
         // Agent Communication Fragments
         ("en", "fragments/org_context") => {
             include_str!("../../prompts/en/fragments/org_context.md.j2")
@@ -128,6 +194,27 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/fragments/coalesce_hint.md.j2")
         }
         // Projects Context
+        ("en", "fragments/worker_clarifications") => {
+            include_str!("../../prompts/en/fragments/worker_clarifications.md.j2")
+        }
+        ("en", "fragments/user_content_fence") => {
+            include_str!("../../prompts/en/fragments/user_content_fence.md.j2")
+        }
+        ("en", "fragments/channel_language") => {
+            include_str!("../../prompts/en/fragments/channel_language.md.j2")
+        }
+        ("en", "fragments/worker_direct_answer") => {
+            include_str!("../../prompts/en/fragments/worker_direct_answer.md.j2")
+        }
+        ("en", "fragments/worker_plan_mode") => {
+            include_str!("../../prompts/en/fragments/worker_plan_mode.md.j2")
+        }
+        ("en", "fragments/worker_workdir") => {
+            include_str!("../../prompts/en/fragments/worker_workdir.md.j2")
+        }
+        ("en", "fragments/worker_tool_restrictions") => {
+            include_str!("../../prompts/en/fragments/worker_tool_restrictions.md.j2")
+        }
         ("en", "fragments/projects_context") => {
             include_str!("../../prompts/en/fragments/projects_context.md.j2")
         }
@@ -142,9 +229,18 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "tools/cancel") => include_str!("../../prompts/en/tools/cancel_description.md.j2"),
         ("en", "tools/skip") => include_str!("../../prompts/en/tools/skip_description.md.j2"),
         ("en", "tools/react") => include_str!("../../prompts/en/tools/react_description.md.j2"),
+        ("en", "tools/ask_clarification") => {
+            include_str!("../../prompts/en/tools/ask_clarification_description.md.j2")
+        }
         ("en", "tools/set_status") => {
             include_str!("../../prompts/en/tools/set_status_description.md.j2")
         }
+        ("en", "tools/get_status") => {
+            include_str!("../../prompts/en/tools/get_status_description.md.j2")
+        }
+        ("en", "tools/set_progress") => {
+            include_str!("../../prompts/en/tools/set_progress_description.md.j2")
+        }
         ("en", "tools/shell") => include_str!("../../prompts/en/tools/shell_description.md.j2"),
         ("en", "tools/install_skill") => {
             include_str!("../../prompts/en/tools/install_skill_description.md.j2")
@@ -162,6 +258,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/tools/file_list_description.md.j2")
         }
         ("en", "tools/browser") => include_str!("../../prompts/en/tools/browser_description.md.j2"),
+        ("en", "tools/http_request") => {
+            include_str!("../../prompts/en/tools/http_request_description.md.j2")
+        }
         ("en", "tools/web_search") => {
             include_str!("../../prompts/en/tools/web_search_description.md.j2")
         }
@@ -242,9 +341,21 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/tools/attachment_recall_description.md.j2")
         }
 
-        // Fallback: unknown language or key -> try English
+        // Spanish
+        ("es", "fragments/system/truncation") => {
+            include_str!("../../prompts/es/fragments/system/truncation.md.j2")
+        }
+        ("es", "fragments/system/worker_cancelled") => {
+            include_str!("../../prompts/es/fragments/system/worker_cancelled.md.j2")
+        }
+        ("es", "fragments/coalesce_hint") => {
+            include_str!("../../prompts/es/fragments/coalesce_hint.md.j2")
+        }
+
+        // Fallback: untranslated key -> English. Translations are partial, so
+        // this is expected and not worth more than a trace.
         (lang, key) if lang != "en" => {
-            tracing::warn!(
+            tracing::trace!(
                 lang,
                 key,
                 "text not found for language, falling back to English"
@@ -393,6 +393,16 @@ impl Sandbox {
         paths
     }
 
+    /// Directories commands see on `PATH`, in lookup order: the persistent
+    /// `tools/bin` first, then the host `PATH`.
+    pub fn path_dirs(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.tools_bin.clone()];
+        if let Some(current) = std::env::var_os("PATH") {
+            paths.extend(std::env::split_paths(&current));
+        }
+        paths
+    }
+
     /// Wrap a command for sandboxed execution.
     ///
     /// Returns a `Command` ready to spawn, potentially prefixed with bwrap or
@@ -417,14 +427,10 @@ impl Sandbox {
 
         // Prepend tools/bin to PATH for all commands
         let path_env = match std::env::var_os("PATH") {
-            Some(current) => {
-                let mut paths = std::env::split_paths(&current).collect::<Vec<_>>();
-                paths.insert(0, self.tools_bin.clone());
-                std::env::join_paths(paths)
-                    .unwrap_or(current)
-                    .to_string_lossy()
-                    .into_owned()
-            }
+            Some(current) => std::env::join_paths(self.path_dirs())
+                .unwrap_or(current)
+                .to_string_lossy()
+                .into_owned(),
             None => self.tools_bin.to_string_lossy().into_owned(),
         };
 
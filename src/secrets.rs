@@ -1,5 +1,7 @@
 //! Credential storage, output protection, and OS keystore integration.
 
 pub mod keystore;
+pub mod provider;
 pub mod scrub;
+pub mod sigv4;
 pub mod store;
@@ -0,0 +1,441 @@
+//! On-demand secret resolution for API keys.
+//!
+//! Config values are normally resolved once at load time, which keeps the key
+//! in memory for the life of the process and means a rotated key needs a
+//! restart. A [`SecretProvider`] is a handle that fetches the current value
+//! when a caller needs it instead, so tools can hold the handle rather than
+//! the raw string.
+//!
+//! Providers are built from config references by [`from_reference`]:
+//! - `file:/path` — read the file on every call (Kubernetes and Vault Agent
+//!   mounted secrets rotate in place).
+//! - `vault:mount/path#field` — Vault KV v2, addressed by `VAULT_ADDR` and
+//!   authenticated with `VAULT_TOKEN`.
+//! - `aws-sm:secret-id#field` — AWS Secrets Manager, using the standard
+//!   `AWS_*` credential environment variables.
+//! - Anything else — a static value, matching the load-time behavior.
+//!
+//! Remote lookups are cached for [`REMOTE_SECRET_TTL`] so every request does
+//! not pay for a round trip.
+
+use crate::error::SecretsError;
+use crate::secrets::sigv4;
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a value fetched from a remote store is reused before refetching.
+pub const REMOTE_SECRET_TTL: Duration = Duration::from_secs(300);
+
+/// Source of a secret value that is resolved each time it is needed.
+pub trait SecretProvider: Send + Sync + Debug + 'static {
+    /// Fetch the current value. `Ok(None)` means the secret is not set.
+    fn resolve(&self) -> impl Future<Output = Result<Option<String>, SecretsError>> + Send;
+}
+
+/// Dynamic companion to [`SecretProvider`], for storing providers as
+/// `Arc<dyn SecretProviderDyn>`.
+pub trait SecretProviderDyn: Send + Sync + Debug + 'static {
+    fn resolve<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<String>, SecretsError>> + Send + 'a>>;
+}
+
+impl<T: SecretProvider> SecretProviderDyn for T {
+    fn resolve<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<String>, SecretsError>> + Send + 'a>> {
+        Box::pin(SecretProvider::resolve(self))
+    }
+}
+
+/// Build a provider for a config value. See the module docs for the
+/// supported prefixes.
+pub fn from_reference(reference: &str) -> Arc<dyn SecretProviderDyn> {
+    if let Some(path) = reference.strip_prefix("file:") {
+        return Arc::new(FileSecret::new(path));
+    }
+    if let Some(location) = reference.strip_prefix("vault:") {
+        let (path, field) = split_field(location);
+        return Arc::new(CachedSecret::new(
+            VaultSecret::new(path, field),
+            REMOTE_SECRET_TTL,
+        ));
+    }
+    if let Some(location) = reference.strip_prefix("aws-sm:") {
+        let (secret_id, field) = split_field(location);
+        return Arc::new(CachedSecret::new(
+            AwsSecretsManagerSecret::new(secret_id, field),
+            REMOTE_SECRET_TTL,
+        ));
+    }
+    Arc::new(StaticSecret::new(reference))
+}
+
+fn split_field(location: &str) -> (&str, Option<&str>) {
+    match location.split_once('#') {
+        Some((path, field)) if !field.is_empty() => (path, Some(field)),
+        Some((path, _)) => (path, None),
+        None => (location, None),
+    }
+}
+
+/// A value fixed at construction. Used for keys written directly in config
+/// or already resolved from `env:` / `secret:` at load time.
+pub struct StaticSecret(String);
+
+impl StaticSecret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+}
+
+impl Debug for StaticSecret {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("StaticSecret([REDACTED])")
+    }
+}
+
+impl SecretProvider for StaticSecret {
+    async fn resolve(&self) -> Result<Option<String>, SecretsError> {
+        Ok(Some(self.0.clone()))
+    }
+}
+
+/// Reads a file on every call, trimming surrounding whitespace.
+#[derive(Debug)]
+pub struct FileSecret {
+    path: PathBuf,
+}
+
+impl FileSecret {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SecretProvider for FileSecret {
+    async fn resolve(&self) -> Result<Option<String>, SecretsError> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => {
+                let value = contents.trim();
+                Ok((!value.is_empty()).then(|| value.to_string()))
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(SecretsError::Provider {
+                provider: "file",
+                details: format!("failed to read {}: {error}", self.path.display()),
+            }),
+        }
+    }
+}
+
+/// HashiCorp Vault KV v2 secret.
+#[derive(Debug)]
+pub struct VaultSecret {
+    client: reqwest::Client,
+    /// `mount/path`, e.g. `secret/spacebot/brave`.
+    path: String,
+    /// Key inside the secret's data. Defaults to `value`.
+    field: String,
+}
+
+impl VaultSecret {
+    pub fn new(path: impl Into<String>, field: Option<&str>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            path: path.into(),
+            field: field.unwrap_or("value").to_string(),
+        }
+    }
+}
+
+impl SecretProvider for VaultSecret {
+    async fn resolve(&self) -> Result<Option<String>, SecretsError> {
+        let provider_error = |details: String| SecretsError::Provider {
+            provider: "vault",
+            details,
+        };
+
+        let address = std::env::var("VAULT_ADDR")
+            .map_err(|_| provider_error("VAULT_ADDR is not set".into()))?;
+        let token = std::env::var("VAULT_TOKEN")
+            .map_err(|_| provider_error("VAULT_TOKEN is not set".into()))?;
+
+        // KV v2 puts `data/` between the mount and the secret path.
+        let (mount, secret_path) = self
+            .path
+            .split_once('/')
+            .ok_or_else(|| provider_error(format!("expected mount/path, got {}", self.path)))?;
+        let url = format!(
+            "{}/v1/{mount}/data/{secret_path}",
+            address.trim_end_matches('/')
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .map_err(|error| provider_error(error.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(provider_error(format!("HTTP {}", response.status())));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|error| provider_error(error.to_string()))?;
+        Ok(body["data"]["data"][&self.field]
+            .as_str()
+            .map(ToString::to_string))
+    }
+}
+
+/// AWS Secrets Manager secret, fetched with a SigV4-signed
+/// `GetSecretValue` call.
+#[derive(Debug)]
+pub struct AwsSecretsManagerSecret {
+    client: reqwest::Client,
+    secret_id: String,
+    /// When set, the secret string is parsed as JSON and this key is used.
+    field: Option<String>,
+}
+
+impl AwsSecretsManagerSecret {
+    pub fn new(secret_id: impl Into<String>, field: Option<&str>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            secret_id: secret_id.into(),
+            field: field.map(ToString::to_string),
+        }
+    }
+}
+
+impl SecretProvider for AwsSecretsManagerSecret {
+    async fn resolve(&self) -> Result<Option<String>, SecretsError> {
+        let provider_error = |details: String| SecretsError::Provider {
+            provider: "aws-sm",
+            details,
+        };
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        let region = env("AWS_REGION")
+            .or_else(|| env("AWS_DEFAULT_REGION"))
+            .ok_or_else(|| provider_error("AWS_REGION is not set".into()))?;
+        let access_key = env("AWS_ACCESS_KEY_ID")
+            .ok_or_else(|| provider_error("AWS_ACCESS_KEY_ID is not set".into()))?;
+        let secret_key = env("AWS_SECRET_ACCESS_KEY")
+            .ok_or_else(|| provider_error("AWS_SECRET_ACCESS_KEY is not set".into()))?;
+        let session_token = env("AWS_SESSION_TOKEN");
+
+        let host = format!("secretsmanager.{region}.amazonaws.com");
+        let payload = serde_json::json!({ "SecretId": self.secret_id }).to_string();
+        let now = chrono::Utc::now();
+        let headers = sigv4_headers(&AwsJsonRequest {
+            host: &host,
+            region: &region,
+            target: "secretsmanager.GetSecretValue",
+            payload: &payload,
+            access_key: &access_key,
+            secret_key: &secret_key,
+            session_token: session_token.as_deref(),
+            amz_date: &now.format("%Y%m%dT%H%M%SZ").to_string(),
+        });
+
+        let mut request = self.client.post(format!("https://{host}/")).body(payload);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| provider_error(error.to_string()))?;
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|error| provider_error(error.to_string()))?;
+        if !status.is_success() {
+            if body["__type"]
+                .as_str()
+                .is_some_and(|kind| kind.ends_with("ResourceNotFoundException"))
+            {
+                return Ok(None);
+            }
+            return Err(provider_error(format!(
+                "HTTP {status}: {}",
+                body["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+
+        let Some(secret_string) = body["SecretString"].as_str() else {
+            return Ok(None);
+        };
+        match &self.field {
+            None => Ok(Some(secret_string.to_string())),
+            Some(field) => {
+                let fields: serde_json::Value =
+                    serde_json::from_str(secret_string).map_err(|error| {
+                        provider_error(format!("SecretString is not JSON: {error}"))
+                    })?;
+                Ok(fields[field].as_str().map(ToString::to_string))
+            }
+        }
+    }
+}
+
+struct AwsJsonRequest<'a> {
+    host: &'a str,
+    region: &'a str,
+    target: &'a str,
+    payload: &'a str,
+    access_key: &'a str,
+    secret_key: &'a str,
+    session_token: Option<&'a str>,
+    /// `YYYYMMDDTHHMMSSZ`.
+    amz_date: &'a str,
+}
+
+const AWS_SERVICE: &str = "secretsmanager";
+const AWS_CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Headers for a signed AWS JSON-protocol POST to `/`, including
+/// `Authorization`.
+fn sigv4_headers(request: &AwsJsonRequest<'_>) -> Vec<(&'static str, String)> {
+    // Canonical headers must be lowercase and sorted by name.
+    let mut headers: Vec<(&'static str, String)> = vec![
+        ("content-type", AWS_CONTENT_TYPE.to_string()),
+        ("host", request.host.to_string()),
+        ("x-amz-date", request.amz_date.to_string()),
+    ];
+    if let Some(token) = request.session_token {
+        headers.push(("x-amz-security-token", token.to_string()));
+    }
+    headers.push(("x-amz-target", request.target.to_string()));
+
+    let signed = headers
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect::<Vec<_>>();
+    let authorization = sigv4::authorization(&sigv4::SigV4Request {
+        method: "POST",
+        service: AWS_SERVICE,
+        region: request.region,
+        path: "/",
+        headers: &signed,
+        payload_hash: &sigv4::payload_hash(request.payload.as_bytes()),
+        access_key: request.access_key,
+        secret_key: request.secret_key,
+        amz_date: request.amz_date,
+    });
+
+    headers.push(("authorization", authorization));
+    // Host is set by the HTTP client from the URL.
+    headers.retain(|(name, _)| *name != "host");
+    headers
+}
+
+/// Reuses another provider's value for `ttl` before asking it again.
+#[derive(Debug)]
+pub struct CachedSecret<P> {
+    inner: P,
+    ttl: Duration,
+    cached: tokio::sync::Mutex<Option<(Instant, Option<String>)>>,
+}
+
+impl<P: SecretProvider> CachedSecret<P> {
+    pub fn new(inner: P, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
+}
+
+impl<P: SecretProvider> SecretProvider for CachedSecret<P> {
+    async fn resolve(&self) -> Result<Option<String>, SecretsError> {
+        // Holding the lock across the fetch keeps concurrent callers from
+        // all hitting the remote store when the entry expires.
+        let mut cached = self.cached.lock().await;
+        if let Some((fetched_at, value)) = cached.as_ref()
+            && fetched_at.elapsed() < self.ttl
+        {
+            return Ok(value.clone());
+        }
+
+        let value = SecretProvider::resolve(&self.inner).await?;
+        *cached = Some((Instant::now(), value.clone()));
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AwsJsonRequest, from_reference, sigv4_headers};
+
+    #[tokio::test]
+    async fn file_references_are_reread_on_every_resolve() {
+        let path = std::env::temp_dir().join(format!("spacebot-secret-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "first-key\n").unwrap();
+
+        let provider = from_reference(&format!("file:{}", path.display()));
+        assert_eq!(
+            provider.resolve().await.unwrap().as_deref(),
+            Some("first-key")
+        );
+
+        std::fs::write(&path, "rotated-key").unwrap();
+        assert_eq!(
+            provider.resolve().await.unwrap().as_deref(),
+            Some("rotated-key")
+        );
+
+        std::fs::remove_file(&path).ok();
+        assert_eq!(provider.resolve().await.unwrap(), None);
+
+        let literal = from_reference("BSAplainkey");
+        assert_eq!(
+            literal.resolve().await.unwrap().as_deref(),
+            Some("BSAplainkey")
+        );
+        assert!(!format!("{literal:?}").contains("BSAplainkey"));
+    }
+
+    #[test]
+    fn sigv4_signs_sorted_headers_for_the_request_scope() {
+        let headers = sigv4_headers(&AwsJsonRequest {
+            host: "secretsmanager.us-east-1.amazonaws.com",
+            region: "us-east-1",
+            target: "secretsmanager.GetSecretValue",
+            payload: r#"{"SecretId":"brave"}"#,
+            access_key: "AKIDEXAMPLE",
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            session_token: None,
+            amz_date: "20260101T000000Z",
+        });
+
+        let authorization = &headers
+            .iter()
+            .find(|(name, _)| *name == "authorization")
+            .unwrap()
+            .1;
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260101/us-east-1/secretsmanager/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-target, \
+             Signature=8f4fc77967ecc9cd44850376ddedafe1341a6379afa5b4a639ea761ad6bb4989"
+        );
+        assert!(headers.iter().all(|(name, _)| *name != "host"));
+    }
+}
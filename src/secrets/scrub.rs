@@ -254,6 +254,72 @@ pub fn scrub_leaks(content: &str) -> String {
     result
 }
 
+/// Redaction pass over everything the bot sends to a chat platform.
+///
+/// Runs in the messaging layer after the per-path scrubbing above, so a
+/// secret that slips through some egress path it doesn't cover (a reply
+/// tool argument, a cron delivery, a streamed chunk) is still caught. Uses
+/// the same patterns as [`scrub_leaks`] plus any configured extras.
+#[derive(Debug, Clone)]
+pub struct OutboundRedactor {
+    enabled: bool,
+    extra: Vec<Regex>,
+}
+
+impl Default for OutboundRedactor {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl OutboundRedactor {
+    /// Build a redactor from config. Invalid extra patterns are logged and
+    /// skipped; the built-in set always applies while enabled.
+    pub fn new(config: &crate::config::RedactionConfig) -> Self {
+        let extra = config
+            .patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(error) => {
+                    tracing::warn!(%pattern, %error, "ignoring invalid redaction pattern");
+                    None
+                }
+            })
+            .collect();
+        Self {
+            enabled: config.enabled,
+            extra,
+        }
+    }
+
+    /// Redact `text`, returning `None` when nothing matched.
+    pub fn redact(&self, text: &str) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let mut result = scrub_leaks(text);
+        for pattern in &self.extra {
+            result = pattern.replace_all(&result, "[REDACTED]").into_owned();
+        }
+        (result != text).then_some(result)
+    }
+
+    /// Redact `text` in place, returning whether anything was replaced.
+    pub fn redact_in_place(&self, text: &mut String) -> bool {
+        match self.redact(text) {
+            Some(redacted) => {
+                *text = redacted;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -380,4 +446,26 @@ mod tests {
             "surrounding text should be preserved in: {result}"
         );
     }
+
+    #[test]
+    fn outbound_redactor_applies_builtin_and_configured_patterns() {
+        let config = crate::config::RedactionConfig {
+            enabled: true,
+            patterns: vec![r"corp-[0-9]{6}".to_string(), "(unclosed".to_string()],
+        };
+        let redactor = OutboundRedactor::new(&config);
+        assert_eq!(
+            redactor
+                .redact("key sk-ant-REDACTED and badge corp-123456")
+                .as_deref(),
+            Some("key [LEAKED_SECRET_REDACTED] and badge [REDACTED]")
+        );
+        assert_eq!(redactor.redact("nothing to see"), None);
+
+        let disabled = OutboundRedactor::new(&crate::config::RedactionConfig {
+            enabled: false,
+            patterns: Vec::new(),
+        });
+        assert_eq!(disabled.redact("sk-ant-REDACTED"), None);
+    }
 }
@@ -5,7 +5,7 @@
 //! - `spacebot_docs` tool (on-demand document retrieval)
 //! - `config_inspect` tool (redacted runtime config visibility)
 
-use crate::config::{McpTransport, RuntimeConfig};
+use crate::config::{McpTransport, RuntimeConfig, SearchBackendKind};
 use rust_embed::Embed;
 use serde::Serialize;
 use serde_json::json;
@@ -269,8 +269,12 @@ pub fn runtime_snapshot_value(agent_id: &str, runtime_config: &RuntimeConfig) ->
             "permissions": opencode.permissions,
         },
         "mcp_servers": mcp_servers,
-        "brave_search": {
-            "configured": runtime_config.brave_search_key.load().is_some(),
+        "web_search": {
+            "backend": match runtime_config.web_search.load().backend {
+                SearchBackendKind::Brave => "brave",
+                SearchBackendKind::Searxng => "searxng",
+            },
+            "configured": runtime_config.web_search_enabled(),
         },
         "timezones": {
             "cron_timezone": runtime_config.cron_timezone.load().as_ref().clone(),
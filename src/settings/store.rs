@@ -15,6 +15,12 @@ pub const WORKER_LOG_MODE_KEY: &str = "worker_log_mode";
 pub const CHANNEL_LISTEN_ONLY_MODE_KEY: &str = "channel_listen_only_mode";
 const CHANNEL_LISTEN_ONLY_MODE_PREFIX: &str = "channel_listen_only_mode:";
 const PROMPT_CAPTURE_PREFIX: &str = "prompt_capture:";
+/// Key for the agent-wide mute, which silences every channel.
+pub const MUTED_KEY: &str = "muted";
+const CHANNEL_MUTED_PREFIX: &str = "channel_muted:";
+
+/// Key prefix for the language detected for a channel.
+const CHANNEL_LANGUAGE_PREFIX: &str = "channel_language:";
 
 /// How worker execution logs are stored.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -219,6 +225,95 @@ impl SettingsStore {
         self.set_raw(&key, if enabled { "true" } else { "false" })
     }
 
+    /// Whether every channel of this agent is muted.
+    pub fn muted(&self) -> bool {
+        matches!(self.get_raw(MUTED_KEY), Ok(v) if v == "true")
+    }
+
+    /// Mute or unmute every channel of this agent.
+    pub fn set_muted(&self, muted: bool) -> Result<()> {
+        self.set_raw(MUTED_KEY, if muted { "true" } else { "false" })
+    }
+
+    /// Whether a specific channel is muted on its own, regardless of the
+    /// agent-wide mute.
+    pub fn channel_muted(&self, channel_id: &str) -> bool {
+        let key = format!("{CHANNEL_MUTED_PREFIX}{channel_id}");
+        matches!(self.get_raw(&key), Ok(v) if v == "true")
+    }
+
+    /// Mute or unmute a specific channel.
+    pub fn set_channel_muted(&self, channel_id: &str, muted: bool) -> Result<()> {
+        let key = format!("{CHANNEL_MUTED_PREFIX}{channel_id}");
+        self.set_raw(&key, if muted { "true" } else { "false" })
+    }
+
+    /// IDs of the channels muted on their own, in key order.
+    pub fn muted_channels(&self) -> Result<Vec<String>> {
+        let read_failed = |error: &dyn std::fmt::Display| SettingsError::ReadFailed {
+            key: CHANNEL_MUTED_PREFIX.to_string(),
+            details: error.to_string(),
+        };
+        let read_txn = self.db.begin_read().map_err(|e| read_failed(&e))?;
+        let table = read_txn
+            .open_table(SETTINGS_TABLE)
+            .map_err(|e| read_failed(&e))?;
+
+        let mut channels = Vec::new();
+        for entry in table
+            .range(CHANNEL_MUTED_PREFIX..)
+            .map_err(|e| read_failed(&e))?
+        {
+            let (key, value) = entry.map_err(|e| read_failed(&e))?;
+            let Some(channel_id) = key.value().strip_prefix(CHANNEL_MUTED_PREFIX) else {
+                break;
+            };
+            if value.value() == "true" {
+                channels.push(channel_id.to_string());
+            }
+        }
+        Ok(channels)
+    }
+
+    /// Language last detected for a channel, if any.
+    pub fn channel_language(&self, channel_id: &str) -> Option<String> {
+        let key = format!("{CHANNEL_LANGUAGE_PREFIX}{channel_id}");
+        self.get_raw(&key)
+            .ok()
+            .filter(|language| !language.is_empty())
+    }
+
+    /// Record the language detected for a channel.
+    pub fn set_channel_language(&self, channel_id: &str, language: &str) -> Result<()> {
+        let key = format!("{CHANNEL_LANGUAGE_PREFIX}{channel_id}");
+        self.set_raw(&key, language)
+    }
+
+    /// Detected language per channel as `(channel_id, language)`, in key order.
+    pub fn channel_languages(&self) -> Result<Vec<(String, String)>> {
+        let read_failed = |error: &dyn std::fmt::Display| SettingsError::ReadFailed {
+            key: CHANNEL_LANGUAGE_PREFIX.to_string(),
+            details: error.to_string(),
+        };
+        let read_txn = self.db.begin_read().map_err(|e| read_failed(&e))?;
+        let table = read_txn
+            .open_table(SETTINGS_TABLE)
+            .map_err(|e| read_failed(&e))?;
+
+        let mut languages = Vec::new();
+        for entry in table
+            .range(CHANNEL_LANGUAGE_PREFIX..)
+            .map_err(|e| read_failed(&e))?
+        {
+            let (key, value) = entry.map_err(|e| read_failed(&e))?;
+            let Some(channel_id) = key.value().strip_prefix(CHANNEL_LANGUAGE_PREFIX) else {
+                break;
+            };
+            languages.push((channel_id.to_string(), value.value().to_string()));
+        }
+        Ok(languages)
+    }
+
     /// Check whether prompt capture is enabled for a specific channel.
     pub fn prompt_capture_enabled(&self, channel_id: &str) -> bool {
         let key = format!("{PROMPT_CAPTURE_PREFIX}{channel_id}");
@@ -237,3 +332,36 @@ impl std::fmt::Debug for SettingsStore {
         f.debug_struct("SettingsStore").finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SettingsStore;
+
+    #[test]
+    fn channels_mute_on_their_own_or_all_at_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::new(&dir.path().join("settings.redb")).unwrap();
+        assert!(!store.muted());
+        assert!(!store.channel_muted("discord:1"));
+        assert!(store.muted_channels().unwrap().is_empty());
+
+        store.set_channel_muted("discord:1", true).unwrap();
+        store.set_channel_muted("slack:2", true).unwrap();
+        store.set_channel_language("discord:1", "en").unwrap();
+        assert!(store.channel_muted("discord:1"));
+        assert!(!store.channel_muted("discord:3"));
+        assert_eq!(store.muted_channels().unwrap(), ["discord:1", "slack:2"]);
+
+        store.set_channel_muted("discord:1", false).unwrap();
+        assert!(!store.channel_muted("discord:1"));
+        assert_eq!(store.muted_channels().unwrap(), ["slack:2"]);
+
+        // The agent-wide mute is separate from the per-channel list.
+        store.set_muted(true).unwrap();
+        assert!(store.muted());
+        assert!(!store.channel_muted("discord:1"));
+        assert_eq!(store.muted_channels().unwrap(), ["slack:2"]);
+        store.set_muted(false).unwrap();
+        assert!(!store.muted());
+    }
+}
@@ -37,6 +37,10 @@ pub struct Skill {
     pub source: SkillSource,
     /// GitHub `owner/repo` that this skill was installed from, if any.
     pub source_repo: Option<String>,
+    /// Precedence from the `priority` frontmatter key. When several skills
+    /// match a task, higher values are more specific and are listed and
+    /// applied first. Defaults to 0.
+    pub priority: i32,
 }
 
 /// Where a skill was loaded from, used for precedence tracking.
@@ -113,10 +117,17 @@ impl SkillSet {
         self.skills.is_empty()
     }
 
+    /// All skills in precedence order: highest priority first, then by name.
+    fn by_precedence(&self) -> Vec<&Skill> {
+        let mut sorted_skills: Vec<&Skill> = self.skills.values().collect();
+        sorted_skills.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.name.cmp(&b.name)));
+        sorted_skills
+    }
+
     /// Render the skills summary for injection into the channel system prompt.
     ///
-    /// The channel sees skill names and descriptions but is instructed to
-    /// delegate actual skill execution to workers.
+    /// The channel sees skill names and descriptions, most specific first, but
+    /// is instructed to delegate actual skill execution to workers.
     pub fn render_channel_prompt(
         &self,
         prompt_engine: &crate::prompts::PromptEngine,
@@ -125,16 +136,16 @@ impl SkillSet {
             return Ok(String::new());
         }
 
-        let mut sorted_skills: Vec<&Skill> = self.skills.values().collect();
-        sorted_skills.sort_by(|a, b| a.name.cmp(&b.name));
-
-        let skill_infos: Vec<crate::prompts::SkillInfo> = sorted_skills
+        let skill_infos: Vec<crate::prompts::SkillInfo> = self
+            .by_precedence()
             .into_iter()
             .map(|s| crate::prompts::SkillInfo {
                 name: s.name.clone(),
                 description: s.description.clone(),
                 location: s.file_path.display().to_string(),
                 suggested: false,
+                priority: s.priority,
+                content: None,
             })
             .collect();
 
@@ -144,7 +155,10 @@ impl SkillSet {
     /// Render the skills listing for injection into a worker system prompt.
     ///
     /// Workers see all available skills with any channel-suggested skills flagged.
-    /// They decide which skills are relevant and read them via the read_skill tool.
+    /// Suggested skills come first in precedence order and have their full
+    /// instructions inlined, so several skills compose into one set of
+    /// instructions with the most specific one leading. Other skills are read
+    /// on demand via the read_skill tool.
     pub fn render_worker_skills(
         &self,
         suggested: &[&str],
@@ -154,18 +168,28 @@ impl SkillSet {
             return Ok(String::new());
         }
 
-        let mut sorted_skills: Vec<&Skill> = self.skills.values().collect();
-        sorted_skills.sort_by(|a, b| a.name.cmp(&b.name));
-
         let suggested_lower: Vec<String> = suggested.iter().map(|s| s.to_lowercase()).collect();
+        let is_suggested = |skill: &Skill| suggested_lower.contains(&skill.name.to_lowercase());
 
-        let skill_infos: Vec<crate::prompts::SkillInfo> = sorted_skills
+        // Stable partition keeps precedence order within each group.
+        let (mut ordered, rest): (Vec<&Skill>, Vec<&Skill>) = self
+            .by_precedence()
             .into_iter()
-            .map(|s| crate::prompts::SkillInfo {
-                suggested: suggested_lower.contains(&s.name.to_lowercase()),
-                name: s.name.clone(),
-                description: s.description.clone(),
-                location: s.file_path.display().to_string(),
+            .partition(|skill| is_suggested(skill));
+        ordered.extend(rest);
+
+        let skill_infos: Vec<crate::prompts::SkillInfo> = ordered
+            .into_iter()
+            .map(|s| {
+                let suggested = is_suggested(s);
+                crate::prompts::SkillInfo {
+                    suggested,
+                    name: s.name.clone(),
+                    description: s.description.clone(),
+                    location: s.file_path.display().to_string(),
+                    priority: s.priority,
+                    content: suggested.then(|| s.content.clone()),
+                }
             })
             .collect();
 
@@ -314,6 +338,17 @@ async fn load_skill(
 
     let description = frontmatter.get("description").cloned().unwrap_or_default();
     let source_repo = frontmatter.get("source_repo").cloned();
+    let priority = match frontmatter.get("priority") {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            tracing::warn!(
+                path = %file_path.display(),
+                value = %value,
+                "skill priority is not an integer, using 0"
+            );
+            0
+        }),
+        None => 0,
+    };
 
     // Resolve {baseDir} template variable in the body
     let base_dir_str = base_dir.to_string_lossy();
@@ -327,6 +362,7 @@ async fn load_skill(
         content,
         source,
         source_repo,
+        priority,
     })
 }
 
@@ -475,6 +511,48 @@ mod tests {
         assert!(set.render_channel_prompt(&engine).unwrap().is_empty());
     }
 
+    #[test]
+    fn channel_prompt_omits_empty_sections_without_skills() {
+        let engine = crate::prompts::PromptEngine::new("en").unwrap();
+        assert!(engine.render_skills_channel(Vec::new()).unwrap().is_empty());
+        assert!(engine.render_skills_worker(Vec::new()).unwrap().is_empty());
+
+        let skills_prompt = SkillSet::default().render_channel_prompt(&engine).unwrap();
+        let prompt = engine
+            .render_channel_prompt(
+                None,
+                Some("  \n".into()),
+                Some(skills_prompt),
+                String::new(),
+                Some(String::new()),
+                None,
+                None,
+                None,
+                false,
+            )
+            .unwrap();
+        assert!(!prompt.contains("## Available Skills"));
+        assert!(!prompt.contains("<available_skills>"));
+        assert!(!prompt.contains("## Memory Context"));
+        assert!(!prompt.contains("## Conversation Context"));
+
+        let prompt = engine
+            .render_channel_prompt(
+                None,
+                Some("The user prefers metric units.".into()),
+                None,
+                String::new(),
+                Some("Platform: discord".into()),
+                None,
+                None,
+                None,
+                false,
+            )
+            .unwrap();
+        assert!(prompt.contains("## Memory Context"));
+        assert!(prompt.contains("## Conversation Context"));
+    }
+
     #[test]
     fn test_skill_set_channel_prompt() {
         let mut set = SkillSet::default();
@@ -488,6 +566,7 @@ mod tests {
                 content: "# Weather\n\nUse curl.".into(),
                 source: SkillSource::Instance,
                 source_repo: None,
+                priority: 0,
             },
         );
 
@@ -511,6 +590,7 @@ mod tests {
                 content: "# Weather\n\nUse curl.".into(),
                 source: SkillSource::Instance,
                 source_repo: None,
+                priority: 0,
             },
         );
 
@@ -542,9 +622,39 @@ mod tests {
             content: format!("# {name}"),
             source,
             source_repo: None,
+            priority: 0,
         }
     }
 
+    #[test]
+    fn suggested_skills_compose_in_priority_order() {
+        let mut set = SkillSet::default();
+        for (name, priority) in [("github", 0), ("github-pr-review", 10), ("weather", 5)] {
+            let mut skill = make_skill(name, SkillSource::Instance);
+            skill.priority = priority;
+            set.skills.insert(name.into(), skill);
+        }
+
+        let engine = crate::prompts::PromptEngine::new("en").unwrap();
+
+        let channel = set.render_channel_prompt(&engine).unwrap();
+        let review = channel.find("<name>github-pr-review</name>").unwrap();
+        let weather = channel.find("<name>weather</name>").unwrap();
+        let github = channel.find("<name>github</name>").unwrap();
+        assert!(review < weather && weather < github);
+        assert!(channel.contains("priority=\"10\""));
+
+        let worker = set
+            .render_worker_skills(&["github", "github-pr-review"], &engine)
+            .unwrap();
+        let review = worker
+            .find("<skill_instructions name=\"github-pr-review\">")
+            .unwrap();
+        let github = worker.find("<skill_instructions name=\"github\">").unwrap();
+        assert!(review < github);
+        assert!(!worker.contains("<skill_instructions name=\"weather\">"));
+    }
+
     #[tokio::test]
     async fn remove_instance_skill_is_rejected() {
         let mut set = SkillSet::default();
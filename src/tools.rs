@@ -21,6 +21,7 @@
 //! - `shell`, `file_read`/`file_write`/`file_edit`/`file_list` — stateless, registered at creation
 //! - `task_update` — scoped to the worker's assigned task
 //! - `set_status` — per-worker instance, registered at creation
+//! - `set_progress` — writes the worker's progress channel
 //!
 //! **Cortex ToolServer** (one per agent):
 //! - `memory_save` — registered at startup
@@ -28,30 +29,38 @@
 //! **Cortex Chat ToolServer** (interactive admin chat):
 //! - branch + worker tool superset plus `spacebot_docs`, `config_inspect`, and `spawn_worker`
 
+pub mod ask_clarification;
 pub mod attachment_recall;
 pub mod branch_tool;
 pub mod browser;
 pub mod cancel;
 pub mod channel_recall;
+pub mod command_policy;
 pub mod config_inspect;
 pub mod cron;
 pub mod email_search;
 pub mod file;
+pub mod get_status;
+pub mod http_request;
 pub mod install_skill;
 pub mod mcp;
 pub mod memory_delete;
 pub mod memory_persistence_complete;
 pub mod memory_recall;
 pub mod memory_save;
+pub mod plan;
 pub mod project_manage;
 pub mod react;
 pub mod read_skill;
 pub mod reply;
+pub mod result_format;
+pub mod retry;
 pub mod route;
 pub mod secret_set;
 pub mod send_agent_message;
 pub mod send_file;
 pub mod send_message_to_another_channel;
+pub mod set_progress;
 pub mod set_status;
 pub mod shell;
 pub mod skills_search;
@@ -63,6 +72,7 @@ pub mod task_list;
 pub mod task_update;
 pub mod web_search;
 pub mod worker_inspect;
+pub mod worker_toolset;
 
 pub mod factory_create_agent;
 pub mod factory_list_presets;
@@ -71,6 +81,9 @@ pub mod factory_search_context;
 pub mod factory_update_config;
 pub mod factory_update_identity;
 
+pub use ask_clarification::{
+    AskClarificationArgs, AskClarificationError, AskClarificationOutput, AskClarificationTool,
+};
 pub use attachment_recall::{
     AttachmentRecallArgs, AttachmentRecallError, AttachmentRecallOutput, AttachmentRecallTool,
 };
@@ -93,6 +106,8 @@ pub use file::{
     FileOutput, FileReadArgs, FileReadTool, FileType, FileWriteArgs, FileWriteTool,
     register_file_tools,
 };
+pub use get_status::{GetStatusArgs, GetStatusError, GetStatusOutput, GetStatusTool};
+pub use http_request::{HttpRequestArgs, HttpRequestError, HttpRequestOutput, HttpRequestTool};
 pub use install_skill::{
     InstallSkillArgs, InstallSkillError, InstallSkillOutput, InstallSkillTool,
 };
@@ -111,12 +126,17 @@ pub use memory_recall::{
 pub use memory_save::{
     AssociationInput, MemorySaveArgs, MemorySaveError, MemorySaveOutput, MemorySaveTool,
 };
+pub use plan::{
+    PlanError, PlanLog, PlannedCall, PlannedOutput, PlannedTool, SideEffectTools, WorkerMode,
+};
 pub use project_manage::{
     ProjectManageArgs, ProjectManageError, ProjectManageOutput, ProjectManageTool,
 };
 pub use react::{ReactArgs, ReactError, ReactOutput, ReactTool};
 pub use read_skill::{ReadSkillArgs, ReadSkillError, ReadSkillOutput, ReadSkillTool};
 pub use reply::{RepliedFlag, ReplyArgs, ReplyError, ReplyOutput, ReplyTool, new_replied_flag};
+pub use result_format::{FormattedOutput, FormattedTool, format_value, with_result_format};
+pub use retry::{RetryingTool, classify_tool_error, with_retry};
 pub use route::{RouteArgs, RouteError, RouteOutput, RouteTool};
 pub use secret_set::{SecretSetArgs, SecretSetError, SecretSetOutput, SecretSetTool};
 pub use send_agent_message::{
@@ -126,7 +146,11 @@ pub use send_file::{SendFileArgs, SendFileError, SendFileOutput, SendFileTool};
 pub use send_message_to_another_channel::{
     SendMessageArgs, SendMessageError, SendMessageOutput, SendMessageTool,
 };
-pub use set_status::{SetStatusArgs, SetStatusError, SetStatusOutput, SetStatusTool, StatusKind};
+pub use set_progress::{SetProgressArgs, SetProgressError, SetProgressOutput, SetProgressTool};
+pub use set_status::{
+    SetStatusArgs, SetStatusError, SetStatusOutput, SetStatusTool, StatusEntry, StatusHistory,
+    StatusKind,
+};
 pub use shell::{EnvVar, ShellArgs, ShellError, ShellOutput, ShellResult, ShellTool};
 pub use skills_search::{
     SkillsSearchArgs, SkillsSearchError, SkillsSearchOutput, SkillsSearchTool,
@@ -141,10 +165,14 @@ pub use spawn_worker::{
 pub use task_create::{TaskCreateArgs, TaskCreateError, TaskCreateOutput, TaskCreateTool};
 pub use task_list::{TaskListArgs, TaskListError, TaskListOutput, TaskListTool};
 pub use task_update::{TaskUpdateArgs, TaskUpdateError, TaskUpdateOutput, TaskUpdateTool};
-pub use web_search::{SearchResult, WebSearchArgs, WebSearchError, WebSearchOutput, WebSearchTool};
+pub use web_search::{
+    BraveBackend, SearchBackend, SearchBackendDyn, SearchResult, SearxngBackend, WebSearchArgs,
+    WebSearchError, WebSearchOutput, WebSearchTool,
+};
 pub use worker_inspect::{
     WorkerInspectArgs, WorkerInspectError, WorkerInspectOutput, WorkerInspectTool,
 };
+pub use worker_toolset::{ToolPermissions, WorkerToolset};
 
 pub use factory_create_agent::{
     FactoryCreateAgentArgs, FactoryCreateAgentError, FactoryCreateAgentOutput,
@@ -180,7 +208,7 @@ use rig::tool::Tool as _;
 use rig::tool::server::{ToolServer, ToolServerHandle};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 
 #[derive(Debug, Clone)]
 pub enum BranchToolProfile {
@@ -271,6 +299,48 @@ pub fn truncate_output(value: &str, max_bytes: usize) -> String {
     )
 }
 
+/// Truncate to `max_chars` characters, preferring to end on a sentence.
+///
+/// Cuts after the last sentence end (`.`, `!`, `?` followed by whitespace,
+/// or a line break) that fits, as long as that keeps at least half the
+/// budget. Otherwise cuts at the last word boundary and appends `…`.
+pub fn truncate_at_sentence(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        return value.to_string();
+    }
+    let end = value
+        .char_indices()
+        .nth(max_chars)
+        .map_or(value.len(), |(index, _)| index);
+    let prefix = &value[..end];
+
+    let sentence_end = prefix
+        .char_indices()
+        .filter(|&(index, character)| {
+            let next = value[index + character.len_utf8()..].chars().next();
+            character == '\n'
+                || (matches!(character, '.' | '!' | '?') && next.is_none_or(char::is_whitespace))
+        })
+        .map(|(index, character)| index + character.len_utf8())
+        .next_back();
+    if let Some(sentence_end) = sentence_end
+        && prefix[..sentence_end].chars().count() >= max_chars / 2
+    {
+        return prefix[..sentence_end].trim_end().to_string();
+    }
+
+    // Leave room for the ellipsis.
+    let end = prefix
+        .char_indices()
+        .next_back()
+        .map_or(0, |(index, _)| index);
+    let cut = prefix[..end]
+        .rfind(char::is_whitespace)
+        .filter(|&index| index > 0)
+        .unwrap_or(end);
+    format!("{}…", prefix[..cut].trim_end())
+}
+
 /// Truncate to a byte limit and append `...`, preserving UTF-8 boundaries.
 ///
 /// The returned string will never exceed `max_bytes`. If there's not enough
@@ -367,15 +437,19 @@ pub async fn add_channel_tools(
             .get(state.deps.agent_id.as_ref())
             .cloned()
             .unwrap_or_else(|| state.deps.agent_id.to_string());
+        let output_limit = state.deps.runtime_config.output_limits.load().channel;
         handle
-            .add_tool(ReplyTool::new(
-                response_tx.clone(),
-                conversation_id.clone(),
-                state.conversation_logger.clone(),
-                state.channel_id.clone(),
-                replied_flag.clone(),
-                agent_display_name,
-            ))
+            .add_tool(
+                ReplyTool::new(
+                    response_tx.clone(),
+                    conversation_id.clone(),
+                    state.conversation_logger.clone(),
+                    state.channel_id.clone(),
+                    replied_flag.clone(),
+                    agent_display_name,
+                )
+                .with_output_limit(output_limit),
+            )
             .await?;
     }
     handle.add_tool(BranchTool::new(state.clone())).await?;
@@ -399,11 +473,14 @@ pub async fn add_channel_tools(
             .await?;
     }
     handle
-        .add_tool(SendFileTool::new(
-            response_tx.clone(),
-            state.deps.runtime_config.workspace_dir.clone(),
-            state.deps.sandbox.clone(),
-        ))
+        .add_tool(
+            SendFileTool::new(
+                response_tx.clone(),
+                state.deps.runtime_config.workspace_dir.clone(),
+                state.deps.sandbox.clone(),
+            )
+            .with_screenshot_dir(state.screenshot_dir.clone()),
+        )
         .await?;
     handle
         .add_tool(ProjectManageTool::new(
@@ -562,11 +639,23 @@ pub fn create_branch_tool_server(
 /// Create a per-worker ToolServer with task-appropriate tools.
 ///
 /// Each worker gets its own isolated ToolServer. The `set_status` tool is bound to
-/// the specific worker's ID so status updates route correctly. The browser tool
-/// is included when browser automation is enabled in the agent config.
+/// the specific worker's ID so status updates route correctly, and `get_status`
+/// reads back the statuses it recorded. `set_progress` writes to
+/// `progress_tx`. The browser tool
+/// is included when browser automation is enabled in the agent config, and
+/// `http_request` when `worker.http_request.enabled` is set.
 ///
 /// Shell commands are sandboxed via the `Sandbox` backend.
 /// File operations are restricted to `workspace` via path validation.
+/// `workdir` is the worker's isolated working directory, if it has one:
+/// shell commands start there and file tools are jailed to it.
+///
+/// In [`WorkerMode::Plan`], shell, file writes, browser, `http_request`,
+/// `secret_set`, `task_update` and MCP tools record their calls instead of
+/// running.
+///
+/// `permissions` caps whatever `toolset` asks for: a denied tool is never
+/// registered, so the worker can't call it in any mode.
 #[allow(clippy::too_many_arguments)]
 pub fn create_worker_tool_server(
     agent_id: AgentId,
@@ -576,44 +665,120 @@ pub fn create_worker_tool_server(
     event_tx: broadcast::Sender<ProcessEvent>,
     browser_config: BrowserConfig,
     screenshot_dir: PathBuf,
-    brave_search_key: Option<String>,
+    search_backend: Option<Arc<dyn SearchBackendDyn>>,
     workspace: PathBuf,
+    workdir: Option<PathBuf>,
     sandbox: Arc<Sandbox>,
     mcp_tools: Vec<McpToolAdapter>,
     runtime_config: Arc<RuntimeConfig>,
+    toolset: WorkerToolset,
+    permissions: ToolPermissions,
+    progress_tx: watch::Sender<u8>,
+    mode: WorkerMode,
 ) -> ToolServerHandle {
+    let toolset = permissions.restrict(toolset);
+    let path_suggestions = runtime_config.worker.load().path_suggestion_limit();
+    let jailed = workdir.is_some();
+    let workspace = workdir.unwrap_or(workspace);
+
+    let status_history = StatusHistory::default();
     let mut server = ToolServer::new()
-        .tool(ShellTool::new(workspace.clone(), sandbox.clone()))
-        .tool(TaskUpdateTool::for_worker(
-            task_store,
-            agent_id.clone(),
-            worker_id,
-        ))
+        .side_effect_tool(
+            TaskUpdateTool::for_worker(task_store, agent_id.clone(), worker_id),
+            &mode,
+        )
         .tool({
-            let mut status_tool = SetStatusTool::new(agent_id, worker_id, channel_id, event_tx);
+            let mut status_tool = SetStatusTool::new(agent_id, worker_id, channel_id, event_tx)
+                .with_history(status_history.clone());
             if let Some(store) = runtime_config.secrets.load().as_ref() {
                 status_tool = status_tool.with_tool_secrets(store.tool_secret_pairs());
             }
             status_tool
         })
+        .tool(GetStatusTool::new(status_history))
+        .tool(SetProgressTool::new(progress_tx))
         .tool(ReadSkillTool::new(runtime_config.clone()));
 
-    server = register_file_tools(server, workspace, sandbox);
+    let worker_config = runtime_config.worker.load();
+    let tool_retries = &worker_config.tool_retries;
+    let result_formats = &worker_config.tool_result_formats;
+
+    if toolset.shell {
+        server = server.side_effect_tool(
+            with_result_format(
+                with_retry(
+                    ShellTool::new(workspace.clone(), sandbox.clone())
+                        .with_path_suggestions(path_suggestions)
+                        .with_command_policy(runtime_config.clone(), worker_id),
+                    tool_retries,
+                ),
+                result_formats,
+            ),
+            &mode,
+        );
+    }
+
+    server = register_file_tools(
+        server,
+        workspace,
+        sandbox,
+        path_suggestions,
+        jailed,
+        permissions.file_write,
+        result_formats,
+        &mode,
+    );
 
-    if let Some(store) = runtime_config.secrets.load().as_ref() {
-        server = server.tool(SecretSetTool::new(store.clone()));
+    if permissions.secrets
+        && let Some(store) = runtime_config.secrets.load().as_ref()
+    {
+        server = server.side_effect_tool(SecretSetTool::new(store.clone()), &mode);
     }
 
-    if browser_config.enabled {
-        server = register_browser_tools(server, browser_config, screenshot_dir, &runtime_config);
+    if browser_config.enabled && toolset.browser {
+        server = register_browser_tools(
+            server,
+            browser_config,
+            screenshot_dir,
+            &runtime_config,
+            &mode,
+        );
     }
 
-    if let Some(key) = brave_search_key {
-        server = server.tool(WebSearchTool::new(key));
+    if let Some(backend) = search_backend.filter(|_| toolset.web_search) {
+        server = server.tool(with_result_format(
+            with_retry(WebSearchTool::new(backend), tool_retries),
+            result_formats,
+        ));
     }
 
+    // Any method but GET can change something remotely, so plan mode
+    // records every request.
+    if worker_config.http_request.enabled && toolset.http_request {
+        server = server.side_effect_tool(
+            with_result_format(
+                with_retry(
+                    HttpRequestTool::new(worker_config.http_request.clone()),
+                    tool_retries,
+                ),
+                result_formats,
+            ),
+            &mode,
+        );
+    }
+
+    // MCP tools can do anything, so plan mode treats them all as
+    // side-effecting.
+    let mcp_tools = if permissions.mcp {
+        mcp_tools
+    } else {
+        Vec::new()
+    };
     for mcp_tool in mcp_tools {
-        server = server.tool(mcp_tool);
+        server = server.side_effect_tool(
+            with_result_format(with_retry(mcp_tool, tool_retries), result_formats),
+            &mode,
+        );
     }
 
     server.run()
@@ -656,7 +821,7 @@ pub fn create_cortex_chat_tool_server(
     run_logger: crate::conversation::history::ProcessRunLogger,
     browser_config: BrowserConfig,
     screenshot_dir: PathBuf,
-    brave_search_key: Option<String>,
+    search_backend: Option<Arc<dyn SearchBackendDyn>>,
     workspace: PathBuf,
     sandbox: Arc<Sandbox>,
     runtime_config: Arc<RuntimeConfig>,
@@ -664,6 +829,10 @@ pub fn create_cortex_chat_tool_server(
     cortex_ctx: Option<crate::tools::spawn_worker::CortexChatContext>,
 ) -> ToolServerHandle {
     let logs_dir = workspace.join(".spacebot").join("logs");
+    let path_suggestions = runtime_config.worker.load().path_suggestion_limit();
+    let worker_config = runtime_config.worker.load();
+    let tool_retries = &worker_config.tool_retries;
+    let result_formats = &worker_config.tool_result_formats;
 
     let spawn_tool = {
         let tool = DetachedSpawnWorkerTool::new(deps, screenshot_dir.clone(), logs_dir);
@@ -698,16 +867,41 @@ pub fn create_cortex_chat_tool_server(
         ))
         .tool(TaskListTool::new(task_store.clone(), agent_id.to_string()))
         .tool(TaskUpdateTool::for_branch(task_store, agent_id.clone()))
-        .tool(ShellTool::new(workspace.clone(), sandbox.clone()));
+        .tool(with_result_format(
+            with_retry(
+                ShellTool::new(workspace.clone(), sandbox.clone())
+                    .with_path_suggestions(path_suggestions),
+                tool_retries,
+            ),
+            result_formats,
+        ));
 
-    server = register_file_tools(server, workspace, sandbox);
+    server = register_file_tools(
+        server,
+        workspace,
+        sandbox,
+        path_suggestions,
+        false,
+        true,
+        result_formats,
+        &WorkerMode::Execute,
+    );
 
     if browser_config.enabled {
-        server = register_browser_tools(server, browser_config, screenshot_dir, &runtime_config);
+        server = register_browser_tools(
+            server,
+            browser_config,
+            screenshot_dir,
+            &runtime_config,
+            &WorkerMode::Execute,
+        );
     }
 
-    if let Some(key) = brave_search_key {
-        server = server.tool(WebSearchTool::new(key));
+    if let Some(backend) = search_backend {
+        server = server.tool(with_result_format(
+            with_retry(WebSearchTool::new(backend), tool_retries),
+            result_formats,
+        ));
     }
 
     server.run()
@@ -1075,4 +1269,112 @@ mod tests {
         let result = truncate_utf8_ellipsis(text, 7);
         assert_eq!(result, "абв"); // also fits, no truncation needed
     }
+
+    #[test]
+    fn truncate_at_sentence_prefers_sentence_then_word_boundaries() {
+        let text = "The deploy finished. All checks passed on staging and production.";
+        assert_eq!(truncate_at_sentence(text, 200), text);
+        assert_eq!(truncate_at_sentence(text, 40), "The deploy finished.");
+
+        // No sentence end in the first half of the budget: cut on a word.
+        let result = truncate_at_sentence("Checks passed on staging and production", 20);
+        assert_eq!(result, "Checks passed on…");
+        assert!(result.chars().count() <= 20);
+
+        // Decimal points are not sentence ends.
+        assert_eq!(
+            truncate_at_sentence("Version 2.5 shipped. Rollout continues tomorrow.", 30),
+            "Version 2.5 shipped."
+        );
+    }
+
+    #[tokio::test]
+    async fn denied_tools_are_not_registered_on_the_worker_server() {
+        let instance_dir = tempfile::tempdir().expect("failed to create tempdir");
+        let config = crate::config::Config::load_from_env(instance_dir.path())
+            .expect("failed to build config");
+        let resolved = config
+            .resolve_agents()
+            .into_iter()
+            .next()
+            .expect("missing resolved agent config");
+        let runtime_config = Arc::new(RuntimeConfig::new(
+            instance_dir.path(),
+            &resolved,
+            &config.defaults,
+            crate::prompts::PromptEngine::new("en").expect("failed to build prompt engine"),
+            crate::identity::Identity::default(),
+            crate::skills::SkillSet::default(),
+        ));
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        let workspace = instance_dir.path().to_path_buf();
+        let sandbox = Arc::new(crate::sandbox::Sandbox::new_for_test(
+            Arc::new(arc_swap::ArcSwap::from_pointee(
+                crate::sandbox::SandboxConfig::default(),
+            )),
+            workspace.clone(),
+        ));
+
+        let tool_names = |permissions: ToolPermissions| {
+            let server = create_worker_tool_server(
+                "main".into(),
+                uuid::Uuid::new_v4(),
+                None,
+                Arc::new(TaskStore::new(pool.clone())),
+                broadcast::channel(8).0,
+                BrowserConfig::default(),
+                workspace.join("screenshots"),
+                None,
+                workspace.clone(),
+                None,
+                sandbox.clone(),
+                Vec::new(),
+                runtime_config.clone(),
+                WorkerToolset::ALL,
+                permissions,
+                watch::channel(0).0,
+                WorkerMode::Execute,
+            );
+            async move {
+                server
+                    .get_tool_defs(None)
+                    .await
+                    .expect("failed to get tool defs")
+                    .into_iter()
+                    .map(|definition| definition.name)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let full = tool_names(ToolPermissions::ALL).await;
+        assert!(full.iter().any(|name| name == "shell"), "{full:?}");
+        assert!(full.iter().any(|name| name == "file_write"), "{full:?}");
+
+        let no_shell = tool_names(ToolPermissions {
+            shell: false,
+            ..ToolPermissions::ALL
+        })
+        .await;
+        assert!(!no_shell.iter().any(|name| name == "shell"), "{no_shell:?}");
+        assert!(
+            no_shell.iter().any(|name| name == "file_write"),
+            "{no_shell:?}"
+        );
+
+        let read_only = tool_names(ToolPermissions::READ_ONLY).await;
+        for denied in ["shell", "file_write", "file_edit", "http_request"] {
+            assert!(
+                !read_only.iter().any(|name| name == denied),
+                "{read_only:?}"
+            );
+        }
+        assert!(
+            read_only.iter().any(|name| name == "file_read"),
+            "{read_only:?}"
+        );
+    }
 }
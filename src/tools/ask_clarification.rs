@@ -0,0 +1,226 @@
+//! Ask clarification tool for workers.
+//!
+//! Lets a worker pause when its task is underspecified, have the originating
+//! channel ask the user, and continue once the answer arrives. The answer is
+//! delivered through the same input path the channel uses for interactive
+//! follow-ups (`route` while the worker shows as idle).
+
+use crate::{AgentId, ChannelId, ProcessEvent, WorkerId};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, broadcast, mpsc};
+
+/// Tool for asking the user a clarifying question through the channel.
+#[derive(Debug, Clone)]
+pub struct AskClarificationTool {
+    agent_id: AgentId,
+    worker_id: WorkerId,
+    channel_id: Option<ChannelId>,
+    event_tx: broadcast::Sender<ProcessEvent>,
+    answer_rx: Arc<Mutex<mpsc::Receiver<String>>>,
+    timeout: Duration,
+}
+
+impl AskClarificationTool {
+    /// Create a new ask clarification tool.
+    pub fn new(
+        agent_id: AgentId,
+        worker_id: WorkerId,
+        channel_id: Option<ChannelId>,
+        event_tx: broadcast::Sender<ProcessEvent>,
+        answer_rx: mpsc::Receiver<String>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            agent_id,
+            worker_id,
+            channel_id,
+            event_tx,
+            answer_rx: Arc::new(Mutex::new(answer_rx)),
+            timeout,
+        }
+    }
+}
+
+/// Error type for ask clarification tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to ask for clarification: {0}")]
+pub struct AskClarificationError(String);
+
+/// Arguments for ask clarification tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AskClarificationArgs {
+    /// The question to put to the user.
+    pub question: String,
+}
+
+/// Output from ask clarification tool.
+#[derive(Debug, Serialize)]
+pub struct AskClarificationOutput {
+    /// Whether the user answered before the timeout.
+    pub answered: bool,
+    /// The user's answer, if one arrived.
+    pub answer: Option<String>,
+    /// What the worker should do next.
+    pub message: String,
+}
+
+impl Tool for AskClarificationTool {
+    const NAME: &'static str = "ask_clarification";
+
+    type Error = AskClarificationError;
+    type Args = AskClarificationArgs;
+    type Output = AskClarificationOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/ask_clarification").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "question": {
+                        "type": "string",
+                        "description": "One specific question whose answer you need to continue. Include the options you are choosing between when there are any."
+                    }
+                },
+                "required": ["question"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let question = args.question.trim();
+        if question.is_empty() {
+            return Err(AskClarificationError("question cannot be empty".into()));
+        }
+
+        // Hold the receiver for the whole wait so concurrent calls queue up
+        // instead of racing for the same answer.
+        let mut answer_rx = self.answer_rx.lock().await;
+
+        self.event_tx
+            .send(ProcessEvent::WorkerClarification {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.worker_id,
+                channel_id: self.channel_id.clone(),
+                question: question.to_string(),
+            })
+            .map_err(|_| AskClarificationError("no channel is listening for events".into()))?;
+
+        tracing::info!(worker_id = %self.worker_id, "worker waiting for clarification");
+
+        let answer = tokio::time::timeout(self.timeout, answer_rx.recv()).await;
+
+        self.event_tx
+            .send(ProcessEvent::WorkerStatus {
+                agent_id: self.agent_id.clone(),
+                worker_id: self.worker_id,
+                channel_id: self.channel_id.clone(),
+                status: "processing clarification answer".to_string(),
+            })
+            .ok();
+
+        match answer {
+            Ok(Some(answer)) => Ok(AskClarificationOutput {
+                answered: true,
+                answer: Some(answer),
+                message: "The user answered. Continue the task using their answer.".into(),
+            }),
+            Ok(None) => Err(AskClarificationError(
+                "the channel stopped accepting clarification answers".into(),
+            )),
+            Err(_) => {
+                tracing::info!(
+                    worker_id = %self.worker_id,
+                    timeout_secs = self.timeout.as_secs(),
+                    "clarification timed out"
+                );
+                Ok(AskClarificationOutput {
+                    answered: false,
+                    answer: None,
+                    message: format!(
+                        "No answer after {}s. Continue with your best assumption and \
+                         state it clearly in your result.",
+                        self.timeout.as_secs()
+                    ),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AskClarificationArgs, AskClarificationTool};
+    use crate::ProcessEvent;
+    use rig::tool::Tool;
+    use std::time::Duration;
+    use tokio::sync::{broadcast, mpsc};
+
+    fn tool(
+        answer_rx: mpsc::Receiver<String>,
+    ) -> (AskClarificationTool, broadcast::Receiver<ProcessEvent>) {
+        let (event_tx, event_rx) = broadcast::channel(8);
+        let tool = AskClarificationTool::new(
+            "agent".into(),
+            uuid::Uuid::new_v4(),
+            Some("channel".into()),
+            event_tx,
+            answer_rx,
+            Duration::from_secs(60),
+        );
+        (tool, event_rx)
+    }
+
+    fn ask(question: &str) -> AskClarificationArgs {
+        AskClarificationArgs {
+            question: question.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn an_answer_is_returned_to_the_worker() {
+        let (answer_tx, answer_rx) = mpsc::channel(1);
+        let (tool, mut event_rx) = tool(answer_rx);
+        answer_tx.send("the staging one".to_string()).await.unwrap();
+
+        let output = tool.call(ask("Which database?")).await.unwrap();
+        assert!(output.answered);
+        assert_eq!(output.answer.as_deref(), Some("the staging one"));
+
+        let ProcessEvent::WorkerClarification { question, .. } = event_rx.recv().await.unwrap()
+        else {
+            panic!("expected the question to be sent to the channel");
+        };
+        assert_eq!(question, "Which database?");
+        assert!(matches!(
+            event_rx.recv().await.unwrap(),
+            ProcessEvent::WorkerStatus { .. }
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn an_unanswered_question_times_out() {
+        let (_answer_tx, answer_rx) = mpsc::channel(1);
+        let (tool, _event_rx) = tool(answer_rx);
+
+        let output = tool.call(ask("Which database?")).await.unwrap();
+        assert!(!output.answered);
+        assert_eq!(output.answer, None);
+        assert!(output.message.contains("60s"));
+    }
+
+    #[tokio::test]
+    async fn a_closed_answer_channel_is_an_error() {
+        let (answer_tx, answer_rx) = mpsc::channel(1);
+        let (tool, _event_rx) = tool(answer_rx);
+        drop(answer_tx);
+
+        assert!(tool.call(ask("Which database?")).await.is_err());
+    }
+}
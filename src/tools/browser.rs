@@ -13,6 +13,7 @@
 
 use crate::config::BrowserConfig;
 use crate::secrets::store::SecretsStore;
+use crate::tools::{SideEffectTools as _, WorkerMode, with_result_format, with_retry};
 
 use chromiumoxide::browser::{Browser, BrowserConfig as ChromeConfig};
 use chromiumoxide::fetcher::{BrowserFetcher, BrowserFetcherOptions};
@@ -1225,7 +1226,7 @@ pub struct BrowserLaunchTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserLaunchArgs {}
 
 impl Tool for BrowserLaunchTool {
@@ -1256,7 +1257,7 @@ pub struct BrowserNavigateTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserNavigateArgs {
     /// The URL to navigate to.
     pub url: String,
@@ -1317,7 +1318,7 @@ pub struct BrowserSnapshotTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserSnapshotArgs {}
 
 impl Tool for BrowserSnapshotTool {
@@ -1419,7 +1420,7 @@ pub struct BrowserClickTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserClickArgs {
     /// The element index from the snapshot (e.g., 5).
     pub index: Option<usize>,
@@ -1478,7 +1479,7 @@ pub struct BrowserTypeTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserTypeArgs {
     /// The element index from the snapshot.
     pub index: Option<usize>,
@@ -1595,7 +1596,7 @@ pub struct BrowserPressKeyTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserPressKeyArgs {
     /// The key to press (e.g., "Enter", "Tab", "Escape", "ArrowDown").
     pub key: String,
@@ -1639,7 +1640,7 @@ pub struct BrowserScreenshotTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserScreenshotArgs {
     /// Whether to take a full-page screenshot.
     #[serde(default)]
@@ -1722,7 +1723,7 @@ pub struct BrowserEvaluateTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserEvaluateArgs {
     /// JavaScript expression to evaluate in the page.
     pub script: String,
@@ -1787,7 +1788,7 @@ pub struct BrowserTabOpenTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserTabOpenArgs {
     /// URL to open in the new tab. Defaults to about:blank.
     #[serde(default)]
@@ -1859,7 +1860,7 @@ pub struct BrowserTabListTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserTabListArgs {}
 
 impl Tool for BrowserTabListTool {
@@ -1914,7 +1915,7 @@ pub struct BrowserTabCloseTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserTabCloseArgs {
     /// Target ID of the tab to close. If omitted, closes the active tab.
     #[serde(default)]
@@ -1973,7 +1974,7 @@ pub struct BrowserCloseTool {
     context: BrowserContext,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct BrowserCloseArgs {}
 
 impl Tool for BrowserCloseTool {
@@ -2085,12 +2086,14 @@ impl Tool for BrowserCloseTool {
 
 /// Register all browser tools on a `ToolServer`. The tools share a single
 /// `BrowserState` (via `SharedBrowserHandle` for persistent sessions, or a
-/// fresh instance for ephemeral sessions).
+/// fresh instance for ephemeral sessions). Every browser tool acts on the
+/// page, so in plan mode all of them are planned rather than run.
 pub fn register_browser_tools(
     server: rig::tool::server::ToolServer,
     config: BrowserConfig,
     screenshot_dir: PathBuf,
     runtime_config: &crate::config::RuntimeConfig,
+    mode: &WorkerMode,
 ) -> rig::tool::server::ToolServer {
     let state = if let Some(shared) = runtime_config
         .shared_browser
@@ -2105,42 +2108,147 @@ pub fn register_browser_tools(
     let secrets = runtime_config.secrets.load().as_ref().as_ref().cloned();
 
     let context = BrowserContext::new(state, config, screenshot_dir, secrets);
+    let worker_config = runtime_config.worker.load();
+    let retries = &worker_config.tool_retries;
+    let formats = &worker_config.tool_result_formats;
 
     server
-        .tool(BrowserLaunchTool {
-            context: context.clone(),
-        })
-        .tool(BrowserNavigateTool {
-            context: context.clone(),
-        })
-        .tool(BrowserSnapshotTool {
-            context: context.clone(),
-        })
-        .tool(BrowserClickTool {
-            context: context.clone(),
-        })
-        .tool(BrowserTypeTool {
-            context: context.clone(),
-        })
-        .tool(BrowserPressKeyTool {
-            context: context.clone(),
-        })
-        .tool(BrowserScreenshotTool {
-            context: context.clone(),
-        })
-        .tool(BrowserEvaluateTool {
-            context: context.clone(),
-        })
-        .tool(BrowserTabOpenTool {
-            context: context.clone(),
-        })
-        .tool(BrowserTabListTool {
-            context: context.clone(),
-        })
-        .tool(BrowserTabCloseTool {
-            context: context.clone(),
-        })
-        .tool(BrowserCloseTool { context })
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserLaunchTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserNavigateTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserSnapshotTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserClickTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserTypeTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserPressKeyTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserScreenshotTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserEvaluateTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserTabOpenTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserTabListTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(
+                with_retry(
+                    BrowserTabCloseTool {
+                        context: context.clone(),
+                    },
+                    retries,
+                ),
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(
+            with_result_format(with_retry(BrowserCloseTool { context }, retries), formats),
+            mode,
+        )
 }
 
 // Shared helpers
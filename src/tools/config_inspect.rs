@@ -61,7 +61,7 @@ impl Tool for ConfigInspectTool {
                 "properties": {
                     "section": {
                         "type": "string",
-                        "description": "Optional subsection to return. Valid values: all, paths, routing, limits, compaction, memory_persistence, coalesce, ingestion, cortex, warmup, work_readiness, browser, sandbox, opencode, mcp_servers, brave_search, timezones, bulletin, secrets, binary_version, deployment"
+                        "description": "Optional subsection to return. Valid values: all, paths, routing, limits, compaction, memory_persistence, coalesce, ingestion, cortex, warmup, work_readiness, browser, sandbox, opencode, mcp_servers, web_search, timezones, bulletin, secrets, binary_version, deployment"
                     }
                 }
             }),
@@ -106,6 +106,10 @@ fn select_section(
             .get("deployment")
             .cloned()
             .ok_or_else(|| unknown_section_error("deployment")),
+        "brave_search" => snapshot
+            .get("web_search")
+            .cloned()
+            .ok_or_else(|| unknown_section_error("brave_search")),
         "mcp" => snapshot
             .get("mcp_servers")
             .cloned()
@@ -119,6 +123,6 @@ fn select_section(
 
 fn unknown_section_error(section: &str) -> ConfigInspectError {
     ConfigInspectError(format!(
-        "unknown section '{section}'. valid sections: all, paths, routing, limits, compaction, memory_persistence, coalesce, ingestion, cortex, warmup, work_readiness, browser, sandbox, opencode, mcp_servers (or mcp), brave_search, timezones, bulletin, secrets, binary_version, deployment"
+        "unknown section '{section}'. valid sections: all, paths, routing, limits, compaction, memory_persistence, coalesce, ingestion, cortex, warmup, work_readiness, browser, sandbox, opencode, mcp_servers (or mcp), web_search, timezones, bulletin, secrets, binary_version, deployment"
     ))
 }
@@ -4,11 +4,14 @@
 //! `file_list`) backed by a shared `FileContext` that handles sandbox-aware path
 //! validation. This mirrors the flat-tool pattern used by the browser tools.
 
+use crate::config::ToolResultFormat;
 use crate::sandbox::Sandbox;
+use crate::tools::{SideEffectTools as _, WorkerMode, with_result_format};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -21,11 +24,63 @@ use std::sync::Arc;
 pub(crate) struct FileContext {
     workspace: PathBuf,
     sandbox: Arc<Sandbox>,
+    /// Most similar existing names to list when a path doesn't exist. Zero
+    /// disables suggestions.
+    path_suggestions: usize,
+    /// Reject every path outside `workspace`, whatever the sandbox mode. Set
+    /// for workers running in their own working directory.
+    jailed: bool,
 }
 
 impl FileContext {
-    fn new(workspace: PathBuf, sandbox: Arc<Sandbox>) -> Self {
-        Self { workspace, sandbox }
+    fn new(workspace: PathBuf, sandbox: Arc<Sandbox>, path_suggestions: usize) -> Self {
+        Self {
+            workspace,
+            sandbox,
+            path_suggestions,
+            jailed: false,
+        }
+    }
+
+    /// Build a `FileError` for a failed I/O operation on `path`. Not-found
+    /// errors get a "did you mean" list of similarly named entries from the
+    /// same directory so the model can correct a typo instead of guessing.
+    fn io_error(&self, action: &str, path: &Path, error: std::io::Error) -> FileError {
+        let mut message = format!("{action}: {error}");
+        if error.kind() == std::io::ErrorKind::NotFound
+            && let Some(hint) = self.suggest_paths(path)
+        {
+            message.push_str(&hint);
+        }
+        FileError(message)
+    }
+
+    fn suggest_paths(&self, missing: &Path) -> Option<String> {
+        if self.path_suggestions == 0 {
+            return None;
+        }
+        let parent = missing.parent()?;
+        let name = missing.file_name()?.to_str()?;
+        if self.sandbox.mode_enabled() && !self.sandbox.is_path_allowed(parent) {
+            return None;
+        }
+
+        let candidates = std::fs::read_dir(parent)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .take(MAX_SUGGESTION_CANDIDATES)
+            .filter_map(|entry| entry.file_name().into_string().ok());
+        let matches = closest_names(name, candidates, self.path_suggestions);
+        if matches.is_empty() {
+            return None;
+        }
+
+        let listed = matches
+            .iter()
+            .map(|candidate| parent.join(candidate).display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!(". Did you mean: {listed}?"))
     }
 
     /// Resolve and validate a path.
@@ -46,6 +101,15 @@ impl FileContext {
         // existing ancestor and append the remaining components.
         let canonical = best_effort_canonicalize(&resolved);
 
+        if self.jailed && !canonical.starts_with(best_effort_canonicalize(&self.workspace)) {
+            return Err(FileError(format!(
+                "ACCESS DENIED: Path is outside your working directory. \
+                 File operations are restricted to {}. Copy anything you need \
+                 into it with the shell tool.",
+                self.workspace.display()
+            )));
+        }
+
         // When sandbox is disabled, skip workspace boundary enforcement.
         if !self.sandbox.mode_enabled() {
             return Ok(canonical);
@@ -97,6 +161,63 @@ impl FileContext {
     // tools from accessing them — no explicit filename checks needed.
 }
 
+/// Upper bound on directory entries scanned for suggestions, so a typo in a
+/// huge directory doesn't turn into a full listing.
+const MAX_SUGGESTION_CANDIDATES: usize = 2000;
+
+/// Rank `candidates` by similarity to `target` and return at most `limit`
+/// plausible matches, best first.
+///
+/// Similarity is case-insensitive edit distance. Candidates further away than
+/// a third of the target's length (minimum 2 edits) are dropped unless one name
+/// is a prefix of the other, which covers missing or extra extensions.
+pub(crate) fn closest_names(
+    target: &str,
+    candidates: impl IntoIterator<Item = String>,
+    limit: usize,
+) -> Vec<String> {
+    if limit == 0 || target.is_empty() {
+        return Vec::new();
+    }
+
+    let target_lower = target.to_lowercase();
+    let max_distance = (target_lower.chars().count() / 3).max(2);
+
+    let mut scored: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter(|candidate| candidate != target)
+        .filter_map(|candidate| {
+            let candidate_lower = candidate.to_lowercase();
+            let distance = edit_distance(&target_lower, &candidate_lower);
+            let prefix_match = candidate_lower.starts_with(&target_lower)
+                || target_lower.starts_with(&candidate_lower);
+            (distance <= max_distance || prefix_match).then_some((distance, candidate))
+        })
+        .collect();
+
+    scored.sort();
+    scored.truncate(limit);
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
 /// Canonicalize as much of the path as possible. For paths where the final
 /// components don't exist yet (e.g. writing a new file), canonicalize the
 /// deepest existing ancestor and append the rest.
@@ -222,7 +343,7 @@ impl Tool for FileReadTool {
 
         let raw = tokio::fs::read_to_string(&path)
             .await
-            .map_err(|error| FileError(format!("Failed to read file: {error}")))?;
+            .map_err(|error| self.context.io_error("Failed to read file", &path, error))?;
 
         // Apply line-based offset/limit if requested
         let content = if args.offset.is_some() || args.limit.is_some() {
@@ -414,7 +535,7 @@ impl Tool for FileEditTool {
 
         let original = tokio::fs::read_to_string(&path)
             .await
-            .map_err(|error| FileError(format!("Failed to read file: {error}")))?;
+            .map_err(|error| self.context.io_error("Failed to read file", &path, error))?;
 
         // Count occurrences to provide useful feedback
         let match_count = original.matches(&args.old_string).count();
@@ -504,7 +625,12 @@ impl Tool for FileListTool {
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
         let path = self.context.resolve_path(&args.path)?;
-        do_file_list(&path).await
+        match tokio::fs::metadata(&path).await {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Err(self
+                .context
+                .io_error("Failed to read directory", &path, error)),
+            _ => do_file_list(&path).await,
+        }
     }
 }
 
@@ -585,24 +711,56 @@ async fn do_file_list(path: &Path) -> Result<FileOutput, FileError> {
 
 /// Register all file tools on a `ToolServer`. The tools share a single
 /// `FileContext` for path validation and sandbox enforcement.
+///
+/// `path_suggestions` bounds how many similar names a not-found error lists;
+/// zero turns suggestions off. When `jailed` is set, paths outside
+/// `workspace` are rejected even with the sandbox disabled. In plan mode
+/// `file_write` and `file_edit` are planned rather than run; without
+/// `writable` they aren't registered at all.
+#[allow(clippy::too_many_arguments)]
 pub fn register_file_tools(
     server: rig::tool::server::ToolServer,
     workspace: PathBuf,
     sandbox: Arc<Sandbox>,
+    path_suggestions: usize,
+    jailed: bool,
+    writable: bool,
+    formats: &HashMap<String, ToolResultFormat>,
+    mode: &WorkerMode,
 ) -> rig::tool::server::ToolServer {
-    let context = FileContext::new(workspace, sandbox);
+    let context = FileContext {
+        jailed,
+        ..FileContext::new(workspace, sandbox, path_suggestions)
+    };
+
+    let server = server
+        .tool(with_result_format(
+            FileReadTool {
+                context: context.clone(),
+            },
+            formats,
+        ))
+        .tool(with_result_format(
+            FileListTool {
+                context: context.clone(),
+            },
+            formats,
+        ));
+    if !writable {
+        return server;
+    }
 
     server
-        .tool(FileReadTool {
-            context: context.clone(),
-        })
-        .tool(FileWriteTool {
-            context: context.clone(),
-        })
-        .tool(FileEditTool {
-            context: context.clone(),
-        })
-        .tool(FileListTool { context })
+        .side_effect_tool(
+            with_result_format(
+                FileWriteTool {
+                    context: context.clone(),
+                },
+                formats,
+            ),
+            mode,
+        )
+        .side_effect_tool(with_result_format(FileEditTool { context }, formats), mode)
 }
 
 // Legacy types (used by system-internal callers)
@@ -696,7 +854,7 @@ mod tests {
 
     fn make_context(mode: SandboxMode, workspace: &Path) -> FileContext {
         let sandbox = create_sandbox(mode, workspace);
-        FileContext::new(workspace.to_path_buf(), sandbox)
+        FileContext::new(workspace.to_path_buf(), sandbox, 3)
     }
 
     #[tokio::test]
@@ -729,6 +887,34 @@ mod tests {
         assert!(error.contains("ACCESS DENIED"), "unexpected error: {error}");
     }
 
+    #[test]
+    fn jailed_context_rejects_paths_outside_workdir_without_sandbox() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let workdir = temp_dir.path().join("workers").join("one");
+        fs::create_dir_all(&workdir).expect("failed to create workdir");
+
+        let context = FileContext {
+            jailed: true,
+            ..make_context(SandboxMode::Disabled, &workdir)
+        };
+
+        assert!(context.resolve_path("output.txt").is_ok());
+        let sibling = temp_dir
+            .path()
+            .join("workers")
+            .join("two")
+            .join("output.txt");
+        let error = context
+            .resolve_path(&sibling.to_string_lossy())
+            .expect_err("should reject another worker's directory")
+            .to_string();
+        assert!(
+            error.contains("working directory"),
+            "unexpected error: {error}"
+        );
+        assert!(context.resolve_path("../two/output.txt").is_err());
+    }
+
     #[tokio::test]
     async fn sandbox_disabled_allows_read_outside_workspace() {
         let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
@@ -1010,4 +1196,60 @@ mod tests {
             "should have continuation notice"
         );
     }
+
+    #[tokio::test]
+    async fn file_read_not_found_suggests_similar_names() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace).expect("failed to create workspace");
+        for name in ["config.toml", "Cargo.toml", "README.md"] {
+            fs::write(workspace.join(name), "").expect("failed to write file");
+        }
+
+        let tool = FileReadTool {
+            context: make_context(SandboxMode::Disabled, &workspace),
+        };
+        let error = tool
+            .call(FileReadArgs {
+                path: "confg.toml".into(),
+                offset: None,
+                limit: None,
+            })
+            .await
+            .expect_err("missing file should fail")
+            .to_string();
+        assert!(error.contains("Did you mean"), "unexpected error: {error}");
+        assert!(error.contains("config.toml"), "unexpected error: {error}");
+        assert!(!error.contains("README.md"), "unexpected error: {error}");
+
+        let tool = FileReadTool {
+            context: FileContext::new(
+                workspace.clone(),
+                create_sandbox(SandboxMode::Disabled, &workspace),
+                0,
+            ),
+        };
+        let error = tool
+            .call(FileReadArgs {
+                path: "confg.toml".into(),
+                offset: None,
+                limit: None,
+            })
+            .await
+            .expect_err("missing file should fail")
+            .to_string();
+        assert!(!error.contains("Did you mean"), "unexpected error: {error}");
+    }
+
+    #[test]
+    fn closest_names_ranks_by_distance_and_respects_limit() {
+        let candidates =
+            ["main.rs", "mian.rs", "lib.rs", "main.rs.bak", "mod.rs"].map(String::from);
+        assert_eq!(
+            closest_names("man.rs", candidates.clone(), 2),
+            ["main.rs", "mian.rs"]
+        );
+        assert_eq!(closest_names("main", candidates.clone(), 5).len(), 2);
+        assert!(closest_names("main.rs", candidates, 0).is_empty());
+    }
 }
@@ -0,0 +1,84 @@
+//! Get status tool for workers.
+
+use crate::tools::set_status::{StatusEntry, StatusHistory};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for reading back the statuses a worker reported with `set_status`.
+#[derive(Debug, Clone)]
+pub struct GetStatusTool {
+    history: StatusHistory,
+}
+
+impl GetStatusTool {
+    /// Create a get status tool over the history its `set_status` writes to.
+    pub fn new(history: StatusHistory) -> Self {
+        Self { history }
+    }
+}
+
+/// Error type for get status tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to get status: {0}")]
+pub struct GetStatusError(String);
+
+/// Arguments for get status tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetStatusArgs {
+    /// How many recent status updates to return.
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    10
+}
+
+/// Output from get status tool.
+#[derive(Debug, Serialize)]
+pub struct GetStatusOutput {
+    /// The most recent status, if any was reported.
+    pub current: Option<StatusEntry>,
+    /// Recent status updates, oldest first.
+    pub history: Vec<StatusEntry>,
+    /// How many status updates were reported in total, including ones no
+    /// longer kept.
+    pub total_updates: usize,
+}
+
+impl Tool for GetStatusTool {
+    const NAME: &'static str = "get_status";
+
+    type Error = GetStatusError;
+    type Args = GetStatusArgs;
+    type Output = GetStatusOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/get_status").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "default": 10,
+                        "description": "How many recent status updates to return"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (history, total_updates) = self.history.recent(args.limit.max(1));
+        Ok(GetStatusOutput {
+            current: history.last().cloned(),
+            history,
+            total_updates,
+        })
+    }
+}
@@ -0,0 +1,488 @@
+//! HTTP request tool (task workers only).
+//!
+//! Workers used to reach APIs by shelling out to `curl`, which breaks on
+//! quoting and leaves the model parsing progress output. This tool takes the
+//! method, URL, headers and body as structured arguments and returns the
+//! status, headers and body.
+//!
+//! Every request, and every redirect it follows, is checked against
+//! [`HttpRequestConfig`]: the host must pass the allow and deny lists, and
+//! unless `allow_private_networks` is set every address it resolves to must
+//! be public. The connection is pinned to the checked addresses, so a DNS
+//! answer that changes between the check and the connect can't reach an
+//! internal service.
+
+use crate::config::HttpRequestConfig;
+use crate::llm::tool_quirks::pattern_matches;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Url};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+/// Redirects followed before the request fails.
+const MAX_REDIRECTS: usize = 5;
+
+/// Headers dropped when a redirect leaves the original host.
+const CREDENTIAL_HEADERS: &[&str] = &["authorization", "cookie", "proxy-authorization"];
+
+/// Tool for making HTTP requests.
+#[derive(Debug, Clone)]
+pub struct HttpRequestTool {
+    config: HttpRequestConfig,
+}
+
+impl HttpRequestTool {
+    pub fn new(config: HttpRequestConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// Error type for the HTTP request tool.
+#[derive(Debug, thiserror::Error)]
+pub enum HttpRequestError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Request blocked: {0}")]
+    Blocked(String),
+
+    #[error("Request timed out after {0}s")]
+    Timeout(u64),
+
+    #[error("HTTP request failed: {0}")]
+    RequestFailed(String),
+}
+
+/// Arguments for the HTTP request tool.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct HttpRequestArgs {
+    /// HTTP method, e.g. "GET" or "POST".
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Absolute http:// or https:// URL.
+    pub url: String,
+    /// Request headers.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Request body, sent as-is.
+    pub body: Option<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// Output from the HTTP request tool.
+#[derive(Debug, Serialize)]
+pub struct HttpRequestOutput {
+    /// HTTP status code.
+    pub status: u16,
+    /// The URL that answered, after redirects.
+    pub url: String,
+    /// Response headers. Repeated headers are joined with ", ".
+    pub headers: BTreeMap<String, String>,
+    /// Response body. Cut off at the size limit, with a note.
+    pub body: String,
+    /// Whether the body was cut off.
+    pub truncated: bool,
+}
+
+impl Tool for HttpRequestTool {
+    const NAME: &'static str = "http_request";
+
+    type Error = HttpRequestError;
+    type Args = HttpRequestArgs;
+    type Output = HttpRequestOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/http_request").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "method": {
+                        "type": "string",
+                        "enum": ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"],
+                        "default": "GET",
+                        "description": "HTTP method"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "Absolute http:// or https:// URL, including any query string"
+                    },
+                    "headers": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Request headers, e.g. {\"Accept\": \"application/json\"}"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Request body, sent as-is. Set a Content-Type header to match."
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let timeout_secs = self.config.timeout_secs;
+        tokio::time::timeout(Duration::from_secs(timeout_secs), self.execute(args))
+            .await
+            .map_err(|_| HttpRequestError::Timeout(timeout_secs))?
+    }
+}
+
+impl HttpRequestTool {
+    async fn execute(&self, args: HttpRequestArgs) -> Result<HttpRequestOutput, HttpRequestError> {
+        let mut method = Method::from_bytes(args.method.trim().to_uppercase().as_bytes())
+            .map_err(|_| HttpRequestError::InvalidRequest(format!("method {:?}", args.method)))?;
+        let mut url = Url::parse(args.url.trim())
+            .map_err(|error| HttpRequestError::InvalidRequest(format!("url: {error}")))?;
+        let mut headers = parse_headers(&args.headers)?;
+        let mut body = args.body;
+
+        for _ in 0..=MAX_REDIRECTS {
+            let addrs = self.check_url(&url).await?;
+            let response = self
+                .send(&method, &url, &addrs, &headers, body.clone())
+                .await?;
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|location| url.join(location).ok());
+            let Some(next) = location.filter(|_| status.is_redirection()) else {
+                return self.read_response(url, response).await;
+            };
+
+            // 303, and 301/302 after a POST, become a GET without a body, as
+            // browsers and curl do.
+            if status == reqwest::StatusCode::SEE_OTHER
+                || (method == Method::POST && matches!(status.as_u16(), 301 | 302))
+            {
+                method = Method::GET;
+                body = None;
+                headers.remove(reqwest::header::CONTENT_TYPE);
+                headers.remove(reqwest::header::CONTENT_LENGTH);
+            }
+            if next.host_str() != url.host_str() {
+                for name in CREDENTIAL_HEADERS {
+                    headers.remove(*name);
+                }
+            }
+            url = next;
+        }
+
+        Err(HttpRequestError::RequestFailed(format!(
+            "more than {MAX_REDIRECTS} redirects"
+        )))
+    }
+
+    /// Check `url` against the host policy and resolve the addresses the
+    /// request may connect to.
+    async fn check_url(&self, url: &Url) -> Result<Vec<SocketAddr>, HttpRequestError> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(HttpRequestError::InvalidRequest(format!(
+                "unsupported scheme {:?}, use http or https",
+                url.scheme()
+            )));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| HttpRequestError::InvalidRequest("url has no host".into()))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_lowercase();
+        check_host(&host, &self.config)?;
+
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addrs: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map_err(|error| {
+                    HttpRequestError::RequestFailed(format!("could not resolve {host}: {error}"))
+                })?
+                .collect(),
+        };
+        if addrs.is_empty() {
+            return Err(HttpRequestError::RequestFailed(format!(
+                "{host} resolved to no addresses"
+            )));
+        }
+        if !self.config.allow_private_networks
+            && let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip()))
+        {
+            return Err(HttpRequestError::Blocked(format!(
+                "{host} resolves to the non-public address {}; set \
+                 allow_private_networks to reach internal hosts",
+                addr.ip()
+            )));
+        }
+        Ok(addrs)
+    }
+
+    async fn send(
+        &self,
+        method: &Method,
+        url: &Url,
+        addrs: &[SocketAddr],
+        headers: &HeaderMap,
+        body: Option<String>,
+    ) -> Result<reqwest::Response, HttpRequestError> {
+        let mut builder = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .gzip(true);
+        if let Some(domain) = url.domain() {
+            builder = builder.resolve_to_addrs(domain, addrs);
+        }
+        let client = builder
+            .build()
+            .map_err(|error| HttpRequestError::RequestFailed(error.to_string()))?;
+
+        let mut request = client
+            .request(method.clone(), url.clone())
+            .headers(headers.clone());
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        request.send().await.map_err(|error| {
+            HttpRequestError::RequestFailed(format!("{method} {url}: {}", error_chain(&error)))
+        })
+    }
+
+    async fn read_response(
+        &self,
+        url: Url,
+        mut response: reqwest::Response,
+    ) -> Result<HttpRequestOutput, HttpRequestError> {
+        let status = response.status().as_u16();
+        let mut headers = BTreeMap::<String, String>::new();
+        for (name, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            headers
+                .entry(name.as_str().to_string())
+                .and_modify(|joined| {
+                    joined.push_str(", ");
+                    joined.push_str(&value);
+                })
+                .or_insert_with(|| value.into_owned());
+        }
+
+        let limit = self.config.max_response_bytes;
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| HttpRequestError::RequestFailed(error_chain(&error)))?
+        {
+            let room = limit - bytes.len();
+            if chunk.len() > room {
+                bytes.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(HttpRequestOutput {
+            status,
+            url: url.to_string(),
+            headers,
+            body: render_body(&bytes, truncated),
+            truncated,
+        })
+    }
+}
+
+fn parse_headers(headers: &HashMap<String, String>) -> Result<HeaderMap, HttpRequestError> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| HttpRequestError::InvalidRequest(format!("header name {name:?}")))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| HttpRequestError::InvalidRequest(format!("value of header {name}")))?;
+        map.append(name, value);
+    }
+    Ok(map)
+}
+
+/// Apply the deny list, then the allow list, to a lowercased host.
+fn check_host(host: &str, config: &HttpRequestConfig) -> Result<(), HttpRequestError> {
+    if config
+        .denied_hosts
+        .iter()
+        .any(|pattern| pattern_matches(pattern, host))
+    {
+        return Err(HttpRequestError::Blocked(format!(
+            "{host} is in denied_hosts"
+        )));
+    }
+    if !config.allowed_hosts.is_empty()
+        && !config
+            .allowed_hosts
+            .iter()
+            .any(|pattern| pattern_matches(pattern, host))
+    {
+        return Err(HttpRequestError::Blocked(format!(
+            "{host} is not in allowed_hosts"
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `ip` is a globally routable address. Loopback, private,
+/// link-local (including cloud metadata at 169.254.169.254), carrier-grade
+/// NAT, multicast, documentation and reserved ranges are not.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8)
+        || ip.segments()[..6] == [0, 0, 0, 0, 0, 0])
+}
+
+fn render_body(bytes: &[u8], truncated: bool) -> String {
+    let mut body = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        // A cut can split a multi-byte character at the very end.
+        Err(error) if truncated && error.valid_up_to() + 4 > bytes.len() => {
+            String::from_utf8_lossy(&bytes[..error.valid_up_to()]).into_owned()
+        }
+        Err(_) => return format!("[{} bytes of binary content omitted]", bytes.len()),
+    };
+    if truncated {
+        body.push_str(&format!(
+            "\n\n[response truncated after {} bytes. Request a smaller range or a \
+             narrower query if you need the rest]",
+            bytes.len()
+        ));
+    }
+    body
+}
+
+/// reqwest's top-level error hides the cause (DNS, TLS, refused).
+fn error_chain(error: &reqwest::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_public_addresses_are_recognised() {
+        for blocked in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(blocked.parse().unwrap()), "{blocked}");
+        }
+        for public in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(public.parse().unwrap()), "{public}");
+        }
+    }
+
+    #[test]
+    fn deny_list_wins_over_allow_list() {
+        let config = HttpRequestConfig {
+            allowed_hosts: vec!["*.example.com".into()],
+            denied_hosts: vec!["admin.example.com".into()],
+            ..HttpRequestConfig::default()
+        };
+        assert!(check_host("api.example.com", &config).is_ok());
+        assert!(check_host("admin.example.com", &config).is_err());
+        assert!(check_host("example.org", &config).is_err());
+        assert!(check_host("anything.test", &HttpRequestConfig::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn loopback_is_blocked_unless_allowed() {
+        let tool = HttpRequestTool::new(HttpRequestConfig::default());
+        let url = Url::parse("http://127.0.0.1:8080/admin").unwrap();
+        assert!(matches!(
+            tool.check_url(&url).await,
+            Err(HttpRequestError::Blocked(_))
+        ));
+        assert!(matches!(
+            tool.check_url(&Url::parse("file:///etc/passwd").unwrap())
+                .await,
+            Err(HttpRequestError::InvalidRequest(_))
+        ));
+
+        let tool = HttpRequestTool::new(HttpRequestConfig {
+            allow_private_networks: true,
+            ..HttpRequestConfig::default()
+        });
+        assert_eq!(tool.check_url(&url).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn truncated_bodies_carry_a_note() {
+        let body = render_body("héllo".as_bytes().split_at(2).0, true);
+        assert!(body.starts_with('h'));
+        assert!(body.contains("response truncated after 2 bytes"));
+        assert_eq!(
+            render_body(&[0xff, 0x00, 0xfe, 0x01, 0x02, 0x03], false),
+            "[6 bytes of binary content omitted]"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-//! Memory recall tool for branches.
+//! Memory recall tool for branches and opted-in workers.
 
 use crate::error::Result;
 use crate::memory::MemorySearch;
@@ -0,0 +1,180 @@
+//! Plan-only worker mode.
+//!
+//! A worker in [`WorkerMode::Plan`] works through its task as usual, but
+//! tools that change anything (shell, file writes, browser, secrets, MCP)
+//! are registered as [`PlannedTool`]s: the call is checked against the
+//! tool's arguments, recorded in the mode's [`PlanLog`], and answered with a
+//! note that nothing ran. Read-only tools run normally so the plan can be
+//! grounded in the real workspace. The recorded calls come back on the
+//! worker's outcome for review before the task is run for real.
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use rig::tool::server::ToolServer;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// Whether a worker's side-effecting tools run or are only planned.
+#[derive(Debug, Clone, Default)]
+pub enum WorkerMode {
+    /// Tools run normally.
+    #[default]
+    Execute,
+    /// Side-effecting tools are recorded into the log instead of run.
+    Plan(PlanLog),
+}
+
+impl WorkerMode {
+    /// A plan-only mode with an empty log.
+    pub fn plan() -> Self {
+        Self::Plan(PlanLog::default())
+    }
+
+    pub fn is_plan(&self) -> bool {
+        matches!(self, Self::Plan(_))
+    }
+
+    /// Calls recorded so far, in the order the worker made them. Always
+    /// empty in execute mode.
+    pub fn planned_calls(&self) -> Vec<PlannedCall> {
+        match self {
+            Self::Execute => Vec::new(),
+            Self::Plan(log) => log.calls(),
+        }
+    }
+}
+
+/// A tool call a plan-only worker intended to make.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedCall {
+    pub tool: String,
+    pub args: serde_json::Value,
+}
+
+/// Shared record of the calls a plan-only worker's tools received.
+#[derive(Debug, Clone, Default)]
+pub struct PlanLog(Arc<Mutex<Vec<PlannedCall>>>);
+
+impl PlanLog {
+    pub fn calls(&self) -> Vec<PlannedCall> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn record(&self, call: PlannedCall) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).push(call);
+    }
+}
+
+/// A tool that records its calls in a [`PlanLog`] instead of running.
+#[derive(Debug, Clone)]
+pub struct PlannedTool<T> {
+    inner: T,
+    log: PlanLog,
+}
+
+impl<T: Tool> PlannedTool<T> {
+    pub fn new(inner: T, log: PlanLog) -> Self {
+        Self { inner, log }
+    }
+}
+
+/// Error type for planned tools: the arguments don't fit the real tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid arguments for {tool}: {reason}")]
+pub struct PlanError {
+    tool: String,
+    reason: String,
+}
+
+/// Output from a planned tool call.
+#[derive(Debug, Serialize)]
+pub struct PlannedOutput {
+    pub planned: bool,
+    pub tool: String,
+    pub message: String,
+}
+
+impl<T: Tool> Tool for PlannedTool<T> {
+    const NAME: &'static str = T::NAME;
+
+    type Error = PlanError;
+    type Args = serde_json::Value;
+    type Output = PlannedOutput;
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.inner.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let tool = self.inner.name();
+        // Reject what the real tool would reject, so the plan only holds
+        // calls that could actually run.
+        if let Err(error) = serde_json::from_value::<T::Args>(args.clone()) {
+            return Err(PlanError {
+                tool,
+                reason: error.to_string(),
+            });
+        }
+        self.log.record(PlannedCall {
+            tool: tool.clone(),
+            args,
+        });
+        Ok(PlannedOutput {
+            planned: true,
+            message: format!(
+                "Plan mode: {tool} was not run. The call was recorded for review; \
+                 continue as if it succeeded and finish the plan."
+            ),
+            tool,
+        })
+    }
+}
+
+/// Registration of tools that change something outside the worker.
+pub trait SideEffectTools {
+    /// Register `tool`, or a [`PlannedTool`] in its place when `mode` is
+    /// plan-only.
+    fn side_effect_tool<T: Tool + 'static>(self, tool: T, mode: &WorkerMode) -> Self;
+}
+
+impl SideEffectTools for ToolServer {
+    fn side_effect_tool<T: Tool + 'static>(self, tool: T, mode: &WorkerMode) -> Self {
+        match mode {
+            WorkerMode::Execute => self.tool(tool),
+            WorkerMode::Plan(log) => self.tool(PlannedTool::new(tool, log.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlannedTool, WorkerMode};
+    use crate::tools::SetProgressTool;
+    use rig::tool::Tool;
+
+    #[tokio::test]
+    async fn planned_calls_are_recorded_and_never_run() {
+        let (progress_tx, progress_rx) = tokio::sync::watch::channel(0);
+        let mode = WorkerMode::plan();
+        let WorkerMode::Plan(log) = &mode else {
+            unreachable!()
+        };
+        let tool = PlannedTool::new(SetProgressTool::new(progress_tx), log.clone());
+
+        let output = tool.call(serde_json::json!({"percent": 60})).await.unwrap();
+        assert!(output.planned);
+        assert_eq!(*progress_rx.borrow(), 0);
+
+        assert!(tool.call(serde_json::json!({"done": true})).await.is_err());
+
+        let calls = mode.planned_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].tool, "set_progress");
+        assert_eq!(calls[0].args["percent"], 60);
+        assert!(WorkerMode::Execute.planned_calls().is_empty());
+    }
+}
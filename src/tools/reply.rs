@@ -1,5 +1,6 @@
 //! Reply tool for sending messages to users (channel only).
 
+use crate::config::{OutputLimit, OutputOverflow};
 use crate::conversation::ConversationLogger;
 
 use crate::{ChannelId, OutboundResponse, RoutedSender};
@@ -45,6 +46,10 @@ pub struct ReplyTool {
     channel_id: ChannelId,
     replied_flag: RepliedFlag,
     agent_display_name: String,
+    output_limit: OutputLimit,
+    /// Set once an over-long reply has been bounced back for a shorter one,
+    /// so the next over-long reply in the turn is truncated instead.
+    limit_reprompted: Arc<AtomicBool>,
 }
 
 impl ReplyTool {
@@ -64,8 +69,16 @@ impl ReplyTool {
             channel_id,
             replied_flag,
             agent_display_name: agent_display_name.into(),
+            output_limit: OutputLimit::default(),
+            limit_reprompted: Arc::new(AtomicBool::new(false)),
         }
     }
+
+    /// Cap the length of each reply.
+    pub fn with_output_limit(mut self, output_limit: OutputLimit) -> Self {
+        self.output_limit = output_limit;
+        self
+    }
 }
 
 /// Error type for reply tool.
@@ -396,6 +409,34 @@ impl Tool for ReplyTool {
             ));
         }
 
+        let converted_content = if self.output_limit.exceeded_by(&converted_content) {
+            let length = converted_content.chars().count();
+            let max_chars = self.output_limit.max_chars;
+            if self.output_limit.on_exceed == OutputOverflow::Reprompt
+                && !self.limit_reprompted.swap(true, Ordering::Relaxed)
+            {
+                tracing::info!(
+                    conversation_id = %self.conversation_id,
+                    length,
+                    max_chars,
+                    "reply over output limit, asking for a shorter one"
+                );
+                return Err(ReplyError(format!(
+                    "reply not sent: it is {length} characters, over the {max_chars}-character limit. \
+                     Call reply again with a version of at most {max_chars} characters."
+                )));
+            }
+            tracing::info!(
+                conversation_id = %self.conversation_id,
+                length,
+                max_chars,
+                "truncating reply over output limit"
+            );
+            crate::tools::truncate_at_sentence(&converted_content, max_chars)
+        } else {
+            converted_content
+        };
+
         let thread_name = args
             .thread_name
             .as_ref()
@@ -437,12 +478,14 @@ impl Tool for ReplyTool {
             OutboundResponse::Text(converted_content.clone())
         };
 
+        let message_id = uuid::Uuid::new_v4().to_string();
         self.response_tx
-            .send(response)
+            .send_logged(response, Some(message_id.clone()))
             .await
             .map_err(|e| ReplyError(format!("failed to send reply: {e}")))?;
 
-        self.conversation_logger.log_bot_message_with_name(
+        self.conversation_logger.log_bot_message_with_id(
+            &message_id,
             &self.channel_id,
             &converted_content,
             Some(&self.agent_display_name),
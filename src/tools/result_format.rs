@@ -0,0 +1,308 @@
+//! Per-tool formatting of results before they reach the model.
+//!
+//! Tool outputs enter history as compact JSON, which is fine for small
+//! results but hard for the model to scan when a tool returns a list of
+//! records or a deeply nested object. [`FormattedTool`] wraps a tool and
+//! renders its output according to the tool's [`ToolResultFormat`]. Outputs
+//! the format can't represent (a table of a plain string, say) stay raw.
+//!
+//! Rig serializes every tool output to JSON, so rendered text comes out of
+//! the tool server as a quoted string literal with escaped newlines.
+//! [`model_text`] turns it back into the text itself when the request for
+//! the model is built.
+
+use crate::config::ToolResultFormat;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// A tool whose output is rendered with a [`ToolResultFormat`].
+#[derive(Debug, Clone)]
+pub struct FormattedTool<T> {
+    inner: T,
+    format: ToolResultFormat,
+}
+
+/// Wrap `tool` with its format from `formats`, looked up by the tool's
+/// registered name. Tools without a format pass their output through.
+pub fn with_result_format<T: Tool>(
+    tool: T,
+    formats: &HashMap<String, ToolResultFormat>,
+) -> FormattedTool<T> {
+    let format = formats.get(&tool.name()).copied().unwrap_or_default();
+    FormattedTool {
+        inner: tool,
+        format,
+    }
+}
+
+/// A tool output, either untouched or rendered as text. Rendered text
+/// serializes as a JSON string; see [`model_text`].
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum FormattedOutput<O> {
+    Raw(O),
+    Formatted(String),
+}
+
+impl<T: Tool> Tool for FormattedTool<T> {
+    const NAME: &'static str = T::NAME;
+
+    type Error = T::Error;
+    type Args = T::Args;
+    type Output = FormattedOutput<T::Output>;
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.inner.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let output = self.inner.call(args).await?;
+        if self.format == ToolResultFormat::Raw {
+            return Ok(FormattedOutput::Raw(output));
+        }
+        let formatted = serde_json::to_value(&output)
+            .ok()
+            .and_then(|value| format_value(&value, self.format));
+        Ok(match formatted {
+            Some(text) => FormattedOutput::Formatted(text),
+            None => FormattedOutput::Raw(output),
+        })
+    }
+}
+
+/// The text a tool result shows the model. A result that is a JSON string
+/// literal (rendered output, or any tool whose output is a string) is sent
+/// as the string itself rather than quoted with escaped newlines; anything
+/// else passes through.
+pub fn model_text(result: &str) -> Cow<'_, str> {
+    if result.starts_with('"')
+        && let Ok(text) = serde_json::from_str::<String>(result)
+    {
+        return Cow::Owned(text);
+    }
+    Cow::Borrowed(result)
+}
+
+/// Render `value` in `format`, or `None` when it should stay raw.
+pub fn format_value(value: &Value, format: ToolResultFormat) -> Option<String> {
+    match format {
+        ToolResultFormat::Raw => None,
+        ToolResultFormat::JsonPretty => serde_json::to_string_pretty(value).ok(),
+        ToolResultFormat::Table => format_table(value),
+        ToolResultFormat::KeyValue => format_key_value(value),
+    }
+}
+
+/// A markdown table for a list of records. An object renders its scalar
+/// fields as `key: value` lines and each list of records as a titled table.
+fn format_table(value: &Value) -> Option<String> {
+    match value {
+        Value::Array(rows) => markdown_table(rows),
+        Value::Object(fields) => {
+            let mut lines = Vec::new();
+            let mut tables = Vec::new();
+            for (key, field) in fields {
+                match field.as_array().and_then(|rows| markdown_table(rows)) {
+                    Some(table) => tables.push(format!("{key}:\n{table}")),
+                    None => lines.push(format!("{key}: {}", cell_text(field))),
+                }
+            }
+            if tables.is_empty() {
+                return None;
+            }
+            lines.extend(tables);
+            Some(lines.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Rows must all be objects; columns are their keys in first-seen order.
+fn markdown_table(rows: &[Value]) -> Option<String> {
+    if rows.is_empty() {
+        return None;
+    }
+    let records: Vec<&Map<String, Value>> =
+        rows.iter().map(Value::as_object).collect::<Option<_>>()?;
+
+    let mut columns: Vec<&str> = Vec::new();
+    for record in &records {
+        for key in record.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let mut table = format!("| {} |\n", columns.join(" | "));
+    table.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+    for record in records {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                record
+                    .get(*column)
+                    .map(|cell| cell_text(cell).replace('|', "\\|").replace('\n', " "))
+                    .unwrap_or_default()
+            })
+            .collect();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table.pop();
+    Some(table)
+}
+
+/// One `key: value` line per leaf, with dotted paths for nested objects and
+/// array indices.
+fn format_key_value(value: &Value) -> Option<String> {
+    if !(value.is_object() || value.is_array()) {
+        return None;
+    }
+    let mut lines = Vec::new();
+    push_key_value_lines(&mut lines, "", value);
+    Some(lines.join("\n"))
+}
+
+fn push_key_value_lines(lines: &mut Vec<String>, path: &str, value: &Value) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, field) in fields {
+                push_key_value_lines(lines, &join(key), field);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, item) in items.iter().enumerate() {
+                push_key_value_lines(lines, &join(&index.to_string()), item);
+            }
+        }
+        leaf => lines.push(format!("{path}: {}", cell_text(leaf))),
+    }
+}
+
+/// Strings without their quotes; everything else as compact JSON.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_value, model_text, with_result_format};
+    use crate::config::ToolResultFormat;
+    use rig::OneOrMany;
+    use rig::completion::ToolDefinition;
+    use rig::message::{Message, ToolResultContent, UserContent};
+    use rig::tool::Tool;
+    use rig::tool::server::ToolServer;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("unreachable")]
+    struct NeverFails;
+
+    /// Returns a fixed list of search results.
+    struct SearchTool;
+
+    impl Tool for SearchTool {
+        const NAME: &'static str = "search";
+
+        type Error = NeverFails;
+        type Args = serde_json::Value;
+        type Output = serde_json::Value;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: String::new(),
+                parameters: json!({"type": "object"}),
+            }
+        }
+
+        async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Ok(json!([
+                {"title": "The Book", "url": "https://doc.rust-lang.org/book"},
+                {"title": "Rustonomicon", "url": "https://doc.rust-lang.org/nomicon"},
+            ]))
+        }
+    }
+
+    #[test]
+    fn records_render_as_a_markdown_table() {
+        let value = json!({
+            "query": "rust",
+            "results": [
+                {"title": "The Book", "url": "https://doc.rust-lang.org/book"},
+                {"title": "a | b", "url": "https://example.com"},
+            ],
+        });
+        assert_eq!(
+            format_value(&value, ToolResultFormat::Table).unwrap(),
+            "query: rust\nresults:\n| title | url |\n| --- | --- |\n\
+             | The Book | https://doc.rust-lang.org/book |\n| a \\| b | https://example.com |"
+        );
+        assert_eq!(format_value(&json!("text"), ToolResultFormat::Table), None);
+        assert_eq!(
+            format_value(&json!({"exit_code": 0}), ToolResultFormat::Table),
+            None
+        );
+    }
+
+    #[test]
+    fn nested_fields_render_as_dotted_key_value_lines() {
+        let value = json!({"exit_code": 1, "result": {"stderr": "boom", "lines": ["a", "b"]}});
+        assert_eq!(
+            format_value(&value, ToolResultFormat::KeyValue).unwrap(),
+            "exit_code: 1\nresult.lines.0: a\nresult.lines.1: b\nresult.stderr: boom"
+        );
+        assert_eq!(format_value(&json!(42), ToolResultFormat::KeyValue), None);
+        assert_eq!(format_value(&value, ToolResultFormat::Raw), None);
+    }
+
+    #[tokio::test]
+    async fn the_model_sees_rendered_text_not_a_json_string() {
+        let formats = HashMap::from([("search".to_string(), ToolResultFormat::Table)]);
+        let server = ToolServer::new()
+            .tool(with_result_format(SearchTool, &formats))
+            .run();
+        let output = server.call_tool("search", "{}").await.unwrap();
+        assert!(output.starts_with('"'), "{output}");
+
+        // The tool result as the agent loop puts it into history.
+        let history = OneOrMany::one(Message::User {
+            content: OneOrMany::one(UserContent::tool_result(
+                "call-1",
+                ToolResultContent::from_tool_output(output),
+            )),
+        });
+        let converted = crate::llm::model::convert_messages_to_anthropic(&history);
+        assert_eq!(
+            converted[0]["content"][0]["content"],
+            "| title | url |\n| --- | --- |\n\
+             | The Book | https://doc.rust-lang.org/book |\n\
+             | Rustonomicon | https://doc.rust-lang.org/nomicon |"
+        );
+
+        assert_eq!(model_text(r#"{"exit_code":0}"#), r#"{"exit_code":0}"#);
+        assert_eq!(model_text(r#""unterminated"#), r#""unterminated"#);
+    }
+}
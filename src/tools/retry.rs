@@ -0,0 +1,242 @@
+//! Transparent retry of transient tool failures.
+//!
+//! A flaky navigation or a dropped connection usually succeeds on the next
+//! attempt, but surfacing it to the model costs a turn and often derails the
+//! plan. [`RetryingTool`] wraps a tool and repeats failing calls with the same
+//! arguments according to its [`ToolRetryPolicy`], so the model only sees the
+//! failure once retrying has given up. This is separate from LLM-level
+//! retries in `llm::model`, which repeat completion requests.
+
+use crate::config::{ToolErrorKind, ToolRetryPolicy};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Longest delay between two attempts, however many retries are configured.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A tool that retries transient failures before returning them.
+#[derive(Debug, Clone)]
+pub struct RetryingTool<T> {
+    inner: T,
+    policy: Option<ToolRetryPolicy>,
+}
+
+/// Wrap `tool` with its policy from `policies`, looked up by the tool's
+/// registered name. Tools without a policy pass straight through.
+pub fn with_retry<T>(tool: T, policies: &HashMap<String, ToolRetryPolicy>) -> RetryingTool<T>
+where
+    T: Tool,
+    T::Args: Clone,
+{
+    let policy = policies
+        .get(&tool.name())
+        .filter(|policy| policy.max_retries > 0 && !policy.retry_on.is_empty())
+        .cloned();
+    RetryingTool {
+        inner: tool,
+        policy,
+    }
+}
+
+/// Classify a tool error message into a transient failure kind, if it
+/// looks like one.
+pub fn classify_tool_error(message: &str) -> Option<ToolErrorKind> {
+    let lower = message.to_lowercase();
+    if lower.contains("rate limit") || lower.contains("429") || lower.contains("too many requests")
+    {
+        Some(ToolErrorKind::RateLimited)
+    } else if lower.contains("timed out") || lower.contains("timeout") {
+        Some(ToolErrorKind::Timeout)
+    } else if lower.contains("connection")
+        || lower.contains("error sending request")
+        || lower.contains("dns error")
+        || lower.contains("network")
+        || lower.contains("broken pipe")
+        || lower.contains("reset by peer")
+        || lower.contains("unreachable")
+    {
+        Some(ToolErrorKind::Network)
+    } else if lower.contains("500 internal")
+        || lower.contains("502")
+        || lower.contains("503")
+        || lower.contains("504")
+        || lower.contains("bad gateway")
+        || lower.contains("service unavailable")
+    {
+        Some(ToolErrorKind::Server)
+    } else {
+        None
+    }
+}
+
+/// Delay before retry number `retry` (zero-based), doubling each time.
+fn backoff(policy: &ToolRetryPolicy, retry: u32) -> Duration {
+    let factor = 2u64.saturating_pow(retry);
+    Duration::from_millis(policy.backoff_ms.saturating_mul(factor)).min(MAX_BACKOFF)
+}
+
+impl<T> Tool for RetryingTool<T>
+where
+    T: Tool,
+    T::Args: Clone,
+{
+    const NAME: &'static str = T::NAME;
+
+    type Error = T::Error;
+    type Args = T::Args;
+    type Output = T::Output;
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.inner.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let Some(policy) = &self.policy else {
+            return self.inner.call(args).await;
+        };
+
+        let mut retry = 0;
+        loop {
+            let error = match self.inner.call(args.clone()).await {
+                Ok(output) => return Ok(output),
+                Err(error) => error,
+            };
+            let message = error.to_string();
+            let retriable =
+                classify_tool_error(&message).is_some_and(|kind| policy.retry_on.contains(&kind));
+            if !retriable || retry >= policy.max_retries {
+                if retry > 0 {
+                    tracing::warn!(
+                        tool = %self.inner.name(),
+                        attempts = retry + 1,
+                        error = %message,
+                        "tool still failing after retries"
+                    );
+                }
+                return Err(error);
+            }
+
+            let delay = backoff(policy, retry);
+            retry += 1;
+            tracing::info!(
+                tool = %self.inner.name(),
+                retry,
+                max_retries = policy.max_retries,
+                delay_ms = delay.as_millis() as u64,
+                error = %message,
+                "retrying transient tool failure"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_tool_error, with_retry};
+    use crate::config::{ToolErrorKind, ToolRetryPolicy};
+    use rig::completion::ToolDefinition;
+    use rig::tool::Tool;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("{0}")]
+    struct FlakyError(String);
+
+    /// Fails with `error` for the first `failures` calls, then succeeds.
+    struct FlakyTool {
+        failures: u32,
+        error: &'static str,
+        calls: AtomicU32,
+    }
+
+    impl Tool for FlakyTool {
+        const NAME: &'static str = "flaky";
+
+        type Error = FlakyError;
+        type Args = serde_json::Value;
+        type Output = u32;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: String::new(),
+                parameters: serde_json::json!({"type": "object"}),
+            }
+        }
+
+        async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= self.failures {
+                Err(FlakyError(self.error.to_string()))
+            } else {
+                Ok(call)
+            }
+        }
+    }
+
+    fn policies(max_retries: u32) -> HashMap<String, ToolRetryPolicy> {
+        HashMap::from([(
+            "flaky".to_string(),
+            ToolRetryPolicy {
+                max_retries,
+                backoff_ms: 0,
+                ..Default::default()
+            },
+        )])
+    }
+
+    fn flaky(failures: u32, error: &'static str) -> FlakyTool {
+        FlakyTool {
+            failures,
+            error,
+            calls: AtomicU32::new(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_until_success() {
+        let tool = with_retry(flaky(2, "navigation timed out"), &policies(2));
+        let result = tool.call(serde_json::json!({})).await;
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_the_limit_and_skip_permanent_errors() {
+        let tool = with_retry(flaky(5, "connection refused"), &policies(1));
+        assert!(tool.call(serde_json::json!({})).await.is_err());
+        assert_eq!(tool.inner.calls.load(Ordering::SeqCst), 2);
+
+        let tool = with_retry(flaky(5, "element not found"), &policies(3));
+        assert!(tool.call(serde_json::json!({})).await.is_err());
+        assert_eq!(tool.inner.calls.load(Ordering::SeqCst), 1);
+
+        let tool = with_retry(flaky(1, "timed out"), &HashMap::new());
+        assert!(tool.call(serde_json::json!({})).await.is_err());
+    }
+
+    #[test]
+    fn error_messages_are_classified_by_kind() {
+        assert_eq!(
+            classify_tool_error("Rate limited by Brave Search API"),
+            Some(ToolErrorKind::RateLimited)
+        );
+        assert_eq!(
+            classify_tool_error("Web search request failed: error sending request"),
+            Some(ToolErrorKind::Network)
+        );
+        assert_eq!(
+            classify_tool_error("upstream returned 503"),
+            Some(ToolErrorKind::Server)
+        );
+        assert_eq!(classify_tool_error("invalid selector"), None);
+    }
+}
@@ -32,6 +32,10 @@ pub struct RouteArgs {
     pub worker_id: String,
     /// The message to send to the worker.
     pub message: String,
+    /// Hand an interactive worker off to finish on its own: `message` is its
+    /// last instruction and it stops accepting follow-ups.
+    #[serde(default)]
+    pub continue_in_background: bool,
 }
 
 /// Output from route tool.
@@ -45,6 +49,83 @@ pub struct RouteOutput {
     pub message: String,
 }
 
+impl RouteTool {
+    /// Hand an interactive worker off to fire-and-forget mode.
+    ///
+    /// The input sender is removed from the channel state and dropped after
+    /// delivering the final instruction, so the worker sees its input closed
+    /// once that turn is done and completes with that turn's answer as its
+    /// result. An idle worker gets the instruction as follow-up input; a
+    /// running one gets it as context injection.
+    async fn hand_off(
+        &self,
+        worker_id: WorkerId,
+        message: &str,
+    ) -> std::result::Result<RouteOutput, RouteError> {
+        let Some(input_tx) = self.state.worker_inputs.write().await.remove(&worker_id) else {
+            return Err(RouteError(format!(
+                "Worker {worker_id} is not an interactive worker waiting for input, so there \
+                 is nothing to hand off. Fire-and-forget workers already finish on their own."
+            )));
+        };
+
+        let instruction = self
+            .state
+            .deps
+            .runtime_config
+            .prompts
+            .load()
+            .render_system_worker_continue_in_background(message)
+            .map_err(|error| RouteError(format!("failed to render handoff: {error}")))?;
+
+        let worker_is_idle = {
+            let status = self.state.status_block.read().await;
+            status
+                .active_workers
+                .iter()
+                .any(|w| w.id == worker_id && w.status == "idle")
+        };
+        let delivered = if worker_is_idle {
+            input_tx.send(instruction).await.is_ok()
+        } else {
+            let inject_tx = self
+                .state
+                .worker_injections
+                .read()
+                .await
+                .get(&worker_id)
+                .cloned();
+            match inject_tx {
+                Some(inject_tx) => inject_tx.send(instruction).await.is_ok(),
+                None => false,
+            }
+        };
+        drop(input_tx);
+
+        tracing::info!(
+            worker_id = %worker_id,
+            channel_id = %self.state.channel_id,
+            delivered,
+            "interactive worker handed off to finish in the background"
+        );
+        Ok(RouteOutput {
+            routed: delivered,
+            worker_id,
+            message: if delivered {
+                format!(
+                    "Worker {worker_id} will finish in the background and its final result \
+                     will be posted here. It no longer accepts follow-ups."
+                )
+            } else {
+                format!(
+                    "Worker {worker_id} no longer accepts follow-ups and will finish with its \
+                     current work, but the final instruction could not be delivered."
+                )
+            },
+        })
+    }
+}
+
 impl Tool for RouteTool {
     const NAME: &'static str = "route";
 
@@ -66,6 +147,11 @@ impl Tool for RouteTool {
                     "message": {
                         "type": "string",
                         "description": "The message to send to the worker"
+                    },
+                    "continue_in_background": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Interactive workers only. Let the worker finish the task without further input and post its final result back. The message is its last instruction."
                     }
                 },
                 "required": ["worker_id", "message"]
@@ -79,6 +165,10 @@ impl Tool for RouteTool {
             .parse::<WorkerId>()
             .map_err(|e| RouteError(format!("Invalid worker ID: {e}")))?;
 
+        if args.continue_in_background {
+            return self.hand_off(worker_id, &args.message).await;
+        }
+
         // Check the status block to determine the worker's actual state.
         // Using sender map presence alone is unreliable: interactive workers
         // register both `worker_inputs` and `worker_injections` at spawn
@@ -1,5 +1,6 @@
 //! Send file tool for delivering file attachments to users (channel only).
 
+use crate::messaging::traits::max_attachment_bytes;
 use crate::sandbox::Sandbox;
 use crate::{OutboundResponse, RoutedSender};
 use rig::completion::ToolDefinition;
@@ -15,11 +16,18 @@ use std::sync::Arc;
 /// in the conversation. The channel process creates a response sender per
 /// conversation turn and this tool routes file responses through it.
 /// When sandbox mode is enabled, file access is restricted to the agent's
-/// workspace boundary. When sandbox is disabled, any readable path is allowed.
+/// workspace boundary, plus the browser's screenshot directory when one is
+/// set, so screenshots taken by workers can be delivered. When sandbox is
+/// disabled, any readable path is allowed.
+///
+/// Files are checked against the target platform's attachment limit (see
+/// [`max_attachment_bytes`]) before they're read, and refused outright on
+/// platforms that can't receive files.
 #[derive(Debug, Clone)]
 pub struct SendFileTool {
     response_tx: RoutedSender,
     workspace: PathBuf,
+    screenshot_dir: Option<PathBuf>,
     sandbox: Arc<Sandbox>,
 }
 
@@ -28,24 +36,45 @@ impl SendFileTool {
         Self {
             response_tx,
             workspace,
+            screenshot_dir: None,
             sandbox,
         }
     }
 
-    /// Validate that a path falls within the workspace boundary.
+    /// Also allow files from the browser's screenshot directory.
+    pub fn with_screenshot_dir(mut self, screenshot_dir: PathBuf) -> Self {
+        self.screenshot_dir = Some(screenshot_dir);
+        self
+    }
+
+    /// Validate that a path falls within the workspace boundary or the
+    /// screenshot directory.
+    fn validate_workspace_path(&self, path: &std::path::Path) -> Result<PathBuf, SendFileError> {
+        let result = self.validate_path_under(path, &self.workspace);
+        match &self.screenshot_dir {
+            Some(screenshot_dir) if result.is_err() => {
+                self.validate_path_under(path, screenshot_dir).or(result)
+            }
+            _ => result,
+        }
+    }
+
+    /// Validate that a path falls within `workspace`.
     ///
     /// Checks both the canonicalized path and individual path components for
     /// symlinks to prevent TOCTOU races where a symlink is swapped between
     /// validation and the actual file read.
-    fn validate_workspace_path(&self, path: &std::path::Path) -> Result<PathBuf, SendFileError> {
-        let workspace = &self.workspace;
-
+    fn validate_path_under(
+        &self,
+        path: &std::path::Path,
+        workspace: &std::path::Path,
+    ) -> Result<PathBuf, SendFileError> {
         let canonical = path.canonicalize().map_err(|error| {
             SendFileError(format!("can't resolve path '{}': {error}", path.display()))
         })?;
         let workspace_canonical = workspace
             .canonicalize()
-            .unwrap_or_else(|_| workspace.clone());
+            .unwrap_or_else(|_| workspace.to_path_buf());
 
         if !canonical.starts_with(&workspace_canonical) {
             return Err(SendFileError(format!(
@@ -108,9 +137,6 @@ pub struct SendFileOutput {
     pub size_bytes: u64,
 }
 
-/// Maximum file size: 25 MB (Discord's limit for non-boosted servers).
-const MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024;
-
 impl Tool for SendFileTool {
     const NAME: &'static str = "send_file";
 
@@ -146,6 +172,14 @@ impl Tool for SendFileTool {
             return Err(SendFileError("file_path must be an absolute path".into()));
         }
 
+        let platform = self.response_tx.target().source.as_str();
+        let Some(max_bytes) = max_attachment_bytes(platform) else {
+            return Err(SendFileError(format!(
+                "{platform} can't receive files. Describe the file or share its \
+                 contents as text instead."
+            )));
+        };
+
         let path = if self.sandbox.mode_enabled() {
             self.validate_workspace_path(&raw_path)?
         } else {
@@ -165,11 +199,10 @@ impl Tool for SendFileTool {
             return Err(SendFileError(format!("'{}' is not a file", path.display())));
         }
 
-        if metadata.len() > MAX_FILE_SIZE_BYTES {
+        if metadata.len() > max_bytes {
             return Err(SendFileError(format!(
-                "file is too large ({} bytes, max {} bytes)",
+                "file is too large for {platform} ({} bytes, max {max_bytes} bytes)",
                 metadata.len(),
-                MAX_FILE_SIZE_BYTES,
             )));
         }
 
@@ -304,6 +337,48 @@ mod tests {
         assert!(error.contains("ACCESS DENIED"), "unexpected error: {error}");
     }
 
+    #[test]
+    fn sandbox_enabled_accepts_screenshots() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace = temp_dir.path().join("workspace");
+        let screenshots = temp_dir.path().join("screenshots");
+        fs::create_dir_all(&workspace).expect("failed to create workspace");
+        fs::create_dir_all(&screenshots).expect("failed to create screenshot dir");
+
+        let file = screenshots.join("screenshot_1.png");
+        fs::write(&file, "png").expect("failed to write file");
+
+        let tool = create_tool(workspace).with_screenshot_dir(screenshots);
+        assert!(tool.validate_workspace_path(&file).is_ok());
+    }
+
+    #[tokio::test]
+    async fn platforms_without_file_support_are_refused() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace).expect("failed to create workspace");
+        let file = workspace.join("report.txt");
+        fs::write(&file, "ok").expect("failed to write file");
+
+        let sandbox = create_sandbox(SandboxMode::Enabled, &workspace);
+        let (tx, mut response_rx) = tokio::sync::mpsc::channel(1);
+        let target = crate::InboundMessage {
+            source: "twitch".into(),
+            ..crate::InboundMessage::empty()
+        };
+        let tool = SendFileTool::new(RoutedSender::new(tx, target), workspace, sandbox);
+
+        let error = tool
+            .call(SendFileArgs {
+                file_path: file.to_string_lossy().into_owned(),
+                caption: None,
+            })
+            .await
+            .expect_err("twitch can't receive files");
+        assert!(error.to_string().contains("twitch can't receive files"));
+        assert!(response_rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn sandbox_disabled_allows_file_outside_workspace() {
         let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
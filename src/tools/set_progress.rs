@@ -0,0 +1,105 @@
+//! Set progress tool for workers.
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+/// Tool for reporting how far along a worker is, as a percentage.
+#[derive(Debug, Clone)]
+pub struct SetProgressTool {
+    progress_tx: watch::Sender<u8>,
+}
+
+impl SetProgressTool {
+    /// Create a set progress tool that writes to a worker's progress channel.
+    pub fn new(progress_tx: watch::Sender<u8>) -> Self {
+        Self { progress_tx }
+    }
+}
+
+/// Error type for set progress tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to set progress: {0}")]
+pub struct SetProgressError(String);
+
+/// Arguments for set progress tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetProgressArgs {
+    /// Percentage of the task done, 0 to 100.
+    pub percent: u8,
+}
+
+/// Output from set progress tool.
+#[derive(Debug, Serialize)]
+pub struct SetProgressOutput {
+    /// The progress now shown, which never goes backwards.
+    pub progress: u8,
+}
+
+/// Raise `progress` to `percent`, capped at 100. Progress only moves
+/// forward, so a lower report leaves it where it is. Returns whether it
+/// changed.
+pub fn advance_progress(progress: &mut u8, percent: u8) -> bool {
+    let percent = percent.min(100);
+    if percent > *progress {
+        *progress = percent;
+        true
+    } else {
+        false
+    }
+}
+
+impl Tool for SetProgressTool {
+    const NAME: &'static str = "set_progress";
+
+    type Error = SetProgressError;
+    type Args = SetProgressArgs;
+    type Output = SetProgressOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/set_progress").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "percent": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": 100,
+                        "description": "How much of the task is done, as a percentage"
+                    }
+                },
+                "required": ["percent"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.progress_tx
+            .send_if_modified(|progress| advance_progress(progress, args.percent));
+        Ok(SetProgressOutput {
+            progress: *self.progress_tx.borrow(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::advance_progress;
+
+    #[test]
+    fn progress_only_moves_forward_and_caps_at_100() {
+        let mut progress = 0;
+        assert!(advance_progress(&mut progress, 40));
+        assert_eq!(progress, 40);
+
+        assert!(!advance_progress(&mut progress, 25));
+        assert_eq!(progress, 40);
+
+        assert!(advance_progress(&mut progress, 250));
+        assert_eq!(progress, 100);
+    }
+}
@@ -1,12 +1,66 @@
 //! Set status tool for workers.
 
 use crate::{AgentId, ChannelId, ProcessEvent, WorkerId};
+use chrono::{DateTime, Utc};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 
+/// Most status updates a worker's [`StatusHistory`] keeps.
+const MAX_STATUS_HISTORY: usize = 50;
+
+/// One status update a worker reported.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusEntry {
+    pub status: String,
+    pub kind: StatusKind,
+    pub reported_at: DateTime<Utc>,
+}
+
+/// A worker's own status updates, oldest first. Shared between `set_status`,
+/// which appends to it, and `get_status`, which reads it back, so the worker
+/// can recall what it reported after compaction drops those turns.
+#[derive(Debug, Clone, Default)]
+pub struct StatusHistory {
+    inner: Arc<Mutex<StatusHistoryInner>>,
+}
+
+#[derive(Debug, Default)]
+struct StatusHistoryInner {
+    entries: VecDeque<StatusEntry>,
+    total: usize,
+}
+
+impl StatusHistory {
+    fn record(&self, status: String, kind: StatusKind) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.entries.push_back(StatusEntry {
+            status,
+            kind,
+            reported_at: Utc::now(),
+        });
+        inner.total += 1;
+        while inner.entries.len() > MAX_STATUS_HISTORY {
+            inner.entries.pop_front();
+        }
+    }
+
+    /// The last `limit` updates, oldest first, and how many were ever
+    /// reported.
+    pub fn recent(&self, limit: usize) -> (Vec<StatusEntry>, usize) {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = inner.entries.len().saturating_sub(limit);
+        (
+            inner.entries.iter().skip(skip).cloned().collect(),
+            inner.total,
+        )
+    }
+}
+
 /// Tool for setting worker status.
 #[derive(Debug, Clone)]
 pub struct SetStatusTool {
@@ -16,6 +70,8 @@ pub struct SetStatusTool {
     event_tx: broadcast::Sender<ProcessEvent>,
     /// Tool secret pairs for scrubbing status text before it reaches the channel.
     tool_secret_pairs: Vec<(String, String)>,
+    /// Where reported statuses are kept for `get_status`.
+    history: StatusHistory,
 }
 
 impl SetStatusTool {
@@ -32,9 +88,16 @@ impl SetStatusTool {
             channel_id,
             event_tx,
             tool_secret_pairs: Vec::new(),
+            history: StatusHistory::default(),
         }
     }
 
+    /// Record statuses in `history`, typically shared with a `get_status` tool.
+    pub fn with_history(mut self, history: StatusHistory) -> Self {
+        self.history = history;
+        self
+    }
+
     /// Set tool secret pairs for output scrubbing.
     pub fn with_tool_secrets(mut self, pairs: Vec<(String, String)>) -> Self {
         self.tool_secret_pairs = pairs;
@@ -149,6 +212,7 @@ impl Tool for SetStatusTool {
         };
 
         let _ = self.event_tx.send(event);
+        self.history.record(status.clone(), args.kind);
 
         Ok(SetStatusOutput {
             success: true,
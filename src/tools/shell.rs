@@ -3,8 +3,13 @@
 //! This is the unified execution tool — it replaces the previous `shell` + `exec`
 //! split. Commands run through `sh -c` with optional per-command environment
 //! variables. Dangerous env vars that enable library injection are blocked.
+//! Worker shells also check each command against the agent's command
+//! policy (see [`crate::tools::command_policy`]).
 
+use crate::WorkerId;
+use crate::config::RuntimeConfig;
 use crate::sandbox::Sandbox;
+use crate::tools::command_policy::check_command;
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -37,15 +42,81 @@ const DANGEROUS_ENV_VARS: &[&str] = &[
 pub struct ShellTool {
     workspace: PathBuf,
     sandbox: Arc<Sandbox>,
+    path_suggestions: usize,
+    /// Config to read `worker.command_policy` from on every call, and the
+    /// worker refusals are logged for.
+    command_policy: Option<(Arc<RuntimeConfig>, WorkerId)>,
 }
 
 impl ShellTool {
     /// Create a new shell tool with sandbox containment.
     pub fn new(workspace: PathBuf, sandbox: Arc<Sandbox>) -> Self {
-        Self { workspace, sandbox }
+        Self {
+            workspace,
+            sandbox,
+            path_suggestions: 0,
+            command_policy: None,
+        }
+    }
+
+    /// Refuse commands that `worker.command_policy` denies. The policy is
+    /// read on every call, so a config reload applies to running workers.
+    pub fn with_command_policy(
+        mut self,
+        runtime_config: Arc<RuntimeConfig>,
+        worker_id: WorkerId,
+    ) -> Self {
+        self.command_policy = Some((runtime_config, worker_id));
+        self
+    }
+
+    /// When a command fails because its program isn't on `PATH`, append up
+    /// to `limit` similarly named executables to the summary.
+    pub fn with_path_suggestions(mut self, limit: usize) -> Self {
+        self.path_suggestions = limit;
+        self
+    }
+
+    fn suggest_commands(&self, stderr: &str) -> Option<String> {
+        if self.path_suggestions == 0 {
+            return None;
+        }
+        let missing = missing_command_name(stderr)?;
+
+        let mut seen = std::collections::HashSet::new();
+        let candidates = self
+            .sandbox
+            .path_dirs()
+            .into_iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| seen.insert(name.clone()));
+        let matches = crate::tools::file::closest_names(missing, candidates, self.path_suggestions);
+        if matches.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "\n`{missing}` is not on PATH. Similar commands: {}\n",
+            matches.join(", ")
+        ))
     }
 }
 
+/// Extract the program name from a shell's "command not found" message, e.g.
+/// `sh: 1: pyhton: not found` or `bash: line 1: pyhton: command not found`.
+fn missing_command_name(stderr: &str) -> Option<&str> {
+    stderr.lines().find_map(|line| {
+        let line = line.trim_end();
+        let prefix = line
+            .strip_suffix(": command not found")
+            .or_else(|| line.strip_suffix(": not found"))?;
+        let name = prefix.rsplit(": ").next()?.trim();
+        (!name.is_empty() && !name.contains('/')).then_some(name)
+    })
+}
+
 /// Error type for shell tool.
 #[derive(Debug, thiserror::Error)]
 #[error("Shell command failed: {message}")]
@@ -55,7 +126,7 @@ pub struct ShellError {
 }
 
 /// A key-value environment variable pair.
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct EnvVar {
     /// The variable name.
     pub key: String,
@@ -64,7 +135,7 @@ pub struct EnvVar {
 }
 
 /// Arguments for shell tool.
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct ShellArgs {
     /// The shell command to execute.
     pub command: String,
@@ -154,6 +225,31 @@ impl Tool for ShellTool {
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if let Some((runtime_config, worker_id)) = &self.command_policy
+            && let Err(refusal) =
+                check_command(&runtime_config.worker.load().command_policy, &args.command)
+        {
+            tracing::warn!(
+                %worker_id,
+                program = refusal.program(),
+                rule = refusal.rule(),
+                command = %args.command,
+                "shell command refused by command policy"
+            );
+            let message = runtime_config
+                .prompts
+                .load()
+                .render_system_command_refused(&refusal.to_string())
+                .unwrap_or_else(|error| {
+                    tracing::warn!(%error, "failed to render command refusal");
+                    refusal.to_string()
+                });
+            return Err(ShellError {
+                message,
+                exit_code: -1,
+            });
+        }
+
         // Relative working_dir values resolve from the workspace.
         // Workspace boundary enforcement only applies when sandbox mode is enabled.
         let working_dir = if let Some(ref dir) = args.working_dir {
@@ -270,7 +366,12 @@ impl Tool for ShellTool {
         let exit_code = output.status.code().unwrap_or(-1);
         let success = output.status.success();
 
-        let summary = format_shell_output(exit_code, &stdout, &stderr);
+        let mut summary = format_shell_output(exit_code, &stdout, &stderr);
+        if exit_code == 127
+            && let Some(hint) = self.suggest_commands(&stderr)
+        {
+            summary.push_str(&hint);
+        }
 
         Ok(ShellOutput {
             success,
@@ -7,7 +7,9 @@
 
 use crate::WorkerId;
 use crate::agent::channel::ChannelState;
-use crate::agent::channel_dispatch::{spawn_opencode_worker_from_state, spawn_worker_from_state};
+use crate::agent::channel_dispatch::{
+    check_spawn_depth, spawn_opencode_worker_from_state, spawn_worker_from_state,
+};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -42,11 +44,34 @@ pub struct SpawnWorkerArgs {
     /// Whether this is an interactive worker (accepts follow-up messages).
     #[serde(default)]
     pub interactive: bool,
+    /// Hold new user messages until this worker reports back, so the next
+    /// user turn sees its result. Default is fire-and-forget.
+    #[serde(default)]
+    pub wait: bool,
     /// Optional list of skill names to suggest to the worker. The worker sees
     /// all available skills and can read any of them via read_skill, but
     /// suggested skills are flagged as recommended for this task.
     #[serde(default)]
     pub suggested_skills: Vec<String>,
+    /// Give the worker a read-only memory_recall tool. Ignored unless memory
+    /// recall for workers is enabled in config.
+    #[serde(default)]
+    pub memory_recall: bool,
+    /// Optional tools the worker needs (`shell`, `browser`, `web_search`,
+    /// `http_request`).
+    /// When omitted the worker gets all of them, or an inferred subset when
+    /// tool gating is enabled.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+    /// Answer the task with a plain completion and no tools at all. For
+    /// pure Q&A or text tasks; skips tool setup entirely.
+    #[serde(default)]
+    pub direct: bool,
+    /// Deny every tool that can change something (shell, file writes,
+    /// browser, `http_request`, secrets, MCP), on top of whatever the
+    /// agent's config already denies.
+    #[serde(default)]
+    pub read_only: bool,
     /// Worker type: "builtin" (default) runs a Rig agent loop with shell/file
     /// tools. "opencode" spawns an OpenCode subprocess with full coding agent
     /// capabilities. Use "opencode" for complex coding tasks that benefit from
@@ -67,6 +92,15 @@ pub struct SpawnWorkerArgs {
     /// automatically set to the worktree path.
     #[serde(default)]
     pub worktree_id: Option<String>,
+    /// Short human-readable name shown next to the worker ID in status
+    /// updates, log filenames and listings. Derived from the task when
+    /// omitted.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Model to run a builtin worker on instead of the routed worker model,
+    /// e.g. "anthropic/claude-sonnet-4". Must be on a configured provider.
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 /// Output from spawn worker tool.
@@ -91,17 +125,29 @@ impl Tool for SpawnWorkerTool {
 
     async fn definition(&self, _prompt: String) -> ToolDefinition {
         let rc = &self.state.deps.runtime_config;
-        let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let permissions = rc.worker.load().tool_permissions;
+        let browser_enabled = rc.browser_config.load().enabled && permissions.browser;
+        let web_search_enabled = rc.web_search_enabled() && permissions.web_search;
+        let http_request_enabled =
+            rc.worker.load().http_request.enabled && permissions.http_request;
         let opencode_enabled = rc.opencode.load().enabled;
 
-        let mut tools_list = vec!["shell", "file_read", "file_write", "file_edit", "file_list"];
+        let mut tools_list = vec!["file_read", "file_list"];
+        if permissions.shell {
+            tools_list.insert(0, "shell");
+        }
+        if permissions.file_write {
+            tools_list.extend(["file_write", "file_edit"]);
+        }
         if browser_enabled {
             tools_list.push("browser");
         }
         if web_search_enabled {
             tools_list.push("web_search");
         }
+        if http_request_enabled {
+            tools_list.push("http_request");
+        }
 
         let opencode_note = if opencode_enabled {
             " Set `worker_type` to \"opencode\" with a `directory` path for complex coding tasks — this spawns a full OpenCode coding agent with codebase exploration, context management, and its own tool suite. If `worker_type` is omitted, the builtin worker is used."
@@ -128,9 +174,69 @@ impl Tool for SpawnWorkerTool {
                 "type": "array",
                 "items": { "type": "string" },
                 "description": "Skill names from <available_skills> that are likely relevant to this task. The worker sees all skills and decides what to read, but suggested skills are flagged as recommended."
+            },
+            "direct": {
+                "type": "boolean",
+                "default": false,
+                "description": "If true, the worker gets no tools at all and answers in a single completion. Use for pure Q&A, summarizing, rewriting, or drafting text you already have in hand. Faster and cheaper than a normal worker; do not use when the task needs files, commands, or the web."
+            },
+            "read_only": {
+                "type": "boolean",
+                "default": false,
+                "description": "If true, the worker can look but not change anything: no shell, no file writes or edits, no browser, no http_request, no secrets and no MCP tools. Reading files and web search still work. Use for research and review tasks."
+            },
+            "wait": {
+                "type": "boolean",
+                "default": false,
+                "description": "If true, new user messages are held until this worker reports back, so your next turn sees its result first. Use when the next reply depends on the outcome. If false (default), the conversation continues while the worker runs."
+            },
+            "label": {
+                "type": "string",
+                "description": "Optional short name for the worker, e.g. \"deploy-watcher\". Shown next to the worker ID in status updates. Derived from the task when omitted."
+            },
+            "model": {
+                "type": "string",
+                "description": "Optional model to run this worker on instead of the default worker model, as \"provider/model\". Use only when the task clearly needs a stronger or cheaper model. Ignored for OpenCode workers."
             }
         });
 
+        if let Some(obj) = properties.as_object_mut() {
+            let mut optional_tools = Vec::new();
+            if permissions.shell {
+                optional_tools.push("shell");
+            }
+            if browser_enabled {
+                optional_tools.push("browser");
+            }
+            if web_search_enabled {
+                optional_tools.push("web_search");
+            }
+            if http_request_enabled {
+                optional_tools.push("http_request");
+            }
+            obj.insert(
+                "tools".to_string(),
+                serde_json::json!({
+                    "type": "array",
+                    "items": { "type": "string", "enum": optional_tools },
+                    "description": "Optional tools this worker needs. File tools are always included. Omit to let the worker get the default set; pass [] for pure text tasks like summarizing or rewriting."
+                }),
+            );
+        }
+
+        if rc.worker.load().memory_recall
+            && let Some(obj) = properties.as_object_mut()
+        {
+            obj.insert(
+                "memory_recall".to_string(),
+                serde_json::json!({
+                    "type": "boolean",
+                    "default": false,
+                    "description": "If true, the worker gets a read-only memory_recall tool to look up stored facts relevant to its task. Leave false unless the task benefits from memories; workers are isolated by default."
+                }),
+            );
+        }
+
         if opencode_enabled && let Some(obj) = properties.as_object_mut() {
             obj.insert(
                 "worker_type".to_string(),
@@ -179,6 +285,10 @@ impl Tool for SpawnWorkerTool {
         let readiness = self.state.deps.runtime_config.work_readiness();
         let is_opencode = args.worker_type.as_deref() == Some("opencode");
 
+        if let Some(refusal) = spawn_depth_refusal(&self.state.deps, args.interactive) {
+            return Ok(refusal);
+        }
+
         // Reject if an active worker already has the same task. This prevents
         // duplicate workers when the LLM emits multiple spawn_worker calls in
         // a single response and one fails/retries.
@@ -217,9 +327,15 @@ impl Tool for SpawnWorkerTool {
             })?;
 
             // OpenCode workers are always interactive — ignore args.interactive.
-            spawn_opencode_worker_from_state(&self.state, &args.task, directory, true)
-                .await
-                .map_err(|e| SpawnWorkerError(format!("{e}")))?
+            spawn_opencode_worker_from_state(
+                &self.state,
+                &args.task,
+                directory,
+                true,
+                args.label.as_deref(),
+            )
+            .await
+            .map_err(|e| SpawnWorkerError(format!("{e}")))?
         } else {
             spawn_worker_from_state(
                 &self.state,
@@ -230,11 +346,21 @@ impl Tool for SpawnWorkerTool {
                     .iter()
                     .map(String::as_str)
                     .collect::<Vec<_>>(),
+                args.memory_recall,
+                args.tools.as_deref(),
+                args.direct,
+                args.read_only,
+                args.label.as_deref(),
+                args.model.as_deref(),
             )
             .await
             .map_err(|e| SpawnWorkerError(format!("{e}")))?
         };
 
+        if args.wait {
+            self.state.awaited_workers.write().await.insert(worker_id);
+        }
+
         // Link the worker to project/worktree if specified (fire-and-forget update).
         if args.project_id.is_some() || args.worktree_id.is_some() {
             self.state.process_run_logger.log_worker_project_link(
@@ -271,11 +397,17 @@ impl Tool for SpawnWorkerTool {
             )
         };
 
+        let wait_note = if args.wait {
+            " New user messages are held until it reports back."
+        } else {
+            ""
+        };
+
         Ok(SpawnWorkerOutput {
             worker_id,
             spawned: true,
             interactive: effectively_interactive,
-            message: format!("{message}{readiness_note}"),
+            message: format!("{message}{wait_note}{readiness_note}"),
         })
     }
 }
@@ -354,7 +486,8 @@ impl Tool for DetachedSpawnWorkerTool {
     async fn definition(&self, _prompt: String) -> ToolDefinition {
         let rc = &self.deps.runtime_config;
         let browser_enabled = rc.browser_config.load().enabled;
-        let web_search_enabled = rc.brave_search_key.load().is_some();
+        let web_search_enabled = rc.web_search_enabled();
+        let http_request_enabled = rc.worker.load().http_request.enabled;
 
         let mut tools_list = vec!["shell", "file_read", "file_write", "file_edit", "file_list"];
         if browser_enabled {
@@ -363,6 +496,9 @@ impl Tool for DetachedSpawnWorkerTool {
         if web_search_enabled {
             tools_list.push("web_search");
         }
+        if http_request_enabled {
+            tools_list.push("http_request");
+        }
 
         let description = format!(
             "Spawn an independent worker process with {} tools. The worker runs \
@@ -390,8 +526,19 @@ impl Tool for DetachedSpawnWorkerTool {
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if let Some(refusal) = spawn_depth_refusal(&self.deps, false) {
+            return Ok(refusal);
+        }
+
         let rc = &self.deps.runtime_config;
         let prompt_engine = rc.prompts.load();
+        let task = crate::agent::worker::fit_task_to_budget(
+            args.task,
+            &rc.worker.load(),
+            &rc.saved_dir(),
+            &prompt_engine,
+        )
+        .map_err(|error| SpawnWorkerError(format!("{error}")))?;
 
         // Build worker status text (time + model) for the system prompt.
         let system_info =
@@ -429,28 +576,30 @@ impl Tool for DetachedSpawnWorkerTool {
                 SpawnWorkerError(format!("failed to render worker prompt: {error}"))
             })?;
 
-        let brave_search_key = (**rc.brave_search_key.load()).clone();
+        let search_backend = rc.search_backend();
 
         let worker = crate::agent::worker::Worker::new(
             None, // no parent channel
-            &args.task,
+            &task,
             worker_system_prompt,
-            self.deps.clone(),
+            self.deps.for_spawned_worker(),
             browser_config,
             self.screenshot_dir.clone(),
-            brave_search_key,
+            search_backend,
             self.logs_dir.clone(),
         );
 
         let (worker, _input_tx) = worker;
         let worker_id = worker.id;
+        let label = worker.label.clone();
 
         // Emit WorkerStarted event so the UI can track it.
         let _ = self.deps.event_tx.send(crate::ProcessEvent::WorkerStarted {
             agent_id: self.deps.agent_id.clone(),
             worker_id,
             channel_id: None,
-            task: args.task.clone(),
+            task: task.clone(),
+            label: label.clone(),
             worker_type: "cortex".into(),
             interactive: false,
             directory: None,
@@ -462,7 +611,8 @@ impl Tool for DetachedSpawnWorkerTool {
         run_logger.log_worker_started(
             None,
             worker_id,
-            &args.task,
+            &task,
+            &label,
             "cortex",
             &self.deps.agent_id,
             false,
@@ -473,6 +623,7 @@ impl Tool for DetachedSpawnWorkerTool {
         let worker_span = tracing::info_span!(
             "worker.run",
             worker_id = %worker_id,
+            worker_label = %label,
             spawned_by = "cortex_chat",
         );
         crate::agent::channel_dispatch::spawn_worker_task(
@@ -502,7 +653,7 @@ impl Tool for DetachedSpawnWorkerTool {
             }
         }
 
-        tracing::info!(worker_id = %worker_id, task = %args.task, "cortex chat spawned detached worker");
+        tracing::info!(worker_id = %worker_id, task = %task, "cortex chat spawned detached worker");
 
         Ok(SpawnWorkerOutput {
             worker_id,
@@ -510,12 +661,35 @@ impl Tool for DetachedSpawnWorkerTool {
             interactive: false,
             message: format!(
                 "Worker {worker_id} spawned for: {}. It will report back when done.",
-                args.task
+                task
             ),
         })
     }
 }
 
+/// Build the structured refusal returned when `deps` is already at the
+/// configured maximum spawn depth, or `None` when spawning is allowed.
+///
+/// Returned as a result rather than an error so the LLM sees why nothing was
+/// spawned and can do the work itself instead of retrying.
+fn spawn_depth_refusal(deps: &crate::AgentDeps, interactive: bool) -> Option<SpawnWorkerOutput> {
+    let max_depth = deps.runtime_config.worker.load().max_spawn_depth;
+    let error = check_spawn_depth(deps.spawn_depth, max_depth).err()?;
+    tracing::warn!(
+        depth = deps.spawn_depth,
+        max_depth,
+        "refusing worker spawn past max spawn depth"
+    );
+    Some(SpawnWorkerOutput {
+        worker_id: WorkerId::nil(),
+        spawned: false,
+        interactive,
+        message: format!(
+            "No worker was spawned: {error}. Complete this task directly with your own tools."
+        ),
+    })
+}
+
 /// Resolve a working directory from project/worktree IDs.
 ///
 /// Priority: explicit `directory` > `worktree_id` > `project_id` root.
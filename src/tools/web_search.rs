@@ -1,29 +1,112 @@
-//! Web search tool using the Brave Search API (task workers only).
+//! Web search tool (task workers only).
+//!
+//! The tool itself only validates arguments and shapes results; the query is
+//! run by a [`SearchBackend`]. [`BraveBackend`] calls the Brave Search API and
+//! is the default. [`SearxngBackend`] calls the JSON API of a self-hosted
+//! SearXNG instance, selected with `[defaults.web_search] backend = "searxng"`.
+
+use crate::secrets::provider::SecretProviderDyn;
 
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
 const BRAVE_WEB_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
 
-/// Tool for searching the web via Brave Search.
+/// A service that can answer web search queries.
+pub trait SearchBackend: Send + Sync + std::fmt::Debug + 'static {
+    /// Name used in errors and logs, e.g. "Brave Search".
+    fn name(&self) -> &'static str;
+
+    /// Run `args.query`, returning at most `count` results.
+    fn search(
+        &self,
+        args: &WebSearchArgs,
+        count: u8,
+    ) -> impl Future<Output = Result<Vec<SearchResult>, WebSearchError>> + Send;
+}
+
+/// Dynamic companion to [`SearchBackend`], for holding the configured
+/// backend as `Arc<dyn SearchBackendDyn>`.
+pub trait SearchBackendDyn: Send + Sync + std::fmt::Debug + 'static {
+    fn name(&self) -> &'static str;
+
+    fn search<'a>(
+        &'a self,
+        args: &'a WebSearchArgs,
+        count: u8,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SearchResult>, WebSearchError>> + Send + 'a>>;
+}
+
+impl<T: SearchBackend> SearchBackendDyn for T {
+    fn name(&self) -> &'static str {
+        SearchBackend::name(self)
+    }
+
+    fn search<'a>(
+        &'a self,
+        args: &'a WebSearchArgs,
+        count: u8,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SearchResult>, WebSearchError>> + Send + 'a>> {
+        Box::pin(SearchBackend::search(self, args, count))
+    }
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .gzip(true)
+        .build()
+        .expect("hardcoded reqwest client config")
+}
+
+/// Tool for searching the web through a [`SearchBackend`].
 #[derive(Debug, Clone)]
 pub struct WebSearchTool {
-    client: reqwest::Client,
-    api_key: String,
+    backend: Arc<dyn SearchBackendDyn>,
 }
 
 impl WebSearchTool {
-    pub fn new(api_key: impl Into<String>) -> Self {
-        let client = reqwest::Client::builder()
-            .gzip(true)
-            .build()
-            .expect("hardcoded reqwest client config");
+    pub fn new(backend: Arc<dyn SearchBackendDyn>) -> Self {
+        Self { backend }
+    }
+}
 
+/// Brave Search API backend.
+#[derive(Debug)]
+pub struct BraveBackend {
+    client: reqwest::Client,
+    /// Fetched for every request so a rotated key takes effect immediately.
+    api_key: Arc<dyn SecretProviderDyn>,
+}
+
+impl BraveBackend {
+    pub fn new(api_key: Arc<dyn SecretProviderDyn>) -> Self {
         Self {
-            client,
-            api_key: api_key.into(),
+            client: http_client(),
+            api_key,
+        }
+    }
+}
+
+/// SearXNG backend. The instance must have `json` enabled under
+/// `search.formats` in its `settings.yml`.
+#[derive(Debug)]
+pub struct SearxngBackend {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl SearxngBackend {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: http_client(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
         }
     }
 }
@@ -37,12 +120,15 @@ pub enum WebSearchError {
     #[error("Failed to parse search response: {0}")]
     InvalidResponse(String),
 
-    #[error("Rate limited by Brave Search API")]
-    RateLimited,
+    #[error("Rate limited by {0}")]
+    RateLimited(&'static str),
+
+    #[error("Brave Search API key is unavailable: {0}")]
+    MissingApiKey(String),
 }
 
 /// Arguments for web search tool.
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct WebSearchArgs {
     /// The search query.
     pub query: String,
@@ -111,6 +197,185 @@ struct BraveWebResult {
     age: Option<String>,
 }
 
+// -- SearXNG API response types --
+
+#[derive(Debug, Deserialize)]
+struct SearxngApiResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngResult {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    content: String,
+    #[serde(rename = "publishedDate")]
+    published_date: Option<String>,
+}
+
+/// Check the status of a backend response and decode its JSON body.
+async fn decode_response<T: DeserializeOwned>(
+    backend: &'static str,
+    response: reqwest::Response,
+) -> Result<T, WebSearchError> {
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(WebSearchError::RateLimited(backend));
+    }
+    if !status.is_success() {
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "failed to read response body".into());
+        return Err(WebSearchError::RequestFailed(format!(
+            "{backend} returned HTTP {status}: {body}"
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|error| WebSearchError::InvalidResponse(error.to_string()))
+}
+
+impl SearchBackend for BraveBackend {
+    fn name(&self) -> &'static str {
+        "Brave Search"
+    }
+
+    async fn search(
+        &self,
+        args: &WebSearchArgs,
+        count: u8,
+    ) -> Result<Vec<SearchResult>, WebSearchError> {
+        let api_key = self
+            .api_key
+            .resolve()
+            .await
+            .map_err(|error| WebSearchError::MissingApiKey(error.to_string()))?
+            .ok_or_else(|| WebSearchError::MissingApiKey("no value is set".into()))?;
+
+        let mut request = self
+            .client
+            .get(BRAVE_WEB_SEARCH_URL)
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", api_key)
+            .query(&[("q", &args.query)])
+            .query(&[("count", &count.to_string())]);
+
+        if let Some(country) = &args.country {
+            request = request.query(&[("country", country)]);
+        }
+        if let Some(search_lang) = &args.search_lang {
+            request = request.query(&[("search_lang", search_lang)]);
+        }
+        if let Some(freshness) = &args.freshness {
+            request = request.query(&[("freshness", freshness)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| WebSearchError::RequestFailed(error.to_string()))?;
+        let api_response: BraveApiResponse =
+            decode_response(SearchBackend::name(self), response).await?;
+
+        Ok(api_response
+            .web
+            .map(|web| {
+                web.results
+                    .into_iter()
+                    .map(|result| SearchResult {
+                        title: clean_html_tags(&result.title),
+                        url: result.url,
+                        description: clean_html_tags(&result.description),
+                        age: result.age,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+impl SearchBackend for SearxngBackend {
+    fn name(&self) -> &'static str {
+        "SearXNG"
+    }
+
+    async fn search(
+        &self,
+        args: &WebSearchArgs,
+        count: u8,
+    ) -> Result<Vec<SearchResult>, WebSearchError> {
+        let mut request = self
+            .client
+            .get(format!("{}/search", self.base_url))
+            .header("Accept", "application/json")
+            .query(&[("q", args.query.as_str()), ("format", "json")]);
+
+        if let Some(language) = searxng_language(args) {
+            request = request.query(&[("language", language)]);
+        }
+        if let Some(time_range) = args.freshness.as_deref().and_then(searxng_time_range) {
+            request = request.query(&[("time_range", time_range)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| WebSearchError::RequestFailed(error.to_string()))?;
+        let api_response: SearxngApiResponse =
+            decode_response(SearchBackend::name(self), response).await?;
+
+        Ok(searxng_results(api_response, count))
+    }
+}
+
+/// SearXNG takes a single locale (`en` or `en-GB`) where Brave takes a
+/// language and a country separately.
+fn searxng_language(args: &WebSearchArgs) -> Option<String> {
+    match (&args.search_lang, &args.country) {
+        (Some(language), Some(country)) => Some(format!(
+            "{}-{}",
+            language.to_lowercase(),
+            country.to_uppercase()
+        )),
+        (Some(language), None) => Some(language.to_lowercase()),
+        (None, _) => None,
+    }
+}
+
+/// Map Brave's freshness codes onto SearXNG's `time_range` values.
+fn searxng_time_range(freshness: &str) -> Option<&'static str> {
+    match freshness {
+        "pd" => Some("day"),
+        "pw" => Some("week"),
+        "pm" => Some("month"),
+        "py" => Some("year"),
+        _ => None,
+    }
+}
+
+/// SearXNG has no result count parameter, so the page is cut down here.
+fn searxng_results(response: SearxngApiResponse, count: u8) -> Vec<SearchResult> {
+    response
+        .results
+        .into_iter()
+        .filter(|result| !result.url.is_empty())
+        .take(usize::from(count))
+        .map(|result| SearchResult {
+            title: result.title,
+            url: result.url,
+            description: clean_html_tags(&result.content),
+            age: result.published_date,
+        })
+        .collect()
+}
+
 impl Tool for WebSearchTool {
     const NAME: &'static str = "web_search";
 
@@ -157,64 +422,7 @@ impl Tool for WebSearchTool {
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
         let count = args.count.clamp(1, 20);
-
-        let mut request = self
-            .client
-            .get(BRAVE_WEB_SEARCH_URL)
-            .header("Accept", "application/json")
-            .header("X-Subscription-Token", &self.api_key)
-            .query(&[("q", &args.query)])
-            .query(&[("count", &count.to_string())]);
-
-        if let Some(country) = &args.country {
-            request = request.query(&[("country", country)]);
-        }
-        if let Some(search_lang) = &args.search_lang {
-            request = request.query(&[("search_lang", search_lang)]);
-        }
-        if let Some(freshness) = &args.freshness {
-            request = request.query(&[("freshness", freshness)]);
-        }
-
-        let response = request
-            .send()
-            .await
-            .map_err(|error| WebSearchError::RequestFailed(error.to_string()))?;
-
-        let status = response.status();
-        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(WebSearchError::RateLimited);
-        }
-        if !status.is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "failed to read response body".into());
-            return Err(WebSearchError::RequestFailed(format!(
-                "HTTP {status}: {body}"
-            )));
-        }
-
-        let api_response: BraveApiResponse = response
-            .json()
-            .await
-            .map_err(|error| WebSearchError::InvalidResponse(error.to_string()))?;
-
-        let results: Vec<SearchResult> = api_response
-            .web
-            .map(|web| {
-                web.results
-                    .into_iter()
-                    .map(|result| SearchResult {
-                        title: clean_html_tags(&result.title),
-                        url: result.url,
-                        description: clean_html_tags(&result.description),
-                        age: result.age,
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
+        let results = self.backend.search(&args, count).await?;
         let result_count = results.len();
 
         Ok(WebSearchOutput {
@@ -225,7 +433,7 @@ impl Tool for WebSearchTool {
     }
 }
 
-/// Strip basic HTML tags (like <strong>) from Brave API text fields.
+/// Strip basic HTML tags (like <strong>) from backend text fields.
 fn clean_html_tags(text: &str) -> String {
     let mut output = String::with_capacity(text.len());
     let mut in_tag = false;
@@ -257,6 +465,44 @@ mod tests {
         assert_eq!(clean_html_tags(""), "");
     }
 
+    #[test]
+    fn searxng_results_are_mapped_and_capped() {
+        let response: SearxngApiResponse = serde_json::from_value(serde_json::json!({
+            "query": "rust async",
+            "results": [
+                {
+                    "title": "Async Rust",
+                    "url": "https://rust-lang.github.io/async-book/",
+                    "content": "An <em>async</em> primer",
+                    "publishedDate": "2025-01-02T00:00:00",
+                    "engine": "duckduckgo"
+                },
+                {"title": "No link", "url": "", "content": "dropped"},
+                {"title": "Tokio", "url": "https://tokio.rs/", "content": "Runtime"},
+                {"title": "Third", "url": "https://example.com/", "content": "Cut"}
+            ]
+        }))
+        .unwrap();
+
+        let results = searxng_results(response, 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].description, "An async primer");
+        assert_eq!(results[0].age.as_deref(), Some("2025-01-02T00:00:00"));
+        assert_eq!(results[1].url, "https://tokio.rs/");
+
+        let args: WebSearchArgs = serde_json::from_value(serde_json::json!({
+            "query": "q", "search_lang": "EN", "country": "gb", "freshness": "pw"
+        }))
+        .unwrap();
+        assert_eq!(searxng_language(&args).as_deref(), Some("en-GB"));
+        assert_eq!(searxng_time_range("pw"), Some("week"));
+        assert_eq!(searxng_time_range("all"), None);
+        assert_eq!(
+            SearxngBackend::new("https://search.example.com/").base_url,
+            "https://search.example.com"
+        );
+    }
+
     #[test]
     fn test_default_count() {
         let args: WebSearchArgs = serde_json::from_str(r#"{"query": "test"}"#).unwrap();
@@ -198,8 +198,8 @@ impl WorkerInspectTool {
                 _ => "[-]",
             };
             summary.push_str(&format!(
-                "{status_marker} `{}` — {} ({})\n",
-                row.id, row.task, row.status,
+                "{status_marker} `{}` {} — {} ({})\n",
+                row.id, row.label, row.task, row.status,
             ));
             if let Some(channel) = &row.channel_name {
                 summary.push_str(&format!("  Channel: {channel}\n"));
@@ -0,0 +1,315 @@
+//! Per-worker selection of optional tools.
+//!
+//! File, status, task, and skill tools are always registered. Shell, browser,
+//! web search and HTTP requests are optional: a worker summarizing pasted text has no use
+//! for them, and every extra tool schema costs attention and widens what a
+//! confused model can do. The spawn request can name the tools it wants, and
+//! with `tool_gating` enabled the set is otherwise inferred from the task.
+//!
+//! [`ToolPermissions`] are the hard limit on top: which tools a worker may
+//! have at all, whatever its task asks for. They come from
+//! `[defaults.worker.tool_permissions]` and can be narrowed per spawn, so a
+//! channel can start a read-only worker with no shell and no file writes.
+
+/// Which optional tools a worker gets. Availability still applies on top:
+/// browser needs `browser.enabled`, web search needs a configured backend,
+/// HTTP requests need `worker.http_request.enabled`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkerToolset {
+    pub shell: bool,
+    pub browser: bool,
+    pub web_search: bool,
+    pub http_request: bool,
+}
+
+impl Default for WorkerToolset {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Words that suggest the task needs to run commands or touch the system.
+const SHELL_WORDS: &[&str] = &[
+    "run",
+    "execute",
+    "command",
+    "shell",
+    "script",
+    "install",
+    "build",
+    "compile",
+    "test",
+    "tests",
+    "git",
+    "repo",
+    "repository",
+    "deploy",
+    "directory",
+    "folder",
+    "file",
+    "files",
+    "code",
+    "debug",
+    "fix",
+    "download",
+    "curl",
+    "grep",
+    "process",
+    "server",
+    "docker",
+    "npm",
+    "cargo",
+    "python",
+    "package",
+    "log",
+    "logs",
+    "check",
+    "create",
+    "generate",
+    "convert",
+];
+
+/// Words that suggest the task needs to operate a web page.
+const BROWSER_WORDS: &[&str] = &[
+    "browse",
+    "browser",
+    "website",
+    "webpage",
+    "page",
+    "click",
+    "screenshot",
+    "login",
+    "form",
+    "navigate",
+    "scrape",
+    "site",
+];
+
+/// Words that suggest the task needs to call an HTTP endpoint.
+const HTTP_REQUEST_WORDS: &[&str] = &[
+    "api", "endpoint", "http", "https", "request", "webhook", "fetch", "download", "json", "rest",
+    "url",
+];
+
+/// Words that suggest the task needs information from the web.
+const WEB_SEARCH_WORDS: &[&str] = &[
+    "search", "google", "lookup", "research", "latest", "news", "current", "online", "web",
+    "internet", "price", "prices", "weather",
+];
+
+impl WorkerToolset {
+    pub const ALL: Self = Self {
+        shell: true,
+        browser: true,
+        web_search: true,
+        http_request: true,
+    };
+
+    pub const NONE: Self = Self {
+        shell: false,
+        browser: false,
+        web_search: false,
+        http_request: false,
+    };
+
+    /// Build a toolset from explicit tool names. Unknown names are ignored.
+    pub fn from_hints(hints: &[String]) -> Self {
+        let wants = |name: &str| hints.iter().any(|hint| hint.trim() == name);
+        Self {
+            shell: wants("shell"),
+            browser: wants("browser"),
+            web_search: wants("web_search"),
+            http_request: wants("http_request"),
+        }
+    }
+
+    /// Guess the tools a task needs from its wording.
+    ///
+    /// Deliberately keyword-based and generous: a false positive costs one
+    /// tool schema, a false negative can make the task impossible.
+    pub fn infer(task: &str) -> Self {
+        let lowered = task.to_lowercase();
+        let has_url =
+            lowered.contains("http://") || lowered.contains("https://") || lowered.contains("www.");
+        let words: Vec<&str> = lowered
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let mentions = |vocabulary: &[&str]| words.iter().any(|word| vocabulary.contains(word));
+
+        Self {
+            shell: mentions(SHELL_WORDS),
+            browser: has_url || mentions(BROWSER_WORDS),
+            web_search: has_url || mentions(WEB_SEARCH_WORDS),
+            http_request: has_url || mentions(HTTP_REQUEST_WORDS),
+        }
+    }
+
+    /// Effective toolset for a spawn: explicit hints win, then the inferred
+    /// set when gating is enabled, otherwise everything.
+    pub fn resolve(task: &str, hints: Option<&[String]>, gating_enabled: bool) -> Self {
+        match hints {
+            Some(hints) => Self::from_hints(hints),
+            None if gating_enabled => Self::infer(task),
+            None => Self::ALL,
+        }
+    }
+
+    /// Names of the optional tools in this set, for logs and prompts.
+    pub fn names(&self) -> Vec<&'static str> {
+        [
+            (self.shell, "shell"),
+            (self.browser, "browser"),
+            (self.web_search, "web_search"),
+            (self.http_request, "http_request"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+        .collect()
+    }
+}
+
+/// Tools a worker is allowed to have. Status, task, skill, file read and
+/// file list tools are always allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolPermissions {
+    pub shell: bool,
+    /// `file_write` and `file_edit`.
+    pub file_write: bool,
+    pub browser: bool,
+    pub web_search: bool,
+    pub http_request: bool,
+    /// `secret_set`.
+    pub secrets: bool,
+    /// Tools from connected MCP servers.
+    pub mcp: bool,
+}
+
+impl Default for ToolPermissions {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl ToolPermissions {
+    pub const ALL: Self = Self {
+        shell: true,
+        file_write: true,
+        browser: true,
+        web_search: true,
+        http_request: true,
+        secrets: true,
+        mcp: true,
+    };
+
+    /// No tool that can change anything: only reading files and searching
+    /// the web. The browser and HTTP requests are out too, since both can
+    /// submit forms and call APIs.
+    pub const READ_ONLY: Self = Self {
+        shell: false,
+        file_write: false,
+        browser: false,
+        web_search: true,
+        http_request: false,
+        secrets: false,
+        mcp: false,
+    };
+
+    /// Only what both sets allow.
+    pub fn intersect(self, other: Self) -> Self {
+        Self {
+            shell: self.shell && other.shell,
+            file_write: self.file_write && other.file_write,
+            browser: self.browser && other.browser,
+            web_search: self.web_search && other.web_search,
+            http_request: self.http_request && other.http_request,
+            secrets: self.secrets && other.secrets,
+            mcp: self.mcp && other.mcp,
+        }
+    }
+
+    /// The optional tools of `toolset` these permissions allow.
+    pub fn restrict(&self, toolset: WorkerToolset) -> WorkerToolset {
+        WorkerToolset {
+            shell: toolset.shell && self.shell,
+            browser: toolset.browser && self.browser,
+            web_search: toolset.web_search && self.web_search,
+            http_request: toolset.http_request && self.http_request,
+        }
+    }
+
+    /// Names of the denied tools, as the worker prompt refers to them.
+    pub fn denied_tools(&self) -> Vec<&'static str> {
+        [
+            (self.shell, &["shell"][..]),
+            (self.file_write, &["file_write", "file_edit"][..]),
+            (self.browser, &["browser_*"][..]),
+            (self.web_search, &["web_search"][..]),
+            (self.http_request, &["http_request"][..]),
+            (self.secrets, &["secret_set"][..]),
+            (self.mcp, &["MCP tools"][..]),
+        ]
+        .into_iter()
+        .filter(|(allowed, _)| !allowed)
+        .flat_map(|(_, names)| names.iter().copied())
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ToolPermissions, WorkerToolset};
+
+    #[test]
+    fn pure_text_tasks_get_no_optional_tools() {
+        let toolset = WorkerToolset::infer(
+            "Summarize the following text in three bullet points: the meeting covered Q3 goals.",
+        );
+        assert_eq!(toolset, WorkerToolset::NONE);
+    }
+
+    #[test]
+    fn task_wording_enables_matching_tools() {
+        let toolset = WorkerToolset::infer("Open https://example.com and take a screenshot");
+        assert!(toolset.browser);
+        assert!(!toolset.shell);
+
+        let toolset = WorkerToolset::infer("Run the test suite in ~/project and fix failures");
+        assert_eq!(toolset.names(), ["shell"]);
+
+        let toolset = WorkerToolset::infer("Search for the latest Rust release notes");
+        assert!(toolset.web_search);
+
+        let toolset = WorkerToolset::infer("POST the report to the team webhook");
+        assert_eq!(toolset.names(), ["http_request"]);
+    }
+
+    #[test]
+    fn explicit_hints_override_inference_and_gating() {
+        let hints = vec!["web_search".to_string(), "unknown".to_string()];
+        let toolset = WorkerToolset::resolve("Run cargo build", Some(&hints), true);
+        assert_eq!(toolset.names(), ["web_search"]);
+
+        assert_eq!(
+            WorkerToolset::resolve("Summarize this", None, false),
+            WorkerToolset::ALL
+        );
+    }
+
+    #[test]
+    fn permissions_cap_the_toolset() {
+        let toolset = ToolPermissions::READ_ONLY.restrict(WorkerToolset::ALL);
+        assert_eq!(toolset.names(), ["web_search"]);
+
+        let no_shell = ToolPermissions {
+            shell: false,
+            ..ToolPermissions::ALL
+        };
+        assert_eq!(
+            no_shell.intersect(ToolPermissions::READ_ONLY),
+            ToolPermissions::READ_ONLY
+        );
+        assert_eq!(no_shell.denied_tools(), ["shell"]);
+        assert!(ToolPermissions::ALL.denied_tools().is_empty());
+    }
+}
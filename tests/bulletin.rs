@@ -130,6 +130,7 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
             spacebot::agent::process_control::ProcessControlRegistry::new(),
         ),
         injection_tx: tokio::sync::mpsc::channel(1).0,
+        spawn_depth: 0,
     })
 }
 
@@ -129,6 +129,7 @@ async fn bootstrap_deps() -> anyhow::Result<(spacebot::AgentDeps, spacebot::conf
             spacebot::agent::process_control::ProcessControlRegistry::new(),
         ),
         injection_tx: tokio::sync::mpsc::channel(1).0,
+        spawn_depth: 0,
     };
 
     Ok((deps, config))
@@ -179,10 +180,19 @@ fn build_channel_system_prompt(rc: &spacebot::config::RuntimeConfig) -> String {
         .unwrap_or_default();
 
     let browser_enabled = rc.browser_config.load().enabled;
-    let web_search_enabled = rc.brave_search_key.load().is_some();
+    let web_search_enabled = rc.web_search_enabled();
     let opencode_enabled = rc.opencode.load().enabled;
     let worker_capabilities = prompt_engine
-        .render_worker_capabilities(browser_enabled, web_search_enabled, opencode_enabled, &[])
+        .render_worker_capabilities(
+            browser_enabled,
+            web_search_enabled,
+            rc.worker.load().http_request.enabled,
+            opencode_enabled,
+            false,
+            false,
+            rc.worker.load().tool_permissions,
+            &[],
+        )
         .expect("failed to render worker capabilities");
 
     let conversation_context = prompt_engine
@@ -237,6 +247,7 @@ async fn dump_channel_context() {
         worker_inputs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_injections: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         reserved_tasks: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
+        awaited_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
         status_block,
         deps: deps.clone(),
         conversation_logger,
@@ -384,7 +395,7 @@ async fn dump_worker_context() {
         .expect("failed to render worker prompt");
     print_section("WORKER SYSTEM PROMPT", &worker_prompt);
     print_stats("System prompt", &worker_prompt);
-    let brave_search_key = (**rc.brave_search_key.load()).clone();
+    let search_backend = rc.search_backend();
     let worker_id = uuid::Uuid::new_v4();
 
     let worker_tool_server = spacebot::tools::create_worker_tool_server(
@@ -395,11 +406,16 @@ async fn dump_worker_context() {
         deps.event_tx.clone(),
         browser_config,
         std::path::PathBuf::from("/tmp/screenshots"),
-        brave_search_key,
+        search_backend,
         std::path::PathBuf::from("/tmp"),
+        None,
         deps.sandbox.clone(),
         vec![],
         deps.runtime_config.clone(),
+        spacebot::tools::WorkerToolset::default(),
+        spacebot::tools::ToolPermissions::default(),
+        tokio::sync::watch::channel(0).0,
+        spacebot::tools::WorkerMode::Execute,
     );
 
     let tool_defs = worker_tool_server
@@ -475,6 +491,7 @@ async fn dump_all_contexts() {
         worker_inputs: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         worker_injections: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         reserved_tasks: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
+        awaited_workers: Arc::new(tokio::sync::RwLock::new(std::collections::HashSet::new())),
         status_block: Arc::new(tokio::sync::RwLock::new(
             spacebot::agent::status::StatusBlock::new(),
         )),
@@ -566,7 +583,7 @@ async fn dump_all_contexts() {
             None,
         )
         .expect("failed to render worker prompt");
-    let brave_search_key = (**rc.brave_search_key.load()).clone();
+    let search_backend = rc.search_backend();
     let worker_tool_server = spacebot::tools::create_worker_tool_server(
         deps.agent_id.clone(),
         uuid::Uuid::new_v4(),
@@ -575,11 +592,16 @@ async fn dump_all_contexts() {
         deps.event_tx.clone(),
         browser_config,
         std::path::PathBuf::from("/tmp/screenshots"),
-        brave_search_key,
+        search_backend,
         std::path::PathBuf::from("/tmp"),
+        None,
         deps.sandbox.clone(),
         vec![],
         deps.runtime_config.clone(),
+        spacebot::tools::WorkerToolset::default(),
+        spacebot::tools::ToolPermissions::default(),
+        tokio::sync::watch::channel(0).0,
+        spacebot::tools::WorkerMode::Execute,
     );
     let worker_tool_defs = worker_tool_server.get_tool_defs(None).await.unwrap();
     let worker_tools_text = format_tool_defs(&worker_tool_defs);